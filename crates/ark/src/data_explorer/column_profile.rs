@@ -7,12 +7,14 @@
 
 use amalthea::comm::comm_channel::CommMsg;
 use amalthea::comm::data_explorer_comm::ColumnFrequencyTable;
+use amalthea::comm::data_explorer_comm::ColumnFrequencyTableParams;
 use amalthea::comm::data_explorer_comm::ColumnHistogram;
 use amalthea::comm::data_explorer_comm::ColumnProfileParams;
 use amalthea::comm::data_explorer_comm::ColumnProfileRequest;
 use amalthea::comm::data_explorer_comm::ColumnProfileResult;
 use amalthea::comm::data_explorer_comm::ColumnProfileSpec;
 use amalthea::comm::data_explorer_comm::ColumnProfileType;
+use amalthea::comm::data_explorer_comm::ColumnSchema;
 use amalthea::comm::data_explorer_comm::ColumnSummaryStats;
 use amalthea::comm::data_explorer_comm::DataExplorerFrontendEvent;
 use amalthea::comm::data_explorer_comm::FormatOptions;
@@ -29,7 +31,11 @@ use stdext::unwrap;
 
 use crate::data_explorer::histogram;
 use crate::data_explorer::summary_stats::summary_stats;
+use crate::data_explorer::summary_stats::summary_stats_timezone;
+use crate::data_explorer::summary_stats::summary_stats_with;
 use crate::data_explorer::table::Table;
+use crate::data_explorer::table_backend::LazyView;
+use crate::data_explorer::table_backend::TableBackend;
 use crate::data_explorer::utils::display_type;
 use crate::modules::ARK_ENVS;
 
@@ -38,6 +44,11 @@ pub struct ProcessColumnsProfilesParams {
     pub indices: Option<Vec<i32>>,
     pub kind: TableKind,
     pub request: GetColumnProfilesParams,
+    /// The backend of lazy tables. Profiles are then computed by the backend
+    /// on the `view`, and `indices` is unused.
+    pub backend: Option<&'static dyn TableBackend>,
+    pub view: LazyView,
+    pub columns: Vec<ColumnSchema>,
 }
 
 pub async fn handle_columns_profiles_requests(
@@ -47,14 +58,29 @@ pub async fn handle_columns_profiles_requests(
     let callback_id = params.request.callback_id;
     let n_profiles = params.request.profiles.len();
 
-    let profiles = process_columns_profiles_requests(
-        params.table,
-        params.indices,
-        params.kind,
-        params.request.profiles,
-        params.request.format_options,
-    )
-    .await
+    let profiles = match params.backend {
+        Some(backend) => {
+            process_lazy_columns_profiles_requests(
+                params.table,
+                backend,
+                params.view,
+                params.columns,
+                params.request.profiles,
+                params.request.format_options,
+            )
+            .await
+        },
+        None => {
+            process_columns_profiles_requests(
+                params.table,
+                params.indices,
+                params.kind,
+                params.request.profiles,
+                params.request.format_options,
+            )
+            .await
+        },
+    }
     .unwrap_or_else(|e| {
         // In case something goes wrong while computing the profiles, we send
         // an empty response. Ideally, we would have a way to comunicate an that
@@ -109,6 +135,100 @@ async fn process_columns_profiles_requests(
     Ok(results)
 }
 
+async fn process_lazy_columns_profiles_requests(
+    table: Table,
+    backend: &'static dyn TableBackend,
+    view: LazyView,
+    columns: Vec<ColumnSchema>,
+    profiles: Vec<ColumnProfileRequest>,
+    format_options: FormatOptions,
+) -> anyhow::Result<Vec<ColumnProfileResult>> {
+    // See `process_columns_profiles_requests()`
+    let data = table.get()?;
+    let mut results: Vec<ColumnProfileResult> = Vec::with_capacity(profiles.len());
+
+    for profile in profiles.into_iter() {
+        let Some(column) = columns.get(profile.column_index as usize) else {
+            log::error!("Column index out of range: {}", profile.column_index);
+            results.push(empty_column_profile_result());
+            continue;
+        };
+
+        results.push(
+            profile_lazy_column(backend, &data, &view, column, profile, &format_options).await,
+        );
+        // Yield to the idle event loop
+        tokio::task::yield_now().await;
+    }
+
+    Ok(results)
+}
+
+// Like `profile_column()`, but the profiles are computed by the backend of a
+// lazy table. Histograms require the values of the column and aren't
+// supported.
+async fn profile_lazy_column(
+    backend: &'static dyn TableBackend,
+    table: &RObject,
+    view: &LazyView,
+    column: &ColumnSchema,
+    request: ColumnProfileRequest,
+    format_options: &FormatOptions,
+) -> ColumnProfileResult {
+    let mut output = empty_column_profile_result();
+    let name = column.column_name.as_str();
+
+    for profile_req in request.profiles {
+        let result = match profile_req.profile_type {
+            ColumnProfileType::NullCount => backend
+                .null_count(table, view, name)
+                .map(|count| output.null_count = Some(count)),
+            ColumnProfileType::SummaryStats => summary_stats_with(
+                column.type_display.clone(),
+                format_options,
+                |fun| backend.summary_stats(table, view, name, fun),
+                || {
+                    let ptype = backend.ptype(table)?;
+                    let ptype_column = tbl_get_column(
+                        ptype.sexp,
+                        column.column_index as i32,
+                        TableKind::Dataframe,
+                    )?;
+                    summary_stats_timezone(ptype_column.sexp)
+                },
+            )
+            .map(|stats| output.summary_stats = Some(stats)),
+            ColumnProfileType::SmallHistogram | ColumnProfileType::LargeHistogram => Ok(()),
+            ColumnProfileType::SmallFrequencyTable | ColumnProfileType::LargeFrequencyTable => {
+                frequency_table_params(&profile_req)
+                    .and_then(|params| {
+                        let results = backend.frequency_table(table, view, name, params.limit)?;
+                        histogram::frequency_table_from_results(results, params, format_options)
+                    })
+                    .map(|frequency_table| match profile_req.profile_type {
+                        ColumnProfileType::SmallFrequencyTable => {
+                            output.small_frequency_table = Some(frequency_table)
+                        },
+                        _ => output.large_frequency_table = Some(frequency_table),
+                    })
+            },
+        };
+
+        if let Err(err) = result {
+            log::error!(
+                "Error computing {:?} with the {} backend for column {}: {err}",
+                profile_req.profile_type,
+                backend.name(),
+                request.column_index
+            );
+        }
+
+        // Yield to the R console loop
+        tokio::task::yield_now().await;
+    }
+    output
+}
+
 // This function does not return a Result because it must handle still handle other profile types
 // if one of them fails. Thus it needs to gracefully handle the errors that might have resulted
 // here.
@@ -230,19 +350,25 @@ pub fn empty_column_profile_result() -> ColumnProfileResult {
     }
 }
 
+fn frequency_table_params(
+    profile_spec: &ColumnProfileSpec,
+) -> anyhow::Result<&ColumnFrequencyTableParams> {
+    match &profile_spec.params {
+        None => Err(anyhow!("Missing parameters for the frequency table")),
+        Some(par) => match par {
+            ColumnProfileParams::SmallFrequencyTable(p) => Ok(p),
+            ColumnProfileParams::LargeFrequencyTable(p) => Ok(p),
+            _ => Err(anyhow!("Wrong type of parameters for the frequency table.")),
+        },
+    }
+}
+
 fn profile_frequency_table(
     column: RObject,
     format_options: &FormatOptions,
     profile_spec: &ColumnProfileSpec,
 ) -> anyhow::Result<ColumnFrequencyTable> {
-    let params = match &profile_spec.params {
-        None => return Err(anyhow!("Missing parameters for the frequency table")),
-        Some(par) => match par {
-            ColumnProfileParams::SmallFrequencyTable(p) => p,
-            ColumnProfileParams::LargeFrequencyTable(p) => p,
-            _ => return Err(anyhow!("Wrong type of parameters for the frequency table.")),
-        },
-    };
+    let params = frequency_table_params(profile_spec)?;
     let frequency_table =
         histogram::profile_frequency_table(column.sexp, &params, &format_options)?;
     Ok(frequency_table)
@@ -14,6 +14,15 @@ use amalthea::comm::data_explorer_comm::RowFilter;
 use amalthea::comm::data_explorer_comm::RowFilterParams;
 use amalthea::comm::data_explorer_comm::RowFilterType;
 use amalthea::comm::data_explorer_comm::TextSearchType;
+use harp::utils::r_inherits;
+use libr::SEXP;
+
+/// Names of the supported code syntaxes
+pub const DPLYR_SYNTAX: &str = "dplyr";
+pub const BASE_R_SYNTAX: &str = "base R";
+pub const DATA_TABLE_SYNTAX: &str = "data.table";
+pub const DUCKPLYR_SYNTAX: &str = "duckplyr";
+pub const SQL_SYNTAX: &str = "SQL";
 
 /// Sort key with resolved column name
 #[derive(Clone, Debug)]
@@ -39,6 +48,7 @@ trait CodeConverter {
         params: ConvertToCodeParams,
         object_name: Option<&str>,
         resolved_sort_keys: &[ResolvedSortKey],
+        resolved_columns: Option<&[String]>,
     ) -> ConvertedCode;
 }
 
@@ -147,6 +157,7 @@ impl CodeConverter for DplyrCodeConverter {
         params: ConvertToCodeParams,
         object_name: Option<&str>,
         resolved_sort_keys: &[ResolvedSortKey],
+        resolved_columns: Option<&[String]>,
     ) -> ConvertedCode {
         let table_name = object_name.unwrap_or("dat").to_string();
         let mut builder = PipeBuilder::new(table_name);
@@ -164,20 +175,379 @@ impl CodeConverter for DplyrCodeConverter {
             builder.add_operation(sort_op);
         }
 
+        // Select columns last, since filters and sorts may use columns that
+        // are not selected
+        if let Some(columns) = resolved_columns {
+            let columns: Vec<String> = columns
+                .iter()
+                .map(|column| format_column_name(column))
+                .collect();
+            builder.add_operation(format!("select({})", columns.join(", ")));
+        }
+
         builder.build(vec!["library(dplyr)".to_string()])
     }
 }
 
+/// Combine filter expressions with `&`, for syntaxes where all filters are
+/// passed as a single condition. Expressions that contain `|` or start with
+/// `!`, which has a lower precedence than `&` in R, are wrapped in
+/// parentheses to keep their meaning.
+fn combine_filters(expressions: Vec<String>) -> Option<String> {
+    if expressions.is_empty() {
+        return None;
+    }
+
+    let expressions: Vec<String> = expressions
+        .into_iter()
+        .map(|expr| {
+            if expr.contains(" | ") || expr.starts_with('!') {
+                format!("({})", expr)
+            } else {
+                expr
+            }
+        })
+        .collect();
+
+    Some(expressions.join(" & "))
+}
+
+/// Base R filter handler. Filter expressions are the same as for dplyr, and
+/// are evaluated with `subset()`, which also drops rows where the condition
+/// is `NA`.
+struct BaseFilterHandler;
+
+impl FilterHandler for BaseFilterHandler {
+    fn convert_filter(&self, filter: &RowFilter) -> Option<String> {
+        row_filter_to_dplyr(filter)
+    }
+}
+
+/// Base R sort handler. Rows are ordered with the same radix sort as the
+/// data explorer.
+struct BaseSortHandler {
+    table_name: String,
+}
+
+impl SortHandler for BaseSortHandler {
+    fn convert_sorts(&self, sort_keys: &[ResolvedSortKey]) -> Option<String> {
+        if sort_keys.is_empty() {
+            return None;
+        }
+
+        let mut arguments: Vec<String> = sort_keys
+            .iter()
+            .map(|sort_key| {
+                format!(
+                    "{}[[{}]]",
+                    self.table_name,
+                    escape_character_constant(&sort_key.column_name)
+                )
+            })
+            .collect();
+
+        if sort_keys.iter().any(|sort_key| !sort_key.ascending) {
+            let decreasing: Vec<&str> = sort_keys
+                .iter()
+                .map(|sort_key| if sort_key.ascending { "FALSE" } else { "TRUE" })
+                .collect();
+            arguments.push(format!("decreasing = c({})", decreasing.join(", ")));
+        }
+        arguments.push("method = \"radix\"".to_string());
+
+        Some(format!(
+            "{}[order({}), , drop = FALSE]",
+            self.table_name,
+            arguments.join(", ")
+        ))
+    }
+}
+
+/// Base R code converter
+struct BaseCodeConverter;
+
+impl CodeConverter for BaseCodeConverter {
+    fn build_code(
+        &self,
+        params: ConvertToCodeParams,
+        object_name: Option<&str>,
+        resolved_sort_keys: &[ResolvedSortKey],
+        resolved_columns: Option<&[String]>,
+    ) -> ConvertedCode {
+        let table_name = object_name.unwrap_or("dat").to_string();
+
+        let filter_handler = BaseFilterHandler;
+        let sort_handler = BaseSortHandler {
+            table_name: table_name.clone(),
+        };
+
+        // Sort first, so that the sort keys can refer to the table by name.
+        // Filtering with `subset()` keeps the order of the rows.
+        let sorted = sort_handler
+            .convert_sorts(resolved_sort_keys)
+            .unwrap_or(table_name);
+        let mut builder = PipeBuilder::new(sorted);
+
+        let condition = combine_filters(
+            params
+                .row_filters
+                .iter()
+                .filter_map(|filter| filter_handler.convert_filter(filter))
+                .collect(),
+        );
+        let select = resolved_columns.map(|columns| {
+            let columns: Vec<String> = columns
+                .iter()
+                .map(|column| format_column_name(column))
+                .collect();
+            format!("select = c({})", columns.join(", "))
+        });
+
+        let arguments: Vec<String> = condition.into_iter().chain(select).collect();
+        if !arguments.is_empty() {
+            builder.add_operation(format!("subset({})", arguments.join(", ")));
+        }
+
+        builder.build(vec![])
+    }
+}
+
+/// data.table filter handler
+struct DataTableFilterHandler;
+
+impl FilterHandler for DataTableFilterHandler {
+    fn convert_filter(&self, filter: &RowFilter) -> Option<String> {
+        match filter.filter_type {
+            // A single column name in `i` would be evaluated in the calling
+            // scope rather than as a column
+            RowFilterType::IsTrue => Some(format!(
+                "{} == TRUE",
+                format_column_name(&filter.column_schema.column_name)
+            )),
+            _ => row_filter_to_dplyr(filter),
+        }
+    }
+}
+
+/// data.table sort handler
+struct DataTableSortHandler;
+
+impl SortHandler for DataTableSortHandler {
+    fn convert_sorts(&self, sort_keys: &[ResolvedSortKey]) -> Option<String> {
+        if sort_keys.is_empty() {
+            return None;
+        }
+
+        let sort_expressions: Vec<String> = sort_keys
+            .iter()
+            .map(|sort_key| {
+                let formatted_name = format_column_name(&sort_key.column_name);
+                if sort_key.ascending {
+                    formatted_name
+                } else {
+                    format!("-{}", formatted_name)
+                }
+            })
+            .collect();
+
+        Some(format!("order({})", sort_expressions.join(", ")))
+    }
+}
+
+/// data.table code converter. The object is converted with
+/// `as.data.table()` so that the code also works with data frames.
+struct DataTableCodeConverter;
+
+impl CodeConverter for DataTableCodeConverter {
+    fn build_code(
+        &self,
+        params: ConvertToCodeParams,
+        object_name: Option<&str>,
+        resolved_sort_keys: &[ResolvedSortKey],
+        resolved_columns: Option<&[String]>,
+    ) -> ConvertedCode {
+        let table_name = object_name.unwrap_or("dat");
+        let mut code = format!("as.data.table({})", table_name);
+
+        let filter_handler = DataTableFilterHandler;
+        let sort_handler = DataTableSortHandler;
+
+        let condition = combine_filters(
+            params
+                .row_filters
+                .iter()
+                .filter_map(|filter| filter_handler.convert_filter(filter))
+                .collect(),
+        );
+        if let Some(condition) = condition {
+            code.push_str(&format!("[{}]", condition));
+        }
+
+        if let Some(order) = sort_handler.convert_sorts(resolved_sort_keys) {
+            code.push_str(&format!("[{}]", order));
+        }
+
+        if let Some(columns) = resolved_columns {
+            let columns: Vec<String> = columns
+                .iter()
+                .map(|column| escape_character_constant(column))
+                .collect();
+            code.push_str(&format!("[, c({})]", columns.join(", ")));
+        }
+
+        ConvertedCode {
+            converted_code: vec!["library(data.table)".to_string(), "".to_string(), code],
+        }
+    }
+}
+
+/// duckplyr code converter. duckplyr uses the dplyr verbs, evaluated by
+/// DuckDB.
+struct DuckplyrCodeConverter;
+
+impl CodeConverter for DuckplyrCodeConverter {
+    fn build_code(
+        &self,
+        params: ConvertToCodeParams,
+        object_name: Option<&str>,
+        resolved_sort_keys: &[ResolvedSortKey],
+        resolved_columns: Option<&[String]>,
+    ) -> ConvertedCode {
+        let table_name = object_name.unwrap_or("dat").to_string();
+        let mut builder = PipeBuilder::new(table_name);
+        builder.add_operation("as_duckdb_tibble()".to_string());
+
+        let filter_handler = DplyrFilterHandler;
+        let sort_handler = DplyrSortHandler;
+
+        if let Some(filter_op) = filter_handler.convert_filters(&params.row_filters) {
+            builder.add_operation(filter_op);
+        }
+
+        if let Some(sort_op) = sort_handler.convert_sorts(resolved_sort_keys) {
+            builder.add_operation(sort_op);
+        }
+
+        if let Some(columns) = resolved_columns {
+            let columns: Vec<String> = columns
+                .iter()
+                .map(|column| format_column_name(column))
+                .collect();
+            builder.add_operation(format!("select({})", columns.join(", ")));
+        }
+
+        builder.build(vec!["library(duckplyr)".to_string()])
+    }
+}
+
+/// SQL filter handler, for the DuckDB dialect
+struct SqlFilterHandler;
+
+impl FilterHandler for SqlFilterHandler {
+    fn convert_filter(&self, filter: &RowFilter) -> Option<String> {
+        row_filter_to_sql(filter)
+    }
+}
+
+/// SQL sort handler. DuckDB sorts missing values last, like R.
+struct SqlSortHandler;
+
+impl SortHandler for SqlSortHandler {
+    fn convert_sorts(&self, sort_keys: &[ResolvedSortKey]) -> Option<String> {
+        if sort_keys.is_empty() {
+            return None;
+        }
+
+        let sort_expressions: Vec<String> = sort_keys
+            .iter()
+            .map(|sort_key| {
+                let formatted_name = quote_sql_identifier(&sort_key.column_name);
+                if sort_key.ascending {
+                    formatted_name
+                } else {
+                    format!("{} DESC", formatted_name)
+                }
+            })
+            .collect();
+
+        Some(format!("ORDER BY {}", sort_expressions.join(", ")))
+    }
+}
+
+/// SQL code converter. The query is written for DuckDB, with the data object
+/// registered as a table of the same name.
+struct SqlCodeConverter;
+
+impl CodeConverter for SqlCodeConverter {
+    fn build_code(
+        &self,
+        params: ConvertToCodeParams,
+        object_name: Option<&str>,
+        resolved_sort_keys: &[ResolvedSortKey],
+        resolved_columns: Option<&[String]>,
+    ) -> ConvertedCode {
+        let table_name = object_name.unwrap_or("dat");
+
+        let filter_handler = SqlFilterHandler;
+        let sort_handler = SqlSortHandler;
+
+        let columns = match resolved_columns {
+            Some(columns) => columns
+                .iter()
+                .map(|column| quote_sql_identifier(column))
+                .collect::<Vec<_>>()
+                .join(", "),
+            None => "*".to_string(),
+        };
+
+        let mut clauses = vec![
+            format!("SELECT {}", columns),
+            format!("FROM {}", quote_sql_identifier(table_name)),
+        ];
+
+        let conditions: Vec<String> = params
+            .row_filters
+            .iter()
+            .filter_map(|filter| filter_handler.convert_filter(filter))
+            .collect();
+        if !conditions.is_empty() {
+            clauses.push(format!("WHERE {}", conditions.join("\n  AND ")));
+        }
+
+        if let Some(order) = sort_handler.convert_sorts(resolved_sort_keys) {
+            clauses.push(order);
+        }
+
+        ConvertedCode {
+            converted_code: vec![clauses.join("\n")],
+        }
+    }
+}
+
+/// The code converter for a syntax. Unknown syntaxes fall back to dplyr.
+fn code_converter(code_syntax_name: &str) -> Box<dyn CodeConverter> {
+    match code_syntax_name {
+        BASE_R_SYNTAX => Box::new(BaseCodeConverter),
+        DATA_TABLE_SYNTAX => Box::new(DataTableCodeConverter),
+        DUCKPLYR_SYNTAX => Box::new(DuckplyrCodeConverter),
+        SQL_SYNTAX => Box::new(SqlCodeConverter),
+        _ => Box::new(DplyrCodeConverter),
+    }
+}
+
 /// Convert the current data explorer view to executable code
 ///
 /// Takes filters, sort keys, and other parameters and generates code that
-/// can reproduce the current data view.
+/// can reproduce the current data view, in the syntax named by
+/// `params.code_syntax_name`.
 ///
 /// # Arguments
 ///
 /// * `params` - Parameters for the code conversion including filters and sort keys
 /// * `object_name` - Optional name of the data object in the R environment
 /// * `resolved_sort_keys` - Sort keys with resolved column names
+/// * `resolved_columns` - Names of the columns that pass the column filters,
+///   if any column filters are applied
 ///
 /// # Returns
 ///
@@ -186,23 +556,49 @@ pub fn convert_to_code(
     params: ConvertToCodeParams,
     object_name: Option<&str>,
     resolved_sort_keys: &[ResolvedSortKey],
+    resolved_columns: Option<&[String]>,
 ) -> ConvertedCode {
-    // For now, default to dplyr syntax
-    // TODO: Use params.code_syntax_name to choose the appropriate converter
-    let converter = DplyrCodeConverter;
-    converter.build_code(params, object_name, resolved_sort_keys)
+    let converter = code_converter(&params.code_syntax_name.code_syntax_name);
+    converter.build_code(params, object_name, resolved_sort_keys, resolved_columns)
+}
+
+/// The code syntaxes supported by `convert_to_code()`
+pub fn code_syntaxes() -> Vec<CodeSyntaxName> {
+    [
+        DPLYR_SYNTAX,
+        BASE_R_SYNTAX,
+        DATA_TABLE_SYNTAX,
+        DUCKPLYR_SYNTAX,
+        SQL_SYNTAX,
+    ]
+    .iter()
+    .map(|name| CodeSyntaxName {
+        code_syntax_name: name.to_string(),
+    })
+    .collect()
 }
 
-/// Suggest a code syntax based on available options
+/// Suggest a code syntax based on the class of the data object
 ///
-/// Currently always returns "dplyr" as the preferred syntax
+/// data.table objects get data.table syntax, duckplyr frames get duckplyr
+/// syntax, and other objects get dplyr syntax.
+///
+/// Must be called on the R thread.
 ///
 /// # Returns
 ///
 /// A `CodeSyntaxName` with the suggested syntax
-pub fn suggest_code_syntax() -> CodeSyntaxName {
+pub fn suggest_code_syntax(x: SEXP) -> CodeSyntaxName {
+    let code_syntax_name = if r_inherits(x, "data.table") {
+        DATA_TABLE_SYNTAX
+    } else if r_inherits(x, "duckplyr_df") {
+        DUCKPLYR_SYNTAX
+    } else {
+        DPLYR_SYNTAX
+    };
+
     CodeSyntaxName {
-        code_syntax_name: "dplyr".into(),
+        code_syntax_name: code_syntax_name.into(),
     }
 }
 
@@ -309,7 +705,7 @@ fn row_filter_to_dplyr(filter: &RowFilter) -> Option<String> {
                         column_name
                     )),
                     TextSearchType::NotContains => Some(format!(
-                        "grepl({}, {}, fixed = TRUE, invert = TRUE)",
+                        "!grepl({}, {}, fixed = TRUE)",
                         escape_character_constant(&search.term),
                         column_name
                     )),
@@ -437,6 +833,160 @@ fn escape_character_constant(s: &str) -> String {
     format!("\"{}\"", escaped)
 }
 
+/// Converts a single row filter to a SQL condition
+fn row_filter_to_sql(filter: &RowFilter) -> Option<String> {
+    let column_name = quote_sql_identifier(&filter.column_schema.column_name);
+    let display_type = &filter.column_schema.type_display;
+
+    match filter.filter_type {
+        RowFilterType::Compare => {
+            if let Some(RowFilterParams::Comparison(comparison)) = &filter.params {
+                let op = match comparison.op {
+                    FilterComparisonOp::Eq => "=",
+                    FilterComparisonOp::NotEq => "<>",
+                    FilterComparisonOp::Lt => "<",
+                    FilterComparisonOp::LtEq => "<=",
+                    FilterComparisonOp::Gt => ">",
+                    FilterComparisonOp::GtEq => ">=",
+                };
+                let value = format_value_for_sql(display_type, &comparison.value);
+                Some(format!("{} {} {}", column_name, op, value))
+            } else {
+                None
+            }
+        },
+        RowFilterType::Between | RowFilterType::NotBetween => {
+            if let Some(RowFilterParams::Between(between)) = &filter.params {
+                let not = match filter.filter_type {
+                    RowFilterType::NotBetween => "NOT ",
+                    _ => "",
+                };
+                Some(format!(
+                    "{} {}BETWEEN {} AND {}",
+                    column_name,
+                    not,
+                    format_value_for_sql(display_type, &between.left_value),
+                    format_value_for_sql(display_type, &between.right_value)
+                ))
+            } else {
+                None
+            }
+        },
+        RowFilterType::IsNull => Some(format!("{} IS NULL", column_name)),
+        RowFilterType::NotNull => Some(format!("{} IS NOT NULL", column_name)),
+        RowFilterType::IsTrue => Some(format!("{} = TRUE", column_name)),
+        RowFilterType::IsFalse => Some(format!("{} = FALSE", column_name)),
+        RowFilterType::IsEmpty => Some(format!("{} = ''", column_name)),
+        RowFilterType::NotEmpty => Some(format!("{} <> ''", column_name)),
+        RowFilterType::Search => {
+            if let Some(RowFilterParams::TextSearch(search)) = &filter.params {
+                let like = if search.case_sensitive {
+                    "LIKE"
+                } else {
+                    "ILIKE"
+                };
+                let term = escape_sql_like_pattern(&search.term);
+                let escape = if term != search.term {
+                    " ESCAPE '\\'"
+                } else {
+                    ""
+                };
+                let like_pattern = |not: &str, prefix: &str, suffix: &str| {
+                    let pattern = escape_sql_string(&format!("{}{}{}", prefix, term, suffix));
+                    format!("{} {}{} {}{}", column_name, not, like, pattern, escape)
+                };
+
+                match search.search_type {
+                    TextSearchType::Contains => Some(like_pattern("", "%", "%")),
+                    TextSearchType::NotContains => Some(like_pattern("NOT ", "%", "%")),
+                    TextSearchType::StartsWith => Some(like_pattern("", "", "%")),
+                    TextSearchType::EndsWith => Some(like_pattern("", "%", "")),
+                    TextSearchType::RegexMatch => {
+                        let options = if search.case_sensitive { "" } else { ", 'i'" };
+                        Some(format!(
+                            "regexp_matches({}, {}{})",
+                            column_name,
+                            escape_sql_string(&search.term),
+                            options
+                        ))
+                    },
+                }
+            } else {
+                None
+            }
+        },
+        RowFilterType::SetMembership => {
+            if let Some(RowFilterParams::SetMembership(set)) = &filter.params {
+                let values = set
+                    .values
+                    .iter()
+                    .map(|v| format_value_for_sql(display_type, v))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                if set.inclusive {
+                    Some(format!("{} IN ({})", column_name, values))
+                } else {
+                    Some(format!("{} NOT IN ({})", column_name, values))
+                }
+            } else {
+                None
+            }
+        },
+    }
+}
+
+/// Formats a value for use in SQL based on the column type
+fn format_value_for_sql(display_type: &ColumnDisplayType, value: &str) -> String {
+    match display_type {
+        ColumnDisplayType::Boolean => match value.to_lowercase().as_str() {
+            "true" => "TRUE".to_string(),
+            "false" => "FALSE".to_string(),
+            _ => escape_sql_string(value),
+        },
+        ColumnDisplayType::Integer |
+        ColumnDisplayType::Floating |
+        ColumnDisplayType::Decimal => value.to_string(),
+        _ => escape_sql_string(value),
+    }
+}
+
+/// Quotes a SQL identifier, unless it's a plain name that isn't a keyword
+fn quote_sql_identifier(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "all", "and", "as", "asc", "between", "by", "case", "desc", "else", "end", "false", "from",
+        "group", "having", "in", "is", "join", "like", "limit", "not", "null", "on", "or", "order",
+        "select", "table", "then", "true", "when", "where",
+    ];
+
+    let mut chars = name.chars();
+    let is_plain = match chars.next() {
+        Some(first) => {
+            (first.is_ascii_alphabetic() || first == '_') &&
+                chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        },
+        None => false,
+    };
+
+    if is_plain && !KEYWORDS.contains(&name.to_lowercase().as_str()) {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+/// Quotes a SQL string literal
+fn escape_sql_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+/// Escapes the wildcards of a `LIKE` pattern, with `\` as escape character
+fn escape_sql_like_pattern(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
 #[cfg(test)]
 mod tests {
     use amalthea::comm::data_explorer_comm::ColumnSchema;
@@ -720,7 +1270,7 @@ mod tests {
         let result = filter_handler.convert_filter(&not_contains_filter);
         assert_eq!(
             result,
-            Some("grepl(\"john\", name, fixed = TRUE, invert = TRUE)".to_string())
+            Some("!grepl(\"john\", name, fixed = TRUE)".to_string())
         );
 
         // Test starts with
@@ -775,7 +1325,7 @@ mod tests {
             },
         };
 
-        let result = convert_to_code(params, Some("my_data"), &[]);
+        let result = convert_to_code(params, Some("my_data"), &[], None);
 
         assert_eq!(result.converted_code, vec![
             "library(dplyr)".to_string(),
@@ -800,7 +1350,7 @@ mod tests {
             ascending: false,
         }];
 
-        let result = convert_to_code(params, Some("my_data"), &resolved_sorts);
+        let result = convert_to_code(params, Some("my_data"), &resolved_sorts, None);
 
         assert_eq!(result.converted_code, vec![
             "library(dplyr)".to_string(),
@@ -844,7 +1394,7 @@ mod tests {
             },
         ];
 
-        let result = convert_to_code(params, Some("my_data"), &resolved_sorts);
+        let result = convert_to_code(params, Some("my_data"), &resolved_sorts, None);
 
         assert_eq!(result.converted_code, vec![
             "library(dplyr)".to_string(),
@@ -864,7 +1414,7 @@ mod tests {
             },
         };
 
-        let result = convert_to_code(params, None, &[]);
+        let result = convert_to_code(params, None, &[], None);
 
         assert_eq!(result.converted_code, vec![
             "library(dplyr)".to_string(),
@@ -873,6 +1423,34 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_complete_conversion_column_selection() {
+        let params = ConvertToCodeParams {
+            column_filters: vec![],
+            row_filters: vec![comparison_filter(
+                "price",
+                FilterComparisonOp::Gt,
+                "100",
+                ColumnDisplayType::Floating,
+            )],
+            sort_keys: vec![],
+            code_syntax_name: amalthea::comm::data_explorer_comm::CodeSyntaxName {
+                code_syntax_name: "dplyr".to_string(),
+            },
+        };
+
+        let columns = vec!["name".to_string(), "unit price".to_string()];
+        let result = convert_to_code(params, Some("my_data"), &[], Some(&columns));
+
+        // Columns are selected after filtering, which may use other columns
+        assert_eq!(result.converted_code, vec![
+            "library(dplyr)".to_string(),
+            "".to_string(),
+            "my_data |>\n  filter(\n    price > 100\n  ) |>\n  select(name, `unit price`)"
+                .to_string(),
+        ]);
+    }
+
     #[test]
     fn test_needs_backticks_syntactic_names() {
         // Valid syntactic names don't need backticks
@@ -995,7 +1573,7 @@ mod tests {
             ascending: false,
         }];
 
-        let result = convert_to_code(params, Some("my_data"), &resolved_sorts);
+        let result = convert_to_code(params, Some("my_data"), &resolved_sorts, None);
 
         assert_eq!(result.converted_code, vec![
             "library(dplyr)".to_string(),
@@ -1004,6 +1582,207 @@ mod tests {
                 .to_string(),
         ]);
     }
+
+    fn code_params(code_syntax_name: &str, row_filters: Vec<RowFilter>) -> ConvertToCodeParams {
+        ConvertToCodeParams {
+            column_filters: vec![],
+            row_filters,
+            sort_keys: vec![],
+            code_syntax_name: amalthea::comm::data_explorer_comm::CodeSyntaxName {
+                code_syntax_name: code_syntax_name.to_string(),
+            },
+        }
+    }
+
+    fn sales_sort_keys() -> Vec<ResolvedSortKey> {
+        vec![
+            ResolvedSortKey {
+                column_name: "sales".to_string(),
+                ascending: false,
+            },
+            ResolvedSortKey {
+                column_name: "region".to_string(),
+                ascending: true,
+            },
+        ]
+    }
+
+    fn price_and_category_filters() -> Vec<RowFilter> {
+        vec![
+            comparison_filter(
+                "price",
+                FilterComparisonOp::Gt,
+                "100",
+                ColumnDisplayType::Floating,
+            ),
+            comparison_filter(
+                "category",
+                FilterComparisonOp::Eq,
+                "Electronics",
+                ColumnDisplayType::String,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_base_r_conversion() {
+        let params = code_params(BASE_R_SYNTAX, price_and_category_filters());
+        let columns = vec!["name".to_string(), "unit price".to_string()];
+
+        let result = convert_to_code(params, Some("my_data"), &sales_sort_keys(), Some(&columns));
+
+        // Rows are sorted first and then filtered, which keeps their order
+        assert_eq!(result.converted_code, vec![
+            "my_data[order(my_data[[\"sales\"]], my_data[[\"region\"]], decreasing = c(TRUE, FALSE), method = \"radix\"), , drop = FALSE] |>\n  subset(price > 100 & category == \"Electronics\", select = c(name, `unit price`))".to_string(),
+        ]);
+
+        let params = code_params(BASE_R_SYNTAX, vec![]);
+        let result = convert_to_code(params, Some("my_data"), &[], None);
+        assert_eq!(result.converted_code, vec!["my_data".to_string()]);
+    }
+
+    #[test]
+    fn test_base_r_conversion_combines_alternatives() {
+        let mut not_between = between_filter("price", "100", "500", ColumnDisplayType::Floating);
+        not_between.filter_type = RowFilterType::NotBetween;
+        let filters = vec![not_between, price_and_category_filters().pop().unwrap()];
+
+        let params = code_params(BASE_R_SYNTAX, filters);
+        let result = convert_to_code(params, Some("my_data"), &[], None);
+
+        assert_eq!(result.converted_code, vec![
+            "my_data |>\n  subset((price < 100 | price > 500) & category == \"Electronics\")"
+                .to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_base_r_conversion_combines_negations() {
+        let not_contains = text_search_filter("name", "a", TextSearchType::NotContains, true);
+        let filters = vec![not_contains, price_and_category_filters().pop().unwrap()];
+
+        let params = code_params(BASE_R_SYNTAX, filters);
+        let result = convert_to_code(params, Some("my_data"), &[], None);
+
+        assert_eq!(result.converted_code, vec![
+            "my_data |>\n  subset((!grepl(\"a\", name, fixed = TRUE)) & category == \"Electronics\")"
+                .to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_data_table_conversion() {
+        let params = code_params(DATA_TABLE_SYNTAX, price_and_category_filters());
+        let columns = vec!["name".to_string(), "unit price".to_string()];
+
+        let result = convert_to_code(params, Some("my_data"), &sales_sort_keys(), Some(&columns));
+
+        assert_eq!(result.converted_code, vec![
+            "library(data.table)".to_string(),
+            "".to_string(),
+            "as.data.table(my_data)[price > 100 & category == \"Electronics\"][order(-sales, region)][, c(\"name\", \"unit price\")]".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_data_table_is_true() {
+        // A single column name in `i` isn't evaluated as a column
+        let filter = RowFilter {
+            filter_id: "test".to_string(),
+            column_schema: test_column_schema("active", ColumnDisplayType::Boolean),
+            filter_type: RowFilterType::IsTrue,
+            condition: RowFilterCondition::And,
+            params: None,
+            is_valid: Some(true),
+            error_message: None,
+        };
+
+        let result = convert_to_code(
+            code_params(DATA_TABLE_SYNTAX, vec![filter]),
+            Some("my_data"),
+            &[],
+            None,
+        );
+
+        assert_eq!(
+            result.converted_code[2],
+            "as.data.table(my_data)[active == TRUE]".to_string()
+        );
+    }
+
+    #[test]
+    fn test_duckplyr_conversion() {
+        let params = code_params(DUCKPLYR_SYNTAX, price_and_category_filters());
+
+        let result = convert_to_code(params, Some("my_data"), &sales_sort_keys(), None);
+
+        assert_eq!(result.converted_code, vec![
+            "library(duckplyr)".to_string(),
+            "".to_string(),
+            "my_data |>\n  as_duckdb_tibble() |>\n  filter(\n    price > 100,\n    category == \"Electronics\"\n  ) |>\n  arrange(desc(sales), region)".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_sql_conversion() {
+        let mut filters = price_and_category_filters();
+        filters.push(text_search_filter(
+            "name",
+            "50%",
+            TextSearchType::Contains,
+            false,
+        ));
+        let params = code_params(SQL_SYNTAX, filters);
+        let columns = vec!["name".to_string(), "unit price".to_string()];
+
+        let result = convert_to_code(params, Some("my_data"), &sales_sort_keys(), Some(&columns));
+
+        assert_eq!(result.converted_code, vec![
+            "SELECT name, \"unit price\"\nFROM my_data\nWHERE price > 100\n  AND category = 'Electronics'\n  AND name ILIKE '%50\\%%' ESCAPE '\\'\nORDER BY sales DESC, region".to_string(),
+        ]);
+
+        let params = code_params(SQL_SYNTAX, vec![between_filter(
+            "price",
+            "1",
+            "2",
+            ColumnDisplayType::Integer,
+        )]);
+        let result = convert_to_code(params, Some("my data"), &[], None);
+        assert_eq!(result.converted_code, vec![
+            "SELECT *\nFROM \"my data\"\nWHERE price BETWEEN 1 AND 2".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_quote_sql_identifier() {
+        assert_eq!(quote_sql_identifier("price"), "price");
+        assert_eq!(quote_sql_identifier("_Price2"), "_Price2");
+        assert_eq!(quote_sql_identifier("Sepal.Length"), "\"Sepal.Length\"");
+        assert_eq!(quote_sql_identifier("order"), "\"order\"");
+        assert_eq!(quote_sql_identifier("2025"), "\"2025\"");
+        assert_eq!(quote_sql_identifier("a\"b"), "\"a\"\"b\"");
+        assert_eq!(quote_sql_identifier(""), "\"\"");
+    }
+
+    #[test]
+    fn test_unknown_code_syntax_uses_dplyr() {
+        let result = convert_to_code(code_params("unknown", vec![]), Some("my_data"), &[], None);
+
+        assert_eq!(result.converted_code, vec![
+            "library(dplyr)".to_string(),
+            "".to_string(),
+            "my_data".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_code_syntaxes() {
+        let syntaxes: Vec<String> = code_syntaxes()
+            .into_iter()
+            .map(|syntax| syntax.code_syntax_name)
+            .collect();
+        assert_eq!(syntaxes, vec!["dplyr", "base R", "data.table", "duckplyr", "SQL"]);
+    }
 }
 
 #[cfg(test)]
@@ -1101,7 +1880,7 @@ mod execution_tests {
             },
         };
 
-        let generated_code = convert_to_code(params, Some("test_people"), &[]);
+        let generated_code = convert_to_code(params, Some("test_people"), &[], None);
 
         execute_generated_code_and_assign_result(generated_code.converted_code, "filtered_people")
             .expect("Failed to execute generated code");
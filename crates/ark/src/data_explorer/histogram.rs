@@ -121,11 +121,22 @@ pub fn profile_frequency_table(
     params: &ColumnFrequencyTableParams,
     format_options: &FormatOptions,
 ) -> anyhow::Result<ColumnFrequencyTable> {
-    let results: HashMap<String, RObject> = RFunction::from("profile_frequency_table")
+    let results = RFunction::from("profile_frequency_table")
         .add(column)
         .add(params.limit as i32)
-        .call_in(ARK_ENVS.positron_ns)?
-        .try_into()?;
+        .call_in(ARK_ENVS.positron_ns)?;
+
+    frequency_table_from_results(results, params, format_options)
+}
+
+/// Frequency table from the results of `profile_frequency_table()`, or of an
+/// R function returning the same values
+pub(crate) fn frequency_table_from_results(
+    results: RObject,
+    params: &ColumnFrequencyTableParams,
+    format_options: &FormatOptions,
+) -> anyhow::Result<ColumnFrequencyTable> {
+    let results: HashMap<String, RObject> = results.try_into()?;
 
     let values = unwrap!(results.get("values"), None => {
         return Err(anyhow!("Something went wrong when computing `values`"));
@@ -13,4 +13,5 @@ pub mod histogram;
 pub mod r_data_explorer;
 pub mod summary_stats;
 pub mod table;
+pub mod table_backend;
 pub mod utils;
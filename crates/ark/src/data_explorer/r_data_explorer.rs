@@ -7,6 +7,8 @@
 
 use std::cmp;
 use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
 
 use amalthea::comm::comm_channel::CommMsg;
 use amalthea::comm::data_explorer_comm::ArraySelection;
@@ -40,6 +42,8 @@ use amalthea::comm::data_explorer_comm::GetColumnProfilesFeatures;
 use amalthea::comm::data_explorer_comm::GetColumnProfilesParams;
 use amalthea::comm::data_explorer_comm::GetDataValuesParams;
 use amalthea::comm::data_explorer_comm::GetSchemaParams;
+use amalthea::comm::data_explorer_comm::OpenDatasetParams;
+use amalthea::comm::data_explorer_comm::OpenDatasetResult;
 use amalthea::comm::data_explorer_comm::RowFilter;
 use amalthea::comm::data_explorer_comm::RowFilterParams;
 use amalthea::comm::data_explorer_comm::RowFilterType;
@@ -49,6 +53,7 @@ use amalthea::comm::data_explorer_comm::SearchSchemaParams;
 use amalthea::comm::data_explorer_comm::SearchSchemaResult;
 use amalthea::comm::data_explorer_comm::SearchSchemaSortOrder;
 use amalthea::comm::data_explorer_comm::SetColumnFiltersFeatures;
+use amalthea::comm::data_explorer_comm::SetColumnFiltersParams;
 use amalthea::comm::data_explorer_comm::SetRowFiltersFeatures;
 use amalthea::comm::data_explorer_comm::SetRowFiltersParams;
 use amalthea::comm::data_explorer_comm::SetSortColumnsFeatures;
@@ -71,6 +76,7 @@ use anyhow::Context;
 use crossbeam::channel::unbounded;
 use crossbeam::channel::Sender;
 use crossbeam::select;
+use harp::environment::Environment;
 use harp::exec::RFunction;
 use harp::exec::RFunctionExt;
 use harp::object::RObject;
@@ -83,6 +89,7 @@ use harp::ColumnNames;
 use harp::TableKind;
 use itertools::Itertools;
 use libr::*;
+use regex::RegexBuilder;
 use serde::Deserialize;
 use serde::Serialize;
 use stdext::local;
@@ -90,6 +97,7 @@ use stdext::result::ResultExt;
 use stdext::spawn;
 use stdext::unwrap;
 use tracing::Instrument;
+use url::Url;
 use uuid::Uuid;
 
 use crate::console::Console;
@@ -100,6 +108,9 @@ use crate::data_explorer::export_selection;
 use crate::data_explorer::format;
 use crate::data_explorer::format::format_string;
 use crate::data_explorer::table::Table;
+use crate::data_explorer::table_backend::table_backend;
+use crate::data_explorer::table_backend::LazyView;
+use crate::data_explorer::table_backend::TableBackend;
 use crate::data_explorer::utils::display_type;
 use crate::data_explorer::utils::tbl_subset_with_view_indices;
 use crate::lsp::events::EVENTS;
@@ -150,6 +161,11 @@ pub struct RDataExplorer {
     /// A cache containing the current set of column filters
     col_filters: Vec<ColumnFilter>,
 
+    /// The set of column indices that pass the column filters, if any column
+    /// filters are applied. They're sorted in ascending order. The schema
+    /// seen by the frontend only includes these columns.
+    filtered_columns: Option<Vec<i64>>,
+
     /// The set of sorted row indices, if any sorts are applied. This always
     /// includes all row indices.
     sorted_indices: Option<Vec<i32>>,
@@ -164,6 +180,15 @@ pub struct RDataExplorer {
     /// data viewer.
     view_indices: Option<Vec<i32>>,
 
+    /// The backend of lazy tables (e.g. database or Arrow tables), which are
+    /// never collected in full. Sorts and filters are then evaluated by the
+    /// backend, and the sorted and filtered indices are unused.
+    backend: Option<&'static dyn TableBackend>,
+
+    /// For lazy tables with row filters, the number of rows that pass the
+    /// filters.
+    lazy_num_rows: Option<i64>,
+
     /// The communication socket for the data viewer.
     comm: CommSocket,
 
@@ -205,10 +230,13 @@ impl RDataExplorer {
 
         spawn!(format!("ark-data-viewer-{}-{}", title, id), move || {
             // Get the initial set of column schemas for the data object
-            let shape = r_task(|| Self::r_get_shape(table.get()?));
+            let shape = r_task(|| {
+                let data = table.get()?;
+                Ok::<_, anyhow::Error>((table_backend(data.sexp), Self::r_get_shape(data)?))
+            });
             match shape {
                 // shape the columns; start the data viewer
-                Ok(shape) => {
+                Ok((backend, shape)) => {
                     // Create the initial state for the data viewer
                     let viewer = Self {
                         title,
@@ -221,6 +249,9 @@ impl RDataExplorer {
                         sort_keys: vec![],
                         row_filters: vec![],
                         col_filters: vec![],
+                        filtered_columns: None,
+                        backend,
+                        lazy_num_rows: None,
                         comm,
                         comm_event_tx,
                     };
@@ -248,6 +279,94 @@ impl RDataExplorer {
         Ok(id)
     }
 
+    /// Read a data file.
+    ///
+    /// CSV, TSV, RDS, Feather (Arrow IPC) and Parquet files are supported.
+    /// They are read with whichever of base R, readr, arrow or nanoparquet is
+    /// installed. The data is titled with the file name, and bound in a new
+    /// environment under a syntactic name derived from the file name (e.g.
+    /// `sales.2024` for `sales 2024.csv`), so that code converted from the
+    /// data viewer refers to a valid R object name.
+    ///
+    /// - `uri`: A `file://` URI or a file path.
+    ///
+    /// Must be called on the R thread. Returns the title, the data, and its
+    /// binding.
+    fn read_dataset(uri: &str) -> anyhow::Result<(String, RObject, DataObjectEnvInfo)> {
+        let path = match Url::parse(uri) {
+            Ok(url) if url.scheme() == "file" => url
+                .to_file_path()
+                .map_err(|_| anyhow!("Invalid file URI: '{uri}'"))?,
+            // Windows paths like `C:\data.csv` parse as URLs with a `c:` scheme
+            _ => PathBuf::from(uri),
+        };
+
+        if !path.is_file() {
+            return Err(anyhow!("Can't find dataset '{}'", path.display()));
+        }
+
+        let path = path.to_string_lossy().to_string();
+
+        let data = RFunction::new("", "read_dataset")
+            .add(path.as_str())
+            .call_in(ARK_ENVS.positron_ns)?;
+
+        let name: String = RFunction::new("", "dataset_name")
+            .add(path.as_str())
+            .call_in(ARK_ENVS.positron_ns)?
+            .try_into()?;
+
+        let title = match Path::new(&path).file_name() {
+            Some(file_name) => file_name.to_string_lossy().to_string(),
+            None => path.clone(),
+        };
+
+        let env = Environment::new_empty();
+        env.bind(name.as_str().into(), &data);
+
+        let binding = DataObjectEnvInfo {
+            name,
+            env: RThreadSafe::new(RObject::from(env)),
+        };
+
+        Ok((title, data, binding))
+    }
+
+    /// Replace the data of this data viewer with the contents of a data file.
+    /// Sorts and filters are cleared and the frontend is notified with a
+    /// schema update.
+    fn load_dataset(&mut self, uri: &str) -> anyhow::Result<()> {
+        let (title, binding, backend, shape) = r_task(|| -> anyhow::Result<_> {
+            let (title, data, binding) = Self::read_dataset(uri)?;
+            let backend = table_backend(data.sexp);
+            let shape = Self::r_get_shape(data.clone())?;
+            self.table.set(RThreadSafe::new(data));
+            Ok((title, binding, backend, shape))
+        })?;
+
+        self.title = title;
+        self.binding = Some(binding);
+        self.backend = backend;
+        self.shape = shape;
+
+        // Sorts and filters of the previous data don't apply
+        self.sort_keys.clear();
+        self.row_filters.clear();
+        self.col_filters.clear();
+        self.filtered_columns = None;
+        self.sorted_indices = None;
+        self.filtered_indices = None;
+        self.view_indices = None;
+        self.lazy_num_rows = None;
+
+        let event = DataExplorerFrontendEvent::SchemaUpdate;
+        self.comm
+            .outgoing_tx
+            .send(CommMsg::Data(serde_json::to_value(event)?))?;
+
+        Ok(())
+    }
+
     pub fn execution_thread(mut self) {
         // Register a handler for environment change events. We do this before sending
         // `CommManagerEvent::Opened` to ensure we can't miss any events (#781).
@@ -383,8 +502,14 @@ impl RDataExplorer {
         //
         // Consider: there may be a cheaper way to test the schema for changes
         // than regenerating it, but it'd be a lot more complicated.
-        let new_shape = match r_task(|| Self::r_get_shape(self.table.get()?.clone())) {
-            Ok(shape) => shape,
+        let new_shape = match r_task(|| {
+            let data = self.table.get()?;
+            Ok::<_, anyhow::Error>((table_backend(data.sexp), Self::r_get_shape(data)?))
+        }) {
+            Ok((backend, shape)) => {
+                self.backend = backend;
+                shape
+            },
             Err(_) => {
                 // The most likely cause of this error is that the object is no
                 // longer something with a usable shape -- it's been removed or
@@ -404,6 +529,9 @@ impl RDataExplorer {
             // Update row filters to reflect the new schema
             self.row_filters_update()?;
 
+            // Reapply column filters to the new columns
+            self.filtered_columns = self.col_filters_compute();
+
             // Clear precomputed indices
             self.sorted_indices = None;
             self.filtered_indices = None;
@@ -425,8 +553,12 @@ impl RDataExplorer {
 
             // Columns didn't change, but the data has. If there are sort
             // keys, we need to sort the rows again to reflect the new data.
+            // Lazy tables are sorted by their backend.
             if self.sort_keys.len() > 0 {
-                self.sorted_indices = Some(r_task(|| self.r_sort_rows())?);
+                self.sorted_indices = match self.backend {
+                    Some(_) => None,
+                    None => Some(r_task(|| self.r_sort_rows())?),
+                };
             }
 
             // Recompute and apply filters and sorts.
@@ -497,9 +629,12 @@ impl RDataExplorer {
                 self.sort_keys = keys.clone();
 
                 // If there are no sort keys, clear the precomputed sorted
-                // indices; otherwise, sort the rows and save the result
+                // indices; otherwise, sort the rows and save the result.
+                // Lazy tables are sorted by their backend when collecting
+                // rows.
                 self.sorted_indices = match keys.len() {
                     0 => None,
+                    _ if self.backend.is_some() => None,
                     _ => Some(r_task(|| self.r_sort_rows())?),
                 };
 
@@ -522,10 +657,7 @@ impl RDataExplorer {
 
                 Ok(DataExplorerBackendReply::SetRowFiltersReply({
                     FilterResult {
-                        selected_num_rows: match self.filtered_indices {
-                            Some(ref indices) => indices.len() as i64,
-                            None => self.shape.num_rows as i64,
-                        },
+                        selected_num_rows: self.num_filtered_rows(),
                         had_errors,
                     }
                 }))
@@ -542,14 +674,26 @@ impl RDataExplorer {
 
             DataExplorerBackendRequest::GetState => r_task(|| self.r_get_state()),
 
-            DataExplorerBackendRequest::OpenDataset(_) => {
-                return Err(anyhow!("Data Explorer: Not yet supported"));
+            DataExplorerBackendRequest::OpenDataset(OpenDatasetParams { uri }) => {
+                // The dataset replaces the data of this data viewer. Failures
+                // are reported to the frontend as part of the result.
+                let result = self.load_dataset(&uri);
+
+                Ok(DataExplorerBackendReply::OpenDatasetReply(
+                    OpenDatasetResult {
+                        error_message: result.err().map(|err| format!("{err:#}")),
+                    },
+                ))
             },
 
             DataExplorerBackendRequest::SearchSchema(params) => self.search_schema(params),
 
-            DataExplorerBackendRequest::SetColumnFilters(_) => {
-                return Err(anyhow!("Data Explorer: Not yet supported"));
+            DataExplorerBackendRequest::SetColumnFilters(SetColumnFiltersParams { filters }) => {
+                // Save the new column filters and compute the filtered columns
+                self.col_filters = filters;
+                self.filtered_columns = self.col_filters_compute();
+
+                Ok(DataExplorerBackendReply::SetColumnFiltersReply())
             },
 
             DataExplorerBackendRequest::GetRowLabels(req) => {
@@ -562,6 +706,9 @@ impl RDataExplorer {
                 ))
             },
 
+            DataExplorerBackendRequest::ExportDataSelection(_) if self.backend.is_some() => {
+                bail!("Exporting data selections isn't supported for lazy tables")
+            },
             DataExplorerBackendRequest::ExportDataSelection(ExportDataSelectionParams {
                 selection,
                 format,
@@ -574,9 +721,10 @@ impl RDataExplorer {
             DataExplorerBackendRequest::ConvertToCode(params) => Ok(
                 DataExplorerBackendReply::ConvertToCodeReply(self.convert_to_code(params)),
             ),
-            DataExplorerBackendRequest::SuggestCodeSyntax => Ok(
-                DataExplorerBackendReply::SuggestCodeSyntaxReply(self.suggest_code_syntax()),
-            ),
+            DataExplorerBackendRequest::SuggestCodeSyntax => {
+                let syntax = r_task(|| self.r_suggest_code_syntax())?;
+                Ok(DataExplorerBackendReply::SuggestCodeSyntaxReply(syntax))
+            },
         }
     }
 }
@@ -584,6 +732,15 @@ impl RDataExplorer {
 // Methods that must be run on the main R thread
 impl RDataExplorer {
     pub(crate) fn r_get_shape(table: RObject) -> anyhow::Result<DataObjectShape> {
+        // The schema of lazy tables is the schema of their first rows. Their
+        // rows are counted by the backend.
+        if let Some(backend) = table_backend(table.sexp) {
+            let mut shape = Self::r_get_shape(backend.ptype(&table)?)?;
+            let num_rows = backend.count(&table, &LazyView::default())?;
+            shape.num_rows = num_rows.clamp(0, i32::MAX as i64) as i32;
+            return Ok(shape);
+        }
+
         unsafe {
             let table = table.clone();
 
@@ -676,6 +833,9 @@ impl RDataExplorer {
             indices: self.filtered_indices.clone(),
             kind: self.shape.kind,
             request: params,
+            backend: self.backend,
+            view: self.lazy_view().unsorted(),
+            columns: self.shape.columns.clone(),
         };
         let comm = self.comm.clone();
         r_task::spawn_idle(async move |_| {
@@ -772,10 +932,17 @@ impl RDataExplorer {
     // Implicitly updates the `row_filters` with validity status and error messages, if they
     // fail during the computation.
     fn row_filters_compute(&mut self) -> anyhow::Result<(Option<Vec<i32>>, Option<bool>)> {
+        self.lazy_num_rows = None;
+
         if self.row_filters.len() == 0 {
             return Ok((None, None));
         }
 
+        if let Some(backend) = self.backend {
+            let had_errors = self.lazy_row_filters_compute(backend)?;
+            return Ok((None, Some(had_errors)));
+        }
+
         let (indices, errors) = r_task(|| self.r_filter_rows())?;
         // this is called for the side-effect of updating the row_filters with validty status and
         // error messages
@@ -784,6 +951,74 @@ impl RDataExplorer {
         Ok((Some(indices), had_errors))
     }
 
+    // Count the rows of a lazy table that pass the `row_filters`, storing the
+    // result in `lazy_num_rows`.
+    //
+    // The filters are evaluated together by the backend, so when the query
+    // fails all of them are marked as failing.
+    fn lazy_row_filters_compute(
+        &mut self,
+        backend: &'static dyn TableBackend,
+    ) -> anyhow::Result<bool> {
+        let mut had_errors = false;
+        for filter in self.row_filters.iter_mut() {
+            let is_valid = Self::is_valid_filter(filter)?;
+            let is_translated = backend.supports_row_filter(filter);
+            filter.is_valid = Some(is_valid && is_translated);
+            filter.error_message = if !is_valid {
+                Some("Unsupported column type for filter".to_string())
+            } else if !is_translated {
+                Some(format!("Unsupported filter for {} tables", backend.name()))
+            } else {
+                None
+            };
+            had_errors |= filter.error_message.is_some();
+        }
+
+        let view = self.lazy_view().unsorted();
+        let count = r_task(|| backend.count(&self.table.get()?, &view));
+
+        match count {
+            Ok(count) => {
+                self.lazy_num_rows = Some(count);
+            },
+            Err(err) => {
+                for filter in self.row_filters.iter_mut() {
+                    if filter.is_valid == Some(true) {
+                        filter.is_valid = Some(false);
+                        filter.error_message = Some(format!("{err}"));
+                    }
+                }
+                had_errors = true;
+            },
+        }
+
+        Ok(had_errors)
+    }
+
+    /// The row filters and sort keys of the data viewer, for lazy table
+    /// backends
+    fn lazy_view(&self) -> LazyView {
+        match self.backend {
+            Some(backend) => LazyView::new(
+                backend,
+                &self.row_filters,
+                &self.sort_keys,
+                &self.shape.columns,
+            ),
+            None => LazyView::default(),
+        }
+    }
+
+    /// Number of rows that pass the row filters
+    fn num_filtered_rows(&self) -> i64 {
+        match (&self.filtered_indices, self.lazy_num_rows) {
+            (Some(indices), _) => indices.len() as i64,
+            (None, Some(num_rows)) => num_rows,
+            (None, None) => self.shape.num_rows as i64,
+        }
+    }
+
     // Check if a filter is valid by looking at it's type and the type of the column its applied to.
     // Uses logic similar to python side: https://github.com/posit-dev/positron/blob/aafe313a261fd133b9f4a9f87c92bb10dc9966ad/extensions/positron-python/python_files/positron/positron_ipykernel/data_explorer.py#L743-L744
     fn is_valid_filter(filter: &RowFilter) -> anyhow::Result<bool> {
@@ -903,24 +1138,18 @@ impl RDataExplorer {
     ) -> anyhow::Result<DataExplorerBackendReply> {
         let all_columns = &self.shape.columns;
 
-        // Apply column filters to find matching columns using iterator chaining
-        let mut matching_indices: Vec<i64> = all_columns
-            .iter()
-            .enumerate()
-            .filter_map(|(index, column)| {
-                let column_index = index as i64;
-
+        // Apply column filters to find matching columns using iterator chaining.
+        // Columns excluded by the active column filters are never matched.
+        let mut matching_indices: Vec<i64> = self
+            .visible_columns()
+            .into_iter()
+            .filter(|&column_index| {
                 // Check if column matches all filters
-                let matches = params
+                let column = &all_columns[column_index as usize];
+                params
                     .filters
                     .iter()
-                    .all(|filter| self.column_matches_filter(column, filter));
-
-                if matches {
-                    Some(column_index)
-                } else {
-                    None
-                }
+                    .all(|filter| Self::column_matches_filter(column, filter))
             })
             .collect();
 
@@ -968,8 +1197,50 @@ impl RDataExplorer {
         ))
     }
 
+    /// Compute the indices of the columns that pass all the column filters in
+    /// `self.col_filters`.
+    ///
+    /// Returns `None` when there are no column filters.
+    fn col_filters_compute(&self) -> Option<Vec<i64>> {
+        if self.col_filters.is_empty() {
+            return None;
+        }
+
+        let indices = self
+            .shape
+            .columns
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| {
+                self.col_filters
+                    .iter()
+                    .all(|filter| Self::column_matches_filter(column, filter))
+            })
+            .map(|(index, _)| index as i64)
+            .collect();
+
+        Some(indices)
+    }
+
+    /// The indices of the columns shown in the data viewer, i.e. the columns
+    /// that pass the column filters, or all columns if there are none.
+    fn visible_columns(&self) -> Vec<i64> {
+        match self.filtered_columns {
+            Some(ref indices) => indices.clone(),
+            None => (0..self.shape.columns.len() as i64).collect(),
+        }
+    }
+
+    /// Whether the column at `column_index` passes the column filters
+    fn is_visible_column(&self, column_index: i64) -> bool {
+        match self.filtered_columns {
+            Some(ref indices) => indices.binary_search(&column_index).is_ok(),
+            None => true,
+        }
+    }
+
     /// Check if a column matches a given column filter.
-    fn column_matches_filter(&self, column: &ColumnSchema, filter: &ColumnFilter) -> bool {
+    fn column_matches_filter(column: &ColumnSchema, filter: &ColumnFilter) -> bool {
         match filter.filter_type {
             ColumnFilterType::TextSearch => {
                 if let ColumnFilterParams::TextSearch(text_search) = &filter.params {
@@ -991,9 +1262,12 @@ impl RDataExplorer {
                         TextSearchType::StartsWith => column_name.starts_with(&search_term),
                         TextSearchType::EndsWith => column_name.ends_with(&search_term),
                         TextSearchType::RegexMatch => {
-                            // For regex matching, we use simple string matching as a fallback
-                            // A full regex implementation would require additional dependencies
-                            column_name.contains(&search_term)
+                            // Invalid patterns don't match any column
+                            RegexBuilder::new(&text_search.term)
+                                .case_insensitive(!text_search.case_sensitive)
+                                .build()
+                                .map(|regex| regex.is_match(&column.column_name))
+                                .unwrap_or(false)
                         },
                     }
                 } else {
@@ -1012,10 +1286,13 @@ impl RDataExplorer {
 
     /// Get the schema for a vector of columns in the data object.
     ///
-    /// - `column_indices`: The vector of columns in the data object.
+    /// - `column_indices`: The vector of columns in the data object, relative
+    ///   to the columns that pass the column filters.
     fn get_schema(&self, column_indices: Vec<i64>) -> anyhow::Result<DataExplorerBackendReply> {
+        let visible_columns = self.visible_columns();
+
         // Get the columns length. (Does Rust optimize loop invariants well?)
-        let columns_len = self.shape.columns.len();
+        let columns_len = visible_columns.len();
 
         // Gather the column schemas to return.
         let mut columns: Vec<ColumnSchema> = Vec::new();
@@ -1036,7 +1313,9 @@ impl RDataExplorer {
                 break;
             }
 
-            // Push the column schema.
+            // Push the column schema. Its `column_index` is the index of the
+            // column in the unfiltered table.
+            let column_index = visible_columns[column_index] as usize;
             columns.push(self.shape.columns[column_index].clone());
         }
 
@@ -1047,20 +1326,32 @@ impl RDataExplorer {
     }
 
     fn r_get_state(&self) -> anyhow::Result<DataExplorerBackendReply> {
-        let row_names = RFunction::new("base", "row.names")
-            .add(self.table.get()?)
-            .call_in(ARK_ENVS.positron_ns)?;
+        // Lazy tables have no row names
+        let has_row_labels = match self.backend {
+            Some(_) => false,
+            None => !RFunction::new("base", "row.names")
+                .add(self.table.get()?)
+                .call_in(ARK_ENVS.positron_ns)?
+                .is_null(),
+        };
+
+        // Histograms and exports require the values of the columns, which
+        // aren't collected for lazy tables
+        let collected_support_status = match self.backend {
+            Some(_) => SupportStatus::Unsupported,
+            None => SupportStatus::Supported,
+        };
 
         let state = BackendState {
             display_name: self.title.clone(),
             connected: Some(true),
             error_message: None,
             table_shape: TableShape {
-                num_rows: match self.filtered_indices {
+                num_rows: self.num_filtered_rows(),
+                num_columns: match self.filtered_columns {
                     Some(ref indices) => indices.len() as i64,
-                    None => self.shape.num_rows as i64,
+                    None => self.shape.columns.len() as i64,
                 },
-                num_columns: self.shape.columns.len() as i64,
             },
             table_unfiltered_shape: TableShape {
                 num_rows: self.shape.num_rows as i64,
@@ -1069,7 +1360,7 @@ impl RDataExplorer {
             row_filters: self.row_filters.clone(),
             column_filters: self.col_filters.clone(),
             sort_keys: self.sort_keys.clone(),
-            has_row_labels: !row_names.is_null(),
+            has_row_labels,
             supported_features: SupportedFeatures {
                 get_column_profiles: GetColumnProfilesFeatures {
                     support_status: SupportStatus::Supported,
@@ -1084,7 +1375,7 @@ impl RDataExplorer {
                         },
                         ColumnProfileTypeSupportStatus {
                             profile_type: ColumnProfileType::SmallHistogram,
-                            support_status: SupportStatus::Supported,
+                            support_status: collected_support_status,
                         },
                         ColumnProfileTypeSupportStatus {
                             profile_type: ColumnProfileType::SmallFrequencyTable,
@@ -1092,7 +1383,7 @@ impl RDataExplorer {
                         },
                         ColumnProfileTypeSupportStatus {
                             profile_type: ColumnProfileType::LargeHistogram,
-                            support_status: SupportStatus::Supported,
+                            support_status: collected_support_status,
                         },
                         ColumnProfileTypeSupportStatus {
                             profile_type: ColumnProfileType::LargeFrequencyTable,
@@ -1140,14 +1431,23 @@ impl RDataExplorer {
                     supports_conditions: SupportStatus::Unsupported,
                 },
                 set_column_filters: SetColumnFiltersFeatures {
-                    support_status: SupportStatus::Unsupported,
-                    supported_types: vec![],
+                    support_status: SupportStatus::Supported,
+                    supported_types: vec![
+                        ColumnFilterTypeSupportStatus {
+                            column_filter_type: ColumnFilterType::TextSearch,
+                            support_status: SupportStatus::Supported,
+                        },
+                        ColumnFilterTypeSupportStatus {
+                            column_filter_type: ColumnFilterType::MatchDataTypes,
+                            support_status: SupportStatus::Supported,
+                        },
+                    ],
                 },
                 set_sort_columns: SetSortColumnsFeatures {
                     support_status: SupportStatus::Supported,
                 },
                 export_data_selection: ExportDataSelectionFeatures {
-                    support_status: SupportStatus::Supported,
+                    support_status: collected_support_status,
                     supported_formats: vec![
                         ExportFormat::Csv,
                         ExportFormat::Tsv,
@@ -1156,9 +1456,7 @@ impl RDataExplorer {
                 },
                 convert_to_code: ConvertToCodeFeatures {
                     support_status: SupportStatus::Supported,
-                    code_syntaxes: Some(vec![CodeSyntaxName {
-                        code_syntax_name: "dplyr".into(),
-                    }]),
+                    code_syntaxes: Some(convert_to_code::code_syntaxes()),
                 },
             },
         };
@@ -1170,8 +1468,19 @@ impl RDataExplorer {
         columns: Vec<ColumnSelection>,
         format_options: FormatOptions,
     ) -> anyhow::Result<DataExplorerBackendReply> {
+        if let Some(backend) = self.backend {
+            return self.r_get_lazy_data_values(backend, columns, format_options);
+        }
+
         let mut column_data: Vec<Vec<ColumnValue>> = Vec::with_capacity(columns.len());
         for selection in columns {
+            // Column indices are relative to the unfiltered table. Columns
+            // excluded by the column filters have no values in the view.
+            if !self.is_visible_column(selection.column_index) {
+                column_data.push(vec![]);
+                continue;
+            }
+
             let tbl = tbl_subset_with_view_indices(
                 self.table.get()?.sexp,
                 &self.view_indices,
@@ -1192,11 +1501,106 @@ impl RDataExplorer {
         Ok(DataExplorerBackendReply::GetDataValuesReply(response))
     }
 
+    /// Like `r_get_data_values()`, for lazy tables. The rows spanned by the
+    /// selections are collected at once from the backend.
+    fn r_get_lazy_data_values(
+        &self,
+        backend: &'static dyn TableBackend,
+        columns: Vec<ColumnSelection>,
+        format_options: FormatOptions,
+    ) -> anyhow::Result<DataExplorerBackendReply> {
+        let selections: Vec<(i64, Vec<i64>)> = columns
+            .into_iter()
+            .map(|selection| {
+                let rows = self.get_row_selection_indices(selection.spec);
+                (selection.column_index, rows)
+            })
+            .collect();
+
+        // The names of the columns to collect, in order of first selection
+        let mut names: Vec<String> = vec![];
+        for (column_index, _) in &selections {
+            if !self.is_visible_column(*column_index) {
+                continue;
+            }
+            let Some(column) = self.shape.columns.get(*column_index as usize) else {
+                return Err(anyhow!("Column index out of range {column_index}"));
+            };
+            if !names.contains(&column.column_name) {
+                names.push(column.column_name.clone());
+            }
+        }
+
+        let rows = selections
+            .iter()
+            .filter(|(column_index, _)| self.is_visible_column(*column_index))
+            .flat_map(|(_, rows)| rows.iter().copied());
+        let (first_row, last_row) = match rows.minmax().into_option() {
+            Some(range) => range,
+            None => (0, -1),
+        };
+
+        let page = match names.is_empty() || last_row < first_row {
+            true => None,
+            false => Some(backend.collect(
+                &self.table.get()?,
+                &self.lazy_view(),
+                &names,
+                first_row,
+                last_row - first_row + 1,
+            )?),
+        };
+
+        let mut column_data: Vec<Vec<ColumnValue>> = Vec::with_capacity(selections.len());
+        for (column_index, rows) in selections {
+            // Columns excluded by the column filters have no values
+            if !self.is_visible_column(column_index) {
+                column_data.push(vec![]);
+                continue;
+            }
+            let Some(ref page) = page else {
+                column_data.push(vec![]);
+                continue;
+            };
+
+            let name = &self.shape.columns[column_index as usize].column_name;
+            let position = names.iter().position(|x| x == name).unwrap_or_default();
+            let column = tbl_get_column(page.sexp, position as i32, TableKind::Dataframe)?;
+
+            // 1-based positions of the selected rows in the page
+            let positions: Vec<i32> = rows
+                .iter()
+                .map(|row| (row - first_row + 1) as i32)
+                .collect();
+            let column = RFunction::from("col_filter_indices")
+                .add(column)
+                .add(RObject::try_from(&positions)?)
+                .call_in(ARK_ENVS.positron_ns)?;
+
+            column_data.push(format::format_column(column.sexp, &format_options));
+        }
+
+        Ok(DataExplorerBackendReply::GetDataValuesReply(TableData {
+            columns: column_data,
+        }))
+    }
+
     fn r_get_row_labels(
         &self,
         selection: ArraySelection,
         format_options: &FormatOptions,
     ) -> anyhow::Result<Vec<String>> {
+        // Lazy tables have no row names, their rows are labelled with their
+        // position in the view
+        if self.backend.is_some() {
+            let labels = self
+                .get_row_selection_indices(selection)
+                .into_iter()
+                .map(|row| (row + 1).to_string())
+                .collect();
+            return Ok(labels);
+        }
+
         let tbl = tbl_subset_with_view_indices(
             self.table.get()?.sexp,
             &self.view_indices,
@@ -1226,9 +1630,9 @@ impl RDataExplorer {
     // Also does some sanity checks to avoid OOB access.
     fn get_row_selection_indices(&self, selection: ArraySelection) -> Vec<i64> {
         let num_view_rows = match self.view_indices {
-            Some(ref indices) => indices.len() as i32,
-            None => self.shape.num_rows,
-        } as i64;
+            Some(ref indices) => indices.len() as i64,
+            None => self.num_filtered_rows(),
+        };
 
         // Returns the indices that will be collected
         match selection {
@@ -1262,9 +1666,19 @@ impl RDataExplorer {
 
     /// Suggest code syntax for code conversion
     ///
-    /// Returns the preferred code syntax for converting data explorer operations to code.
-    fn suggest_code_syntax(&self) -> CodeSyntaxName {
-        convert_to_code::suggest_code_syntax()
+    /// Returns the preferred code syntax for converting data explorer operations to code,
+    /// based on the class of the data object.
+    fn r_suggest_code_syntax(&self) -> anyhow::Result<CodeSyntaxName> {
+        Ok(convert_to_code::suggest_code_syntax(self.table.get()?.sexp))
+    }
+
+    /// Name of the database table of a dbplyr table, if any. Tables built
+    /// from queries don't have one.
+    fn r_remote_name(&self) -> anyhow::Result<Option<String>> {
+        let name = RFunction::new("", ".ps.lazy_remote_name")
+            .add(self.table.get()?.sexp)
+            .call_in(ARK_ENVS.positron_ns)?;
+        Ok(Option::<String>::try_from(name)?)
     }
 
     /// Convert the current data view state to code
@@ -1272,11 +1686,22 @@ impl RDataExplorer {
     /// Takes the current filters, sort keys, and other parameters and converts them
     /// to executable code that can reproduce the current data view.
     fn convert_to_code(&self, params: ConvertToCodeParams) -> ConvertedCode {
+        // SQL queries of database tables select from the remote table rather
+        // than from the R object
+        let is_sql = params.code_syntax_name.code_syntax_name == convert_to_code::SQL_SYNTAX;
+        let remote_name = if is_sql && self.backend.is_some() {
+            r_task(|| self.r_remote_name()).unwrap_or_else(|err| {
+                log::warn!("Can't get the name of the remote table: {err:?}");
+                None
+            })
+        } else {
+            None
+        };
+
         // Get object name if available, fallback to title
-        let object_name = self
-            .binding
-            .as_ref()
-            .map(|b| b.name.as_str())
+        let object_name = remote_name
+            .as_deref()
+            .or_else(|| self.binding.as_ref().map(|b| b.name.as_str()))
             .or_else(|| Some(self.title.as_str()));
 
         // Resolve column names for sort keys using the same pattern as r_sort_rows()
@@ -1298,8 +1723,32 @@ impl RDataExplorer {
             })
             .collect();
 
-        // Call the conversion function with resolved sort keys
-        convert_to_code::convert_to_code(params, object_name, &resolved_sort_keys)
+        // Resolve the names of the columns that pass the column filters
+        let resolved_columns: Option<Vec<String>> = if params.column_filters.is_empty() {
+            None
+        } else {
+            let columns = self
+                .shape
+                .columns
+                .iter()
+                .filter(|column| {
+                    params
+                        .column_filters
+                        .iter()
+                        .all(|filter| Self::column_matches_filter(column, filter))
+                })
+                .map(|column| column.column_name.clone())
+                .collect();
+            Some(columns)
+        };
+
+        // Call the conversion function with resolved sort keys and columns
+        convert_to_code::convert_to_code(
+            params,
+            object_name,
+            &resolved_sort_keys,
+            resolved_columns.as_deref(),
+        )
     }
 }
 
@@ -34,27 +34,50 @@ pub fn summary_stats(
     column: SEXP,
     display_type: ColumnDisplayType,
     format_options: &FormatOptions,
+) -> anyhow::Result<ColumnSummaryStats> {
+    summary_stats_with(
+        display_type,
+        format_options,
+        |function| call_summary_fn(function, column),
+        || summary_stats_timezone(column),
+    )
+}
+
+/// Summary stats computed by `compute`, which calls the R summary function
+/// of the given name, e.g. `summary_stats_number()`, or computes the same
+/// values in another way. Lazy tables compute them in their backend.
+pub(crate) fn summary_stats_with(
+    display_type: ColumnDisplayType,
+    format_options: &FormatOptions,
+    compute: impl Fn(&str) -> anyhow::Result<RObject>,
+    timezone: impl FnOnce() -> anyhow::Result<Option<String>>,
 ) -> anyhow::Result<ColumnSummaryStats> {
     let mut stats = empty_column_summary_stats();
     stats.type_display = display_type;
     match stats.type_display {
         ColumnDisplayType::Integer => {
-            stats.number_stats = Some(summary_stats_number(column, format_options, true)?);
+            let r_stats = compute("summary_stats_number")?;
+            stats.number_stats = Some(summary_stats_number(r_stats, format_options, true)?);
         },
-        ColumnDisplayType::Floating => {
-            stats.number_stats = Some(summary_stats_number(column, format_options, false)?);
-        },
-        ColumnDisplayType::Decimal => {
-            stats.number_stats = Some(summary_stats_number(column, format_options, false)?);
+        ColumnDisplayType::Floating | ColumnDisplayType::Decimal => {
+            let r_stats = compute("summary_stats_number")?;
+            stats.number_stats = Some(summary_stats_number(r_stats, format_options, false)?);
         },
         ColumnDisplayType::String => {
-            stats.string_stats = Some(summary_stats_string(column)?);
+            stats.string_stats = Some(summary_stats_string(compute("summary_stats_string")?)?);
         },
         ColumnDisplayType::Boolean => {
-            stats.boolean_stats = Some(summary_stats_boolean(column)?);
+            stats.boolean_stats = Some(summary_stats_boolean(compute("summary_stats_boolean")?)?);
+        },
+        ColumnDisplayType::Date => {
+            stats.date_stats = Some(summary_stats_date(compute("summary_stats_date")?)?)
+        },
+        ColumnDisplayType::Datetime => {
+            stats.datetime_stats = Some(summary_stats_datetime(
+                compute("summary_stats_date")?,
+                timezone()?,
+            )?)
         },
-        ColumnDisplayType::Date => stats.date_stats = Some(summary_stats_date(column)?),
-        ColumnDisplayType::Datetime => stats.datetime_stats = Some(summary_stats_datetime(column)?),
         _ => {
             return Err(anyhow::anyhow!("Unkown type"));
         },
@@ -63,12 +86,10 @@ pub fn summary_stats(
 }
 
 fn summary_stats_number(
-    column: SEXP,
+    r_stats: RObject,
     format_options: &FormatOptions,
     is_integer: bool,
 ) -> anyhow::Result<SummaryStatsNumber> {
-    let r_stats = call_summary_fn("summary_stats_number", column)?;
-
     let names = unsafe { CharacterVector::new_unchecked(r_names2(r_stats.sexp)) };
     let values = format_string(r_stats.sexp, format_options);
 
@@ -115,8 +136,7 @@ fn summary_stats_number(
     }
 }
 
-fn summary_stats_string(column: SEXP) -> anyhow::Result<SummaryStatsString> {
-    let stats = call_summary_fn("summary_stats_string", column)?;
+fn summary_stats_string(stats: RObject) -> anyhow::Result<SummaryStatsString> {
     let r_stats: HashMap<String, i32> = stats.try_into()?;
 
     Ok(SummaryStatsString {
@@ -125,8 +145,7 @@ fn summary_stats_string(column: SEXP) -> anyhow::Result<SummaryStatsString> {
     })
 }
 
-fn summary_stats_boolean(column: SEXP) -> anyhow::Result<SummaryStatsBoolean> {
-    let stats = call_summary_fn("summary_stats_boolean", column)?;
+fn summary_stats_boolean(stats: RObject) -> anyhow::Result<SummaryStatsBoolean> {
     let r_stats: HashMap<String, i32> = stats.try_into()?;
 
     Ok(SummaryStatsBoolean {
@@ -135,9 +154,8 @@ fn summary_stats_boolean(column: SEXP) -> anyhow::Result<SummaryStatsBoolean> {
     })
 }
 
-fn summary_stats_date(column: SEXP) -> anyhow::Result<SummaryStatsDate> {
-    let r_stats: HashMap<String, RObject> =
-        call_summary_fn("summary_stats_date", column)?.try_into()?;
+fn summary_stats_date(stats: RObject) -> anyhow::Result<SummaryStatsDate> {
+    let r_stats: HashMap<String, RObject> = stats.try_into()?;
 
     let num_unique: Option<i64> = get_stat::<i32, RObject>(&r_stats, "num_unique")
         .ok()
@@ -152,21 +170,18 @@ fn summary_stats_date(column: SEXP) -> anyhow::Result<SummaryStatsDate> {
     })
 }
 
-fn summary_stats_datetime(column: SEXP) -> anyhow::Result<SummaryStatsDatetime> {
+fn summary_stats_datetime(
+    stats: RObject,
+    timezone: Option<String>,
+) -> anyhow::Result<SummaryStatsDatetime> {
     // Use the same implementationas the date summary stats
     // but add the timezone.
-    let r_stats: HashMap<String, RObject> =
-        call_summary_fn("summary_stats_date", column)?.try_into()?;
+    let r_stats: HashMap<String, RObject> = stats.try_into()?;
 
     let num_unique: Option<i64> = get_stat::<i32, RObject>(&r_stats, "num_unique")
         .ok()
         .and_then(|x| Some(x as i64));
 
-    let timezone: Option<String> = RFunction::from("summary_stats_get_timezone")
-        .add(column)
-        .call_in(ARK_ENVS.positron_ns)?
-        .try_into()?;
-
     Ok(SummaryStatsDatetime {
         min_date: get_stat(&r_stats, "min_date").ok(),
         mean_date: get_stat(&r_stats, "mean_date").ok(),
@@ -177,6 +192,13 @@ fn summary_stats_datetime(column: SEXP) -> anyhow::Result<SummaryStatsDatetime>
     })
 }
 
+pub(crate) fn summary_stats_timezone(column: SEXP) -> anyhow::Result<Option<String>> {
+    Ok(RFunction::from("summary_stats_get_timezone")
+        .add(column)
+        .call_in(ARK_ENVS.positron_ns)?
+        .try_into()?)
+}
+
 fn call_summary_fn(function: &str, column: SEXP) -> anyhow::Result<RObject> {
     Ok(RFunction::from(function)
         .add(column)
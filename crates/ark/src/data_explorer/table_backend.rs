@@ -0,0 +1,378 @@
+//
+// table_backend.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+//! Backends for lazy tables
+//!
+//! Lazy tables, such as dbplyr `tbl_lazy` tables, duckplyr frames, and Arrow
+//! tables and datasets, are viewed without being collected in memory. The
+//! row filters, sort keys and column profiles of the data explorer are
+//! translated to dplyr verbs that are evaluated by the backend (e.g. as SQL
+//! queries), and only the rows of the requested pages are collected.
+//!
+//! Row filters are passed to R as data and translated to filter calls there,
+//! so that their values are never parsed as code. Text searches are
+//! translated per backend, as they don't have a common translation: dbplyr
+//! only translates `LIKE` patterns portably, while Arrow supports `grepl()`,
+//! `startsWith()` and `endsWith()`.
+//!
+//! The R side of the backends lives in `r_data_explorer_lazy.R`.
+
+use amalthea::comm::data_explorer_comm::ColumnSchema;
+use amalthea::comm::data_explorer_comm::ColumnSortKey;
+use amalthea::comm::data_explorer_comm::RowFilter;
+use amalthea::comm::data_explorer_comm::RowFilterParams;
+use amalthea::comm::data_explorer_comm::RowFilterType;
+use harp::exec::RFunction;
+use harp::exec::RFunctionExt;
+use harp::utils::r_inherits;
+use harp::RObject;
+use libr::SEXP;
+
+use crate::modules::ARK_ENVS;
+
+/// The row filters and sort keys of a data viewer, evaluated by a lazy table
+/// backend
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LazyView {
+    /// Row filters, applied in turn
+    pub filters: Vec<RowFilter>,
+
+    /// Names of the sort columns
+    pub sort_names: Vec<String>,
+
+    /// Whether each sort column is sorted in ascending order
+    pub sort_ascending: Vec<bool>,
+}
+
+impl LazyView {
+    /// Create a view from the row filters and sort keys of a data viewer.
+    ///
+    /// Filters known to be invalid or that the backend can't translate are
+    /// skipped, as well as sort keys whose column doesn't exist.
+    pub fn new(
+        backend: &dyn TableBackend,
+        row_filters: &[RowFilter],
+        sort_keys: &[ColumnSortKey],
+        columns: &[ColumnSchema],
+    ) -> Self {
+        let filters = row_filters
+            .iter()
+            .filter(|filter| filter.is_valid != Some(false))
+            .filter(|filter| backend.supports_row_filter(filter))
+            .cloned()
+            .collect();
+
+        let (sort_names, sort_ascending) = sort_keys
+            .iter()
+            .filter_map(|key| {
+                let column = columns.get(key.column_index as usize)?;
+                Some((column.column_name.clone(), key.ascending))
+            })
+            .unzip();
+
+        Self {
+            filters,
+            sort_names,
+            sort_ascending,
+        }
+    }
+
+    /// The view with the filters only. Counts and profiles don't depend on
+    /// the sort order.
+    pub fn unsorted(&self) -> Self {
+        Self {
+            filters: self.filters.clone(),
+            sort_names: vec![],
+            sort_ascending: vec![],
+        }
+    }
+}
+
+/// A backend evaluating data explorer queries on a lazy table.
+///
+/// All methods must be called on the R thread.
+pub trait TableBackend: Send + Sync {
+    /// Name of the backend, for logging
+    fn name(&self) -> &'static str;
+
+    /// Whether the backend can evaluate a row filter. Filters are translated
+    /// to dplyr calls by `lazy_filter_call()` on the R side.
+    fn supports_row_filter(&self, filter: &RowFilter) -> bool {
+        has_filter_params(filter)
+    }
+
+    /// Collect `n` rows of the `columns` of the view, starting at the 0-based
+    /// row `offset`. Returns a data frame.
+    fn collect(
+        &self,
+        table: &RObject,
+        view: &LazyView,
+        columns: &[String],
+        offset: i64,
+        n: i64,
+    ) -> anyhow::Result<RObject>;
+
+    /// A zero-row data frame with the columns of the table
+    fn ptype(&self, table: &RObject) -> anyhow::Result<RObject> {
+        Ok(RFunction::new("", ".ps.lazy_ptype")
+            .add(table.sexp)
+            .call_in(ARK_ENVS.positron_ns)?)
+    }
+
+    /// Number of rows in the view
+    fn count(&self, table: &RObject, view: &LazyView) -> anyhow::Result<i64> {
+        let count: f64 = RFunction::new("", ".ps.lazy_count")
+            .add(table.sexp)
+            .add(r_row_filters(&view.filters)?)
+            .call_in(ARK_ENVS.positron_ns)?
+            .try_into()?;
+        Ok(count as i64)
+    }
+
+    /// Number of missing values in a column of the view
+    fn null_count(&self, table: &RObject, view: &LazyView, column: &str) -> anyhow::Result<i64> {
+        let count: f64 = RFunction::new("", ".ps.lazy_null_count")
+            .add(table.sexp)
+            .add(r_row_filters(&view.filters)?)
+            .add(column)
+            .call_in(ARK_ENVS.positron_ns)?
+            .try_into()?;
+        Ok(count as i64)
+    }
+
+    /// Summary statistics of a column of the view, in the shape returned by
+    /// the in-memory summary function `fun`, e.g. `summary_stats_number()`
+    fn summary_stats(
+        &self,
+        table: &RObject,
+        view: &LazyView,
+        column: &str,
+        fun: &str,
+    ) -> anyhow::Result<RObject> {
+        Ok(RFunction::new("", ".ps.lazy_summary_stats")
+            .add(table.sexp)
+            .add(r_row_filters(&view.filters)?)
+            .add(column)
+            .add(fun)
+            .call_in(ARK_ENVS.positron_ns)?)
+    }
+
+    /// The `limit` most frequent values of a column of the view, in the shape
+    /// returned by `profile_frequency_table()`
+    fn frequency_table(
+        &self,
+        table: &RObject,
+        view: &LazyView,
+        column: &str,
+        limit: i64,
+    ) -> anyhow::Result<RObject> {
+        Ok(RFunction::new("", ".ps.lazy_frequency_table")
+            .add(table.sexp)
+            .add(r_row_filters(&view.filters)?)
+            .add(column)
+            .add(limit as i32)
+            .call_in(ARK_ENVS.positron_ns)?)
+    }
+}
+
+/// Backend for tables queried with SQL: dbplyr tables and duckplyr frames.
+/// Unsorted views are paged with `LIMIT` and `OFFSET`. In sorted views, rows
+/// are numbered with a `ROW_NUMBER()` window over the sort keys and pages are
+/// selected by row number.
+pub struct SqlBackend;
+
+impl TableBackend for SqlBackend {
+    fn name(&self) -> &'static str {
+        "sql"
+    }
+
+    fn collect(
+        &self,
+        table: &RObject,
+        view: &LazyView,
+        columns: &[String],
+        offset: i64,
+        n: i64,
+    ) -> anyhow::Result<RObject> {
+        collect_with(".ps.lazy_collect_sql", table, view, columns, offset, n)
+    }
+}
+
+/// Backend for Arrow tables, datasets and queries. Pages are sliced in Arrow
+/// before being converted to data frames.
+pub struct ArrowBackend;
+
+impl TableBackend for ArrowBackend {
+    fn name(&self) -> &'static str {
+        "arrow"
+    }
+
+    fn collect(
+        &self,
+        table: &RObject,
+        view: &LazyView,
+        columns: &[String],
+        offset: i64,
+        n: i64,
+    ) -> anyhow::Result<RObject> {
+        collect_with(".ps.lazy_collect_arrow", table, view, columns, offset, n)
+    }
+}
+
+/// Whether a row filter has the parameters of its type
+fn has_filter_params(filter: &RowFilter) -> bool {
+    match filter.filter_type {
+        RowFilterType::Compare => {
+            matches!(filter.params, Some(RowFilterParams::Comparison(_)))
+        },
+        RowFilterType::Between | RowFilterType::NotBetween => {
+            matches!(filter.params, Some(RowFilterParams::Between(_)))
+        },
+        RowFilterType::Search => {
+            matches!(filter.params, Some(RowFilterParams::TextSearch(_)))
+        },
+        RowFilterType::SetMembership => {
+            matches!(filter.params, Some(RowFilterParams::SetMembership(_)))
+        },
+        _ => true,
+    }
+}
+
+/// Convert row filters to an R list by marshaling them through the JSON
+/// layer, as for the filters of in-memory tables
+fn r_row_filters(filters: &[RowFilter]) -> anyhow::Result<RObject> {
+    let filters = filters
+        .iter()
+        .map(|filter| Ok(RObject::try_from(serde_json::to_value(filter)?)?))
+        .collect::<anyhow::Result<Vec<RObject>>>()?;
+    Ok(RObject::try_from(filters)?)
+}
+
+fn collect_with(
+    function: &str,
+    table: &RObject,
+    view: &LazyView,
+    columns: &[String],
+    offset: i64,
+    n: i64,
+) -> anyhow::Result<RObject> {
+    Ok(RFunction::new("", function)
+        .add(table.sexp)
+        .add(r_row_filters(&view.filters)?)
+        .add(view.sort_names.clone())
+        .add(RObject::try_from(&view.sort_ascending)?)
+        .add(columns.to_vec())
+        .add(offset as f64)
+        .add(n as f64)
+        .call_in(ARK_ENVS.positron_ns)?)
+}
+
+/// The backend of a lazy table, or `None` for in-memory tables.
+///
+/// Keep in sync with `is_lazy_table()` on the R side.
+pub fn table_backend(x: SEXP) -> Option<&'static dyn TableBackend> {
+    if r_inherits(x, "tbl_lazy") || r_inherits(x, "duckplyr_df") {
+        return Some(&SqlBackend);
+    }
+
+    if r_inherits(x, "ArrowTabular") ||
+        r_inherits(x, "Dataset") ||
+        r_inherits(x, "arrow_dplyr_query")
+    {
+        return Some(&ArrowBackend);
+    }
+
+    None
+}
+
+pub fn is_lazy_table(x: SEXP) -> bool {
+    table_backend(x).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use amalthea::comm::data_explorer_comm::ColumnDisplayType;
+    use amalthea::comm::data_explorer_comm::FilterComparison;
+    use amalthea::comm::data_explorer_comm::FilterComparisonOp;
+    use amalthea::comm::data_explorer_comm::RowFilterCondition;
+
+    use super::*;
+
+    fn column(name: &str, index: i64) -> ColumnSchema {
+        ColumnSchema {
+            column_name: name.to_string(),
+            column_label: None,
+            column_index: index,
+            type_name: "dbl".to_string(),
+            type_display: ColumnDisplayType::Floating,
+            description: None,
+            children: None,
+            precision: None,
+            scale: None,
+            timezone: None,
+            type_size: None,
+        }
+    }
+
+    fn not_null(column: ColumnSchema, is_valid: Option<bool>) -> RowFilter {
+        RowFilter {
+            filter_id: "filter".to_string(),
+            filter_type: RowFilterType::NotNull,
+            column_schema: column,
+            condition: RowFilterCondition::And,
+            is_valid,
+            error_message: None,
+            params: None,
+        }
+    }
+
+    #[test]
+    fn test_lazy_view() {
+        let columns = vec![column("x", 0), column("y", 1)];
+
+        let filters = vec![
+            not_null(columns[0].clone(), Some(true)),
+            not_null(columns[1].clone(), Some(false)),
+        ];
+        let sort_keys = vec![
+            ColumnSortKey {
+                column_index: 1,
+                ascending: false,
+            },
+            ColumnSortKey {
+                column_index: 5,
+                ascending: true,
+            },
+        ];
+
+        let view = LazyView::new(&SqlBackend, &filters, &sort_keys, &columns);
+        assert_eq!(view.filters, vec![filters[0].clone()]);
+        assert_eq!(view.sort_names, vec!["y"]);
+        assert_eq!(view.sort_ascending, vec![false]);
+
+        let unsorted = view.unsorted();
+        assert_eq!(unsorted.filters, view.filters);
+        assert!(unsorted.sort_names.is_empty());
+    }
+
+    #[test]
+    fn test_supports_row_filter() {
+        let mut filter = not_null(column("x", 0), None);
+        assert!(SqlBackend.supports_row_filter(&filter));
+
+        // Filters without the parameters of their type can't be evaluated
+        filter.filter_type = RowFilterType::Compare;
+        assert!(!SqlBackend.supports_row_filter(&filter));
+        assert!(!ArrowBackend.supports_row_filter(&filter));
+
+        filter.params = Some(RowFilterParams::Comparison(FilterComparison {
+            op: FilterComparisonOp::Eq,
+            value: String::from("1"),
+        }));
+        assert!(SqlBackend.supports_row_filter(&filter));
+    }
+}
@@ -0,0 +1,193 @@
+//
+// arguments.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use tree_sitter::Node;
+
+use crate::lsp::traits::node::NodeExt;
+use crate::treesitter::node_is_pipe_rhs;
+use crate::treesitter::NodeTypeExt;
+
+/// Returns the placeholder standing for the implicit first argument if the
+/// call is on the right-hand side of a pipe. Returns `None` when the piped
+/// value is passed explicitly with a placeholder.
+pub(crate) fn pipe_placeholder(call: &Node, contents: &str) -> Option<&'static str> {
+    if !node_is_pipe_rhs(call, contents) {
+        return None;
+    }
+
+    let pipe = call.parent()?;
+    let placeholder = if pipe.is_native_pipe_operator() {
+        "_"
+    } else {
+        "."
+    };
+
+    let explicit = call
+        .arguments_values()
+        .flatten()
+        .any(|value| value.node_as_str(contents).ok() == Some(placeholder));
+
+    (!explicit).then_some(placeholder)
+}
+
+pub(crate) struct ArgumentMatches<'tree, 'a> {
+    /// Formal matched by the implicit first argument of a pipe
+    pub implicit: Option<&'a String>,
+
+    /// Positional arguments and the formal they match
+    pub positional: Vec<(Node<'tree>, &'a String)>,
+
+    /// Names of arguments that match no formal before `...`, either
+    /// because there is no such formal or because the partial match is
+    /// ambiguous. These are collected by `...` if there is one.
+    pub unmatched_names: Vec<Node<'tree>>,
+
+    /// Positional arguments in excess of the formals before `...`. These are
+    /// collected by `...` if there is one.
+    pub unmatched_positional: Vec<Node<'tree>>,
+}
+
+/// Match the arguments of a call to formals, following the R rules: first by
+/// exact name, then by partial name, then by position. Formals following `...`
+/// can only be matched by exact name.
+pub(crate) fn match_arguments<'tree, 'a>(
+    call: &Node<'tree>,
+    formals: &'a [String],
+    implicit_first: bool,
+    contents: &str,
+) -> anyhow::Result<ArgumentMatches<'tree, 'a>> {
+    let dots = formals.iter().position(|formal| formal == "...");
+
+    let mut remaining: Vec<Option<&'a String>> = formals.iter().map(Some).collect();
+    let mut unmatched = Vec::new();
+
+    // Exact matching
+    let mut named = Vec::new();
+    for (name, value) in call.arguments() {
+        match name {
+            Some(name_node) => {
+                let name = argument_name(&name_node, contents)?;
+                let exact = remaining
+                    .iter()
+                    .position(|formal| formal.is_some_and(|formal| formal.as_str() == name));
+                match exact {
+                    Some(index) => remaining[index] = None,
+                    None => named.push((name_node, name)),
+                }
+            },
+            None => {
+                if let Some(value) = value {
+                    unmatched.push(value);
+                }
+            },
+        }
+    }
+
+    // Partial matching, only for formals before `...`
+    let mut unmatched_names = Vec::new();
+    for (name_node, name) in named {
+        let candidates: Vec<usize> = remaining
+            .iter()
+            .enumerate()
+            .filter(|(index, formal)| {
+                dots.map_or(true, |dots| *index < dots) &&
+                    formal.is_some_and(|formal| formal.starts_with(name))
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        if let [index] = candidates[..] {
+            remaining[index] = None;
+        } else {
+            unmatched_names.push(name_node);
+        }
+    }
+
+    // Positional matching, up to `...`
+    let mut positional_formals = remaining
+        .into_iter()
+        .flatten()
+        .take_while(|formal| formal.as_str() != "...");
+
+    let implicit = if implicit_first {
+        positional_formals.next()
+    } else {
+        None
+    };
+
+    // Zip from the formals so that excess values are not consumed
+    let mut values = unmatched.into_iter();
+    let positional = positional_formals
+        .zip(values.by_ref())
+        .map(|(formal, value)| (value, formal))
+        .collect();
+    let unmatched_positional = values.collect();
+
+    Ok(ArgumentMatches {
+        implicit,
+        positional,
+        unmatched_names,
+        unmatched_positional,
+    })
+}
+
+/// The name of an argument, without the quotes of `"x" = 1` or the backticks
+/// of `` `x` = 1 ``
+pub(crate) fn argument_name<'a>(name: &Node, contents: &'a str) -> anyhow::Result<&'a str> {
+    let text = name.node_as_str(contents)?;
+
+    for quote in ['"', '\'', '`'] {
+        if let Some(name) = text
+            .strip_prefix(quote)
+            .and_then(|text| text.strip_suffix(quote))
+        {
+            return Ok(name);
+        }
+    }
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsp::document::Document;
+
+    fn formals(formals: &[&str]) -> Vec<String> {
+        formals.iter().map(|formal| formal.to_string()).collect()
+    }
+
+    /// The formals matched by position, and the number of unmatched names
+    fn positional<'a>(code: &str, formals: &'a [String]) -> (Vec<&'a str>, usize) {
+        let document = Document::new(code, None);
+        let call = document.ast.root_node().child(0).unwrap();
+        let contents = document.contents.as_str();
+
+        let matches = match_arguments(&call, formals, false, contents).unwrap();
+        let positional = matches
+            .positional
+            .iter()
+            .map(|(_value, formal)| formal.as_str())
+            .collect();
+
+        (positional, matches.unmatched_names.len())
+    }
+
+    #[test]
+    fn test_match_arguments_quoted_names() {
+        let formals = formals(&["x", "yy", "z"]);
+
+        assert_eq!(positional("f(\"x\" = 1, 2)", &formals), (vec!["yy"], 0));
+        assert_eq!(positional("f('z' = 1, 2)", &formals), (vec!["x"], 0));
+        assert_eq!(positional("f(`z` = 1, 2)", &formals), (vec!["x"], 0));
+
+        // Partial matching
+        assert_eq!(positional("f(`y` = 1, 2)", &formals), (vec!["x"], 0));
+
+        assert_eq!(positional("f(\"w\" = 1)", &formals), (vec![], 1));
+    }
+}
@@ -19,6 +19,7 @@ use crossbeam::channel::Sender;
 use serde_json::Value;
 use stdext::result::ResultExt;
 use tokio::net::TcpListener;
+use tokio::runtime::Builder;
 use tokio::runtime::Runtime;
 use tokio::sync::mpsc::unbounded_channel as tokio_unbounded_channel;
 use tokio::sync::mpsc::UnboundedSender as AsyncUnboundedSender;
@@ -30,6 +31,7 @@ use tower_lsp::lsp_types::FoldingRange;
 use tower_lsp::lsp_types::SelectionRange;
 use tower_lsp::lsp_types::*;
 use tower_lsp::Client;
+use tower_lsp::ClientSocket;
 use tower_lsp::LanguageServer;
 use tower_lsp::LspService;
 use tower_lsp::Server;
@@ -49,6 +51,8 @@ use crate::lsp::input_boundaries::InputBoundariesResponse;
 use crate::lsp::main_loop::Event;
 use crate::lsp::main_loop::GlobalState;
 use crate::lsp::main_loop::TokioUnboundedSender;
+use crate::lsp::notebook;
+use crate::lsp::standalone;
 use crate::lsp::statement_range;
 use crate::lsp::statement_range::StatementRangeParams;
 use crate::lsp::statement_range::StatementRangeResponse;
@@ -107,6 +111,12 @@ fn report_crash() {
         "with full logs (see https://positron.posit.co/troubleshooting.html#python-and-r-logs)."
     );
 
+    // There is no frontend to notify without a kernel
+    if standalone::is_standalone() {
+        log::error!("{user_message}");
+        return;
+    }
+
     r_task(|| {
         let event = UiFrontendEvent::ShowMessage(UiShowMessageParams {
             message: String::from(user_message),
@@ -137,6 +147,9 @@ pub(crate) enum LspNotification {
     DidCreateFiles(CreateFilesParams),
     DidDeleteFiles(DeleteFilesParams),
     DidRenameFiles(RenameFilesParams),
+    DidOpenNotebookDocument(DidOpenNotebookDocumentParams),
+    DidChangeNotebookDocument(DidChangeNotebookDocumentParams),
+    DidCloseNotebookDocument(DidCloseNotebookDocumentParams),
 }
 
 #[derive(Debug)]
@@ -154,10 +167,25 @@ pub(crate) enum LspRequest {
     GotoImplementation(GotoImplementationParams),
     SelectionRange(SelectionRangeParams),
     References(ReferenceParams),
+    PrepareRename(TextDocumentPositionParams),
+    Rename(RenameParams),
+    SemanticTokensFull(SemanticTokensParams),
+    SemanticTokensFullDelta(SemanticTokensDeltaParams),
+    InlayHint(InlayHintParams),
+    Formatting(DocumentFormattingParams),
+    RangeFormatting(DocumentRangeFormattingParams),
+    PrepareCallHierarchy(CallHierarchyPrepareParams),
+    IncomingCalls(CallHierarchyIncomingCallsParams),
+    OutgoingCalls(CallHierarchyOutgoingCallsParams),
+    DocumentHighlight(DocumentHighlightParams),
     StatementRange(StatementRangeParams),
     HelpTopic(HelpTopicParams),
     OnTypeFormatting(DocumentOnTypeFormattingParams),
     CodeAction(CodeActionParams),
+    CodeLens(CodeLensParams),
+    CodeLensResolve(CodeLens),
+    DocumentDiagnostic(DocumentDiagnosticParams),
+    WorkspaceDiagnostic(WorkspaceDiagnosticParams),
     VirtualDocument(VirtualDocumentParams),
     InputBoundaries(InputBoundariesParams),
 }
@@ -177,10 +205,25 @@ pub(crate) enum LspResponse {
     GotoImplementation(Option<GotoImplementationResponse>),
     SelectionRange(Option<Vec<SelectionRange>>),
     References(Option<Vec<Location>>),
+    PrepareRename(Option<PrepareRenameResponse>),
+    Rename(Option<WorkspaceEdit>),
+    SemanticTokensFull(Option<SemanticTokensResult>),
+    SemanticTokensFullDelta(Option<SemanticTokensFullDeltaResult>),
+    InlayHint(Option<Vec<InlayHint>>),
+    Formatting(Option<Vec<TextEdit>>),
+    RangeFormatting(Option<Vec<TextEdit>>),
+    PrepareCallHierarchy(Option<Vec<CallHierarchyItem>>),
+    IncomingCalls(Option<Vec<CallHierarchyIncomingCall>>),
+    OutgoingCalls(Option<Vec<CallHierarchyOutgoingCall>>),
+    DocumentHighlight(Option<Vec<DocumentHighlight>>),
     StatementRange(Option<StatementRangeResponse>),
     HelpTopic(Option<HelpTopicResponse>),
     OnTypeFormatting(Option<Vec<TextEdit>>),
     CodeAction(Option<CodeActionResponse>),
+    CodeLens(Option<Vec<CodeLens>>),
+    CodeLensResolve(CodeLens),
+    DocumentDiagnostic(DocumentDiagnosticReportResult),
+    WorkspaceDiagnostic(WorkspaceDiagnosticReportResult),
     VirtualDocument(VirtualDocumentResponse),
     InputBoundaries(InputBoundariesResponse),
 }
@@ -431,6 +474,118 @@ impl LanguageServer for Backend {
         )
     }
 
+    async fn prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> Result<Option<PrepareRenameResponse>> {
+        cast_response!(
+            self,
+            self.request(LspRequest::PrepareRename(params)).await,
+            LspResponse::PrepareRename
+        )
+    }
+
+    async fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+        cast_response!(
+            self,
+            self.request(LspRequest::Rename(params)).await,
+            LspResponse::Rename
+        )
+    }
+
+    async fn semantic_tokens_full(
+        &self,
+        params: SemanticTokensParams,
+    ) -> Result<Option<SemanticTokensResult>> {
+        cast_response!(
+            self,
+            self.request(LspRequest::SemanticTokensFull(params)).await,
+            LspResponse::SemanticTokensFull
+        )
+    }
+
+    async fn semantic_tokens_full_delta(
+        &self,
+        params: SemanticTokensDeltaParams,
+    ) -> Result<Option<SemanticTokensFullDeltaResult>> {
+        cast_response!(
+            self,
+            self.request(LspRequest::SemanticTokensFullDelta(params)).await,
+            LspResponse::SemanticTokensFullDelta
+        )
+    }
+
+    async fn inlay_hint(&self, params: InlayHintParams) -> Result<Option<Vec<InlayHint>>> {
+        cast_response!(
+            self,
+            self.request(LspRequest::InlayHint(params)).await,
+            LspResponse::InlayHint
+        )
+    }
+
+    async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
+        cast_response!(
+            self,
+            self.request(LspRequest::Formatting(params)).await,
+            LspResponse::Formatting
+        )
+    }
+
+    async fn range_formatting(
+        &self,
+        params: DocumentRangeFormattingParams,
+    ) -> Result<Option<Vec<TextEdit>>> {
+        cast_response!(
+            self,
+            self.request(LspRequest::RangeFormatting(params)).await,
+            LspResponse::RangeFormatting
+        )
+    }
+
+    async fn prepare_call_hierarchy(
+        &self,
+        params: CallHierarchyPrepareParams,
+    ) -> Result<Option<Vec<CallHierarchyItem>>> {
+        cast_response!(
+            self,
+            self.request(LspRequest::PrepareCallHierarchy(params)).await,
+            LspResponse::PrepareCallHierarchy
+        )
+    }
+
+    async fn incoming_calls(
+        &self,
+        params: CallHierarchyIncomingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyIncomingCall>>> {
+        cast_response!(
+            self,
+            self.request(LspRequest::IncomingCalls(params)).await,
+            LspResponse::IncomingCalls
+        )
+    }
+
+    async fn outgoing_calls(
+        &self,
+        params: CallHierarchyOutgoingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyOutgoingCall>>> {
+        cast_response!(
+            self,
+            self.request(LspRequest::OutgoingCalls(params)).await,
+            LspResponse::OutgoingCalls
+        )
+    }
+
+    async fn document_highlight(
+        &self,
+        params: DocumentHighlightParams,
+    ) -> Result<Option<Vec<DocumentHighlight>>> {
+        cast_response!(
+            self,
+            self.request(LspRequest::DocumentHighlight(params)).await,
+            LspResponse::DocumentHighlight
+        )
+    }
+
     async fn on_type_formatting(
         &self,
         params: DocumentOnTypeFormattingParams,
@@ -449,6 +604,44 @@ impl LanguageServer for Backend {
             LspResponse::CodeAction
         )
     }
+
+    async fn code_lens(&self, params: CodeLensParams) -> Result<Option<Vec<CodeLens>>> {
+        cast_response!(
+            self,
+            self.request(LspRequest::CodeLens(params)).await,
+            LspResponse::CodeLens
+        )
+    }
+
+    async fn code_lens_resolve(&self, params: CodeLens) -> Result<CodeLens> {
+        cast_response!(
+            self,
+            self.request(LspRequest::CodeLensResolve(params)).await,
+            LspResponse::CodeLensResolve
+        )
+    }
+
+    async fn diagnostic(
+        &self,
+        params: DocumentDiagnosticParams,
+    ) -> Result<DocumentDiagnosticReportResult> {
+        cast_response!(
+            self,
+            self.request(LspRequest::DocumentDiagnostic(params)).await,
+            LspResponse::DocumentDiagnostic
+        )
+    }
+
+    async fn workspace_diagnostic(
+        &self,
+        params: WorkspaceDiagnosticParams,
+    ) -> Result<WorkspaceDiagnosticReportResult> {
+        cast_response!(
+            self,
+            self.request(LspRequest::WorkspaceDiagnostic(params)).await,
+            LspResponse::WorkspaceDiagnostic
+        )
+    }
 }
 
 // Custom methods for the backend.
@@ -514,6 +707,20 @@ impl Backend {
     async fn notification(&self, params: Option<Value>) {
         log::info!("Received Positron notification: {:?}", params);
     }
+
+    // The `notebookDocument/*` notifications are not part of the
+    // `LanguageServer` trait of tower-lsp
+    async fn did_open_notebook(&self, params: DidOpenNotebookDocumentParams) {
+        self.notify(LspNotification::DidOpenNotebookDocument(params));
+    }
+
+    async fn did_change_notebook(&self, params: DidChangeNotebookDocumentParams) {
+        self.notify(LspNotification::DidChangeNotebookDocument(params));
+    }
+
+    async fn did_close_notebook(&self, params: DidCloseNotebookDocumentParams) {
+        self.notify(LspNotification::DidCloseNotebookDocument(params));
+    }
 }
 
 pub fn start_lsp(
@@ -581,20 +788,7 @@ pub fn start_lsp(
             }
         };
 
-        let (service, socket) = LspService::build(init)
-            .custom_method(
-                statement_range::POSITRON_STATEMENT_RANGE_REQUEST,
-                Backend::statement_range,
-            )
-            .custom_method(help_topic::POSITRON_HELP_TOPIC_REQUEST, Backend::help_topic)
-            .custom_method(ARK_VDOC_REQUEST, Backend::virtual_document)
-            // In principle this should probably be a Jupyter request
-            .custom_method(
-                input_boundaries::POSITRON_INPUT_BOUNDARIES_REQUEST,
-                Backend::input_boundaries,
-            )
-            .custom_method("positron/notification", Backend::notification)
-            .finish();
+        let (service, socket) = new_service(init);
 
         let server = Server::new(read, write, socket);
 
@@ -623,6 +817,78 @@ pub fn start_lsp(
     })
 }
 
+/// Start the LSP on stdin and stdout, without an R session
+///
+/// This is the entry point of `ark --lsp`, used by editors and tools that
+/// launch ark as a standalone language server. Features that evaluate R code
+/// are disabled, see `standalone::is_standalone()`.
+pub fn start_lsp_stdio() -> anyhow::Result<()> {
+    standalone::set_standalone();
+
+    let runtime = Builder::new_multi_thread().enable_all().build()?;
+
+    runtime.block_on(async {
+        // Console notifications have no recipient without a kernel, drain them
+        let (console_notification_tx, mut console_notification_rx) =
+            tokio_unbounded_channel::<ConsoleNotification>();
+        tokio::spawn(async move { while console_notification_rx.recv().await.is_some() {} });
+
+        let (shutdown_tx, mut shutdown_rx) = tokio::sync::mpsc::channel::<()>(1);
+
+        let init = |client: Client| {
+            let state = GlobalState::new(client, console_notification_tx);
+            let events_tx = state.events_tx();
+
+            // Start main loop and hold onto the handle that keeps it alive
+            let main_loop = state.start();
+
+            Backend {
+                shutdown_tx,
+                events_tx,
+                _main_loop: main_loop,
+            }
+        };
+
+        let (service, socket) = new_service(init);
+
+        let server = Server::new(tokio::io::stdin(), tokio::io::stdout(), socket);
+
+        tokio::select! {
+            _ = server.serve(service) => {
+                log::trace!("LSP: Exiting after stdin was closed.");
+            },
+            _ = shutdown_rx.recv() => {
+                log::trace!("LSP: Exiting after receiving a shutdown request.");
+            }
+        }
+    });
+
+    Ok(())
+}
+
+fn new_service<F>(init: F) -> (LspService<Backend>, ClientSocket)
+where
+    F: FnOnce(Client) -> Backend,
+{
+    LspService::build(init)
+        .custom_method(
+            statement_range::POSITRON_STATEMENT_RANGE_REQUEST,
+            Backend::statement_range,
+        )
+        .custom_method(help_topic::POSITRON_HELP_TOPIC_REQUEST, Backend::help_topic)
+        .custom_method(ARK_VDOC_REQUEST, Backend::virtual_document)
+        // In principle this should probably be a Jupyter request
+        .custom_method(
+            input_boundaries::POSITRON_INPUT_BOUNDARIES_REQUEST,
+            Backend::input_boundaries,
+        )
+        .custom_method("positron/notification", Backend::notification)
+        .custom_method(notebook::NOTEBOOK_DID_OPEN, Backend::did_open_notebook)
+        .custom_method(notebook::NOTEBOOK_DID_CHANGE, Backend::did_change_notebook)
+        .custom_method(notebook::NOTEBOOK_DID_CLOSE, Backend::did_close_notebook)
+        .finish()
+}
+
 fn new_jsonrpc_error(message: String) -> jsonrpc::Error {
     jsonrpc::Error {
         code: jsonrpc::ErrorCode::ServerError(-1),
@@ -0,0 +1,915 @@
+//
+// base_symbols.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+/// Objects of the base package, which package code uses without importing
+/// them. The list is static so that package code can be checked without an R
+/// session. Replacement functions and most internal helpers are omitted.
+pub(crate) const BASE_SYMBOLS: &[&str] = &[
+    ".ArgsEnv",
+    ".AutoloadEnv",
+    ".BaseNamespaceEnv",
+    ".bincode",
+    ".C",
+    ".cache_class",
+    ".Call",
+    ".Call.graphics",
+    ".class2",
+    ".col",
+    ".colMeans",
+    ".colSums",
+    ".Date",
+    ".decode_numeric_version",
+    ".Defunct",
+    ".deparseOpts",
+    ".Deprecated",
+    ".Device",
+    ".Devices",
+    ".difftime",
+    ".doTrace",
+    ".dynLibs",
+    ".encode_numeric_version",
+    ".expand_R_libs_env_var",
+    ".External",
+    ".External.graphics",
+    ".External2",
+    ".First.sys",
+    ".format.zeros",
+    ".Fortran",
+    ".GenericArgsEnv",
+    ".getNamespace",
+    ".getRequiredPackages",
+    ".getRequiredPackages2",
+    ".GlobalEnv",
+    ".handleSimpleError",
+    ".Internal",
+    ".isMethodsDispatchOn",
+    ".knownS3Generics",
+    ".kronecker",
+    ".Last.value",
+    ".leap.seconds",
+    ".libPaths",
+    ".Library",
+    ".Machine",
+    ".makeMessage",
+    ".mapply",
+    ".NotYetImplemented",
+    ".NotYetUsed",
+    ".Options",
+    ".packages",
+    ".Platform",
+    ".POSIXct",
+    ".POSIXlt",
+    ".Primitive",
+    ".primTrace",
+    ".primUntrace",
+    ".row",
+    ".row_names_info",
+    ".rowMeans",
+    ".rowSums",
+    ".S3method",
+    ".S3PrimitiveGenerics",
+    ".set_row_names",
+    ".signalSimpleWarning",
+    ".standard_regexps",
+    ".subset",
+    ".subset2",
+    ".traceback",
+    ".tryResumeInterrupt",
+    ".userHooksEnv",
+    ".valid.factor",
+    "abbreviate",
+    "abs",
+    "acos",
+    "acosh",
+    "activeBindingFunction",
+    "addNA",
+    "addTaskCallback",
+    "agrep",
+    "agrepl",
+    "alist",
+    "all",
+    "all.equal",
+    "all.names",
+    "all.vars",
+    "allowInterrupts",
+    "any",
+    "anyDuplicated",
+    "anyNA",
+    "aperm",
+    "append",
+    "apply",
+    "Arg",
+    "args",
+    "array",
+    "arrayInd",
+    "as.array",
+    "as.call",
+    "as.character",
+    "as.complex",
+    "as.data.frame",
+    "as.Date",
+    "as.difftime",
+    "as.double",
+    "as.environment",
+    "as.factor",
+    "as.function",
+    "as.hexmode",
+    "as.integer",
+    "as.list",
+    "as.list.environment",
+    "as.logical",
+    "as.matrix",
+    "as.name",
+    "as.null",
+    "as.numeric",
+    "as.numeric_version",
+    "as.octmode",
+    "as.ordered",
+    "as.package_version",
+    "as.pairlist",
+    "as.POSIXct",
+    "as.POSIXlt",
+    "as.qr",
+    "as.raw",
+    "as.single",
+    "as.symbol",
+    "as.table",
+    "as.vector",
+    "asin",
+    "asinh",
+    "asNamespace",
+    "asplit",
+    "asS3",
+    "asS4",
+    "assign",
+    "atan",
+    "atan2",
+    "atanh",
+    "attach",
+    "attachNamespace",
+    "attr",
+    "attr.all.equal",
+    "attributes",
+    "autoload",
+    "autoloader",
+    "backsolve",
+    "baseenv",
+    "basename",
+    "besselI",
+    "besselJ",
+    "besselK",
+    "besselY",
+    "beta",
+    "bindingIsActive",
+    "bindingIsLocked",
+    "bindtextdomain",
+    "bitwAnd",
+    "bitwNot",
+    "bitwOr",
+    "bitwShiftL",
+    "bitwShiftR",
+    "bitwXor",
+    "body",
+    "bquote",
+    "break",
+    "browser",
+    "browserCondition",
+    "browserSetDebug",
+    "browserText",
+    "builtins",
+    "by",
+    "bzfile",
+    "c",
+    "call",
+    "capabilities",
+    "casefold",
+    "cat",
+    "cbind",
+    "ceiling",
+    "char.expand",
+    "character",
+    "charmatch",
+    "charToRaw",
+    "chartr",
+    "chkDots",
+    "chol",
+    "chol2inv",
+    "choose",
+    "chooseOpsMethod",
+    "class",
+    "close",
+    "closeAllConnections",
+    "col",
+    "colMeans",
+    "colnames",
+    "colSums",
+    "commandArgs",
+    "comment",
+    "complete.cases",
+    "complex",
+    "computeRestarts",
+    "conditionCall",
+    "conditionMessage",
+    "conflictRules",
+    "conflicts",
+    "Conj",
+    "contributors",
+    "cos",
+    "cosh",
+    "cospi",
+    "crossprod",
+    "Cstack_info",
+    "cummax",
+    "cummin",
+    "cumprod",
+    "cumsum",
+    "curlGetHeaders",
+    "cut",
+    "cut.Date",
+    "cut.default",
+    "cut.POSIXt",
+    "data.class",
+    "data.frame",
+    "data.matrix",
+    "date",
+    "debug",
+    "debuggingState",
+    "debugonce",
+    "delayedAssign",
+    "deparse",
+    "deparse1",
+    "det",
+    "detach",
+    "determinant",
+    "dget",
+    "diag",
+    "diff",
+    "difftime",
+    "digamma",
+    "dim",
+    "dimnames",
+    "dir",
+    "dir.create",
+    "dir.exists",
+    "dirname",
+    "do.call",
+    "dontCheck",
+    "double",
+    "dput",
+    "dQuote",
+    "drop",
+    "droplevels",
+    "dump",
+    "duplicated",
+    "dyn.load",
+    "dyn.unload",
+    "dynGet",
+    "eapply",
+    "eigen",
+    "emptyenv",
+    "enc2native",
+    "enc2utf8",
+    "encodeString",
+    "Encoding",
+    "endsWith",
+    "enquote",
+    "env.profile",
+    "environment",
+    "environmentIsLocked",
+    "environmentName",
+    "errorCondition",
+    "eval",
+    "eval.parent",
+    "evalq",
+    "exists",
+    "exp",
+    "expand.grid",
+    "expm1",
+    "expression",
+    "extSoftVersion",
+    "F",
+    "factor",
+    "factorial",
+    "fifo",
+    "file",
+    "file.access",
+    "file.append",
+    "file.choose",
+    "file.copy",
+    "file.create",
+    "file.exists",
+    "file.info",
+    "file.link",
+    "file.mode",
+    "file.mtime",
+    "file.path",
+    "file.remove",
+    "file.rename",
+    "file.show",
+    "file.size",
+    "file.symlink",
+    "Filter",
+    "Find",
+    "find.package",
+    "findInterval",
+    "findPackageEnv",
+    "findRestart",
+    "floor",
+    "flush",
+    "for",
+    "force",
+    "forceAndCall",
+    "formals",
+    "format",
+    "format.Date",
+    "format.POSIXct",
+    "format.POSIXlt",
+    "formatC",
+    "forwardsolve",
+    "function",
+    "gamma",
+    "gc",
+    "gc.time",
+    "gcinfo",
+    "gctorture",
+    "gctorture2",
+    "get",
+    "get0",
+    "getAllConnections",
+    "getCallingDLL",
+    "getCallingDLLe",
+    "getConnection",
+    "getDLLRegisteredRoutines",
+    "getElement",
+    "geterrmessage",
+    "getExportedValue",
+    "getHook",
+    "getLoadedDLLs",
+    "getNamespace",
+    "getNamespaceExports",
+    "getNamespaceImports",
+    "getNamespaceInfo",
+    "getNamespaceName",
+    "getNamespaceUsers",
+    "getNamespaceVersion",
+    "getNativeSymbolInfo",
+    "getOption",
+    "getRversion",
+    "getSrcLines",
+    "getTaskCallbackNames",
+    "gettext",
+    "gettextf",
+    "getwd",
+    "gl",
+    "globalCallingHandlers",
+    "globalenv",
+    "gregexec",
+    "gregexpr",
+    "grep",
+    "grepl",
+    "grepRaw",
+    "grouping",
+    "gsub",
+    "gzcon",
+    "gzfile",
+    "I",
+    "iconv",
+    "iconvlist",
+    "icuGetCollate",
+    "icuSetCollate",
+    "identical",
+    "identity",
+    "if",
+    "ifelse",
+    "Im",
+    "importIntoEnv",
+    "infoRDS",
+    "inherits",
+    "integer",
+    "interaction",
+    "interactive",
+    "intersect",
+    "intToBits",
+    "intToUtf8",
+    "inverse.rle",
+    "invisible",
+    "invokeRestart",
+    "invokeRestartInteractively",
+    "is.array",
+    "is.atomic",
+    "is.call",
+    "is.character",
+    "is.complex",
+    "is.data.frame",
+    "is.double",
+    "is.element",
+    "is.environment",
+    "is.expression",
+    "is.factor",
+    "is.finite",
+    "is.function",
+    "is.infinite",
+    "is.integer",
+    "is.language",
+    "is.list",
+    "is.loaded",
+    "is.logical",
+    "is.matrix",
+    "is.na",
+    "is.na.data.frame",
+    "is.name",
+    "is.nan",
+    "is.null",
+    "is.numeric",
+    "is.numeric_version",
+    "is.object",
+    "is.ordered",
+    "is.package_version",
+    "is.pairlist",
+    "is.primitive",
+    "is.qr",
+    "is.R",
+    "is.raw",
+    "is.recursive",
+    "is.single",
+    "is.symbol",
+    "is.table",
+    "is.unsorted",
+    "is.vector",
+    "isa",
+    "isatty",
+    "isBaseNamespace",
+    "isdebugged",
+    "isFALSE",
+    "isIncomplete",
+    "isNamespace",
+    "isNamespaceLoaded",
+    "ISOdate",
+    "ISOdatetime",
+    "isOpen",
+    "isRestart",
+    "isS4",
+    "isSeekable",
+    "isSymmetric",
+    "isTRUE",
+    "jitter",
+    "julian",
+    "kappa",
+    "kronecker",
+    "l10n_info",
+    "La.svd",
+    "La_library",
+    "La_version",
+    "labels",
+    "lapply",
+    "lazyLoad",
+    "lazyLoadDBexec",
+    "lazyLoadDBfetch",
+    "lbeta",
+    "lchoose",
+    "length",
+    "length.POSIXlt",
+    "lengths",
+    "LETTERS",
+    "letters",
+    "levels",
+    "lfactorial",
+    "lgamma",
+    "libcurlVersion",
+    "library",
+    "library.dynam",
+    "library.dynam.unload",
+    "licence",
+    "license",
+    "list",
+    "list.dirs",
+    "list.files",
+    "list2DF",
+    "list2env",
+    "load",
+    "loadedNamespaces",
+    "loadingNamespaceInfo",
+    "loadNamespace",
+    "local",
+    "lockBinding",
+    "lockEnvironment",
+    "log",
+    "log10",
+    "log1p",
+    "log2",
+    "logb",
+    "logical",
+    "lower.tri",
+    "ls",
+    "make.names",
+    "make.unique",
+    "makeActiveBinding",
+    "Map",
+    "mapply",
+    "margin.table",
+    "marginSums",
+    "mat.or.vec",
+    "match",
+    "match.arg",
+    "match.call",
+    "match.fun",
+    "matrix",
+    "max",
+    "max.col",
+    "mean",
+    "mean.Date",
+    "mean.default",
+    "mean.difftime",
+    "mean.POSIXct",
+    "mean.POSIXlt",
+    "mem.maxNSize",
+    "mem.maxVSize",
+    "memCompress",
+    "memDecompress",
+    "memory.profile",
+    "merge",
+    "message",
+    "methods",
+    "mget",
+    "min",
+    "missing",
+    "Mod",
+    "mode",
+    "month.abb",
+    "month.name",
+    "months",
+    "mtfrm",
+    "names",
+    "nargs",
+    "nchar",
+    "NCOL",
+    "ncol",
+    "Negate",
+    "new.env",
+    "next",
+    "NextMethod",
+    "ngettext",
+    "nlevels",
+    "noquote",
+    "norm",
+    "normalizePath",
+    "NROW",
+    "nrow",
+    "nullfile",
+    "numeric",
+    "numeric_version",
+    "numToBits",
+    "numToInts",
+    "nzchar",
+    "objects",
+    "oldClass",
+    "OlsonNames",
+    "on.exit",
+    "open",
+    "open.connection",
+    "Ops.Date",
+    "Ops.difftime",
+    "Ops.factor",
+    "Ops.numeric_version",
+    "Ops.ordered",
+    "Ops.POSIXt",
+    "options",
+    "order",
+    "ordered",
+    "outer",
+    "package_version",
+    "packageEvent",
+    "packageHasNamespace",
+    "packageNotFoundError",
+    "packageStartupMessage",
+    "packBits",
+    "pairlist",
+    "parent.env",
+    "parent.frame",
+    "parse",
+    "parseNamespaceFile",
+    "paste",
+    "paste0",
+    "path.expand",
+    "path.package",
+    "pcre_config",
+    "pi",
+    "pipe",
+    "plot",
+    "pmatch",
+    "pmax",
+    "pmax.int",
+    "pmin",
+    "pmin.int",
+    "polyroot",
+    "pos.to.env",
+    "Position",
+    "pretty",
+    "prettyNum",
+    "print",
+    "print.data.frame",
+    "print.default",
+    "prmatrix",
+    "proc.time",
+    "prod",
+    "prop.table",
+    "proportions",
+    "provideDimnames",
+    "psigamma",
+    "pushBack",
+    "pushBackLength",
+    "q",
+    "qr",
+    "qr.coef",
+    "qr.default",
+    "qr.fitted",
+    "qr.Q",
+    "qr.qty",
+    "qr.qy",
+    "qr.R",
+    "qr.resid",
+    "qr.solve",
+    "qr.X",
+    "quarters",
+    "quit",
+    "quote",
+    "R.home",
+    "R.Version",
+    "R.version",
+    "R.version.string",
+    "R_system_version",
+    "range",
+    "rank",
+    "rapply",
+    "raw",
+    "rawConnection",
+    "rawConnectionValue",
+    "rawShift",
+    "rawToBits",
+    "rawToChar",
+    "rbind",
+    "rcond",
+    "Re",
+    "read.dcf",
+    "readBin",
+    "readChar",
+    "readline",
+    "readLines",
+    "readRDS",
+    "readRenviron",
+    "Recall",
+    "Reduce",
+    "reg.finalizer",
+    "regexec",
+    "regexpr",
+    "regmatches",
+    "remove",
+    "removeTaskCallback",
+    "rep",
+    "rep.int",
+    "rep_len",
+    "repeat",
+    "replace",
+    "require",
+    "requireNamespace",
+    "return",
+    "returnValue",
+    "rev",
+    "rev.default",
+    "rle",
+    "rm",
+    "RNGkind",
+    "RNGversion",
+    "round",
+    "round.Date",
+    "round.POSIXt",
+    "row",
+    "row.names",
+    "rowMeans",
+    "rownames",
+    "rowsum",
+    "rowsum.data.frame",
+    "rowsum.default",
+    "rowSums",
+    "sample",
+    "sample.int",
+    "sapply",
+    "save",
+    "save.image",
+    "saveRDS",
+    "scale",
+    "scan",
+    "search",
+    "searchpaths",
+    "seek",
+    "seq",
+    "seq.Date",
+    "seq.default",
+    "seq.int",
+    "seq.POSIXt",
+    "seq_along",
+    "seq_len",
+    "sequence",
+    "serialize",
+    "serverSocket",
+    "set.seed",
+    "setdiff",
+    "setequal",
+    "setHook",
+    "setNamespaceInfo",
+    "setSessionTimeLimit",
+    "setTimeLimit",
+    "setwd",
+    "showConnections",
+    "shQuote",
+    "sign",
+    "signalCondition",
+    "signif",
+    "simpleCondition",
+    "simpleError",
+    "simpleMessage",
+    "simpleWarning",
+    "simplify2array",
+    "sin",
+    "single",
+    "sinh",
+    "sink",
+    "sink.number",
+    "sinpi",
+    "slice.index",
+    "socketAccept",
+    "socketConnection",
+    "socketSelect",
+    "socketTimeout",
+    "solve",
+    "solve.default",
+    "solve.qr",
+    "sort",
+    "sort.default",
+    "sort.int",
+    "sort.list",
+    "sort_by",
+    "source",
+    "split",
+    "split.data.frame",
+    "split.default",
+    "sprintf",
+    "sqrt",
+    "sQuote",
+    "srcfile",
+    "srcfilealias",
+    "srcfilecopy",
+    "srcref",
+    "standardGeneric",
+    "startsWith",
+    "stderr",
+    "stdin",
+    "stdout",
+    "stop",
+    "stopifnot",
+    "storage.mode",
+    "strftime",
+    "strptime",
+    "strrep",
+    "strsplit",
+    "strtoi",
+    "strtrim",
+    "structure",
+    "strwrap",
+    "sub",
+    "subset",
+    "substitute",
+    "substr",
+    "substring",
+    "sum",
+    "summary",
+    "summary.data.frame",
+    "summary.default",
+    "summary.factor",
+    "summary.matrix",
+    "suppressMessages",
+    "suppressPackageStartupMessages",
+    "suppressWarnings",
+    "suspendInterrupts",
+    "svd",
+    "sweep",
+    "switch",
+    "sys.call",
+    "sys.calls",
+    "Sys.chmod",
+    "Sys.Date",
+    "sys.frame",
+    "sys.frames",
+    "sys.function",
+    "Sys.getenv",
+    "Sys.getlocale",
+    "Sys.getpid",
+    "Sys.glob",
+    "Sys.info",
+    "sys.load.image",
+    "Sys.localeconv",
+    "sys.nframe",
+    "sys.on.exit",
+    "sys.parent",
+    "sys.parents",
+    "Sys.readlink",
+    "sys.save.image",
+    "Sys.setenv",
+    "Sys.setFileTime",
+    "Sys.setLanguage",
+    "Sys.setlocale",
+    "Sys.sleep",
+    "sys.source",
+    "sys.status",
+    "Sys.time",
+    "Sys.timezone",
+    "Sys.umask",
+    "Sys.unsetenv",
+    "Sys.which",
+    "system",
+    "system.file",
+    "system.time",
+    "system2",
+    "T",
+    "t",
+    "table",
+    "tabulate",
+    "tan",
+    "tanh",
+    "tanpi",
+    "tapply",
+    "tcrossprod",
+    "tempdir",
+    "tempfile",
+    "textConnection",
+    "textConnectionValue",
+    "tolower",
+    "topenv",
+    "toString",
+    "toupper",
+    "trace",
+    "traceback",
+    "tracemem",
+    "tracingState",
+    "transform",
+    "trigamma",
+    "trimws",
+    "trunc",
+    "trunc.POSIXt",
+    "truncate",
+    "try",
+    "tryCatch",
+    "tryInvokeRestart",
+    "typeof",
+    "unclass",
+    "undebug",
+    "union",
+    "unique",
+    "unique.default",
+    "units",
+    "unlink",
+    "unlist",
+    "unloadNamespace",
+    "unlockBinding",
+    "unname",
+    "unserialize",
+    "unsplit",
+    "untrace",
+    "untracemem",
+    "unz",
+    "upper.tri",
+    "url",
+    "UseMethod",
+    "utf8ToInt",
+    "validEnc",
+    "validUTF8",
+    "vapply",
+    "vector",
+    "Vectorize",
+    "warning",
+    "warningCondition",
+    "warnings",
+    "weekdays",
+    "which",
+    "which.max",
+    "which.min",
+    "while",
+    "with",
+    "withCallingHandlers",
+    "within",
+    "withRestarts",
+    "write",
+    "write.dcf",
+    "writeBin",
+    "writeChar",
+    "writeLines",
+    "xor",
+    "xpdrows.data.frame",
+    "xtfrm",
+    "xzfile",
+    "zapsmall",
+];
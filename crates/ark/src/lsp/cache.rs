@@ -0,0 +1,390 @@
+//
+// cache.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+//! Persistent on-disk caches
+//!
+//! The workspace index and the metadata of installed packages are cached
+//! across sessions so that large projects are quickly available on startup.
+//! Caches are JSON files stored in the user cache directory. They are
+//! versioned with `CACHE_VERSION` and the version of Ark, and are discarded
+//! when either changes.
+//!
+//! - Index entries of workspace files are keyed by file path, modification
+//!   time, and content hash. A file whose modification time changed but not
+//!   its contents isn't parsed again.
+//!
+//! - Package metadata (NAMESPACE and INDEX) is keyed by package path,
+//!   version, and modification time of the DESCRIPTION and NAMESPACE files,
+//!   so that a package reinstalled at the same version is loaded again.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::lsp::indexer::IndexEntry;
+use crate::lsp::inputs::package_index::Index;
+use crate::lsp::inputs::package_namespace::Namespace;
+
+/// Version of the cache format. Bump when the cached data changes shape or
+/// meaning.
+const CACHE_VERSION: u32 = 2;
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile<T> {
+    version: u32,
+    ark_version: String,
+    data: T,
+}
+
+/// Directory of the LSP caches, e.g. `~/.cache/ark/lsp` on Linux
+pub(crate) fn cache_dir() -> Option<PathBuf> {
+    #[cfg(unix)]
+    {
+        let dirs = xdg::BaseDirectories::with_prefix("ark").ok()?;
+        Some(dirs.get_cache_home().join("lsp"))
+    }
+
+    #[cfg(not(unix))]
+    {
+        let local = std::env::var_os("LOCALAPPDATA")?;
+        Some(PathBuf::from(local).join("ark").join("cache").join("lsp"))
+    }
+}
+
+fn read_cache<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let contents = std::fs::read(path).ok()?;
+
+    let file: CacheFile<T> = match serde_json::from_slice(&contents) {
+        Ok(file) => file,
+        Err(err) => {
+            tracing::warn!("Discarding unreadable cache {}: {err}", path.display());
+            return None;
+        },
+    };
+
+    if file.version != CACHE_VERSION || file.ark_version != env!("CARGO_PKG_VERSION") {
+        tracing::info!("Discarding outdated cache {}", path.display());
+        return None;
+    }
+
+    Some(file.data)
+}
+
+fn write_cache<T: Serialize>(path: &Path, data: T) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let file = CacheFile {
+        version: CACHE_VERSION,
+        ark_version: String::from(env!("CARGO_PKG_VERSION")),
+        data,
+    };
+
+    // Write to a temporary file first so that concurrent sessions never read
+    // a partially written cache
+    let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+    std::fs::write(&tmp, serde_json::to_vec(&file)?)?;
+    std::fs::rename(&tmp, path)?;
+
+    Ok(())
+}
+
+/// Modification time of a file in nanoseconds since the Unix epoch
+pub(crate) fn file_modified(path: &Path) -> Option<u128> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_nanos())
+}
+
+/// Index entries of a workspace file
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CachedFile {
+    pub(crate) modified: u128,
+    pub(crate) hash: String,
+    pub(crate) entries: Vec<IndexEntry>,
+}
+
+/// Cache of the index entries of the files of a set of workspace folders
+#[derive(Debug, Default)]
+pub(crate) struct IndexCache {
+    path: Option<PathBuf>,
+    files: HashMap<PathBuf, CachedFile>,
+    dirty: bool,
+}
+
+impl IndexCache {
+    /// Load the cache of the workspace `folders` from the cache directory
+    pub(crate) fn load(folders: &[String]) -> Self {
+        let Some(dir) = cache_dir() else {
+            return Self::default();
+        };
+
+        let mut folders = folders.to_vec();
+        folders.sort();
+        let key = blake3::hash(folders.join("\n").as_bytes());
+
+        Self::load_from(dir.join(format!("index-{}.json", &key.to_hex()[..16])))
+    }
+
+    pub(crate) fn load_from(path: PathBuf) -> Self {
+        let files = read_cache(&path).unwrap_or_default();
+        Self {
+            path: Some(path),
+            files,
+            dirty: false,
+        }
+    }
+
+    /// The entries of `path` if it wasn't modified since it was cached
+    pub(crate) fn get(&self, path: &Path, modified: u128) -> Option<&Vec<IndexEntry>> {
+        let file = self.files.get(path)?;
+        (file.modified == modified).then_some(&file.entries)
+    }
+
+    /// The entries of `path` if its contents didn't change since it was
+    /// cached. The modification time is updated.
+    pub(crate) fn get_unchanged(
+        &mut self,
+        path: &Path,
+        hash: &str,
+        modified: u128,
+    ) -> Option<&Vec<IndexEntry>> {
+        let file = self.files.get_mut(path)?;
+        if file.hash != hash {
+            return None;
+        }
+
+        if file.modified != modified {
+            file.modified = modified;
+            self.dirty = true;
+        }
+
+        Some(&file.entries)
+    }
+
+    pub(crate) fn insert(&mut self, path: PathBuf, file: CachedFile) {
+        self.files.insert(path, file);
+        self.dirty = true;
+    }
+
+    pub(crate) fn remove(&mut self, path: &Path) {
+        if self.files.remove(path).is_some() {
+            self.dirty = true;
+        }
+    }
+
+    pub(crate) fn rename(&mut self, old: &Path, new: PathBuf) {
+        if let Some(file) = self.files.remove(old) {
+            self.files.insert(new, file);
+            self.dirty = true;
+        }
+    }
+
+    /// Drop the files that no longer exist in the workspace
+    pub(crate) fn retain(&mut self, mut keep: impl FnMut(&Path) -> bool) {
+        let n = self.files.len();
+        self.files.retain(|path, _| keep(path));
+        self.dirty |= self.files.len() != n;
+    }
+
+    /// Write the cache if it changed since it was loaded or last saved
+    pub(crate) fn save(&mut self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if !self.dirty {
+            return Ok(());
+        }
+
+        write_cache(path, &self.files)?;
+        self.dirty = false;
+
+        Ok(())
+    }
+}
+
+/// Metadata of an installed package
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct CachedPackage {
+    pub(crate) version: String,
+    /// Latest modification time of the DESCRIPTION and NAMESPACE files
+    pub(crate) modified: u128,
+    pub(crate) namespace: Namespace,
+    pub(crate) index: Index,
+}
+
+/// Cache of the metadata of installed packages, shared by all sessions
+#[derive(Debug, Default)]
+pub struct LibraryCache {
+    path: Option<PathBuf>,
+    packages: HashMap<PathBuf, CachedPackage>,
+    dirty: bool,
+}
+
+impl LibraryCache {
+    /// Load the cache from the cache directory
+    pub fn load() -> Self {
+        match cache_dir() {
+            Some(dir) => Self::load_from(dir.join("library.json")),
+            None => Self::default(),
+        }
+    }
+
+    pub(crate) fn load_from(path: PathBuf) -> Self {
+        let packages = read_cache(&path).unwrap_or_default();
+        Self {
+            path: Some(path),
+            packages,
+            dirty: false,
+        }
+    }
+
+    /// The metadata of the package installed at `path` if it was cached for
+    /// the same installation, i.e. the same version and modification time
+    pub(crate) fn get(&self, path: &Path, version: &str, modified: u128) -> Option<&CachedPackage> {
+        self.packages
+            .get(path)
+            .filter(|package| package.version == version && package.modified == modified)
+    }
+
+    pub(crate) fn insert(&mut self, path: PathBuf, package: CachedPackage) {
+        self.packages.insert(path, package);
+        self.dirty = true;
+    }
+
+    /// Write the cache if packages were loaded since it was loaded or last
+    /// saved. Entries written by other sessions in the meantime are kept.
+    pub(crate) fn save(&mut self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if !self.dirty {
+            return Ok(());
+        }
+
+        let mut packages: HashMap<PathBuf, CachedPackage> = read_cache(path).unwrap_or_default();
+        packages.extend(
+            self.packages
+                .iter()
+                .map(|(path, package)| (path.clone(), package.clone())),
+        );
+
+        write_cache(path, &packages)?;
+        self.dirty = false;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tower_lsp::lsp_types::Range;
+
+    use super::*;
+    use crate::lsp::indexer::IndexEntryData;
+
+    fn entry(name: &str) -> IndexEntry {
+        IndexEntry {
+            key: name.to_string(),
+            range: Range::default(),
+            data: IndexEntryData::Function {
+                name: name.to_string(),
+                arguments: vec![String::from("x")],
+            },
+        }
+    }
+
+    #[test]
+    fn test_index_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_path = dir.path().join("index.json");
+        let file = PathBuf::from("/project/R/f.R");
+
+        let mut cache = IndexCache::load_from(cache_path.clone());
+        cache.insert(file.clone(), CachedFile {
+            modified: 1,
+            hash: String::from("abc"),
+            entries: vec![entry("f")],
+        });
+        cache.save().unwrap();
+
+        let mut cache = IndexCache::load_from(cache_path.clone());
+        assert_eq!(cache.get(&file, 1), Some(&vec![entry("f")]));
+
+        // Modified files are checked against their contents
+        assert_eq!(cache.get(&file, 2), None);
+        assert_eq!(cache.get_unchanged(&file, "def", 2), None);
+        assert_eq!(cache.get_unchanged(&file, "abc", 2), Some(&vec![entry("f")]));
+        assert_eq!(cache.get(&file, 2), Some(&vec![entry("f")]));
+
+        cache.retain(|path| path != file.as_path());
+        cache.save().unwrap();
+        let cache = IndexCache::load_from(cache_path);
+        assert_eq!(cache.get(&file, 2), None);
+    }
+
+    #[test]
+    fn test_outdated_cache_is_discarded() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_path = dir.path().join("index.json");
+        let file = PathBuf::from("/project/R/f.R");
+
+        let data: HashMap<PathBuf, CachedFile> = HashMap::from([(file.clone(), CachedFile {
+            modified: 1,
+            hash: String::from("abc"),
+            entries: vec![entry("f")],
+        })]);
+        let outdated = CacheFile {
+            version: CACHE_VERSION + 1,
+            ark_version: String::from(env!("CARGO_PKG_VERSION")),
+            data,
+        };
+        std::fs::write(&cache_path, serde_json::to_vec(&outdated).unwrap()).unwrap();
+
+        let cache = IndexCache::load_from(cache_path.clone());
+        assert_eq!(cache.get(&file, 1), None);
+
+        // Unreadable caches are discarded too
+        std::fs::write(&cache_path, "{").unwrap();
+        let cache = IndexCache::load_from(cache_path);
+        assert_eq!(cache.get(&file, 1), None);
+    }
+
+    #[test]
+    fn test_library_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_path = dir.path().join("library.json");
+        let package = PathBuf::from("/library/pkg");
+
+        let mut cache = LibraryCache::load_from(cache_path.clone());
+        cache.insert(package.clone(), CachedPackage {
+            version: String::from("1.0.0"),
+            modified: 1,
+            namespace: Namespace {
+                exports: vec![String::from("foo")],
+                ..Default::default()
+            },
+            index: Index::default(),
+        });
+        cache.save().unwrap();
+
+        let cache = LibraryCache::load_from(cache_path);
+        let cached = cache.get(&package, "1.0.0", 1).unwrap();
+        assert_eq!(cached.namespace.exports, vec!["foo"]);
+
+        // Other versions are loaded again
+        assert!(cache.get(&package, "1.1.0", 1).is_none());
+
+        // Reinstalls of the same version are loaded again
+        assert!(cache.get(&package, "1.0.0", 2).is_none());
+    }
+}
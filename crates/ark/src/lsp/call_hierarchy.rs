@@ -0,0 +1,674 @@
+//
+// call_hierarchy.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use std::sync::Arc;
+
+use serde_json::json;
+use tower_lsp::lsp_types::CallHierarchyIncomingCall;
+use tower_lsp::lsp_types::CallHierarchyIncomingCallsParams;
+use tower_lsp::lsp_types::CallHierarchyItem;
+use tower_lsp::lsp_types::CallHierarchyOutgoingCall;
+use tower_lsp::lsp_types::CallHierarchyOutgoingCallsParams;
+use tower_lsp::lsp_types::CallHierarchyPrepareParams;
+use tower_lsp::lsp_types::Position;
+use tower_lsp::lsp_types::Range;
+use tower_lsp::lsp_types::SymbolKind;
+use tree_sitter::Node;
+use tree_sitter::Point;
+use url::Url;
+
+use crate::lsp;
+use crate::lsp::document::Document;
+use crate::lsp::indexer;
+use crate::lsp::indexer::IndexEntryData;
+use crate::lsp::inputs::package::Package;
+use crate::lsp::rename::assignment_target;
+use crate::lsp::rename::is_symbol_node;
+use crate::lsp::rename::resolve_scope;
+use crate::lsp::rename::symbol_name;
+use crate::lsp::rename::Scope;
+use crate::lsp::search_path::search_path;
+use crate::lsp::state::for_each_document;
+use crate::lsp::state::with_document;
+use crate::lsp::state::WorldState;
+use crate::lsp::traits::node::NodeExt;
+use crate::treesitter::NodeType;
+use crate::treesitter::NodeTypeExt;
+
+/// A call target, either a function defined in the workspace or a function
+/// exported by an installed package
+enum Target {
+    Workspace(CallHierarchyItem),
+    Package(Arc<Package>),
+}
+
+pub(crate) fn prepare_call_hierarchy(
+    params: CallHierarchyPrepareParams,
+    state: &WorldState,
+) -> anyhow::Result<Option<Vec<CallHierarchyItem>>> {
+    let uri = params.text_document_position_params.text_document.uri;
+    let position = params.text_document_position_params.position;
+
+    let document = state.get_document(&uri)?;
+    let point = document.tree_sitter_point_from_lsp_position(position)?;
+
+    let Some(node) = find_identifier(document, point) else {
+        return Ok(None);
+    };
+
+    let contents = document.contents.as_str();
+    let name = symbol_name(node.node_as_str(contents)?);
+
+    // `pkg::fn` always refers to an installed package
+    if let Some(parent) = node.parent() {
+        if let NodeType::NamespaceOperator(_) = parent.node_type() {
+            if parent.child_by_field_name("rhs") != Some(node) {
+                return Ok(None);
+            }
+            let Some(package) = parent.child_by_field_name("lhs") else {
+                return Ok(None);
+            };
+            let Some(package) = state.library.get(package.node_as_str(contents)?) else {
+                return Ok(None);
+            };
+            return Ok(Some(vec![package_item(&package, name)?]));
+        }
+    }
+
+    if !is_symbol_node(&node) {
+        return Ok(None);
+    }
+
+    // Local functions are not part of the hierarchy
+    if let Scope::Function(_) = resolve_scope(node, name, contents) {
+        return Ok(None);
+    }
+
+    let item = match resolve_target(name, &uri, document, state)? {
+        Some(Target::Workspace(item)) => item,
+        Some(Target::Package(package)) => package_item(&package, name)?,
+        None => return Ok(None),
+    };
+
+    Ok(Some(vec![item]))
+}
+
+pub(crate) fn incoming_calls(
+    params: CallHierarchyIncomingCallsParams,
+    state: &WorldState,
+) -> anyhow::Result<Option<Vec<CallHierarchyIncomingCall>>> {
+    let item = params.item;
+    let package = item_package(&item);
+
+    let mut incoming: Vec<CallHierarchyIncomingCall> = Vec::new();
+
+    for_each_document(state, |uri, document| {
+        if let Err(err) = collect_incoming_calls(uri, document, &item, package, &mut incoming) {
+            lsp::log_warn!("Can't collect incoming calls in {uri}: {err:?}");
+        }
+    });
+
+    Ok(Some(incoming))
+}
+
+pub(crate) fn outgoing_calls(
+    params: CallHierarchyOutgoingCallsParams,
+    state: &WorldState,
+) -> anyhow::Result<Option<Vec<CallHierarchyOutgoingCall>>> {
+    let item = params.item;
+
+    // Functions from installed packages are leaves of the hierarchy
+    if item_package(&item).is_some() {
+        return Ok(Some(Vec::new()));
+    }
+
+    let uri = item.uri.clone();
+
+    let outgoing = match state.documents.get(&uri) {
+        Some(document) => collect_outgoing_calls(&uri, document, &item, state)?,
+        None => {
+            let path = uri
+                .to_file_path()
+                .map_err(|_| anyhow::anyhow!("Can't convert {uri} to a path"))?;
+            with_document(&path, state, |document| {
+                collect_outgoing_calls(&uri, document, &item, state)
+            })?
+        },
+    };
+
+    Ok(Some(outgoing))
+}
+
+fn collect_incoming_calls(
+    uri: &Url,
+    document: &Document,
+    item: &CallHierarchyItem,
+    package: Option<&str>,
+    incoming: &mut Vec<CallHierarchyIncomingCall>,
+) -> anyhow::Result<()> {
+    let contents = document.contents.as_str();
+
+    let mut calls = Vec::new();
+    collect_calls(document.ast.root_node(), &mut calls);
+
+    for call in calls {
+        let Some(callee) = call.child_by_field_name("function") else {
+            continue;
+        };
+        let Some(name_node) = callee_calls(&callee, &item.name, package, contents)? else {
+            continue;
+        };
+
+        let caller = caller_item(uri, document, &call)?;
+        let range = document.lsp_range_from_tree_sitter_range(name_node.range())?;
+
+        match incoming
+            .iter_mut()
+            .find(|incoming| same_item(&incoming.from, &caller))
+        {
+            Some(incoming) => incoming.from_ranges.push(range),
+            None => incoming.push(CallHierarchyIncomingCall {
+                from: caller,
+                from_ranges: vec![range],
+            }),
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the node of the function name if `callee` refers to the function
+/// `name`. Calls to package functions can be qualified with `pkg::`.
+fn callee_calls<'tree>(
+    callee: &Node<'tree>,
+    name: &str,
+    package: Option<&str>,
+    contents: &str,
+) -> anyhow::Result<Option<Node<'tree>>> {
+    if callee.is_identifier() {
+        if symbol_name(callee.node_as_str(contents)?) != name {
+            return Ok(None);
+        }
+        if resolve_scope(*callee, name, contents) != Scope::TopLevel {
+            return Ok(None);
+        }
+        return Ok(Some(*callee));
+    }
+
+    if callee.is_namespace_operator() {
+        let Some(package) = package else {
+            return Ok(None);
+        };
+        let (Some(lhs), Some(rhs)) = (
+            callee.child_by_field_name("lhs"),
+            callee.child_by_field_name("rhs"),
+        ) else {
+            return Ok(None);
+        };
+        if lhs.node_as_str(contents)? != package {
+            return Ok(None);
+        }
+        if symbol_name(rhs.node_as_str(contents)?) != name {
+            return Ok(None);
+        }
+        return Ok(Some(rhs));
+    }
+
+    Ok(None)
+}
+
+fn collect_outgoing_calls(
+    uri: &Url,
+    document: &Document,
+    item: &CallHierarchyItem,
+    state: &WorldState,
+) -> anyhow::Result<Vec<CallHierarchyOutgoingCall>> {
+    let contents = document.contents.as_str();
+
+    let point = document.tree_sitter_point_from_lsp_position(item.selection_range.start)?;
+    let Some(function) = find_definition(document, point) else {
+        return Ok(Vec::new());
+    };
+
+    let mut calls = Vec::new();
+    collect_calls(function, &mut calls);
+
+    let mut outgoing: Vec<CallHierarchyOutgoingCall> = Vec::new();
+
+    for call in calls {
+        let Some(callee) = call.child_by_field_name("function") else {
+            continue;
+        };
+
+        let (name_node, target) = if callee.is_namespace_operator() {
+            let (Some(lhs), Some(rhs)) = (
+                callee.child_by_field_name("lhs"),
+                callee.child_by_field_name("rhs"),
+            ) else {
+                continue;
+            };
+            let Some(package) = state.library.get(lhs.node_as_str(contents)?) else {
+                continue;
+            };
+            (rhs, Target::Package(package))
+        } else if callee.is_identifier() {
+            let name = symbol_name(callee.node_as_str(contents)?);
+
+            // Calls to local functions don't leave the function
+            if let Scope::Function(_) = resolve_scope(callee, name, contents) {
+                continue;
+            }
+
+            let Some(target) = resolve_target(name, uri, document, state)? else {
+                continue;
+            };
+            (callee, target)
+        } else {
+            continue;
+        };
+
+        let name = symbol_name(name_node.node_as_str(contents)?);
+        let to = match target {
+            Target::Workspace(item) => item,
+            Target::Package(package) => package_item(&package, name)?,
+        };
+        let range = document.lsp_range_from_tree_sitter_range(name_node.range())?;
+
+        match outgoing
+            .iter_mut()
+            .find(|outgoing| same_item(&outgoing.to, &to))
+        {
+            Some(outgoing) => outgoing.from_ranges.push(range),
+            None => outgoing.push(CallHierarchyOutgoingCall {
+                to,
+                from_ranges: vec![range],
+            }),
+        }
+    }
+
+    Ok(outgoing)
+}
+
+/// Resolve a top-level function name, first in the current document, then in
+/// the workspace index, and finally in the installed packages that are in
+/// scope.
+fn resolve_target(
+    name: &str,
+    uri: &Url,
+    document: &Document,
+    state: &WorldState,
+) -> anyhow::Result<Option<Target>> {
+    if let Some(item) = document_item(uri, document, name)? {
+        return Ok(Some(Target::Workspace(item)));
+    }
+
+    if let Some((file_id, entry)) = indexer::find(name) {
+        if let IndexEntryData::Function { name, arguments } = entry.data {
+            return Ok(Some(Target::Workspace(CallHierarchyItem {
+                name: name.clone(),
+                kind: SymbolKind::FUNCTION,
+                tags: None,
+                detail: Some(format!("{name}({})", arguments.join(", "))),
+                uri: file_id.as_uri().clone(),
+                range: entry.range,
+                selection_range: entry.range,
+                data: None,
+            })));
+        }
+    }
+
+    Ok(resolve_package(name, document, state).map(Target::Package))
+}
+
+/// Find a top-level function definition in a document
+fn document_item(
+    uri: &Url,
+    document: &Document,
+    name: &str,
+) -> anyhow::Result<Option<CallHierarchyItem>> {
+    let contents = document.contents.as_str();
+    let root = document.ast.root_node();
+
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        let Some((target, function)) = function_definition(&child) else {
+            continue;
+        };
+        if symbol_name(target.node_as_str(contents)?) != name {
+            continue;
+        }
+        return Ok(Some(function_item(document, uri, &target, &function)?));
+    }
+
+    Ok(None)
+}
+
+/// Find the installed package exporting `name`
+fn resolve_package(name: &str, document: &Document, state: &WorldState) -> Option<Arc<Package>> {
+    search_path(document, state)
+        .iter()
+        .filter_map(|package| state.library.get(package))
+        .find(|package| package.exported_symbols.iter().any(|symbol| symbol == name))
+}
+
+/// The caller of a call is the closest named function containing it, or the
+/// file itself for top-level calls
+fn caller_item(uri: &Url, document: &Document, call: &Node) -> anyhow::Result<CallHierarchyItem> {
+    for node in call.ancestors().skip(1) {
+        if !node.is_function_definition() {
+            continue;
+        }
+        let Some(parent) = node.parent() else {
+            continue;
+        };
+        let Some((target, function)) = function_definition(&parent) else {
+            continue;
+        };
+        if function == node {
+            return function_item(document, uri, &target, &function);
+        }
+    }
+
+    let root = document.ast.root_node();
+    let range = document.lsp_range_from_tree_sitter_range(root.range())?;
+    let name = uri
+        .path_segments()
+        .and_then(|segments| segments.last())
+        .unwrap_or(uri.as_str())
+        .to_string();
+
+    Ok(CallHierarchyItem {
+        name,
+        kind: SymbolKind::FILE,
+        tags: None,
+        detail: None,
+        uri: uri.clone(),
+        range,
+        selection_range: Range::new(range.start, range.start),
+        data: None,
+    })
+}
+
+/// Returns the assigned symbol and the function of an assignment of the form
+/// `name <- function() ...`
+fn function_definition<'tree>(node: &Node<'tree>) -> Option<(Node<'tree>, Node<'tree>)> {
+    let target = assignment_target(node)?;
+    if !target.is_identifier() {
+        return None;
+    }
+
+    let value = match node.child_by_field_name("lhs") {
+        Some(lhs) if lhs == target => node.child_by_field_name("rhs")?,
+        _ => node.child_by_field_name("lhs")?,
+    };
+
+    value.is_function_definition().then_some((target, value))
+}
+
+/// Find the function defined by the assignment whose target is at `point`
+fn find_definition(document: &Document, point: Point) -> Option<Node<'_>> {
+    let node = find_identifier(document, point)?;
+    let (target, function) = function_definition(&node.parent()?)?;
+    (target == node).then_some(function)
+}
+
+fn function_item(
+    document: &Document,
+    uri: &Url,
+    target: &Node,
+    function: &Node,
+) -> anyhow::Result<CallHierarchyItem> {
+    let contents = document.contents.as_str();
+    let name = symbol_name(target.node_as_str(contents)?).to_string();
+
+    let detail = match function.child_by_field_name("parameters") {
+        Some(parameters) => format!("{name}{}", parameters.node_as_str(contents)?),
+        None => name.clone(),
+    };
+
+    let assignment = target.parent().unwrap_or(*target);
+
+    Ok(CallHierarchyItem {
+        name,
+        kind: SymbolKind::FUNCTION,
+        tags: None,
+        detail: Some(detail),
+        uri: uri.clone(),
+        range: document.lsp_range_from_tree_sitter_range(assignment.range())?,
+        selection_range: document.lsp_range_from_tree_sitter_range(target.range())?,
+        data: None,
+    })
+}
+
+/// Functions from installed packages point to the package's `DESCRIPTION`
+/// file as we don't have access to their sources. The package name is stored
+/// in `data` so that we can recognise these items in subsequent requests.
+fn package_item(package: &Package, name: &str) -> anyhow::Result<CallHierarchyItem> {
+    let package_name = package.description.name.as_str();
+
+    let uri = Url::from_file_path(package.path.join("DESCRIPTION"))
+        .map_err(|_| anyhow::anyhow!("Can't convert {} to a URI", package.path.display()))?;
+
+    let range = Range::new(Position::new(0, 0), Position::new(0, 0));
+
+    Ok(CallHierarchyItem {
+        name: name.to_string(),
+        kind: SymbolKind::FUNCTION,
+        tags: None,
+        detail: Some(format!("{package_name}::{name}")),
+        uri,
+        range,
+        selection_range: range,
+        data: Some(json!({ "package": package_name })),
+    })
+}
+
+fn item_package(item: &CallHierarchyItem) -> Option<&str> {
+    item.data.as_ref()?.get("package")?.as_str()
+}
+
+fn same_item(x: &CallHierarchyItem, y: &CallHierarchyItem) -> bool {
+    x.uri == y.uri && x.name == y.name && x.selection_range == y.selection_range
+}
+
+fn collect_calls<'tree>(node: Node<'tree>, calls: &mut Vec<Node<'tree>>) {
+    if node.is_call() {
+        calls.push(node);
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_calls(child, calls);
+    }
+}
+
+/// Find the identifier under the cursor, also looking one character to the
+/// left so that a cursor placed just after an identifier still selects it
+fn find_identifier(document: &Document, point: Point) -> Option<Node<'_>> {
+    let root = document.ast.root_node();
+
+    let node = root.descendant_for_point_range(point, point)?;
+    if node.is_identifier() {
+        return Some(node);
+    }
+
+    if point.column == 0 {
+        return None;
+    }
+
+    let point = Point::new(point.row, point.column - 1);
+    let node = root.descendant_for_point_range(point, point)?;
+    node.is_identifier().then_some(node)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use tower_lsp::lsp_types::TextDocumentIdentifier;
+    use tower_lsp::lsp_types::TextDocumentPositionParams;
+
+    use super::*;
+    use crate::fixtures::point_from_cursor;
+    use crate::lsp::indexer::ResetIndexerGuard;
+    use crate::lsp::inputs::library::Library;
+    use crate::lsp::inputs::package_description::Dcf;
+    use crate::lsp::inputs::package_description::Description;
+    use crate::lsp::inputs::package_namespace::Namespace;
+    use crate::lsp::util::test_path;
+
+    fn mock_package(name: &str, exports: &[&str]) -> Package {
+        let namespace = Namespace {
+            exports: exports.iter().map(|export| export.to_string()).collect(),
+            imports: vec![],
+            package_imports: vec![],
+        };
+        let description = Description {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            depends: vec![],
+            fields: Dcf::new(),
+        };
+        let path = PathBuf::from(format!("/mock/{name}"));
+        Package::from_parts(path, description, namespace)
+    }
+
+    fn prepare(state: &WorldState, uri: &Url, point: Point) -> Vec<CallHierarchyItem> {
+        let params = CallHierarchyPrepareParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                position: Position::new(point.row as u32, point.column as u32),
+            },
+            work_done_progress_params: Default::default(),
+        };
+        prepare_call_hierarchy(params, state)
+            .unwrap()
+            .unwrap_or_default()
+    }
+
+    fn insert_document(state: &mut WorldState, file: &str, code: &str) -> Url {
+        let uri = test_path(file);
+        let document = Document::new(code, None);
+        indexer::update(&document, &uri).unwrap();
+        state.documents.insert(uri.clone(), document);
+        uri
+    }
+
+    #[test]
+    fn test_prepare_call_hierarchy() {
+        let _guard = ResetIndexerGuard;
+
+        let mut state = WorldState::default();
+        state.library = Library::default().insert("pkg", mock_package("pkg", &["helper"]));
+
+        let (code, point) = point_from_cursor("f <- function(x) g(x)\nf@(1)\n");
+        let uri = insert_document(&mut state, "prepare.R", &code);
+
+        let items = prepare(&state, &uri, point);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].name, "f");
+        assert_eq!(items[0].detail.as_deref(), Some("f(x)"));
+        assert_eq!(items[0].selection_range.start, Position::new(0, 0));
+
+        // Package functions are leaves
+        let (code, point) = point_from_cursor("pkg::help@er()\n");
+        let uri = insert_document(&mut state, "prepare_pkg.R", &code);
+
+        let items = prepare(&state, &uri, point);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].detail.as_deref(), Some("pkg::helper"));
+        assert_eq!(item_package(&items[0]), Some("pkg"));
+
+        // Unknown functions
+        let (code, point) = point_from_cursor("unkn@own()\n");
+        let uri = insert_document(&mut state, "prepare_unknown.R", &code);
+        assert!(prepare(&state, &uri, point).is_empty());
+    }
+
+    #[test]
+    fn test_incoming_calls() {
+        let _guard = ResetIndexerGuard;
+
+        let mut state = WorldState::default();
+
+        let (code, point) = point_from_cursor("callee@ <- function() 1\n");
+        let uri = insert_document(&mut state, "incoming_callee.R", &code);
+        insert_document(
+            &mut state,
+            "incoming_callers.R",
+            "a <- function() {\n  callee()\n  callee()\n}\ncallee()\nb <- function(callee) callee()\n",
+        );
+
+        let item = prepare(&state, &uri, point).pop().unwrap();
+        let params = CallHierarchyIncomingCallsParams {
+            item,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+
+        let mut incoming = incoming_calls(params, &state).unwrap().unwrap();
+        incoming.sort_by(|x, y| x.from.name.cmp(&y.from.name));
+
+        // `b` calls its argument, not the top-level function
+        assert_eq!(incoming.len(), 2);
+
+        assert_eq!(incoming[0].from.name, "a");
+        assert_eq!(incoming[0].from.kind, SymbolKind::FUNCTION);
+        assert_eq!(incoming[0].from_ranges.len(), 2);
+
+        assert_eq!(incoming[1].from.name, "incoming_callers.R");
+        assert_eq!(incoming[1].from.kind, SymbolKind::FILE);
+        assert_eq!(incoming[1].from_ranges, vec![Range::new(
+            Position::new(4, 0),
+            Position::new(4, 6)
+        )]);
+    }
+
+    #[test]
+    fn test_outgoing_calls() {
+        let _guard = ResetIndexerGuard;
+
+        let mut state = WorldState::default();
+        state.library = Library::default()
+            .insert("pkg", mock_package("pkg", &["helper"]))
+            .insert("base", mock_package("base", &["paste"]));
+
+        insert_document(&mut state, "outgoing_helpers.R", "util <- function() 1\n");
+        let (code, point) = point_from_cursor(
+            "library(pkg)\nm@ain <- function(x) {\n  local <- function() 1\n  local()\n  util()\n  util()\n  helper()\n  paste(x)\n  pkg::helper()\n  unknown()\n}\n",
+        );
+        let uri = insert_document(&mut state, "outgoing_main.R", &code);
+
+        let item = prepare(&state, &uri, point).pop().unwrap();
+        let params = CallHierarchyOutgoingCallsParams {
+            item,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+
+        let outgoing = outgoing_calls(params, &state).unwrap().unwrap();
+        let outgoing: Vec<(String, Option<String>, usize)> = outgoing
+            .into_iter()
+            .map(|call| (call.to.name, call.to.detail, call.from_ranges.len()))
+            .collect();
+
+        assert_eq!(outgoing, vec![
+            (String::from("util"), Some(String::from("util()")), 2),
+            (String::from("helper"), Some(String::from("pkg::helper")), 2),
+            (String::from("paste"), Some(String::from("base::paste")), 1),
+        ]);
+
+        // Package functions don't have outgoing calls
+        let item = package_item(&mock_package("pkg", &["helper"]), "helper").unwrap();
+        let params = CallHierarchyOutgoingCallsParams {
+            item,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        assert!(outgoing_calls(params, &state).unwrap().unwrap().is_empty());
+    }
+}
@@ -9,14 +9,20 @@ use tower_lsp::lsp_types;
 use tower_lsp::lsp_types::CodeActionKind;
 use tower_lsp::lsp_types::CodeActionOptions;
 use tower_lsp::lsp_types::CodeActionProviderCapability;
+use tower_lsp::lsp_types::OneOf;
+use tower_lsp::lsp_types::RenameOptions;
 use tower_lsp::lsp_types::WorkDoneProgressOptions;
 
 /// Capabilities negotiated with [lsp_types::ClientCapabilities]
 #[derive(Debug)]
 pub(crate) struct Capabilities {
     dynamic_registration_for_did_change_configuration: bool,
+    dynamic_registration_for_did_change_watched_files: bool,
     code_action_literal_support: bool,
     workspace_edit_document_changes: bool,
+    prepare_rename_support: bool,
+    work_done_progress: bool,
+    workspace_diagnostic_refresh: bool,
 }
 
 impl Capabilities {
@@ -28,6 +34,13 @@ impl Capabilities {
             .and_then(|did_change_configuration| did_change_configuration.dynamic_registration)
             .unwrap_or(false);
 
+        let dynamic_registration_for_did_change_watched_files = client_capabilities
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.did_change_watched_files)
+            .and_then(|did_change_watched_files| did_change_watched_files.dynamic_registration)
+            .unwrap_or(false);
+
         // In theory the client also tells us which code action kinds it supports inside
         // `code_action_literal_support`, but clients are guaranteed to ignore any they
         // don't support, so we just return `true` if the field exists (same as
@@ -46,10 +59,34 @@ impl Capabilities {
             .and_then(|workspace_edit| workspace_edit.document_changes)
             .map_or(false, |document_changes| document_changes);
 
+        let prepare_rename_support = client_capabilities
+            .text_document
+            .as_ref()
+            .and_then(|text_document| text_document.rename.as_ref())
+            .and_then(|rename| rename.prepare_support)
+            .unwrap_or(false);
+
+        let work_done_progress = client_capabilities
+            .window
+            .as_ref()
+            .and_then(|window| window.work_done_progress)
+            .unwrap_or(false);
+
+        let workspace_diagnostic_refresh = client_capabilities
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.diagnostic.as_ref())
+            .and_then(|diagnostic| diagnostic.refresh_support)
+            .unwrap_or(false);
+
         Self {
             dynamic_registration_for_did_change_configuration,
+            dynamic_registration_for_did_change_watched_files,
             code_action_literal_support,
             workspace_edit_document_changes,
+            prepare_rename_support,
+            work_done_progress,
+            workspace_diagnostic_refresh,
         }
     }
 
@@ -57,6 +94,10 @@ impl Capabilities {
         self.dynamic_registration_for_did_change_configuration
     }
 
+    pub(crate) fn dynamic_registration_for_did_change_watched_files(&self) -> bool {
+        self.dynamic_registration_for_did_change_watched_files
+    }
+
     pub(crate) fn code_action_literal_support(&self) -> bool {
         self.code_action_literal_support
     }
@@ -85,16 +126,63 @@ impl Capabilities {
         return self;
     }
 
+    pub(crate) fn prepare_rename_support(&self) -> bool {
+        self.prepare_rename_support
+    }
+
+    pub(crate) fn work_done_progress(&self) -> bool {
+        self.work_done_progress
+    }
+
+    // Currently only used for testing
+    #[cfg(test)]
+    pub(crate) fn with_work_done_progress(mut self, work_done_progress: bool) -> Self {
+        self.work_done_progress = work_done_progress;
+        return self;
+    }
+
+    pub(crate) fn workspace_diagnostic_refresh(&self) -> bool {
+        self.workspace_diagnostic_refresh
+    }
+
+    // Currently only used for testing
+    #[cfg(test)]
+    pub(crate) fn with_workspace_diagnostic_refresh(
+        mut self,
+        workspace_diagnostic_refresh: bool,
+    ) -> Self {
+        self.workspace_diagnostic_refresh = workspace_diagnostic_refresh;
+        return self;
+    }
+
+    pub(crate) fn rename_provider_capability(&self) -> Option<OneOf<bool, RenameOptions>> {
+        // Clients that don't support `prepareRename` expect a plain boolean
+        if !self.prepare_rename_support() {
+            return Some(OneOf::Left(true));
+        }
+
+        Some(OneOf::Right(RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: WorkDoneProgressOptions::default(),
+        }))
+    }
+
     pub(crate) fn code_action_provider_capability(&self) -> Option<CodeActionProviderCapability> {
         if !self.code_action_literal_support() {
             return None;
         }
 
-        // Currently we only support documentation generating code actions, which don't
-        // map to an existing kind. rust-analyzer maps them to `EMPTY`, so we follow suit.
-        // Currently no code actions require delayed resolution.
+        // Documentation generating code actions don't map to an existing kind.
+        // rust-analyzer maps them to `EMPTY`, so we follow suit. Fixes attached to
+        // diagnostics are `QUICKFIX`es. Currently no code actions require delayed
+        // resolution.
         Some(CodeActionProviderCapability::Options(CodeActionOptions {
-            code_action_kinds: Some(vec![CodeActionKind::EMPTY]),
+            code_action_kinds: Some(vec![
+                CodeActionKind::EMPTY,
+                CodeActionKind::QUICKFIX,
+                CodeActionKind::REFACTOR_EXTRACT,
+                CodeActionKind::REFACTOR_INLINE,
+            ]),
             work_done_progress_options: WorkDoneProgressOptions::default(),
             resolve_provider: Some(false),
         }))
@@ -108,8 +196,12 @@ impl Default for Capabilities {
     fn default() -> Self {
         Self {
             dynamic_registration_for_did_change_configuration: false,
+            dynamic_registration_for_did_change_watched_files: false,
             code_action_literal_support: false,
             workspace_edit_document_changes: false,
+            prepare_rename_support: false,
+            work_done_progress: false,
+            workspace_diagnostic_refresh: false,
         }
     }
 }
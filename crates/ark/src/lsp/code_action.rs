@@ -14,9 +14,17 @@ use tree_sitter::Range;
 use url::Url;
 
 use crate::lsp::capabilities::Capabilities;
+use crate::lsp::code_action::extract::extract_function;
+use crate::lsp::code_action::extract::extract_variable;
+use crate::lsp::code_action::inline::inline_variable;
+use crate::lsp::code_action::quick_fix::quick_fixes;
 use crate::lsp::code_action::roxygen::roxygen_documentation;
 use crate::lsp::document::Document;
+use crate::lsp::state::WorldState;
 
+mod extract;
+mod inline;
+mod quick_fix;
 mod roxygen;
 
 /// A small wrapper around [CodeActionResponse] that make a few things more ergonomic
@@ -28,11 +36,24 @@ pub(crate) fn code_actions(
     uri: &Url,
     document: &Document,
     range: Range,
+    diagnostics: &[lsp_types::Diagnostic],
+    state: &WorldState,
     capabilities: &Capabilities,
 ) -> lsp_types::CodeActionResponse {
     let mut actions = CodeActions::new();
 
+    quick_fixes(
+        &mut actions,
+        uri,
+        document,
+        diagnostics,
+        state,
+        capabilities,
+    );
     roxygen_documentation(&mut actions, uri, document, range, capabilities);
+    extract_function(&mut actions, uri, document, range, state, capabilities);
+    extract_variable(&mut actions, uri, document, range, capabilities);
+    inline_variable(&mut actions, uri, document, range, state, capabilities);
 
     actions.into_response()
 }
@@ -61,18 +82,30 @@ pub(crate) fn code_action_workspace_text_edit(
     version: Option<i32>,
     edits: Vec<lsp_types::TextEdit>,
     capabilities: &Capabilities,
+) -> lsp_types::WorkspaceEdit {
+    workspace_text_edits(vec![(uri, version, edits)], capabilities)
+}
+
+/// Creates a `WorkspaceEdit` composed of `TextEdit`s to apply to one or more
+/// documents, each identified by its URI and the version the edits apply to
+pub(crate) fn workspace_text_edits(
+    document_edits: Vec<(Url, Option<i32>, Vec<lsp_types::TextEdit>)>,
+    capabilities: &Capabilities,
 ) -> lsp_types::WorkspaceEdit {
     if capabilities.workspace_edit_document_changes() {
         // Prefer the versioned `DocumentChanges` feature
-        let edit = lsp_types::TextDocumentEdit {
-            text_document: lsp_types::OptionalVersionedTextDocumentIdentifier { uri, version },
-            edits: edits
-                .into_iter()
-                .map(|edit| lsp_types::OneOf::Left(edit))
-                .collect(),
-        };
-
-        let document_changes = lsp_types::DocumentChanges::Edits(vec![edit]);
+        let edits = document_edits
+            .into_iter()
+            .map(|(uri, version, edits)| lsp_types::TextDocumentEdit {
+                text_document: lsp_types::OptionalVersionedTextDocumentIdentifier { uri, version },
+                edits: edits
+                    .into_iter()
+                    .map(|edit| lsp_types::OneOf::Left(edit))
+                    .collect(),
+            })
+            .collect();
+
+        let document_changes = lsp_types::DocumentChanges::Edits(edits);
 
         lsp_types::WorkspaceEdit {
             changes: None,
@@ -82,7 +115,9 @@ pub(crate) fn code_action_workspace_text_edit(
     } else {
         // Fall back to hash map of `TextEdit`s if the client doesn't support `DocumentChanges`
         let mut changes = HashMap::new();
-        changes.insert(uri, edits);
+        for (uri, _version, edits) in document_edits {
+            changes.insert(uri, edits);
+        }
 
         lsp_types::WorkspaceEdit {
             changes: Some(changes),
@@ -109,3 +144,58 @@ impl CodeActions {
         self.response
     }
 }
+
+/// Applies the edits of a code action to the document and returns the title
+/// of the action along with the new contents. The edits must be expressed as
+/// `DocumentChanges`.
+#[cfg(test)]
+pub(crate) fn apply_code_action(
+    document: &Document,
+    action: lsp_types::CodeActionOrCommand,
+) -> (String, String) {
+    let lsp_types::CodeActionOrCommand::CodeAction(action) = action else {
+        panic!("Unexpected command");
+    };
+
+    let document_changes = action.edit.unwrap().document_changes.unwrap();
+    let lsp_types::DocumentChanges::Edits(text_document_edits) = document_changes else {
+        panic!("Unexpected document changes");
+    };
+
+    let mut edits: Vec<lsp_types::TextEdit> = text_document_edits
+        .into_iter()
+        .flat_map(|text_document_edit| text_document_edit.edits)
+        .map(|edit| match edit {
+            lsp_types::OneOf::Left(edit) => edit,
+            lsp_types::OneOf::Right(edit) => edit.text_edit,
+        })
+        .collect();
+    edits.sort_by_key(|edit| std::cmp::Reverse(edit.range.start));
+
+    let mut contents = document.contents.clone();
+    for edit in edits {
+        let range = document
+            .tree_sitter_range_from_lsp_range(edit.range)
+            .unwrap();
+        contents.replace_range(range.start_byte..range.end_byte, &edit.new_text);
+    }
+
+    (action.title, contents)
+}
+
+/// Interprets `@` and `%` as the start and end of the user's selection
+#[cfg(test)]
+pub(crate) fn selection_from_cursors(text: &str) -> (String, Range) {
+    use crate::fixtures::point_and_offset_from_cursor;
+
+    let (text, start_point, start_byte) = point_and_offset_from_cursor(text, b'@');
+    let (text, end_point, end_byte) = point_and_offset_from_cursor(&text, b'%');
+
+    let range = Range {
+        start_byte,
+        end_byte,
+        start_point,
+        end_point,
+    };
+    (text, range)
+}
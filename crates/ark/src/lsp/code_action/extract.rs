@@ -0,0 +1,718 @@
+//
+// extract.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use std::collections::HashSet;
+
+use harp::syntax::sym_quote_invalid;
+use itertools::Itertools;
+use tower_lsp::lsp_types;
+use tree_sitter::Node;
+use tree_sitter::Point;
+use tree_sitter::Range;
+use url::Url;
+
+use crate::lsp::capabilities::Capabilities;
+use crate::lsp::code_action::code_action;
+use crate::lsp::code_action::code_action_workspace_text_edit;
+use crate::lsp::code_action::CodeActions;
+use crate::lsp::diagnostics::outermost_scope;
+use crate::lsp::diagnostics::scope_symbol_occurrences;
+use crate::lsp::diagnostics::SymbolAccess;
+use crate::lsp::diagnostics::SymbolOccurrence;
+use crate::lsp::document::Document;
+use crate::lsp::indent::new_line_indent;
+use crate::lsp::rename::assignment_target;
+use crate::lsp::rename::symbol_name;
+use crate::lsp::state::WorldState;
+use crate::lsp::traits::node::NodeExt;
+use crate::treesitter::BinaryOperatorType;
+use crate::treesitter::NodeType;
+use crate::treesitter::NodeTypeExt;
+use crate::treesitter::UnaryOperatorType;
+
+/// Extract the selected statements into a new top level function. Variables
+/// defined before the selection and used in it become the parameters of the
+/// function, and variables assigned in the selection and used after it become
+/// its return value.
+pub(crate) fn extract_function(
+    actions: &mut CodeActions,
+    uri: &Url,
+    document: &Document,
+    range: Range,
+    state: &WorldState,
+    capabilities: &Capabilities,
+) -> Option<()> {
+    if !capabilities.code_action_literal_support() {
+        // This code action returns literal `CodeAction`s, so must have support for them
+        return None;
+    }
+
+    let statements = selected_statements(document, range)?;
+    let first = *statements.first()?;
+    let last = *statements.last()?;
+
+    // Moving `return()`, `break`, or `next` to another function would change
+    // the control flow
+    if statements
+        .iter()
+        .any(|statement| has_escaping_jump(*statement, false))
+    {
+        return None;
+    }
+
+    let contents = &document.contents;
+    let start = first.start_byte();
+    let end = last.end_byte();
+
+    // Occurrences bound in functions defined within the selection are local
+    // to these functions
+    let mut inner_scopes = HashSet::new();
+    for statement in &statements {
+        collect_function_scopes(*statement, &mut inner_scopes);
+    }
+
+    // Variables defined before the selection and used after it are bound
+    // within the enclosing function, or at top level
+    let scope = outermost_scope(first);
+    let occurrences = scope_symbol_occurrences(document, state, scope);
+    let occurrences: Vec<&SymbolOccurrence> = occurrences
+        .iter()
+        .filter(|occurrence| !inner_scopes.contains(&occurrence.scope))
+        .collect();
+
+    let is_selected = |occurrence: &SymbolOccurrence| {
+        occurrence.range.start_byte >= start && occurrence.range.end_byte <= end
+    };
+
+    // Variables first read in the selection that are defined before it.
+    // Occurrences are recorded in evaluation order.
+    let mut seen = HashSet::new();
+    let mut parameters: Vec<&str> = Vec::new();
+    for occurrence in occurrences
+        .iter()
+        .filter(|occurrence| is_selected(occurrence))
+    {
+        if !seen.insert(occurrence.name.as_str()) || occurrence.access != SymbolAccess::Read {
+            continue;
+        }
+        if occurrences
+            .iter()
+            .filter(|other| same_binding(other, occurrence))
+            .any(|other| other.access == SymbolAccess::Write && other.range.end_byte <= start)
+        {
+            parameters.push(&occurrence.name);
+        }
+    }
+
+    // Variables assigned in the selection that are used after it
+    let mut outputs: Vec<&str> = Vec::new();
+    for occurrence in occurrences
+        .iter()
+        .filter(|occurrence| is_selected(occurrence))
+    {
+        if occurrence.access != SymbolAccess::Write || outputs.contains(&occurrence.name.as_str()) {
+            continue;
+        }
+        if occurrences
+            .iter()
+            .filter(|other| same_binding(other, occurrence))
+            .any(|other| other.access == SymbolAccess::Read && other.range.start_byte >= end)
+        {
+            outputs.push(&occurrence.name);
+        }
+    }
+
+    let names = document_names(document);
+    let name = fresh_name(&names, "new_function");
+    let parameters = parameters.into_iter().map(sym_quote_invalid).collect_vec();
+    let outputs = outputs.into_iter().map(sym_quote_invalid).collect_vec();
+
+    // The body of the new function, indented by one level
+    let indent = new_line_indent(&document.config.indent, document.config.indent.indent_size);
+    let column = first.start_position().column;
+
+    let mut lines: Vec<String> = contents[start..end]
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 {
+                line
+            } else {
+                strip_indent(line, column)
+            }
+        })
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{indent}{line}")
+            }
+        })
+        .collect();
+
+    match outputs.as_slice() {
+        [] => {},
+        [output] => lines.push(format!("{indent}{output}")),
+        outputs => {
+            let values = outputs
+                .iter()
+                .map(|output| format!("{output} = {output}"))
+                .join(", ");
+            lines.push(format!("{indent}list({values})"));
+        },
+    }
+
+    let definition = format!(
+        "{name} <- function({parameters}) {{\n{body}\n}}\n\n",
+        parameters = parameters.join(", "),
+        body = lines.join("\n")
+    );
+
+    // The call replacing the selection
+    let call = format!("{name}({})", parameters.join(", "));
+    let call = match outputs.as_slice() {
+        [] => call,
+        [output] => format!("{output} <- {call}"),
+        outputs => {
+            let result = fresh_name(&names, "result");
+            let line_indent = statement_indent(document, &first);
+            let mut lines = vec![format!("{result} <- {call}")];
+            for output in outputs {
+                lines.push(format!("{line_indent}{output} <- {result}${output}"));
+            }
+            lines.join("\n")
+        },
+    };
+
+    let selection = lsp_range(document, first.start_position(), last.end_position())?;
+
+    // The new function is defined before the top level expression containing
+    // the selection
+    let top_level = first
+        .ancestors()
+        .find(|node| node.parent().is_some_and(|parent| parent.is_program()))?;
+
+    let edits = if top_level == first {
+        vec![lsp_types::TextEdit::new(
+            selection,
+            format!("{definition}{call}"),
+        )]
+    } else {
+        let point = Point::new(top_level.start_position().row, 0);
+        vec![
+            lsp_types::TextEdit::new(lsp_range(document, point, point)?, definition),
+            lsp_types::TextEdit::new(selection, call),
+        ]
+    };
+
+    let edit = code_action_workspace_text_edit(uri.clone(), document.version, edits, capabilities);
+
+    actions.add_action(code_action(
+        "Extract into function".to_string(),
+        lsp_types::CodeActionKind::REFACTOR_EXTRACT,
+        edit,
+    ))
+}
+
+/// Hoist the selected expression into a new variable, defined just before the
+/// statement containing the expression
+pub(crate) fn extract_variable(
+    actions: &mut CodeActions,
+    uri: &Url,
+    document: &Document,
+    range: Range,
+    capabilities: &Capabilities,
+) -> Option<()> {
+    if !capabilities.code_action_literal_support() {
+        // This code action returns literal `CodeAction`s, so must have support for them
+        return None;
+    }
+
+    let contents = &document.contents;
+    let (start, end) = trim_selection(contents, range)?;
+
+    // The selection must match an expression exactly
+    let node = document
+        .ast
+        .root_node()
+        .named_descendant_for_byte_range(start, end)?;
+
+    if node.start_byte() != start || node.end_byte() != end || !is_extractable(&node) {
+        return None;
+    }
+
+    let statement = hoisting_statement(node)?;
+
+    let name = fresh_name(&document_names(document), "new_variable");
+
+    let text = node.node_as_str(contents).ok()?;
+    let binding = format!(
+        "{name} <- {text}\n{indent}",
+        indent = statement_indent(document, &statement)
+    );
+
+    let range = lsp_range(document, node.start_position(), node.end_position())?;
+
+    let edits = if statement.start_byte() == node.start_byte() {
+        vec![lsp_types::TextEdit::new(range, format!("{binding}{name}"))]
+    } else {
+        let point = statement.start_position();
+        vec![
+            lsp_types::TextEdit::new(lsp_range(document, point, point)?, binding),
+            lsp_types::TextEdit::new(range, name),
+        ]
+    };
+
+    let edit = code_action_workspace_text_edit(uri.clone(), document.version, edits, capabilities);
+
+    actions.add_action(code_action(
+        "Extract into variable".to_string(),
+        lsp_types::CodeActionKind::REFACTOR_EXTRACT,
+        edit,
+    ))
+}
+
+/// The statements of a program or braced expression that are covered by the
+/// selection. Statements partially covered by the selection are not supported.
+fn selected_statements<'tree>(document: &'tree Document, range: Range) -> Option<Vec<Node<'tree>>> {
+    let (start, end) = trim_selection(&document.contents, range)?;
+
+    let node = document
+        .ast
+        .root_node()
+        .descendant_for_byte_range(start, end)?;
+
+    // A fully selected braced expression is a statement of the parent block
+    let block = node.ancestors().find(|node| {
+        node.is_program() ||
+            (node.is_braced_expression() && (node.start_byte() < start || node.end_byte() > end))
+    })?;
+
+    let mut cursor = block.walk();
+    let statements: Vec<Node> = block
+        .named_children(&mut cursor)
+        .filter(|child| child.end_byte() > start && child.start_byte() < end)
+        .collect();
+
+    if statements
+        .iter()
+        .any(|child| child.start_byte() < start || child.end_byte() > end || child.has_error())
+    {
+        return None;
+    }
+
+    if statements.iter().all(|child| child.is_comment()) {
+        return None;
+    }
+
+    Some(statements)
+}
+
+/// Byte range of the selection without surrounding whitespace. `None` for
+/// empty selections.
+fn trim_selection(contents: &str, range: Range) -> Option<(usize, usize)> {
+    let text = contents.get(range.start_byte..range.end_byte)?;
+
+    let trimmed = text.trim_start();
+    let start = range.start_byte + (text.len() - trimmed.len());
+    let end = start + trimmed.trim_end().len();
+
+    (start < end).then_some((start, end))
+}
+
+/// Whether evaluating `node` may return from the enclosing function, or jump
+/// out of an enclosing loop
+fn has_escaping_jump(node: Node, in_loop: bool) -> bool {
+    match node.node_type() {
+        NodeType::Return => return true,
+        NodeType::Break | NodeType::Next if !in_loop => return true,
+        NodeType::FunctionDefinition => return false,
+        _ => {},
+    }
+
+    let in_loop = in_loop ||
+        matches!(
+            node.node_type(),
+            NodeType::ForStatement | NodeType::WhileStatement | NodeType::RepeatStatement
+        );
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if has_escaping_jump(child, in_loop) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Whether two occurrences refer to the same binding
+fn same_binding(x: &SymbolOccurrence, y: &SymbolOccurrence) -> bool {
+    x.name == y.name && x.scope == y.scope
+}
+
+/// Collect the scope identifiers of functions defined within `node`
+fn collect_function_scopes(node: Node, scopes: &mut HashSet<usize>) {
+    if node.is_function_definition() {
+        scopes.insert(node.id());
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_function_scopes(child, scopes);
+    }
+}
+
+/// Can the expression be moved to a variable? Identifiers are already
+/// variables, and a few nodes are not expressions on their own.
+fn is_extractable(node: &Node) -> bool {
+    match node.node_type() {
+        NodeType::Identifier |
+        NodeType::Comment |
+        NodeType::Argument |
+        NodeType::Arguments |
+        NodeType::Parameter |
+        NodeType::Parameters |
+        NodeType::Program |
+        NodeType::BracedExpression |
+        NodeType::Dots |
+        NodeType::DotDotI |
+        NodeType::StringContent |
+        NodeType::EscapeSequence |
+        NodeType::Error |
+        NodeType::Anonymous(_) => return false,
+        _ => {},
+    }
+
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+
+    match parent.node_type() {
+        // A whole statement
+        NodeType::Program | NodeType::BracedExpression => false,
+        NodeType::NamespaceOperator(_) => false,
+        NodeType::ExtractOperator(_) => parent.child_by_field_name("rhs") != Some(*node),
+        NodeType::Argument => parent.child_by_field_name("name") != Some(*node),
+        _ => assignment_target(&parent) != Some(*node),
+    }
+}
+
+/// The statement before which the expression can be evaluated without
+/// changing its semantics. `None` when the expression is evaluated lazily,
+/// conditionally, or repeatedly.
+fn hoisting_statement(node: Node) -> Option<Node> {
+    let mut child = node;
+
+    loop {
+        let parent = child.parent()?;
+
+        if parent.is_program() || parent.is_braced_expression() {
+            return Some(child);
+        }
+
+        let hoistable = match parent.node_type() {
+            NodeType::FunctionDefinition |
+            NodeType::Parameters |
+            NodeType::WhileStatement |
+            NodeType::RepeatStatement |
+            NodeType::UnaryOperator(UnaryOperatorType::Tilde) |
+            NodeType::BinaryOperator(BinaryOperatorType::Tilde) => false,
+            NodeType::ForStatement => parent.child_by_field_name("sequence") == Some(child),
+            NodeType::IfStatement => parent.child_by_field_name("condition") == Some(child),
+            NodeType::BinaryOperator(BinaryOperatorType::And2 | BinaryOperatorType::Or2) => {
+                parent.child_by_field_name("lhs") == Some(child)
+            },
+            _ => true,
+        };
+
+        if !hoistable {
+            return None;
+        }
+
+        child = parent;
+    }
+}
+
+/// The names of the identifiers of the document
+fn document_names(document: &Document) -> HashSet<&str> {
+    let mut names = HashSet::new();
+    collect_identifiers(document.ast.root_node(), &document.contents, &mut names);
+    names
+}
+
+fn collect_identifiers<'a>(node: Node, contents: &'a str, names: &mut HashSet<&'a str>) {
+    if node.is_identifier() {
+        if let Ok(text) = node.node_as_str(contents) {
+            names.insert(symbol_name(text));
+        }
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_identifiers(child, contents, names);
+    }
+}
+
+/// A name that is not used in the document, based on `base`
+fn fresh_name(names: &HashSet<&str>, base: &str) -> String {
+    std::iter::once(base.to_string())
+        .chain((2..).map(|i| format!("{base}{i}")))
+        .find(|name| !names.contains(name.as_str()))
+        .unwrap()
+}
+
+/// The whitespace preceding a statement on its line, or nothing if the
+/// statement doesn't start its line
+fn statement_indent(document: &Document, node: &Node) -> String {
+    let position = node.start_position();
+
+    document
+        .get_line(position.row)
+        .and_then(|line| line.get(..position.column))
+        .filter(|prefix| prefix.chars().all(|c| c == ' ' || c == '\t'))
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Remove up to `column` bytes of leading whitespace
+fn strip_indent(line: &str, column: usize) -> &str {
+    let n = line
+        .bytes()
+        .take(column)
+        .take_while(|byte| *byte == b' ' || *byte == b'\t')
+        .count();
+    &line[n..]
+}
+
+fn lsp_range(document: &Document, start: Point, end: Point) -> Option<lsp_types::Range> {
+    Some(lsp_types::Range::new(
+        document.lsp_position_from_tree_sitter_point(start).ok()?,
+        document.lsp_position_from_tree_sitter_point(end).ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsp::code_action::apply_code_action;
+    use crate::lsp::code_action::selection_from_cursors;
+    use crate::lsp::util::test_path;
+
+    type Extract =
+        fn(&mut CodeActions, &Url, &Document, Range, &WorldState, &Capabilities) -> Option<()>;
+
+    fn extract_variable_action(
+        actions: &mut CodeActions,
+        uri: &Url,
+        document: &Document,
+        range: Range,
+        _state: &WorldState,
+        capabilities: &Capabilities,
+    ) -> Option<()> {
+        extract_variable(actions, uri, document, range, capabilities)
+    }
+
+    fn extract_test(text: &str, extract: Extract) -> Option<String> {
+        let mut actions = CodeActions::new();
+
+        let capabilities = Capabilities::default()
+            .with_code_action_literal_support(true)
+            .with_workspace_edit_document_changes(true);
+
+        let (text, range) = selection_from_cursors(text);
+        let document = Document::new(&text, None);
+        let state = WorldState::default();
+
+        extract(
+            &mut actions,
+            &test_path("test.R"),
+            &document,
+            range,
+            &state,
+            &capabilities,
+        );
+
+        let mut actions = actions.into_response();
+        assert!(actions.len() <= 1);
+
+        let (_title, contents) = apply_code_action(&document, actions.pop()?);
+        Some(contents)
+    }
+
+    #[test]
+    fn test_extract_function() {
+        let text = "
+x <- 1
+y <- 2
+@z <- x + y
+w <- z * 2%
+print(w)
+";
+        assert_eq!(
+            extract_test(text, extract_function).unwrap(),
+            "
+x <- 1
+y <- 2
+new_function <- function(x, y) {
+  z <- x + y
+  w <- z * 2
+  w
+}
+
+w <- new_function(x, y)
+print(w)
+"
+        );
+    }
+
+    #[test]
+    fn test_extract_function_multiple_outputs() {
+        let text = "
+f <- function(a) {
+  b <- a + 1
+  @c <- b * 2
+  d <- c + a%
+  c + d
+}
+";
+        assert_eq!(
+            extract_test(text, extract_function).unwrap(),
+            "
+new_function <- function(b, a) {
+  c <- b * 2
+  d <- c + a
+  list(c = c, d = d)
+}
+
+f <- function(a) {
+  b <- a + 1
+  result <- new_function(b, a)
+  c <- result$c
+  d <- result$d
+  c + d
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_extract_function_fresh_name() {
+        // Top-level names outside the enclosing function are taken too
+        let text = "
+new_function <- 1
+f <- function(a) {
+  @b <- a + 1%
+  b
+}
+";
+        assert_eq!(
+            extract_test(text, extract_function).unwrap(),
+            "
+new_function <- 1
+new_function2 <- function(a) {
+  b <- a + 1
+  b
+}
+
+f <- function(a) {
+  b <- new_function2(a)
+  b
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_extract_function_unsupported() {
+        // Partially selected statement
+        assert!(extract_test("x <- @1 + 2%\n", extract_function).is_none());
+
+        // Empty selection
+        assert!(extract_test("x <- @%1\n", extract_function).is_none());
+
+        // Control flow escaping the selection
+        let text = "
+f <- function(x) {
+  @if (x) return(1)%
+  2
+}
+";
+        assert!(extract_test(text, extract_function).is_none());
+
+        let text = "
+for (i in 1:10) {
+  @if (i > 5) break%
+}
+";
+        assert!(extract_test(text, extract_function).is_none());
+    }
+
+    #[test]
+    fn test_extract_variable() {
+        assert_eq!(
+            extract_test("y <- f(@x + 1%, 2)\n", extract_variable_action).unwrap(),
+            "new_variable <- x + 1\ny <- f(new_variable, 2)\n"
+        );
+
+        // The expression starts the statement
+        assert_eq!(
+            extract_test("@x + 1% > 2\n", extract_variable_action).unwrap(),
+            "new_variable <- x + 1\nnew_variable > 2\n"
+        );
+
+        // The name is not used in the document yet
+        assert_eq!(
+            extract_test(
+                "new_variable <- 1\ny <- @new_variable + 1%\n",
+                extract_variable_action
+            )
+            .unwrap(),
+            "new_variable <- 1\nnew_variable2 <- new_variable + 1\ny <- new_variable2\n"
+        );
+    }
+
+    #[test]
+    fn test_extract_variable_indented() {
+        let text = "
+g <- function(x) {
+  if (@x > 1%) {
+    x
+  }
+}
+";
+        assert_eq!(
+            extract_test(text, extract_variable_action).unwrap(),
+            "
+g <- function(x) {
+  new_variable <- x > 1
+  if (new_variable) {
+    x
+  }
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_extract_variable_unsupported() {
+        // Identifiers
+        assert!(extract_test("f(@x%)\n", extract_variable_action).is_none());
+
+        // Whole statements
+        assert!(extract_test("@f(x)%\n", extract_variable_action).is_none());
+
+        // Partial expressions
+        assert!(extract_test("f(@x + %1)\n", extract_variable_action).is_none());
+
+        // Evaluated repeatedly or lazily
+        assert!(extract_test("while (@x > 1%) x <- x - 1\n", extract_variable_action).is_none());
+        assert!(extract_test("f <- function(x = @g(1)%) x\n", extract_variable_action).is_none());
+        assert!(extract_test("if (x) f(@g(1)%)\n", extract_variable_action).is_none());
+    }
+}
@@ -0,0 +1,409 @@
+//
+// inline.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use tower_lsp::lsp_types;
+use tree_sitter::Node;
+use tree_sitter::Point;
+use tree_sitter::Range;
+use url::Url;
+
+use crate::lsp::capabilities::Capabilities;
+use crate::lsp::code_action::code_action;
+use crate::lsp::code_action::code_action_workspace_text_edit;
+use crate::lsp::code_action::CodeActions;
+use crate::lsp::diagnostics::outermost_scope;
+use crate::lsp::diagnostics::scope_symbol_occurrences;
+use crate::lsp::diagnostics::SymbolAccess;
+use crate::lsp::document::Document;
+use crate::lsp::rename::assignment_target;
+use crate::lsp::rename::find_symbol_node;
+use crate::lsp::rename::is_super_assignment_target;
+use crate::lsp::rename::symbol_name;
+use crate::lsp::state::WorldState;
+use crate::lsp::traits::node::NodeExt;
+use crate::treesitter::BinaryOperatorType;
+use crate::treesitter::NodeType;
+use crate::treesitter::NodeTypeExt;
+use crate::treesitter::UnaryOperatorType;
+
+/// Replace the single use of a variable by its value, and remove the binding.
+/// The cursor may be on the binding or on the use.
+pub(crate) fn inline_variable(
+    actions: &mut CodeActions,
+    uri: &Url,
+    document: &Document,
+    range: Range,
+    state: &WorldState,
+    capabilities: &Capabilities,
+) -> Option<()> {
+    if !capabilities.code_action_literal_support() {
+        // This code action returns literal `CodeAction`s, so must have support for them
+        return None;
+    }
+
+    let contents = &document.contents;
+    let root = document.ast.root_node();
+
+    let node = find_symbol_node(document, range.start_point)?;
+
+    // The binding and its use are in the same function, unless at top level
+    let scope = outermost_scope(node);
+
+    // Walking the scope is only worth it if the symbol is assigned there
+    let name = symbol_name(node.node_as_str(contents).ok()?);
+    if !has_assignment_statement(scope, name, contents) {
+        return None;
+    }
+
+    let occurrences = scope_symbol_occurrences(document, state, scope);
+    let target = occurrences
+        .iter()
+        .find(|occurrence| occurrence.range == node.range())?;
+
+    let mut bindings = occurrences
+        .iter()
+        .filter(|occurrence| occurrence.name == target.name && occurrence.scope == target.scope);
+
+    // Exactly one definition, followed by exactly one use
+    let (Some(definition), Some(usage), None) = (bindings.next(), bindings.next(), bindings.next())
+    else {
+        return None;
+    };
+    if definition.access != SymbolAccess::Write ||
+        usage.access != SymbolAccess::Read ||
+        definition.range.end_byte > usage.range.start_byte
+    {
+        return None;
+    }
+
+    // The definition must be a local assignment statement
+    let lhs =
+        root.descendant_for_byte_range(definition.range.start_byte, definition.range.end_byte)?;
+    let assignment = lhs.parent()?;
+    if !assignment.is_binary_operator_of_kind(BinaryOperatorType::LeftAssignment) &&
+        !assignment.is_binary_operator_of_kind(BinaryOperatorType::EqualsAssignment)
+    {
+        return None;
+    }
+    if assignment.child_by_field_name("lhs") != Some(lhs) {
+        return None;
+    }
+    if !assignment
+        .parent()
+        .is_some_and(|parent| parent.is_program() || parent.is_braced_expression())
+    {
+        return None;
+    }
+
+    let value = assignment.child_by_field_name("rhs")?;
+    let usage = root.descendant_for_byte_range(usage.range.start_byte, usage.range.end_byte)?;
+
+    if !is_inlinable_usage(usage, assignment) {
+        return None;
+    }
+
+    // The variables of the value must refer to the same bindings at the use
+    let reassigned = occurrences
+        .iter()
+        .filter(|occurrence| {
+            occurrence.access == SymbolAccess::Read &&
+                occurrence.range.start_byte >= value.start_byte() &&
+                occurrence.range.end_byte <= value.end_byte()
+        })
+        .any(|read| {
+            occurrences.iter().any(|write| {
+                write.access == SymbolAccess::Write &&
+                    write.name == read.name &&
+                    write.range.start_byte >= assignment.end_byte() &&
+                    write.range.start_byte < usage.start_byte()
+            })
+        });
+    if reassigned {
+        return None;
+    }
+
+    let value_text = value.node_as_str(contents).ok()?;
+    let value_text = if needs_parentheses(&value, &usage) {
+        format!("({value_text})")
+    } else {
+        value_text.to_string()
+    };
+
+    let edits = vec![
+        lsp_types::TextEdit::new(
+            statement_deletion_range(document, &assignment)?,
+            String::new(),
+        ),
+        lsp_types::TextEdit::new(
+            document
+                .lsp_range_from_tree_sitter_range(usage.range())
+                .ok()?,
+            value_text,
+        ),
+    ];
+
+    let edit = code_action_workspace_text_edit(uri.clone(), document.version, edits, capabilities);
+
+    actions.add_action(code_action(
+        format!("Inline variable `{}`", target.name),
+        lsp_types::CodeActionKind::REFACTOR_INLINE,
+        edit,
+    ))
+}
+
+/// Whether `node` contains an assignment statement to `name`
+fn has_assignment_statement(node: Node, name: &str, contents: &str) -> bool {
+    if node.is_binary_operator_of_kind(BinaryOperatorType::LeftAssignment) ||
+        node.is_binary_operator_of_kind(BinaryOperatorType::EqualsAssignment)
+    {
+        let is_statement = node
+            .parent()
+            .is_some_and(|parent| parent.is_program() || parent.is_braced_expression());
+        let is_target = node
+            .child_by_field_name("lhs")
+            .and_then(|lhs| lhs.node_as_str(contents).ok())
+            .is_some_and(|lhs| symbol_name(lhs) == name);
+
+        if is_statement && is_target {
+            return true;
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if has_assignment_statement(child, name, contents) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Whether the value can be evaluated at the use rather than at the binding.
+/// Uses in functions, loop bodies, branches, the right-hand side of `&&` and
+/// `||`, and formulas defined after the binding would evaluate the value
+/// lazily, conditionally, repeatedly, or not at all, and uses as assignment
+/// targets modify the variable.
+fn is_inlinable_usage(usage: Node, assignment: Node) -> bool {
+    let mut child = usage;
+
+    while let Some(parent) = child.parent() {
+        if assignment_target(&parent) == Some(child) || is_super_assignment_target(&child) {
+            return false;
+        }
+
+        if parent.start_byte() <= assignment.start_byte() &&
+            parent.end_byte() >= assignment.end_byte()
+        {
+            return true;
+        }
+
+        let inlinable = match parent.node_type() {
+            NodeType::FunctionDefinition |
+            NodeType::Parameters |
+            NodeType::WhileStatement |
+            NodeType::RepeatStatement |
+            NodeType::UnaryOperator(UnaryOperatorType::Tilde) |
+            NodeType::BinaryOperator(BinaryOperatorType::Tilde) => false,
+            NodeType::ForStatement => parent.child_by_field_name("sequence") == Some(child),
+            NodeType::IfStatement => parent.child_by_field_name("condition") == Some(child),
+            NodeType::BinaryOperator(BinaryOperatorType::And2 | BinaryOperatorType::Or2) => {
+                parent.child_by_field_name("lhs") == Some(child)
+            },
+            _ => true,
+        };
+
+        if !inlinable {
+            return false;
+        }
+
+        child = parent;
+    }
+
+    true
+}
+
+/// Operators and function definitions are wrapped in parentheses unless the
+/// use is delimited already
+fn needs_parentheses(value: &Node, usage: &Node) -> bool {
+    if !matches!(
+        value.node_type(),
+        NodeType::UnaryOperator(_) | NodeType::BinaryOperator(_) | NodeType::FunctionDefinition
+    ) {
+        return false;
+    }
+
+    let Some(parent) = usage.parent() else {
+        return false;
+    };
+
+    match parent.node_type() {
+        NodeType::Program |
+        NodeType::BracedExpression |
+        NodeType::ParenthesizedExpression |
+        NodeType::Argument => false,
+        NodeType::BinaryOperator(
+            BinaryOperatorType::LeftAssignment | BinaryOperatorType::EqualsAssignment,
+        ) => parent.child_by_field_name("rhs") != Some(*usage),
+        _ => true,
+    }
+}
+
+/// The range of a statement, extended to its whole lines when it's alone on
+/// them
+fn statement_deletion_range(document: &Document, node: &Node) -> Option<lsp_types::Range> {
+    let contents = &document.contents;
+
+    let line_start = contents[..node.start_byte()]
+        .rfind('\n')
+        .map_or(0, |i| i + 1);
+    let line_end = contents[node.end_byte()..]
+        .find('\n')
+        .map(|i| node.end_byte() + i);
+
+    let before = &contents[line_start..node.start_byte()];
+    let after = &contents[node.end_byte()..line_end.unwrap_or(contents.len())];
+
+    let (start, end) = if before.trim().is_empty() && after.trim().is_empty() {
+        let start = Point::new(node.start_position().row, 0);
+        let end = match line_end {
+            Some(_) => Point::new(node.end_position().row + 1, 0),
+            None => Point::new(
+                node.end_position().row,
+                node.end_position().column + after.len(),
+            ),
+        };
+        (start, end)
+    } else {
+        (node.start_position(), node.end_position())
+    };
+
+    Some(lsp_types::Range::new(
+        document.lsp_position_from_tree_sitter_point(start).ok()?,
+        document.lsp_position_from_tree_sitter_point(end).ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsp::code_action::apply_code_action;
+    use crate::lsp::code_action::selection_from_cursors;
+    use crate::lsp::util::test_path;
+
+    fn inline_test(text: &str) -> Option<(String, String)> {
+        let mut actions = CodeActions::new();
+
+        let capabilities = Capabilities::default()
+            .with_code_action_literal_support(true)
+            .with_workspace_edit_document_changes(true);
+
+        let (text, range) = selection_from_cursors(text);
+        let document = Document::new(&text, None);
+        let state = WorldState::default();
+
+        inline_variable(
+            &mut actions,
+            &test_path("test.R"),
+            &document,
+            range,
+            &state,
+            &capabilities,
+        );
+
+        let mut actions = actions.into_response();
+        assert!(actions.len() <= 1);
+
+        Some(apply_code_action(&document, actions.pop()?))
+    }
+
+    #[test]
+    fn test_inline_variable() {
+        // From the use
+        assert_eq!(
+            inline_test("x <- a + 1\ny <- @%x * 2\n").unwrap(),
+            (
+                String::from("Inline variable `x`"),
+                String::from("y <- (a + 1) * 2\n")
+            )
+        );
+
+        // From the definition
+        assert_eq!(inline_test("@%x <- f(1)\ng(x)\n").unwrap().1, "g(f(1))\n");
+
+        // Within a function
+        let text = "
+f <- function() {
+  @%x <- a + 1
+  y <- 2
+  g(x)
+}
+";
+        assert_eq!(
+            inline_test(text).unwrap().1,
+            "
+f <- function() {
+  y <- 2
+  g(a + 1)
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_inline_variable_unsupported() {
+        // Used more than once
+        assert!(inline_test("x <- 1\ny <- @%x + x\n").is_none());
+
+        // Not used
+        assert!(inline_test("@%x <- 1\n").is_none());
+
+        // Assigned more than once
+        assert!(inline_test("x <- 1\nx <- 2\ny <- @%x\n").is_none());
+
+        // Assigned in another function
+        assert!(inline_test("f <- function() x <- 1\ng <- function() @%x\n").is_none());
+
+        // A variable of the value is reassigned before the use
+        assert!(inline_test("x <- a\na <- 2\ny <- @%x\n").is_none());
+
+        // The use is evaluated lazily or repeatedly
+        assert!(inline_test("x <- g()\nf <- function() @%x\n").is_none());
+        assert!(inline_test("x <- g()\nfor (i in 1:2) print(@%x)\n").is_none());
+        assert!(inline_test("x <- g()\nwhile (cond()) print(@%x)\n").is_none());
+        assert!(inline_test("x <- g()\nf(function(a = @%x) a)\n").is_none());
+
+        // The use is evaluated conditionally
+        assert!(inline_test("x <- g()\nif (cond) @%x\n").is_none());
+        assert!(inline_test("x <- g()\nif (cond) 1 else @%x\n").is_none());
+        assert!(inline_test("x <- g()\ncond && @%x\n").is_none());
+        assert!(inline_test("x <- g()\ncond || @%x\n").is_none());
+
+        // The use is not evaluated
+        assert!(inline_test("x <- g()\nlm(y ~ @%x)\n").is_none());
+    }
+
+    #[test]
+    fn test_inline_variable_evaluated_once() {
+        // Conditions, loop sequences, and left-hand sides of `&&` are
+        // evaluated once, like the binding
+        assert_eq!(
+            inline_test("x <- g()\nif (@%x) 1\n").unwrap().1,
+            "if (g()) 1\n"
+        );
+        assert_eq!(
+            inline_test("x <- g()\nfor (i in @%x) print(i)\n")
+                .unwrap()
+                .1,
+            "for (i in g()) print(i)\n"
+        );
+        assert_eq!(
+            inline_test("x <- g()\n@%x && cond\n").unwrap().1,
+            "g() && cond\n"
+        );
+    }
+}
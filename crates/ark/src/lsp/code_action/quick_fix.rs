@@ -0,0 +1,577 @@
+//
+// quick_fix.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use tower_lsp::lsp_types;
+use tree_sitter::Node;
+use tree_sitter::Point;
+use url::Url;
+
+use crate::lsp::capabilities::Capabilities;
+use crate::lsp::code_action::code_action;
+use crate::lsp::code_action::code_action_workspace_text_edit;
+use crate::lsp::code_action::CodeActions;
+use crate::lsp::diagnostics::is_package_code;
+use crate::lsp::diagnostics_rules::DiagnosticRule;
+use crate::lsp::document::Document;
+use crate::lsp::rename::symbol_name;
+use crate::lsp::state::WorldState;
+use crate::lsp::traits::node::NodeExt;
+use crate::treesitter::node_is_call;
+use crate::treesitter::BinaryOperatorType;
+use crate::treesitter::NodeTypeExt;
+
+/// Maximum number of packages offered for a symbol that is not in scope
+const MAX_PACKAGE_SUGGESTIONS: usize = 5;
+
+struct QuickFix {
+    title: String,
+    edits: Vec<lsp_types::TextEdit>,
+    is_preferred: bool,
+}
+
+/// Quick fixes for the diagnostics sent along with the code action request.
+/// Only diagnostics emitted by ark, recognised by their rule code, are fixed.
+pub(crate) fn quick_fixes(
+    actions: &mut CodeActions,
+    uri: &Url,
+    document: &Document,
+    diagnostics: &[lsp_types::Diagnostic],
+    state: &WorldState,
+    capabilities: &Capabilities,
+) -> Option<()> {
+    if !capabilities.code_action_literal_support() {
+        // These code actions return literal `CodeAction`s, so must have support for them
+        return None;
+    }
+
+    for diagnostic in diagnostics {
+        let Some(rule) = DiagnosticRule::from_diagnostic(diagnostic) else {
+            continue;
+        };
+
+        let fixes = match rule {
+            DiagnosticRule::SymbolNotInScope => {
+                symbol_not_in_scope_fixes(uri, document, diagnostic, state)
+            },
+            DiagnosticRule::InvalidNaComparison => invalid_na_comparison_fix(document, diagnostic)
+                .into_iter()
+                .collect(),
+            DiagnosticRule::AssignmentInIfCondition => {
+                assignment_in_if_condition_fix(document, diagnostic)
+                    .into_iter()
+                    .collect()
+            },
+            DiagnosticRule::SyntaxError => missing_close_fix(document, diagnostic)
+                .into_iter()
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        for fix in fixes {
+            let edit = code_action_workspace_text_edit(
+                uri.clone(),
+                document.version,
+                fix.edits,
+                capabilities,
+            );
+
+            let mut action = code_action(fix.title, lsp_types::CodeActionKind::QUICKFIX, edit);
+            action.diagnostics = Some(vec![diagnostic.clone()]);
+            if fix.is_preferred {
+                action.is_preferred = Some(true);
+            }
+
+            actions.add_action(action);
+        }
+    }
+
+    Some(())
+}
+
+/// Smallest named node covering the range of a diagnostic
+fn diagnostic_node<'tree>(
+    document: &'tree Document,
+    diagnostic: &lsp_types::Diagnostic,
+) -> Option<Node<'tree>> {
+    let range = document
+        .tree_sitter_range_from_lsp_range(diagnostic.range)
+        .ok()?;
+
+    document
+        .ast
+        .root_node()
+        .named_descendant_for_point_range(range.start_point, range.end_point)
+}
+
+/// Offer to namespace the symbol with `pkg::`, or to attach the package with
+/// `library(pkg)`, for every installed package exporting the symbol
+fn symbol_not_in_scope_fixes(
+    uri: &Url,
+    document: &Document,
+    diagnostic: &lsp_types::Diagnostic,
+    state: &WorldState,
+) -> Vec<QuickFix> {
+    let Some(node) = diagnostic_node(document, diagnostic) else {
+        return Vec::new();
+    };
+    if !node.is_identifier() {
+        return Vec::new();
+    }
+    let Ok(text) = node.node_as_str(&document.contents) else {
+        return Vec::new();
+    };
+    let name = symbol_name(text);
+
+    let mut packages = state.library.exporting_packages(name);
+    packages.truncate(MAX_PACKAGE_SUGGESTIONS);
+
+    let mut fixes = Vec::new();
+
+    let start = diagnostic.range.start;
+    for package in &packages {
+        fixes.push(QuickFix {
+            title: format!("Use `{package}::{name}`"),
+            edits: vec![lsp_types::TextEdit::new(
+                lsp_types::Range::new(start, start),
+                format!("{package}::"),
+            )],
+            is_preferred: false,
+        });
+    }
+
+    // Packages must declare their dependencies in `DESCRIPTION` rather than
+    // attaching them
+    if is_package_code(uri, state) {
+        return fixes;
+    }
+
+    for package in &packages {
+        let Some(edit) = library_edit(document, node.start_position(), package) else {
+            continue;
+        };
+        fixes.push(QuickFix {
+            title: format!("Add `library({package})`"),
+            edits: vec![edit],
+            is_preferred: false,
+        });
+    }
+
+    fixes
+}
+
+/// Insert a `library()` call after the last top level `library()` or
+/// `require()` call preceding `before`. Without such calls, insert it before
+/// the first top level expression.
+fn library_edit(document: &Document, before: Point, package: &str) -> Option<lsp_types::TextEdit> {
+    let contents = &document.contents;
+    let root = document.ast.root_node();
+
+    let mut cursor = root.walk();
+    let last_attach = root
+        .named_children(&mut cursor)
+        .take_while(|child| child.end_position() <= before)
+        .filter(|child| {
+            node_is_call(child, "library", contents) || node_is_call(child, "require", contents)
+        })
+        .last();
+
+    let (point, new_text) = match last_attach {
+        Some(call) => (call.end_position(), format!("\nlibrary({package})")),
+        None => {
+            let mut cursor = root.walk();
+            let row = root
+                .named_children(&mut cursor)
+                .find(|child| !child.is_comment())
+                .map_or(0, |child| child.start_position().row);
+            (Point::new(row, 0), format!("library({package})\n"))
+        },
+    };
+
+    let position = document.lsp_position_from_tree_sitter_point(point).ok()?;
+    Some(lsp_types::TextEdit::new(
+        lsp_types::Range::new(position, position),
+        new_text,
+    ))
+}
+
+/// Replace `x == NA` with `is.na(x)`, and similarly for `NaN` and `NULL`
+fn invalid_na_comparison_fix(
+    document: &Document,
+    diagnostic: &lsp_types::Diagnostic,
+) -> Option<QuickFix> {
+    let contents = &document.contents;
+
+    let value = diagnostic_node(document, diagnostic)?;
+    let comparison = value.parent()?;
+    if !comparison.is_binary_operator_of_kind(BinaryOperatorType::Equal) {
+        return None;
+    }
+
+    let predicate = match value.node_as_str(contents).ok()? {
+        "NA" => "is.na",
+        "NaN" => "is.nan",
+        "NULL" => "is.null",
+        _ => return None,
+    };
+
+    let lhs = comparison.child_by_field_name("lhs")?;
+    let rhs = comparison.child_by_field_name("rhs")?;
+    let operand = if lhs == value { rhs } else { lhs };
+    let operand = operand.node_as_str(contents).ok()?;
+
+    let new_text = format!("{predicate}({operand})");
+    let range = document
+        .lsp_range_from_tree_sitter_range(comparison.range())
+        .ok()?;
+
+    Some(QuickFix {
+        title: format!("Replace with `{new_text}`"),
+        edits: vec![lsp_types::TextEdit::new(range, new_text)],
+        is_preferred: true,
+    })
+}
+
+/// Turn the assignment in `if (x = 1)` into the comparison `if (x == 1)`
+fn assignment_in_if_condition_fix(
+    document: &Document,
+    diagnostic: &lsp_types::Diagnostic,
+) -> Option<QuickFix> {
+    let assignment = diagnostic_node(document, diagnostic)?;
+    if !assignment.is_binary_operator_of_kind(BinaryOperatorType::EqualsAssignment) {
+        return None;
+    }
+
+    let operator = assignment.child_by_field_name("operator")?;
+    let range = document
+        .lsp_range_from_tree_sitter_range(operator.range())
+        .ok()?;
+
+    Some(QuickFix {
+        title: String::from("Replace `=` with `==`"),
+        edits: vec![lsp_types::TextEdit::new(range, String::from("=="))],
+        is_preferred: true,
+    })
+}
+
+/// Insert the closing delimiter of an unmatched opening delimiter. The
+/// diagnostic range is the opening delimiter, whose parent has a missing
+/// `"close"` field.
+fn missing_close_fix(document: &Document, diagnostic: &lsp_types::Diagnostic) -> Option<QuickFix> {
+    let range = document
+        .tree_sitter_range_from_lsp_range(diagnostic.range)
+        .ok()?;
+
+    let open = document
+        .ast
+        .root_node()
+        .descendant_for_point_range(range.start_point, range.end_point)?;
+
+    let close = open.parent()?.child_by_field_name("close")?;
+    if !close.is_missing() {
+        return None;
+    }
+
+    let close_token = match open.node_as_str(&document.contents).ok()? {
+        "(" => ")",
+        "[" => "]",
+        "[[" => "]]",
+        "{" => "}",
+        _ => return None,
+    };
+
+    let position = document
+        .lsp_position_from_tree_sitter_point(close.start_position())
+        .ok()?;
+
+    Some(QuickFix {
+        title: format!("Insert missing `{close_token}`"),
+        edits: vec![lsp_types::TextEdit::new(
+            lsp_types::Range::new(position, position),
+            close_token.to_string(),
+        )],
+        is_preferred: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use tower_lsp::lsp_types::CodeActionOrCommand;
+    use tower_lsp::lsp_types::DocumentChanges;
+    use tower_lsp::lsp_types::OneOf;
+    use url::Url;
+
+    use super::*;
+    use crate::lsp::inputs::library::Library;
+    use crate::lsp::inputs::package::Package;
+    use crate::lsp::inputs::package_description::Dcf;
+    use crate::lsp::inputs::package_description::Description;
+    use crate::lsp::inputs::package_namespace::Namespace;
+    use crate::lsp::inputs::source_root::SourceRoot;
+    use crate::lsp::util::test_path;
+
+    fn mock_package(path: PathBuf, name: &str, exports: &[&str]) -> Package {
+        let namespace = Namespace {
+            exports: exports.iter().map(|export| export.to_string()).collect(),
+            imports: vec![],
+            package_imports: vec![],
+        };
+        let description = Description {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            depends: vec![],
+            fields: Dcf::new(),
+        };
+        Package::from_parts(path, description, namespace)
+    }
+
+    fn mock_state() -> WorldState {
+        let mut state = WorldState::default();
+        let package = mock_package(PathBuf::from("mockpkg"), "mockpkg", &["foo"]);
+        state.installed_packages = vec!["mockpkg".to_string()];
+        state.library = Library::new(vec![])
+            .insert("mockpkg", package)
+            .with_exports_index(state.installed_packages.clone());
+        state
+    }
+
+    /// Diagnostic of `rule` covering the first occurrence of `needle`
+    fn diagnostic(
+        document: &Document,
+        rule: DiagnosticRule,
+        needle: &str,
+    ) -> lsp_types::Diagnostic {
+        let start = document.contents.find(needle).unwrap();
+        let node = document
+            .ast
+            .root_node()
+            .descendant_for_byte_range(start, start + needle.len())
+            .unwrap();
+        let range = document
+            .lsp_range_from_tree_sitter_range(node.range())
+            .unwrap();
+        rule.diagnostic(range, String::from("message"))
+    }
+
+    fn apply_edits(document: &Document, edits: Vec<lsp_types::TextEdit>) -> String {
+        let mut contents = document.contents.clone();
+
+        let mut edits = edits;
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.range.start));
+
+        for edit in edits {
+            let range = document
+                .tree_sitter_range_from_lsp_range(edit.range)
+                .unwrap();
+            contents.replace_range(range.start_byte..range.end_byte, &edit.new_text);
+        }
+
+        contents
+    }
+
+    /// Titles of the quick fixes and the fixed documents
+    fn quick_fix_test(
+        uri: &Url,
+        text: &str,
+        rule: DiagnosticRule,
+        needle: &str,
+        state: &WorldState,
+    ) -> Vec<(String, String)> {
+        let mut actions = CodeActions::new();
+
+        let capabilities = Capabilities::default()
+            .with_code_action_literal_support(true)
+            .with_workspace_edit_document_changes(true);
+
+        let document = Document::new(text, None);
+        let diagnostic = diagnostic(&document, rule, needle);
+
+        quick_fixes(
+            &mut actions,
+            uri,
+            &document,
+            &[diagnostic.clone()],
+            state,
+            &capabilities,
+        );
+
+        actions
+            .into_response()
+            .into_iter()
+            .map(|action| {
+                let CodeActionOrCommand::CodeAction(action) = action else {
+                    panic!("Unexpected");
+                };
+                assert_eq!(action.kind, Some(lsp_types::CodeActionKind::QUICKFIX));
+                assert_eq!(action.diagnostics, Some(vec![diagnostic.clone()]));
+
+                let document_changes = action.edit.unwrap().document_changes.unwrap();
+                let DocumentChanges::Edits(mut text_document_edits) = document_changes else {
+                    panic!("Unexpected");
+                };
+                let edits = text_document_edits
+                    .pop()
+                    .unwrap()
+                    .edits
+                    .into_iter()
+                    .map(|edit| match edit {
+                        OneOf::Left(edit) => edit,
+                        OneOf::Right(_) => panic!("Unexpected"),
+                    })
+                    .collect();
+
+                (action.title, apply_edits(&document, edits))
+            })
+            .collect()
+    }
+
+    fn fix(title: &str, text: &str) -> (String, String) {
+        (title.to_string(), text.to_string())
+    }
+
+    #[test]
+    fn test_symbol_not_in_scope() {
+        let uri = test_path("test.R");
+        let state = mock_state();
+
+        let fixes = quick_fix_test(
+            &uri,
+            "library(utils)\n\nfoo(1)\n",
+            DiagnosticRule::SymbolNotInScope,
+            "foo",
+            &state,
+        );
+        assert_eq!(fixes, vec![
+            fix("Use `mockpkg::foo`", "library(utils)\n\nmockpkg::foo(1)\n"),
+            fix(
+                "Add `library(mockpkg)`",
+                "library(utils)\nlibrary(mockpkg)\n\nfoo(1)\n"
+            ),
+        ]);
+
+        // Without `library()` calls, the call is added before the first expression
+        let fixes = quick_fix_test(
+            &uri,
+            "# comment\nx <- foo(1)\n",
+            DiagnosticRule::SymbolNotInScope,
+            "foo",
+            &state,
+        );
+        assert_eq!(
+            fixes[1],
+            fix(
+                "Add `library(mockpkg)`",
+                "# comment\nlibrary(mockpkg)\nx <- foo(1)\n"
+            )
+        );
+
+        // Not exported by an installed package
+        let fixes = quick_fix_test(
+            &uri,
+            "bar(1)\n",
+            DiagnosticRule::SymbolNotInScope,
+            "bar",
+            &state,
+        );
+        assert!(fixes.is_empty());
+    }
+
+    #[test]
+    fn test_symbol_not_in_scope_package_code() {
+        let mut state = mock_state();
+
+        let root = std::env::temp_dir().join("mypkg");
+        let package = mock_package(root.clone(), "mypkg", &[]);
+        state.root = Some(SourceRoot::Package(package));
+
+        // No `library()` fix in package code
+        let uri = Url::from_file_path(root.join("R").join("foo.R")).unwrap();
+        let fixes = quick_fix_test(
+            &uri,
+            "foo(1)\n",
+            DiagnosticRule::SymbolNotInScope,
+            "foo",
+            &state,
+        );
+        assert_eq!(fixes, vec![fix("Use `mockpkg::foo`", "mockpkg::foo(1)\n")]);
+    }
+
+    #[test]
+    fn test_invalid_na_comparison() {
+        let uri = test_path("test.R");
+        let state = WorldState::default();
+
+        let fixes = quick_fix_test(
+            &uri,
+            "if (x == NA) 1\n",
+            DiagnosticRule::InvalidNaComparison,
+            "NA",
+            &state,
+        );
+        assert_eq!(
+            fixes,
+            vec![fix("Replace with `is.na(x)`", "if (is.na(x)) 1\n")]
+        );
+
+        let fixes = quick_fix_test(
+            &uri,
+            "NULL == f(y)\n",
+            DiagnosticRule::InvalidNaComparison,
+            "NULL",
+            &state,
+        );
+        assert_eq!(
+            fixes,
+            vec![fix("Replace with `is.null(f(y))`", "is.null(f(y))\n")]
+        );
+    }
+
+    #[test]
+    fn test_assignment_in_if_condition() {
+        let uri = test_path("test.R");
+        let state = WorldState::default();
+
+        let fixes = quick_fix_test(
+            &uri,
+            "if (x = 1) y\n",
+            DiagnosticRule::AssignmentInIfCondition,
+            "x = 1",
+            &state,
+        );
+        assert_eq!(fixes, vec![fix("Replace `=` with `==`", "if (x == 1) y\n")]);
+    }
+
+    #[test]
+    fn test_missing_close() {
+        let uri = test_path("test.R");
+        let state = WorldState::default();
+
+        let fixes = quick_fix_test(&uri, "f(1, 2\n", DiagnosticRule::SyntaxError, "(", &state);
+        assert_eq!(fixes, vec![fix("Insert missing `)`", "f(1, 2)\n")]);
+
+        let fixes = quick_fix_test(&uri, "x[[1\n", DiagnosticRule::SyntaxError, "[[", &state);
+        assert_eq!(fixes, vec![fix("Insert missing `]]`", "x[[1]]\n")]);
+    }
+
+    #[test]
+    fn test_requires_literal_support() {
+        let mut actions = CodeActions::new();
+        let document = Document::new("if (x = 1) y\n", None);
+        let diagnostic = diagnostic(&document, DiagnosticRule::AssignmentInIfCondition, "x = 1");
+
+        quick_fixes(
+            &mut actions,
+            &test_path("test.R"),
+            &document,
+            &[diagnostic],
+            &WorldState::default(),
+            &Capabilities::default(),
+        );
+        assert!(actions.into_response().is_empty());
+    }
+}
@@ -0,0 +1,369 @@
+//
+// code_lens.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+use tower_lsp::lsp_types::CodeLens;
+use tower_lsp::lsp_types::Command;
+use tower_lsp::lsp_types::Location;
+use tower_lsp::lsp_types::Position;
+use tower_lsp::lsp_types::ReferenceContext;
+use tower_lsp::lsp_types::ReferenceParams;
+use tower_lsp::lsp_types::TextDocumentIdentifier;
+use tower_lsp::lsp_types::TextDocumentPositionParams;
+use tree_sitter::Node;
+use url::Url;
+
+use crate::lsp::document::Document;
+use crate::lsp::references::find_references;
+use crate::lsp::roxygen::assigned_function;
+use crate::lsp::roxygen::is_roxygen_comment;
+use crate::lsp::roxygen::TagLine;
+use crate::lsp::state::WorldState;
+use crate::lsp::statement_range::find_roxygen_examples_section;
+use crate::lsp::statement_range::roxygen_examples_code;
+use crate::lsp::traits::node::NodeExt;
+use crate::treesitter::NodeTypeExt;
+
+// The commands of the code lenses are executed by the client
+
+/// Runs code in the console, with a `{ languageId, code, focus }` argument
+const EXECUTE_CODE_COMMAND: &str = "workbench.action.positronConsole.executeCode";
+
+/// Shows references in a peek view, with the uri and position of the symbol
+/// and the locations of its references as arguments
+const SHOW_REFERENCES_COMMAND: &str = "editor.action.showReferences";
+
+/// Location of a function definition whose reference count lens is resolved
+/// lazily with `codeLens/resolve`
+#[derive(Debug, Serialize, Deserialize)]
+struct ReferencesData {
+    uri: Url,
+    position: Position,
+}
+
+/// Code lenses of a document:
+/// - "Run test" and "Debug test" on `test_that()` blocks.
+/// - "Run examples" on roxygen `@examples` and `@examplesIf` sections.
+/// - Reference counts above top-level function definitions. These are
+///   unresolved and computed on `codeLens/resolve`.
+pub(crate) fn code_lenses(uri: &Url, document: &Document) -> anyhow::Result<Vec<CodeLens>> {
+    let mut lenses = Vec::new();
+    let root = document.ast.root_node();
+
+    collect_test_lenses(&root, document, &mut lenses)?;
+
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        if is_roxygen_comment(&child, &document.contents) {
+            collect_examples_lens(&child, document, &mut lenses)?;
+        } else if let Some((lhs, _)) = assigned_function(&child) {
+            if !lhs.is_identifier() {
+                continue;
+            }
+
+            let range = document.lsp_range_from_tree_sitter_range(lhs.range())?;
+            let data = ReferencesData {
+                uri: uri.clone(),
+                position: range.start,
+            };
+
+            lenses.push(CodeLens {
+                range,
+                command: None,
+                data: Some(serde_json::to_value(data)?),
+            });
+        }
+    }
+
+    Ok(lenses)
+}
+
+fn collect_test_lenses(
+    node: &Node,
+    document: &Document,
+    lenses: &mut Vec<CodeLens>,
+) -> anyhow::Result<()> {
+    if is_test_that_call(node, &document.contents) {
+        let contents = &document.contents;
+        let code = node.node_as_str(contents)?;
+        let range = document.lsp_range_from_tree_sitter_range(node.range())?;
+
+        lenses.push(CodeLens {
+            range,
+            command: Some(execute_code_command("Run test", code)),
+            data: None,
+        });
+
+        if let Some(code) = debug_test_code(node, contents) {
+            lenses.push(CodeLens {
+                range,
+                command: Some(execute_code_command("Debug test", &code)),
+                data: None,
+            });
+        }
+
+        // Tests are not nested
+        return Ok(());
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_test_lenses(&child, document, lenses)?;
+    }
+
+    Ok(())
+}
+
+/// Whether `node` is a `test_that()` call with a description, like the ones
+/// shown in the document outline
+fn is_test_that_call(node: &Node, contents: &str) -> bool {
+    if !node.is_call() {
+        return false;
+    }
+
+    let Some(callee) = node.child_by_field_name("function") else {
+        return false;
+    };
+    let callee = if callee.is_namespace_operator() {
+        match callee.child_by_field_name("rhs") {
+            Some(rhs) => rhs,
+            None => return false,
+        }
+    } else {
+        callee
+    };
+
+    if !callee.is_identifier() || callee.node_as_str(contents).ok() != Some("test_that") {
+        return false;
+    }
+
+    node.arguments_values()
+        .next()
+        .flatten()
+        .is_some_and(|value| value.is_string())
+}
+
+/// The code of a `test_that()` call with a `browser()` call at the start of
+/// its braced test code
+fn debug_test_code(node: &Node, contents: &str) -> Option<String> {
+    let code = node
+        .arguments_values()
+        .skip(1)
+        .flatten()
+        .find(|value| value.is_braced_expression())?;
+
+    let open = code.child(0)?;
+    let offset = open.end_byte() - node.start_byte();
+
+    let mut text = node.node_to_string(contents).ok()?;
+    text.insert_str(offset, "\nbrowser()");
+
+    Some(text)
+}
+
+fn collect_examples_lens(
+    comment: &Node,
+    document: &Document,
+    lenses: &mut Vec<CodeLens>,
+) -> anyhow::Result<()> {
+    let contents = &document.contents;
+
+    let Some(line) = TagLine::parse(comment.node_as_str(contents)?) else {
+        return Ok(());
+    };
+    if !matches!(line.tag, "examples" | "examplesIf") {
+        return Ok(());
+    }
+
+    // The section starts on the line following the tag
+    let Some(section) = comment
+        .next_sibling()
+        .filter(|next| next.start_position().row == comment.end_position().row + 1)
+        .and_then(|next| find_roxygen_examples_section(next, contents))
+    else {
+        return Ok(());
+    };
+    let Some(mut code) = roxygen_examples_code(section, contents) else {
+        return Ok(());
+    };
+
+    if line.tag == "examplesIf" {
+        let condition = line.argument.trim();
+        if condition.is_empty() {
+            return Ok(());
+        }
+        code = format!("if ({condition}) {{\n{code}\n}}");
+    }
+
+    lenses.push(CodeLens {
+        range: document.lsp_range_from_tree_sitter_range(comment.range())?,
+        command: Some(execute_code_command("Run examples", &code)),
+        data: None,
+    });
+
+    Ok(())
+}
+
+fn execute_code_command(title: &str, code: &str) -> Command {
+    Command {
+        title: String::from(title),
+        command: String::from(EXECUTE_CODE_COMMAND),
+        arguments: Some(vec![json!({
+            "languageId": "r",
+            "code": code,
+            "focus": true,
+        })]),
+    }
+}
+
+/// Resolve the reference count of a function definition lens
+pub(crate) fn resolve_code_lens(
+    mut lens: CodeLens,
+    state: &WorldState,
+) -> anyhow::Result<CodeLens> {
+    let Some(data) = lens.data.take() else {
+        return Ok(lens);
+    };
+    let data: ReferencesData = serde_json::from_value(data)?;
+
+    let params = ReferenceParams {
+        text_document_position: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier {
+                uri: data.uri.clone(),
+            },
+            position: data.position,
+        },
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+        context: ReferenceContext {
+            include_declaration: false,
+        },
+    };
+
+    // The definition itself is among the references
+    let locations: Vec<Location> = find_references(params, state)?
+        .into_iter()
+        .filter(|location| location.uri != data.uri || location.range.start != data.position)
+        .collect();
+
+    let title = match locations.len() {
+        1 => String::from("1 reference"),
+        n => format!("{n} references"),
+    };
+
+    lens.command = Some(Command {
+        title,
+        command: String::from(SHOW_REFERENCES_COMMAND),
+        arguments: Some(vec![
+            serde_json::to_value(&data.uri)?,
+            serde_json::to_value(data.position)?,
+            serde_json::to_value(locations)?,
+        ]),
+    });
+
+    Ok(lens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsp::util::test_path;
+
+    fn lenses(code: &str) -> Vec<CodeLens> {
+        let document = Document::new(code, None);
+        code_lenses(&test_path("test.R"), &document).unwrap()
+    }
+
+    fn command_code(lens: &CodeLens) -> &str {
+        let arguments = lens.command.as_ref().unwrap().arguments.as_ref().unwrap();
+        arguments[0]["code"].as_str().unwrap()
+    }
+
+    #[test]
+    fn test_code_lens_test_that() {
+        let code = r#"
+test_that("adds", {
+  expect_equal(1 + 1, 2)
+})
+
+testthat::test_that("subtracts", expect_equal(1 - 1, 0))
+
+test_that(desc, {})
+"#;
+        let lenses = lenses(code);
+        let titles: Vec<&str> = lenses
+            .iter()
+            .map(|lens| lens.command.as_ref().unwrap().title.as_str())
+            .collect();
+        assert_eq!(titles, vec!["Run test", "Debug test", "Run test"]);
+
+        assert_eq!(lenses[0].range.start, Position::new(1, 0));
+
+        // The code is run by the client
+        let command = lenses[0].command.as_ref().unwrap();
+        assert_eq!(command.command, EXECUTE_CODE_COMMAND);
+        assert_eq!(command.arguments.as_ref().unwrap()[0]["languageId"], "r");
+        assert_eq!(
+            command_code(&lenses[0]),
+            "test_that(\"adds\", {\n  expect_equal(1 + 1, 2)\n})"
+        );
+        assert_eq!(
+            command_code(&lenses[1]),
+            "test_that(\"adds\", {\nbrowser()\n  expect_equal(1 + 1, 2)\n})"
+        );
+
+        // No braced code to debug
+        assert_eq!(lenses[2].range.start, Position::new(5, 0));
+    }
+
+    #[test]
+    fn test_code_lens_examples() {
+        let code = "
+#' Title
+#'
+#' @examples
+#' f(1)
+#'   f(2)
+#' @examplesIf interactive()
+#' f(3)
+#' @export
+f <- function(x) x
+";
+        let lenses = lenses(code);
+        assert_eq!(lenses.len(), 3);
+
+        assert_eq!(lenses[0].range.start, Position::new(3, 0));
+        assert_eq!(command_code(&lenses[0]), "f(1)\n  f(2)");
+
+        assert_eq!(lenses[1].range.start, Position::new(6, 0));
+        assert_eq!(command_code(&lenses[1]), "if (interactive()) {\nf(3)\n}");
+
+        // Reference count of `f`, resolved lazily
+        assert_eq!(lenses[2].range.start, Position::new(9, 0));
+        assert!(lenses[2].command.is_none());
+        assert!(lenses[2].data.is_some());
+    }
+
+    #[test]
+    fn test_code_lens_functions() {
+        let code = "
+f <- function() 1
+g = function(x) {
+  h <- function() 2
+}
+x$f <- function() 3
+y <- 1
+";
+        let lenses = lenses(code);
+        let rows: Vec<u32> = lenses.iter().map(|lens| lens.range.start.line).collect();
+
+        // Only top-level functions assigned to symbols
+        assert_eq!(rows, vec![1, 2]);
+    }
+}
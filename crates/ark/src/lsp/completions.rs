@@ -17,4 +17,5 @@ mod types;
 mod tests;
 
 pub(crate) use provide::provide_completions;
+pub(crate) use provide::provide_static_completions;
 pub(crate) use resolve::resolve_completion;
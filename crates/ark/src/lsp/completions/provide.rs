@@ -41,3 +41,13 @@ pub(crate) fn provide_completions(
     // set of reasonable completions from composite sources
     Ok(composite::get_completions(&completion_context)?.unwrap_or_default())
 }
+
+/// Completions that don't require an R session, used when the LSP runs
+/// standalone
+pub(crate) fn provide_static_completions(
+    document_context: &DocumentContext,
+    state: &WorldState,
+) -> anyhow::Result<Vec<CompletionItem>> {
+    let completion_context = CompletionContext::new(document_context, state);
+    Ok(composite::get_static_completions(&completion_context)?.unwrap_or_default())
+}
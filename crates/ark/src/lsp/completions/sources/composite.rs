@@ -105,6 +105,34 @@ pub(crate) fn get_completions(
     Ok(Some(completions))
 }
 
+/// Gets completions from the composite sources that don't need to evaluate R
+/// code: keywords, document and workspace symbols
+pub(crate) fn get_static_completions(
+    completion_context: &CompletionContext,
+) -> anyhow::Result<Option<Vec<CompletionItem>>> {
+    let mut completions = HashMap::new();
+
+    if completion_context.document_context.node.is_program() ||
+        is_identifier_like(completion_context.document_context.node)
+    {
+        push_completions(keyword::KeywordSource, completion_context, &mut completions)?;
+
+        push_completions(
+            document::DocumentSource,
+            completion_context,
+            &mut completions,
+        )?;
+
+        push_completions(
+            workspace::WorkspaceSource,
+            completion_context,
+            &mut completions,
+        )?;
+    }
+
+    Ok(Some(finalize_completions(completions)))
+}
+
 fn push_completions<S>(
     source: S,
     completion_context: &CompletionContext,
@@ -5,12 +5,14 @@
 //
 //
 
+use std::collections::BTreeSet;
+use std::collections::HashSet;
 use std::path::Path;
 
 use harp::exec::RFunction;
 use harp::exec::RFunctionExt;
-use regex::Regex;
 use tower_lsp::lsp_types::CompletionItem;
+use tower_lsp::lsp_types::CompletionItemKind;
 use tower_lsp::lsp_types::Documentation;
 use tower_lsp::lsp_types::InsertTextFormat;
 use tower_lsp::lsp_types::MarkupContent;
@@ -22,6 +24,13 @@ use crate::lsp::completions::completion_item::completion_item;
 use crate::lsp::completions::sources::CompletionSource;
 use crate::lsp::completions::types::CompletionData;
 use crate::lsp::document_context::DocumentContext;
+use crate::lsp::indexer;
+use crate::lsp::roxygen::assigned_function;
+use crate::lsp::roxygen::documented_statement;
+use crate::lsp::roxygen::roxygen_comments;
+use crate::lsp::roxygen::TagLine;
+use crate::lsp::roxygen::INHERIT_TAGS;
+use crate::lsp::state::WorldState;
 use crate::lsp::traits::node::NodeExt;
 use crate::treesitter::NodeTypeExt;
 
@@ -36,7 +45,15 @@ impl CompletionSource for CommentSource {
         &self,
         completion_context: &CompletionContext,
     ) -> anyhow::Result<Option<Vec<CompletionItem>>> {
-        completions_from_comment(completion_context.document_context)
+        let context = completion_context.document_context;
+
+        if let Some(completions) =
+            completions_from_roxygen_argument(context, completion_context.state)?
+        {
+            return Ok(Some(completions));
+        }
+
+        completions_from_comment(context)
     }
 }
 
@@ -49,14 +66,16 @@ fn completions_from_comment(
         return Ok(None);
     }
 
-    let pattern = Regex::new(r"^.*\s")?;
-
-    let contents = node.node_as_str(&context.document.contents)?;
-    let token = pattern.replace(contents, "");
-
     let mut completions: Vec<CompletionItem> = vec![];
 
-    if !token.starts_with('@') {
+    // Tags are the first word of roxygen lines
+    let token = comment_before_cursor(context)?
+        .strip_prefix("#'")
+        .map(str::trim_start);
+    let is_tag =
+        token.is_some_and(|token| token.starts_with('@') && !token.contains(char::is_whitespace));
+
+    if !is_tag {
         // We are done, there are no completions, but we are in a comment so
         // no one else should get a chance to register anything
         return Ok(Some(completions));
@@ -105,6 +124,131 @@ fn completions_from_comment(
     Ok(Some(completions))
 }
 
+/// Completions for the argument of roxygen tags: parameters of the
+/// documented function after `@param`, workspace functions after
+/// `@inheritParams` and `@seealso`, and known families after `@family`
+fn completions_from_roxygen_argument(
+    context: &DocumentContext,
+    state: &WorldState,
+) -> anyhow::Result<Option<Vec<CompletionItem>>> {
+    let node = context.node;
+
+    if !node.is_comment() {
+        return Ok(None);
+    }
+
+    let line = comment_before_cursor(context)?;
+    let Some(tag) = TagLine::parse(line) else {
+        return Ok(None);
+    };
+
+    // The cursor is still on the tag
+    if tag.argument.is_empty() && !line.ends_with(char::is_whitespace) {
+        return Ok(None);
+    }
+
+    let is_first_word = !tag.argument.contains(char::is_whitespace);
+    let contents = &context.document.contents;
+
+    let completions = match tag.tag {
+        "param" if is_first_word => {
+            let Some(statement) = documented_statement(&node, contents) else {
+                return Ok(None);
+            };
+            let Some((_, function)) = assigned_function(&statement) else {
+                return Ok(None);
+            };
+
+            // Skip the parameters documented in the block, including those
+            // preceding the cursor on this line
+            let mut documented: HashSet<&str> = HashSet::new();
+            for comment in roxygen_comments(&statement, contents) {
+                if comment != node {
+                    if let Some(tag) = TagLine::parse(comment.node_as_str(contents)?) {
+                        documented.extend(tag.param_names().into_iter().map(|(name, _)| name));
+                    }
+                }
+            }
+            if let Some((_, preceding)) = tag.argument.rsplit_once(',') {
+                documented.extend(preceding.split(','));
+            }
+
+            let mut completions = Vec::new();
+            for parameter in indexer::function_parameters(&function, contents)? {
+                if documented.contains(parameter.as_str()) {
+                    continue;
+                }
+                let mut item = completion_item(&parameter, CompletionData::ScopeParameter {
+                    name: parameter.clone(),
+                })?;
+                item.kind = Some(CompletionItemKind::VARIABLE);
+                completions.push(item);
+            }
+            completions
+        },
+
+        tag if (INHERIT_TAGS.contains(&tag) && is_first_word) || tag == "seealso" => {
+            let mut names = BTreeSet::new();
+            indexer::map(|_uri, _symbol, entry| {
+                if let indexer::IndexEntryData::Function { name, .. } = &entry.data {
+                    names.insert(name.clone());
+                }
+            });
+
+            let mut completions = Vec::new();
+            for name in names {
+                let mut item = completion_item(&name, CompletionData::Object {
+                    name: name.clone(),
+                })?;
+                item.kind = Some(CompletionItemKind::FUNCTION);
+                completions.push(item);
+            }
+            completions
+        },
+
+        "family" => {
+            // Families used in the open documents
+            let mut families = BTreeSet::new();
+            for document in state.documents.values() {
+                for line in document.contents.lines() {
+                    if let Some(tag) = TagLine::parse(line) {
+                        if tag.tag == "family" && !tag.argument.trim().is_empty() {
+                            families.insert(tag.argument.trim().to_string());
+                        }
+                    }
+                }
+            }
+
+            let mut completions = Vec::new();
+            for family in families {
+                let mut item = completion_item(&family, CompletionData::Object {
+                    name: family.clone(),
+                })?;
+                item.kind = Some(CompletionItemKind::VALUE);
+                completions.push(item);
+            }
+            completions
+        },
+
+        _ => return Ok(None),
+    };
+
+    Ok(Some(completions))
+}
+
+/// The text of the comment at the cursor, up to the cursor
+fn comment_before_cursor<'a>(context: &DocumentContext<'a>) -> anyhow::Result<&'a str> {
+    let node = context.node;
+    let contents = node.node_as_str(&context.document.contents)?;
+
+    let offset = context
+        .point
+        .column
+        .saturating_sub(node.start_position().column);
+
+    Ok(contents.get(..offset).unwrap_or(contents))
+}
+
 fn completion_item_from_roxygen(
     name: &str,
     template: Option<&str>,
@@ -238,3 +382,50 @@ fn test_roxygen_completion_item() {
     assert_eq!(item.insert_text, Some("export".to_string()));
     assert_eq!(item.documentation, None);
 }
+
+#[test]
+fn test_roxygen_argument_completions() {
+    use tree_sitter::Point;
+
+    use crate::lsp::document::Document;
+    use crate::lsp::indexer::ResetIndexerGuard;
+    use crate::lsp::util::test_path;
+
+    let _guard = ResetIndexerGuard;
+
+    let code = [
+        "#' Add numbers",
+        "#' @param x A number.",
+        "#' @param ",
+        "#' @inheritParams ",
+        "#' @family arith",
+        "add <- function(x, y, ...) x + y",
+    ]
+    .join("\n");
+    let uri = test_path("test.R");
+    let document = Document::new(&code, None);
+    indexer::update(&document, &uri).unwrap();
+
+    let mut state = WorldState::default();
+    state.documents.insert(uri, document.clone());
+
+    let labels = |row, column| {
+        let context = DocumentContext::new(&document, Point::new(row, column), None);
+        completions_from_roxygen_argument(&context, &state)
+            .unwrap()
+            .map(|items| items.into_iter().map(|item| item.label).collect::<Vec<_>>())
+    };
+
+    // Undocumented parameters of the following function
+    assert_eq!(labels(2, 10), Some(vec![String::from("y"), String::from("...")]));
+
+    // Workspace functions
+    assert_eq!(labels(3, 18), Some(vec![String::from("add")]));
+
+    // Families of the open documents
+    assert_eq!(labels(4, 11), Some(vec![String::from("arith")]));
+
+    // Not in the argument of a tag
+    assert_eq!(labels(0, 5), None);
+    assert_eq!(labels(2, 8), None);
+}
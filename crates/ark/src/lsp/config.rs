@@ -3,6 +3,7 @@ use serde::Serialize;
 use serde_json::Value;
 
 use crate::lsp::diagnostics::DiagnosticsConfig;
+use crate::lsp::diagnostics_rules::rule_severities_from_json;
 
 pub struct Setting<T> {
     pub key: &'static str,
@@ -25,6 +26,10 @@ pub static GLOBAL_SETTINGS: &[Setting<LspConfig>] = &[
                 .unwrap_or_else(|| DiagnosticsConfig::default().enable)
         },
     },
+    Setting {
+        key: "positron.r.diagnostics.rules",
+        set: |cfg, v| cfg.diagnostics.rules = rule_severities_from_json(&v),
+    },
     Setting {
         key: "positron.r.symbols.includeAssignmentsInBlocks",
         set: |cfg, v| {
@@ -41,6 +46,22 @@ pub static GLOBAL_SETTINGS: &[Setting<LspConfig>] = &[
                 .unwrap_or_else(|| WorkspaceSymbolsConfig::default().include_comment_sections)
         },
     },
+    Setting {
+        key: "positron.r.inlayHints.parameterNames.enable",
+        set: |cfg, v| {
+            cfg.inlay_hints.parameter_names = v
+                .as_bool()
+                .unwrap_or_else(|| InlayHintsConfig::default().parameter_names)
+        },
+    },
+    Setting {
+        key: "positron.r.inlayHints.pipePlaceholder.enable",
+        set: |cfg, v| {
+            cfg.inlay_hints.pipe_placeholder = v
+                .as_bool()
+                .unwrap_or_else(|| InlayHintsConfig::default().pipe_placeholder)
+        },
+    },
 ];
 
 /// These document settings are updated on a URI basis. Each document has its
@@ -86,6 +107,7 @@ pub(crate) struct LspConfig {
     pub(crate) diagnostics: DiagnosticsConfig,
     pub(crate) symbols: SymbolsConfig,
     pub(crate) workspace_symbols: WorkspaceSymbolsConfig,
+    pub(crate) inlay_hints: InlayHintsConfig,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -100,6 +122,16 @@ pub struct WorkspaceSymbolsConfig {
     pub include_comment_sections: bool,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct InlayHintsConfig {
+    /// Whether to show parameter names of positional arguments in calls.
+    pub parameter_names: bool,
+
+    /// Whether to show the implicit first argument of calls on the right-hand
+    /// side of `|>` and `%>%`.
+    pub pipe_placeholder: bool,
+}
+
 /// Configuration of a document.
 ///
 /// The naming follows <https://editorconfig.org/> where possible.
@@ -143,6 +175,15 @@ impl Default for WorkspaceSymbolsConfig {
     }
 }
 
+impl Default for InlayHintsConfig {
+    fn default() -> Self {
+        Self {
+            parameter_names: true,
+            pipe_placeholder: false,
+        }
+    }
+}
+
 impl Default for IndentationConfig {
     fn default() -> Self {
         Self {
@@ -10,9 +10,11 @@ use tower_lsp::lsp_types::GotoDefinitionParams;
 use tower_lsp::lsp_types::GotoDefinitionResponse;
 use tower_lsp::lsp_types::LocationLink;
 use tower_lsp::lsp_types::Range;
+use tree_sitter::Point;
 
 use crate::lsp::document::Document;
 use crate::lsp::indexer;
+use crate::lsp::roxygen::inherited_topic;
 use crate::lsp::traits::node::NodeExt;
 use crate::treesitter::NodeTypeExt;
 
@@ -57,6 +59,36 @@ pub fn goto_definition<'a>(
         }
     }
 
+    // Search for the topic of roxygen tags such as `@inheritParams fn`
+    if node.is_comment() && node.start_position().row == point.row {
+        let text = node.node_as_str(&document.contents)?;
+        let column = point.column - node.start_position().column;
+
+        if let Some((topic, range)) = inherited_topic(text, column) {
+            let uri = &params.text_document_position_params.text_document.uri;
+            let info = indexer::find_in_file(topic, uri).or_else(|| indexer::find(topic));
+
+            if let Some((file_id, entry)) = info {
+                let row = node.start_position().row;
+                let column = node.start_position().column;
+                let start = Point::new(row, column + range.start);
+                let end = Point::new(row, column + range.end);
+                let origin = Range {
+                    start: document.lsp_position_from_tree_sitter_point(start)?,
+                    end: document.lsp_position_from_tree_sitter_point(end)?,
+                };
+
+                let link = LocationLink {
+                    origin_selection_range: Some(origin),
+                    target_uri: file_id.as_uri().clone(),
+                    target_range: entry.range,
+                    target_selection_range: entry.range,
+                };
+                return Ok(Some(GotoDefinitionResponse::Link(vec![link])));
+            }
+        }
+    }
+
     // TODO: We should see if we can find the referenced item in:
     //
     // 1. The document's current AST,
@@ -265,4 +297,49 @@ foo
             }
         );
     }
+
+    #[test]
+    fn test_goto_definition_roxygen_inherit_params() {
+        let _guard = indexer::ResetIndexerGuard;
+
+        let code = r#"
+add <- function(x, y) x + y
+
+#' @inheritParams add
+sub <- function(x, y) x - y
+"#;
+        let doc = Document::new(code, None);
+        let uri = test_path("test.R");
+
+        indexer::update(&doc, &uri).unwrap();
+
+        let params = GotoDefinitionParams {
+            text_document_position_params: lsp_types::TextDocumentPositionParams {
+                text_document: lsp_types::TextDocumentIdentifier { uri },
+                position: lsp_types::Position::new(3, 19),
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+
+        assert_matches!(
+            goto_definition(&doc, params).unwrap(),
+            Some(GotoDefinitionResponse::Link(ref links)) => {
+                assert_eq!(
+                    links[0].origin_selection_range,
+                    Some(lsp_types::Range {
+                        start: lsp_types::Position::new(3, 18),
+                        end: lsp_types::Position::new(3, 21),
+                    })
+                );
+                assert_eq!(
+                    links[0].target_range,
+                    lsp_types::Range {
+                        start: lsp_types::Position::new(1, 0),
+                        end: lsp_types::Position::new(1, 3),
+                    }
+                );
+            }
+        );
+    }
 }
@@ -5,9 +5,15 @@
 //
 //
 
+use std::cell::RefCell;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::path::Path;
+use std::rc::Rc;
 use std::sync::Arc;
 
 use anyhow::bail;
@@ -16,19 +22,44 @@ use harp::syntax::is_valid_symbol;
 use harp::syntax::sym_quote_invalid;
 use stdext::*;
 use tower_lsp::lsp_types::Diagnostic;
-use tower_lsp::lsp_types::DiagnosticSeverity;
+use tower_lsp::lsp_types::FullDocumentDiagnosticReport;
+use tower_lsp::lsp_types::PreviousResultId;
+use tower_lsp::lsp_types::UnchangedDocumentDiagnosticReport;
+use tower_lsp::lsp_types::WorkspaceDocumentDiagnosticReport;
+use tower_lsp::lsp_types::WorkspaceFullDocumentDiagnosticReport;
+use tower_lsp::lsp_types::WorkspaceUnchangedDocumentDiagnosticReport;
 use tree_sitter::Node;
 use tree_sitter::Point;
 use tree_sitter::Range;
+use url::Url;
 
 use crate::lsp;
+use crate::lsp::base_symbols::BASE_SYMBOLS;
 use crate::lsp::declarations::top_level_declare;
+use crate::lsp::diagnostics_lints::check_duplicated_arguments;
+use crate::lsp::diagnostics_lints::check_library_in_package;
+use crate::lsp::diagnostics_lints::check_namespace_exports;
+use crate::lsp::diagnostics_lints::check_one_to_length;
+use crate::lsp::diagnostics_lints::check_roxygen_params;
+use crate::lsp::diagnostics_lints::check_seq_misuse;
+use crate::lsp::diagnostics_lints::check_true_false_symbol;
+use crate::lsp::diagnostics_lints::check_undeclared_package;
+use crate::lsp::diagnostics_lints::check_unimported_function;
+use crate::lsp::diagnostics_lints::check_unmatched_arguments;
+use crate::lsp::diagnostics_lints::check_unreachable_code;
+use crate::lsp::diagnostics_lints::check_unused_bindings;
+use crate::lsp::diagnostics_rules::apply_rules;
+use crate::lsp::diagnostics_rules::diagnostics_config;
+use crate::lsp::diagnostics_rules::DiagnosticRule;
+use crate::lsp::diagnostics_rules::RuleSeverity;
 use crate::lsp::diagnostics_syntax::syntax_diagnostics;
 use crate::lsp::document::Document;
 use crate::lsp::indexer;
 use crate::lsp::inputs::library::Library;
 use crate::lsp::inputs::package::Package;
 use crate::lsp::inputs::source_root::SourceRoot;
+use crate::lsp::rename::symbol_name;
+use crate::lsp::state::for_each_workspace_file;
 use crate::lsp::state::WorldState;
 use crate::lsp::traits::node::NodeExt;
 use crate::treesitter::node_has_error_or_missing;
@@ -36,10 +67,15 @@ use crate::treesitter::BinaryOperatorType;
 use crate::treesitter::NodeType;
 use crate::treesitter::NodeTypeExt;
 use crate::treesitter::UnaryOperatorType;
+use crate::url::ExtUrl;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct DiagnosticsConfig {
     pub enable: bool,
+
+    /// Severities of the rules. Rules that are not listed have their default
+    /// severity.
+    pub rules: HashMap<DiagnosticRule, RuleSeverity>,
 }
 
 #[derive(Clone)]
@@ -54,6 +90,13 @@ pub struct DiagnosticContext<'a> {
     /// mapping symbol names to the locations where they were defined.
     pub document_symbols: Vec<HashMap<String, Range>>,
 
+    /// Identifiers of the nodes introducing the scopes of `document_symbols`
+    pub document_scopes: Vec<usize>,
+
+    /// Reads and writes of symbols, only recorded when requested. Shared by
+    /// the contexts cloned for nested scopes.
+    pub occurrences: Option<Rc<RefCell<Vec<SymbolOccurrence>>>>,
+
     /// The symbols defined in the workspace.
     pub workspace_symbols: HashSet<String>,
 
@@ -75,11 +118,45 @@ pub struct DiagnosticContext<'a> {
 
     // Whether or not we're inside of a call-like node's arguments
     pub in_call_like_arguments: bool,
+
+    // Whether or not the document is part of the `R/` folder of a package
+    pub package_code: bool,
+
+    /// The symbols available to package code without being defined in the
+    /// package or imported: those of the base package and of the packages
+    /// listed in `Depends`. Only set for package code.
+    pub namespace_symbols: HashSet<String>,
+
+    /// The symbols defined in the `R/` folder of the package or imported in
+    /// its `NAMESPACE` file. Only set for package code.
+    pub package_symbols: HashSet<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SymbolAccess {
+    Read,
+    Write,
+}
+
+/// An occurrence of a symbol recorded during the scope walk
+#[derive(Clone, Debug, PartialEq)]
+pub struct SymbolOccurrence {
+    pub name: String,
+    pub range: Range,
+    pub access: SymbolAccess,
+
+    /// Identifier of the node introducing the scope the symbol is bound in.
+    /// Symbols that are not bound in the document are attributed to the
+    /// top-level scope.
+    pub scope: usize,
 }
 
 impl Default for DiagnosticsConfig {
     fn default() -> Self {
-        Self { enable: true }
+        Self {
+            enable: true,
+            rules: HashMap::new(),
+        }
     }
 }
 
@@ -88,6 +165,8 @@ impl<'a> DiagnosticContext<'a> {
         Self {
             doc,
             document_symbols: Vec::new(),
+            document_scopes: Vec::new(),
+            occurrences: None,
             session_symbols: HashSet::new(),
             workspace_symbols: HashSet::new(),
             installed_packages: HashSet::new(),
@@ -96,14 +175,73 @@ impl<'a> DiagnosticContext<'a> {
             library_symbols: BTreeMap::new(),
             in_formula: false,
             in_call_like_arguments: false,
+            package_code: false,
+            namespace_symbols: HashSet::new(),
+            package_symbols: HashSet::new(),
         }
     }
 
+    pub fn push_scope(&mut self, node: &Node) {
+        self.document_symbols.push(HashMap::new());
+        self.document_scopes.push(node.id());
+    }
+
     pub fn add_defined_variable(&mut self, name: &str, location: Range) {
+        let scope = self.document_scopes.last().copied();
+        self.add_occurrence(name, location, SymbolAccess::Write, scope);
+
         let symbols = self.document_symbols.last_mut().unwrap();
         symbols.insert(name.to_string(), location);
     }
 
+    /// Super-assignments bind in the closest parent scope defining the symbol,
+    /// or at top level
+    pub fn add_super_assigned_variable(&mut self, name: &str, location: Range) {
+        let index = self.binding_index(name, 1);
+        let scope = self.document_scopes.get(index).copied();
+        self.add_occurrence(name, location, SymbolAccess::Write, scope);
+
+        if let Some(symbols) = self.document_symbols.get_mut(index) {
+            symbols.insert(name.to_string(), location);
+        }
+    }
+
+    pub fn add_used_variable(&mut self, name: &str, location: Range) {
+        let index = self.binding_index(name, 0);
+        let scope = self.document_scopes.get(index).copied();
+        self.add_occurrence(name, location, SymbolAccess::Read, scope);
+    }
+
+    /// Find the innermost scope defining `name`, ignoring the `skip` innermost
+    /// scopes. Falls back to the top-level scope.
+    fn binding_index(&self, name: &str, skip: usize) -> usize {
+        let n = self.document_symbols.len().saturating_sub(skip);
+
+        self.document_symbols[..n]
+            .iter()
+            .rposition(|symbols| symbols.contains_key(name))
+            .unwrap_or(0)
+    }
+
+    fn add_occurrence(
+        &self,
+        name: &str,
+        location: Range,
+        access: SymbolAccess,
+        scope: Option<usize>,
+    ) {
+        let (Some(occurrences), Some(scope)) = (&self.occurrences, scope) else {
+            return;
+        };
+
+        occurrences.borrow_mut().push(SymbolOccurrence {
+            name: symbol_name(name).to_string(),
+            range: location,
+            access,
+            scope,
+        });
+    }
+
     pub fn has_definition(&self, name: &str, start_position: Point) -> bool {
         // Check document symbols
         for symbols in &self.document_symbols {
@@ -132,14 +270,31 @@ impl<'a> DiagnosticContext<'a> {
     }
 }
 
-pub(crate) fn generate_diagnostics(
-    doc: Document,
-    state: WorldState,
-    testthat: bool,
+/// Whether the document is part of the `R/` folder of the package being
+/// developed in the workspace
+pub(crate) fn is_package_code(uri: &Url, state: &WorldState) -> bool {
+    let Some(SourceRoot::Package(root)) = &state.root else {
+        return false;
+    };
+    uri.to_file_path()
+        .is_ok_and(|path| path.starts_with(root.path.join("R")))
+}
+
+pub(crate) fn generate_diagnostics(doc: Document, state: WorldState, uri: &Url) -> Vec<Diagnostic> {
+    generate_diagnostics_with_config(&doc, &state, uri, &state.config.diagnostics)
+}
+
+/// Generate the diagnostics of a document with the given configuration
+/// instead of the one of the world state
+pub(crate) fn generate_diagnostics_with_config(
+    doc: &Document,
+    state: &WorldState,
+    uri: &Url,
+    config: &DiagnosticsConfig,
 ) -> Vec<Diagnostic> {
     let mut diagnostics = Vec::new();
 
-    if !state.config.diagnostics.enable {
+    if !config.enable {
         return diagnostics;
     }
 
@@ -150,23 +305,56 @@ pub(crate) fn generate_diagnostics(
         return diagnostics;
     }
 
-    let mut context = DiagnosticContext::new(&doc, &state.root, &state.library);
+    // The NAMESPACE file of the package is checked against the package code
+    if let Some(SourceRoot::Package(root)) = &state.root {
+        if uri
+            .to_file_path()
+            .is_ok_and(|path| path == root.path.join("NAMESPACE"))
+        {
+            match check_namespace_exports(doc, root) {
+                Ok(mut namespace_diagnostics) => diagnostics.append(&mut namespace_diagnostics),
+                Err(err) => log::error!("Error while generating NAMESPACE diagnostics: {err:?}"),
+            }
+            return apply_rules(diagnostics, config, doc);
+        }
+    }
+
+    let mut context = DiagnosticContext::new(doc, &state.root, &state.library);
+
+    // Symbol occurrences are needed to find unused bindings
+    context.occurrences = Some(Rc::new(RefCell::new(Vec::new())));
 
     // Add a 'root' context for the document.
-    context.document_symbols.push(HashMap::new());
+    context.push_scope(&doc.ast.root_node());
+
+    // If this is a package, add imported symbols to workspace
+    context.package_code = is_package_code(uri, state);
+
+    // The package code only sees the definitions of its `R/` folder
+    let package_folder = match &state.root {
+        Some(SourceRoot::Package(root)) if context.package_code => Some(root.path.join("R")),
+        _ => None,
+    };
 
     // Add the current workspace symbols.
-    indexer::map(|_uri, _symbol, entry| match &entry.data {
-        indexer::IndexEntryData::Function { name, arguments: _ } => {
-            context.workspace_symbols.insert(name.to_string());
-        },
-        indexer::IndexEntryData::Variable { name } => {
-            context.workspace_symbols.insert(name.to_string());
-        },
-        _ => {},
+    indexer::map(|uri, _symbol, entry| {
+        let name = match &entry.data {
+            indexer::IndexEntryData::Function { name, arguments: _ } => name,
+            indexer::IndexEntryData::Variable { name } => name,
+            _ => return,
+        };
+        context.workspace_symbols.insert(name.to_string());
+
+        if let Some(folder) = &package_folder {
+            if uri
+                .to_file_path()
+                .is_ok_and(|path| path.starts_with(folder))
+            {
+                context.package_symbols.insert(name.to_string());
+            }
+        }
     });
 
-    // If this is a package, add imported symbols to workspace
     if let Some(SourceRoot::Package(root)) = &state.root {
         // Add symbols from `importFrom()` directives
         for import in &root.namespace.imports {
@@ -181,6 +369,36 @@ pub(crate) fn generate_diagnostics(
                 }
             }
         }
+
+        if context.package_code {
+            context
+                .package_symbols
+                .extend(root.namespace.imports.iter().cloned());
+            for package_import in &root.namespace.package_imports {
+                if let Some(pkg) = state.library.get(package_import) {
+                    context
+                        .package_symbols
+                        .extend(pkg.namespace.exports.iter().cloned());
+                }
+            }
+
+            // The base symbols are known without an R session. The last scope
+            // of the session, when there is one, is the base package.
+            context
+                .namespace_symbols
+                .extend(BASE_SYMBOLS.iter().map(|symbol| symbol.to_string()));
+            if let Some(base) = state.console_scopes.last() {
+                context.namespace_symbols.extend(base.iter().cloned());
+            }
+
+            for package in &root.description.depends {
+                if let Some(pkg) = state.library.get(package) {
+                    context
+                        .namespace_symbols
+                        .extend(pkg.namespace.exports.iter().cloned());
+                }
+            }
+        }
     }
 
     // Simple workaround to include testthat exports in test files. I think the
@@ -191,6 +409,13 @@ pub(crate) fn generate_diagnostics(
     // don't special-case how workspace inclusion works for packages). We might
     // want to provide a mechanism for test packages to declare this sort of
     // test files setup.
+    //
+    // This is a simple stopgap approach to detect test files that has some
+    // false positives (e.g. when we work on testthat itself the flag will
+    // always be true), but that shouldn't have much practical impact.
+    let testthat = Path::new(uri.path())
+        .components()
+        .any(|c| c.as_os_str() == "testthat");
     if testthat {
         if let Some(pkg) = state.library.get("testthat") {
             for export in &pkg.namespace.exports {
@@ -234,7 +459,159 @@ pub(crate) fn generate_diagnostics(
         Err(err) => log::error!("Error while generating semantic diagnostics: {err:?}"),
     }
 
-    diagnostics
+    apply_rules(diagnostics, config, doc)
+}
+
+/// Diagnostics of the R files of the workspace folders that are not open.
+/// Open documents are diagnosed as they change and their diagnostics are
+/// published by the server.
+///
+/// Each report has a result ID derived from the index `generation`, the
+/// session inputs, the configuration of the file and its modification time.
+/// Files whose result ID matches the one in `previous_result_ids` are not
+/// diagnosed again and get an unchanged report.
+pub(crate) fn workspace_diagnostics(
+    state: &WorldState,
+    previous_result_ids: &[PreviousResultId],
+    generation: u64,
+) -> Vec<WorkspaceDocumentDiagnosticReport> {
+    let previous_result_ids: HashMap<&Url, &str> = previous_result_ids
+        .iter()
+        .map(|previous| (&previous.uri, previous.value.as_str()))
+        .collect();
+
+    // Inputs shared by all files
+    let mut hasher = DefaultHasher::new();
+    generation.hash(&mut hasher);
+    state.console_scopes.hash(&mut hasher);
+    state.installed_packages.hash(&mut hasher);
+    let inputs_hash = hasher.finish();
+
+    let mut reports = Vec::new();
+
+    for_each_workspace_file(state, |path, uri| {
+        if state.documents.contains_key(uri) || !ExtUrl::should_diagnose(uri) {
+            return;
+        }
+
+        // Take the workspace configuration file into account
+        let config = diagnostics_config(uri, state);
+        let result_id = workspace_result_id(inputs_hash, path, &config);
+
+        if let Some(result_id) = &result_id {
+            if previous_result_ids.get(uri) == Some(&result_id.as_str()) {
+                reports.push(WorkspaceDocumentDiagnosticReport::Unchanged(
+                    WorkspaceUnchangedDocumentDiagnosticReport {
+                        uri: uri.clone(),
+                        version: None,
+                        unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport {
+                            result_id: result_id.clone(),
+                        },
+                    },
+                ));
+                return;
+            }
+        }
+
+        let document = match std::fs::read_to_string(path) {
+            Ok(contents) => Document::new(&contents, None),
+            Err(err) => {
+                lsp::log_warn!("Can't read file {}: {err:?}", path.display());
+                return;
+            },
+        };
+        let items = generate_diagnostics_with_config(&document, state, uri, &config);
+
+        reports.push(WorkspaceDocumentDiagnosticReport::Full(
+            WorkspaceFullDocumentDiagnosticReport {
+                uri: uri.clone(),
+                version: None,
+                full_document_diagnostic_report: FullDocumentDiagnosticReport { result_id, items },
+            },
+        ));
+    });
+
+    reports
+}
+
+fn workspace_result_id(
+    inputs_hash: u64,
+    path: &Path,
+    config: &DiagnosticsConfig,
+) -> Option<String> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?;
+
+    let mut rules: Vec<_> = config
+        .rules
+        .iter()
+        .map(|(rule, severity)| (rule.code(), *severity))
+        .collect();
+    rules.sort_by_key(|(code, _)| *code);
+
+    let mut hasher = DefaultHasher::new();
+    inputs_hash.hash(&mut hasher);
+    modified.hash(&mut hasher);
+    metadata.len().hash(&mut hasher);
+    config.enable.hash(&mut hasher);
+    rules.hash(&mut hasher);
+
+    Some(format!("{:016x}", hasher.finish()))
+}
+
+/// Collect the reads and writes of symbols in a document, using the same
+/// scope walk as for diagnostics
+pub(crate) fn symbol_occurrences(doc: &Document, state: &WorldState) -> Vec<SymbolOccurrence> {
+    scope_symbol_occurrences(doc, state, doc.ast.root_node())
+}
+
+/// Collect the reads and writes of symbols in `scope`, the root of the
+/// document or a function definition. Symbols bound outside of a function
+/// are attributed to the top-level scope.
+pub(crate) fn scope_symbol_occurrences(
+    doc: &Document,
+    state: &WorldState,
+    scope: Node,
+) -> Vec<SymbolOccurrence> {
+    let occurrences = Rc::new(RefCell::new(Vec::new()));
+
+    let mut context = DiagnosticContext::new(doc, &state.root, &state.library);
+    context.occurrences = Some(occurrences.clone());
+
+    let root = doc.ast.root_node();
+    context.push_scope(&root);
+
+    let result = if scope == root {
+        semantic_diagnostics(root, &mut context).map(|_| ())
+    } else if node_has_error_or_missing(&scope) {
+        Ok(())
+    } else {
+        recurse(scope, &mut context, &mut Vec::new())
+    };
+
+    if let Err(err) = result {
+        log::error!("Error while collecting symbol occurrences: {err:?}");
+    }
+
+    drop(context);
+    occurrences.take()
+}
+
+/// The outermost function definition containing `node`, or the root of the
+/// document. Bindings referred to within a function are all recorded by the
+/// walk of this scope, except top-level ones.
+pub(crate) fn outermost_scope(node: Node) -> Node {
+    let mut root = node;
+    let mut function = None;
+
+    for ancestor in node.ancestors() {
+        if ancestor.is_function_definition() {
+            function = Some(ancestor);
+        }
+        root = ancestor;
+    }
+
+    function.unwrap_or(root)
 }
 
 fn semantic_diagnostics(
@@ -330,7 +707,7 @@ fn recurse_function(
 
     // Add a new symbols context for this scope.
     let mut context = context.clone();
-    context.document_symbols.push(HashMap::new());
+    context.push_scope(&node);
     let context = &mut context;
 
     // Recurse through the arguments, adding their symbols to the `context`
@@ -338,6 +715,12 @@ fn recurse_function(
         bail!("Missing `parameters` field in a `function_definition` node");
     });
 
+    // Occurrences recorded from here on belong to this function
+    let start = context
+        .occurrences
+        .as_ref()
+        .map(|occurrences| occurrences.borrow().len());
+
     recurse_parameters(parameters, context, diagnostics)?;
 
     // Recurse through the body, if one exists
@@ -345,6 +728,13 @@ fn recurse_function(
         recurse(body, context, diagnostics)?;
     }
 
+    if let (Some(occurrences), Some(start)) = (&context.occurrences, start) {
+        let occurrences = occurrences.borrow();
+        check_unused_bindings(node, &occurrences[start..], context, diagnostics)?;
+    }
+
+    check_roxygen_params(node, context, diagnostics)?;
+
     Ok(())
 }
 
@@ -490,7 +880,20 @@ fn recurse_super_assignment(
     // We could probably add some more advanced diagnostics here, but for
     // now we want to make sure that the `identifier` isn't hit with a "symbol
     // not in scope" diagnostic, so we add it to the `document_symbols` map.
-    recurse_assignment(identifier, expression, context, diagnostics)
+    if let Some(identifier) = identifier {
+        if identifier.is_identifier_or_string() {
+            let name = identifier.node_as_str(&context.doc.contents)?;
+            context.add_super_assigned_variable(name, identifier.range());
+        }
+        handle_assignment_dotty(&identifier, context)?;
+        handle_assignment_complex(&identifier, context)?;
+    }
+
+    if let Some(expression) = expression {
+        recurse(expression, context, diagnostics)?;
+    }
+
+    ().ok()
 }
 
 fn recurse_left_assignment(
@@ -535,6 +938,9 @@ fn recurse_assignment(
 
         // Check for dotty assignment
         handle_assignment_dotty(&identifier, context)?;
+
+        // Check for complex assignment, e.g. `x[i] <- value`
+        handle_assignment_complex(&identifier, context)?;
     }
 
     // Recurse into expression for assignment.
@@ -560,6 +966,55 @@ fn handle_assignment_variable(
     Ok(())
 }
 
+/// Complex assignments such as `names(x) <- value` or `x$a[i] <- value`
+/// modify an existing binding, so we record its symbol and the symbols of
+/// the indices as read.
+fn handle_assignment_complex(
+    identifier: &Node,
+    context: &mut DiagnosticContext,
+) -> anyhow::Result<()> {
+    if identifier.is_identifier_or_string() || is_dotty_target(identifier, context)? {
+        return Ok(());
+    }
+
+    handle_symbol_reads(*identifier, context)
+}
+
+fn is_dotty_target(identifier: &Node, context: &DiagnosticContext) -> anyhow::Result<bool> {
+    if !identifier.is_subset() {
+        return Ok(false);
+    }
+    let Some(dot) = identifier.child_by_field_name("function") else {
+        return Ok(false);
+    };
+    Ok(dot.is_identifier() && dot.node_as_str(&context.doc.contents)? == ".")
+}
+
+/// Record the symbols of an expression that we don't otherwise recurse into
+/// as read, skipping argument names
+fn handle_symbol_reads(node: Node, context: &mut DiagnosticContext) -> anyhow::Result<()> {
+    if context.occurrences.is_none() {
+        return Ok(());
+    }
+
+    handle_symbol_use(node, context)?;
+
+    let name = if node.is_argument() {
+        node.child_by_field_name("name")
+    } else {
+        None
+    };
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if Some(child) != name {
+            handle_symbol_reads(child, context)?;
+        }
+    }
+
+    Ok(())
+}
+
 /// Support for dotty assignment
 ///
 /// Comes in a few forms
@@ -694,10 +1149,12 @@ fn recurse_namespace(
         let range = lhs.range();
         let range = context.doc.lsp_range_from_tree_sitter_range(range)?;
         let message = format!("Package '{}' is not installed.", package);
-        let diagnostic = Diagnostic::new_simple(range, message);
+        let diagnostic = DiagnosticRule::PackageNotInstalled.diagnostic(range, message);
         diagnostics.push(diagnostic);
     }
 
+    check_undeclared_package(lhs, context, diagnostics)?;
+
     // Check for a symbol in this namespace.
     let rhs = unwrap!(node.child_by_field_name("rhs"), None => {
         return ().ok();
@@ -731,6 +1188,15 @@ fn recurse_parameters(
         context.add_defined_variable(symbol, location);
     }
 
+    // Default values are evaluated in the scope of the function and may refer
+    // to other parameters, e.g. `function(x, n = length(x))`
+    let mut cursor = node.walk();
+    for child in node.children_by_field_name("parameter", &mut cursor) {
+        if let Some(default) = child.child_by_field_name("default") {
+            handle_symbol_reads(default, context)?;
+        }
+    }
+
     ().ok()
 }
 
@@ -739,6 +1205,8 @@ fn recurse_braced_expression(
     context: &mut DiagnosticContext,
     diagnostics: &mut Vec<Diagnostic>,
 ) -> Result<()> {
+    check_unreachable_code(node, context, diagnostics)?;
+
     // Recurse into body statements.
     let mut cursor = node.walk();
 
@@ -849,6 +1317,11 @@ fn recurse_call(
                 lsp::log_warn!("Can't handle attach call: {err:?}");
             }
         },
+        "assign" => {
+            if let Err(err) = handle_assign_call(node, context) {
+                lsp::log_warn!("Can't handle assign call: {err:?}");
+            }
+        },
         _ => {},
     };
 
@@ -934,6 +1407,47 @@ fn handle_package_attach_call(node: Node, context: &mut DiagnosticContext) -> an
     Ok(())
 }
 
+/// `assign("x", value)` defines `x` in the current environment. We bail if
+/// other arguments are supplied, e.g. `envir` or `pos`, as the symbol is then
+/// defined elsewhere.
+fn handle_assign_call(node: Node, context: &mut DiagnosticContext) -> anyhow::Result<()> {
+    let contents = &context.doc.contents;
+
+    if node.arguments().count() > 2 {
+        return Ok(());
+    }
+
+    let mut target = None;
+    let mut positional = Vec::new();
+
+    for (name, value) in node.arguments() {
+        match name {
+            Some(name) => match name.node_as_str(contents)? {
+                "x" => target = value,
+                "value" => {},
+                _ => return Ok(()),
+            },
+            None => positional.push(value),
+        }
+    }
+
+    let Some(target) = target.or_else(|| positional.first().copied().flatten()) else {
+        return Ok(());
+    };
+    if target.node_type() != NodeType::String {
+        return Ok(());
+    }
+
+    let name = target.get_identifier_or_string_text(contents)?;
+    let range = target
+        .child_by_field_name("content")
+        .unwrap_or(target)
+        .range();
+    context.add_defined_variable(name, range);
+
+    Ok(())
+}
+
 fn insert_package_exports(
     package_name: &str,
     attach_pos: Point,
@@ -976,11 +1490,38 @@ fn recurse_default(
     context: &mut DiagnosticContext,
     diagnostics: &mut Vec<Diagnostic>,
 ) -> Result<()> {
+    // Record uses of symbols, e.g. for document highlights
+    if context.occurrences.is_some() {
+        if let Err(err) = handle_symbol_use(node, context) {
+            log::error!("{err:?}");
+        }
+    }
+
     // Apply diagnostic functions to node.
     dispatch(node, context, diagnostics);
     recurse_children(node, context, diagnostics)
 }
 
+fn handle_symbol_use(node: Node, context: &mut DiagnosticContext) -> anyhow::Result<()> {
+    if !node.is_identifier() {
+        return Ok(());
+    }
+
+    // Skip if this identifier belongs to a '$' or `@` node.
+    if let Some(parent) = node.parent() {
+        if matches!(parent.node_type(), NodeType::ExtractOperator(_)) &&
+            parent.child_by_field_name("rhs") == Some(node)
+        {
+            return Ok(());
+        }
+    }
+
+    let name = node.node_as_str(&context.doc.contents)?;
+    context.add_used_variable(name, node.range());
+
+    Ok(())
+}
+
 fn recurse_children(
     node: Node,
     context: &mut DiagnosticContext,
@@ -1000,6 +1541,13 @@ fn dispatch(node: Node, context: &mut DiagnosticContext, diagnostics: &mut Vec<D
         check_invalid_na_comparison(node, context, diagnostics)?;
         check_symbol_in_scope(node, context, diagnostics)?;
         check_unexpected_assignment_in_if_conditional(node, context, diagnostics)?;
+        check_true_false_symbol(node, context, diagnostics)?;
+        check_one_to_length(node, context, diagnostics)?;
+        check_seq_misuse(node, context, diagnostics)?;
+        check_duplicated_arguments(node, context, diagnostics)?;
+        check_unmatched_arguments(node, context, diagnostics)?;
+        check_library_in_package(node, context, diagnostics)?;
+        check_unimported_function(node, context, diagnostics)?;
         true.ok()
     };
 
@@ -1036,8 +1584,7 @@ fn check_invalid_na_comparison(
             };
             let range = child.range();
             let range = context.doc.lsp_range_from_tree_sitter_range(range)?;
-            let mut diagnostic = Diagnostic::new_simple(range, message.into());
-            diagnostic.severity = Some(DiagnosticSeverity::INFORMATION);
+            let diagnostic = DiagnosticRule::InvalidNaComparison.diagnostic(range, message.into());
             diagnostics.push(diagnostic);
         }
     }
@@ -1071,7 +1618,7 @@ fn check_unexpected_assignment_in_if_conditional(
     let range = condition.range();
     let range = context.doc.lsp_range_from_tree_sitter_range(range)?;
     let message = "Unexpected '='; use '==' to compare values for equality.";
-    let diagnostic = Diagnostic::new_simple(range, message.into());
+    let diagnostic = DiagnosticRule::AssignmentInIfCondition.diagnostic(range, message.into());
     diagnostics.push(diagnostic);
 
     true.ok()
@@ -1120,8 +1667,7 @@ fn check_symbol_in_scope(
     let range = context.doc.lsp_range_from_tree_sitter_range(range)?;
     let identifier = node.node_as_str(&context.doc.contents)?;
     let message = format!("No symbol named '{}' in scope.", identifier);
-    let mut diagnostic = Diagnostic::new_simple(range, message);
-    diagnostic.severity = Some(DiagnosticSeverity::WARNING);
+    let diagnostic = DiagnosticRule::SymbolNotInScope.diagnostic(range, message);
     diagnostics.push(diagnostic);
 
     true.ok()
@@ -1134,23 +1680,31 @@ mod tests {
     use harp::eval::RParseEvalOptions;
     use once_cell::sync::Lazy;
     use tower_lsp::lsp_types;
+    use tower_lsp::lsp_types::DiagnosticSeverity;
+    use tower_lsp::lsp_types::DiagnosticTag;
     use tower_lsp::lsp_types::Position;
+    use url::Url;
 
     use crate::console::console_inputs;
+    use crate::lsp::diagnostics_rules::DiagnosticRule;
+    use crate::lsp::diagnostics_rules::RuleSeverity;
     use crate::lsp::document::Document;
+    use crate::lsp::indexer;
     use crate::lsp::inputs::library::Library;
     use crate::lsp::inputs::package::Package;
     use crate::lsp::inputs::package_description::Dcf;
     use crate::lsp::inputs::package_description::Description;
     use crate::lsp::inputs::package_namespace::Namespace;
+    use crate::lsp::inputs::source_root::SourceRoot;
     use crate::lsp::state::WorldState;
+    use crate::lsp::util::test_path;
     use crate::r_task;
 
     // Default state that includes installed packages and default scopes.
     static DEFAULT_STATE: Lazy<WorldState> = Lazy::new(|| current_state());
 
     fn generate_diagnostics(doc: Document, state: WorldState) -> Vec<lsp_types::Diagnostic> {
-        super::generate_diagnostics(doc, state, false)
+        super::generate_diagnostics(doc, state, &test_path("test.R"))
     }
 
     fn current_state() -> WorldState {
@@ -1877,4 +2431,674 @@ foo
             assert_eq!(diagnostics.len(), 3);
         })
     }
+
+    #[test]
+    fn test_diagnostic_rules() {
+        r_task(|| {
+            let code = "
+foo # ark: ignore[symbol-not-in-scope]
+bar
+if (x = 1) NULL # ark: ignore
+";
+            let document = Document::new(code, None);
+            let diagnostics = generate_diagnostics(document.clone(), DEFAULT_STATE.clone());
+            assert_eq!(diagnostics.len(), 1);
+            assert_eq!(diagnostics[0].message, "No symbol named 'bar' in scope.");
+            assert_eq!(
+                diagnostics[0].code,
+                Some(lsp_types::NumberOrString::String(String::from(
+                    "symbol-not-in-scope"
+                )))
+            );
+
+            // Rules can be turned off in the configuration
+            let mut state = DEFAULT_STATE.clone();
+            state
+                .config
+                .diagnostics
+                .rules
+                .insert(DiagnosticRule::SymbolNotInScope, RuleSeverity::Off);
+            let diagnostics = generate_diagnostics(document, state);
+            assert!(diagnostics.is_empty());
+        })
+    }
+
+    fn rule_diagnostics(
+        diagnostics: Vec<lsp_types::Diagnostic>,
+        rule: DiagnosticRule,
+    ) -> Vec<lsp_types::Diagnostic> {
+        let code = lsp_types::NumberOrString::String(rule.code().to_string());
+        diagnostics
+            .into_iter()
+            .filter(|diagnostic| diagnostic.code.as_ref() == Some(&code))
+            .collect()
+    }
+
+    #[test]
+    fn test_unused_variable() {
+        r_task(|| {
+            let code = "
+f <- function(x) {
+  y <- 1
+  x
+}
+";
+            let document = Document::new(code, None);
+            let diagnostics = generate_diagnostics(document, DEFAULT_STATE.clone());
+            assert_eq!(diagnostics.len(), 1);
+
+            let diagnostic = diagnostics.get(0).unwrap();
+            insta::assert_snapshot!(diagnostic.message);
+            assert_eq!(diagnostic.range.start, Position::new(2, 2));
+            assert_eq!(diagnostic.range.end, Position::new(2, 3));
+            assert_eq!(diagnostic.tags, Some(vec![DiagnosticTag::UNNECESSARY]));
+
+            // Reads in defaults, complex assignments, and interpolated strings
+            // count as uses. Top-level variables and loop variables are not
+            // reported.
+            let code = r#"
+unused <- 1
+f <- function(x, n = length(x)) {
+  out <- x
+  names(out) <- "a"
+  message("{n}")
+  for (i in out) NULL
+  out
+}
+"#;
+            let document = Document::new(code, None);
+            let diagnostics = generate_diagnostics(document, DEFAULT_STATE.clone());
+            assert!(diagnostics.is_empty());
+
+            // Functions accessing their environment are not linted
+            let code = "
+f <- function() {
+  y <- 1
+  environment()
+}
+";
+            let document = Document::new(code, None);
+            let diagnostics = generate_diagnostics(document, DEFAULT_STATE.clone());
+            assert!(diagnostics.is_empty());
+        })
+    }
+
+    #[test]
+    fn test_unused_parameter() {
+        r_task(|| {
+            let code = "f <- function(x, y, ...) x";
+            let document = Document::new(code, None);
+            let diagnostics = generate_diagnostics(document, DEFAULT_STATE.clone());
+            assert_eq!(diagnostics.len(), 1);
+
+            let diagnostic = diagnostics.get(0).unwrap();
+            insta::assert_snapshot!(diagnostic.message);
+            assert_eq!(diagnostic.range.start, Position::new(0, 17));
+            assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::HINT));
+
+            // Parameters used in nested functions are used
+            let code = "f <- function(x) function() x";
+            let document = Document::new(code, None);
+            let diagnostics = generate_diagnostics(document, DEFAULT_STATE.clone());
+            assert!(diagnostics.is_empty());
+        })
+    }
+
+    #[test]
+    fn test_unreachable_code() {
+        r_task(|| {
+            let code = "
+f <- function(x) {
+  return(x)
+  x + 1
+  x + 2
+}
+";
+            let document = Document::new(code, None);
+            let diagnostics = generate_diagnostics(document, DEFAULT_STATE.clone());
+            assert_eq!(diagnostics.len(), 1);
+
+            let diagnostic = diagnostics.get(0).unwrap();
+            insta::assert_snapshot!(diagnostic.message);
+            assert_eq!(diagnostic.range.start, Position::new(3, 2));
+            assert_eq!(diagnostic.range.end, Position::new(4, 7));
+
+            let code = r#"
+for (i in 1:2) {
+  next
+  print(i)
+}
+f <- function(x) {
+  if (x) stop("nope")
+  x
+}
+"#;
+            let document = Document::new(code, None);
+            let diagnostics = generate_diagnostics(document, DEFAULT_STATE.clone());
+            let diagnostics = rule_diagnostics(diagnostics, DiagnosticRule::UnreachableCode);
+            assert_eq!(diagnostics.len(), 1);
+            assert_eq!(diagnostics[0].range.start, Position::new(3, 2));
+        })
+    }
+
+    #[test]
+    fn test_true_false_symbol() {
+        r_task(|| {
+            let code = "x <- T";
+            let document = Document::new(code, None);
+            let diagnostics = generate_diagnostics(document, DEFAULT_STATE.clone());
+            assert_eq!(diagnostics.len(), 1);
+
+            let diagnostic = diagnostics.get(0).unwrap();
+            insta::assert_snapshot!(diagnostic.message);
+            assert_eq!(diagnostic.range.start, Position::new(0, 5));
+
+            // Not reported in formulas, after `$`, or when defined
+            let code = "
+y ~ T
+list()$F
+F <- 0
+F
+";
+            let document = Document::new(code, None);
+            let diagnostics = generate_diagnostics(document, DEFAULT_STATE.clone());
+            assert!(diagnostics.is_empty());
+        })
+    }
+
+    #[test]
+    fn test_one_to_length() {
+        r_task(|| {
+            let code = "
+x <- 1:3
+1:length(x)
+1:nrow(x)
+2:length(x)
+";
+            let document = Document::new(code, None);
+            let diagnostics = generate_diagnostics(document, DEFAULT_STATE.clone());
+            assert_eq!(diagnostics.len(), 2);
+
+            let diagnostic = diagnostics.get(0).unwrap();
+            insta::assert_snapshot!(diagnostic.message);
+            assert_eq!(diagnostic.range.start, Position::new(2, 0));
+            assert_eq!(diagnostic.range.end, Position::new(2, 11));
+
+            let diagnostic = diagnostics.get(1).unwrap();
+            insta::assert_snapshot!(diagnostic.message);
+        })
+    }
+
+    #[test]
+    fn test_seq_misuse() {
+        r_task(|| {
+            let code = "
+x <- 1:3
+seq(length(x))
+seq_along(length(x))
+seq_len(c(1, 2))
+seq_len(length(x))
+seq(2, 10)
+";
+            let document = Document::new(code, None);
+            let diagnostics = generate_diagnostics(document, DEFAULT_STATE.clone());
+            assert_eq!(diagnostics.len(), 3);
+
+            let diagnostic = diagnostics.get(0).unwrap();
+            insta::assert_snapshot!(diagnostic.message);
+            assert_eq!(diagnostic.range.start, Position::new(2, 0));
+
+            let diagnostic = diagnostics.get(1).unwrap();
+            insta::assert_snapshot!(diagnostic.message);
+
+            let diagnostic = diagnostics.get(2).unwrap();
+            insta::assert_snapshot!(diagnostic.message);
+        })
+    }
+
+    #[test]
+    fn test_duplicated_argument() {
+        r_task(|| {
+            let code = r#"
+list(a = 1, b = 2, a = 3)
+list(`a` = 1, "a" = 2)
+mutate(df, a = 1, a = a + 1)
+"#;
+            let document = Document::new(code, None);
+            let diagnostics = generate_diagnostics(document, DEFAULT_STATE.clone());
+            let diagnostics = rule_diagnostics(diagnostics, DiagnosticRule::DuplicatedArgument);
+            assert_eq!(diagnostics.len(), 2);
+
+            let diagnostic = diagnostics.get(0).unwrap();
+            insta::assert_snapshot!(diagnostic.message);
+            assert_eq!(diagnostic.range.start, Position::new(1, 19));
+            assert_eq!(diagnostics[1].range.start, Position::new(2, 14));
+        })
+    }
+
+    #[test]
+    fn test_unmatched_argument() {
+        r_task(|| {
+            let _guard = indexer::ResetIndexerGuard;
+
+            let definitions = Document::new("f <- function(x, y) NULL", None);
+            indexer::update(&definitions, &test_path("definitions.R")).unwrap();
+
+            let code = "
+f(1, z = 2)
+f(1, 2, 3, 4)
+f(y = 1, 2)
+f(1, ye = 2)
+g <- function() {
+  f <- function(...) NULL
+  f(1, 2, 3)
+}
+";
+            let document = Document::new(code, None);
+            let diagnostics = generate_diagnostics(document, DEFAULT_STATE.clone());
+            let diagnostics = rule_diagnostics(diagnostics, DiagnosticRule::UnmatchedArgument);
+            assert_eq!(diagnostics.len(), 3);
+
+            let diagnostic = diagnostics.get(0).unwrap();
+            insta::assert_snapshot!(diagnostic.message);
+            assert_eq!(diagnostic.range.start, Position::new(1, 5));
+
+            let diagnostic = diagnostics.get(1).unwrap();
+            insta::assert_snapshot!(diagnostic.message);
+            assert_eq!(diagnostic.range.start, Position::new(2, 8));
+            assert_eq!(diagnostic.range.end, Position::new(2, 12));
+
+            assert_eq!(diagnostics[2].range.start, Position::new(4, 5));
+        })
+    }
+
+    #[test]
+    fn test_unmatched_argument_library() {
+        r_task(|| {
+            let dir = crate::lsp::inputs::package::temp_palmerpenguin();
+            std::fs::create_dir(dir.path().join("R")).unwrap();
+            std::fs::write(
+                dir.path().join("R").join("path.R"),
+                "path_to_file <- function(path = NULL) NULL\n",
+            )
+            .unwrap();
+            let package = Package::load_from_folder(dir.path()).unwrap().unwrap();
+
+            let mut state = DEFAULT_STATE.clone();
+            state.library = Library::new(vec![]).insert("penguins", package);
+            state.installed_packages.push(String::from("penguins"));
+
+            let code = "
+penguins::path_to_file('a.csv')
+penguins::path_to_file('a.csv', 'b.csv')
+";
+            let document = Document::new(code, None);
+            let diagnostics = generate_diagnostics(document, state);
+            assert_eq!(diagnostics.len(), 1);
+
+            let diagnostic = diagnostics.get(0).unwrap();
+            insta::assert_snapshot!(diagnostic.message);
+            assert_eq!(diagnostic.range.start, Position::new(2, 32));
+        })
+    }
+
+    #[test]
+    fn test_library_in_package() {
+        r_task(|| {
+            let description = Description {
+                name: "mockpkg".to_string(),
+                version: "1.0.0".to_string(),
+                depends: vec![],
+                fields: Dcf::new(),
+            };
+            let path = std::env::temp_dir().join("mockpkg");
+            let package = Package::from_parts(path.clone(), description, Namespace::default());
+
+            let mut state = DEFAULT_STATE.clone();
+            state.root = Some(SourceRoot::Package(package));
+
+            let code = "library(utils)";
+            let document = Document::new(code, None);
+
+            let uri = Url::from_file_path(path.join("R").join("utils.R")).unwrap();
+            let diagnostics = super::generate_diagnostics(document.clone(), state.clone(), &uri);
+            assert_eq!(diagnostics.len(), 1);
+
+            let diagnostic = diagnostics.get(0).unwrap();
+            insta::assert_snapshot!(diagnostic.message);
+
+            // Fine in tests and scripts of the package
+            let uri = Url::from_file_path(path.join("tests").join("testthat").join("test-utils.R"))
+                .unwrap();
+            let diagnostics = super::generate_diagnostics(document, state, &uri);
+            assert!(diagnostics.is_empty());
+        })
+    }
+
+    #[test]
+    fn test_roxygen_params() {
+        r_task(|| {
+            let code = "
+#' Add numbers
+#'
+#' @param x,z A number.
+add <- function(x, y) x + y
+";
+            let document = Document::new(code, None);
+            let diagnostics = generate_diagnostics(document, DEFAULT_STATE.clone());
+            assert_eq!(diagnostics.len(), 2);
+
+            let unknown = diagnostics
+                .iter()
+                .find(|diagnostic| {
+                    DiagnosticRule::from_diagnostic(diagnostic) ==
+                        Some(DiagnosticRule::UnknownRoxygenParam)
+                })
+                .unwrap();
+            insta::assert_snapshot!(unknown.message);
+            assert_eq!(unknown.range.start, Position::new(3, 12));
+            assert_eq!(unknown.range.end, Position::new(3, 13));
+
+            let undocumented = diagnostics
+                .iter()
+                .find(|diagnostic| {
+                    DiagnosticRule::from_diagnostic(diagnostic) ==
+                        Some(DiagnosticRule::UndocumentedParameter)
+                })
+                .unwrap();
+            insta::assert_snapshot!(undocumented.message);
+            assert_eq!(undocumented.range.start, Position::new(4, 19));
+            assert_eq!(undocumented.severity, Some(DiagnosticSeverity::INFORMATION));
+
+            // Blocks without a title, and parameters inherited from other
+            // topics are not reported
+            let code = "
+#' @export
+f <- function(x) x
+
+#' Subtract numbers
+#' @inheritParams f
+g <- function(x, y) x - y
+";
+            let document = Document::new(code, None);
+            let diagnostics = generate_diagnostics(document, DEFAULT_STATE.clone());
+            assert!(diagnostics.is_empty());
+        })
+    }
+
+    fn mock_package_state(description: &str, namespace: Namespace) -> (WorldState, PathBuf) {
+        let description = Description::parse(description).unwrap();
+        let path = std::env::temp_dir().join("mockpkg");
+        let package = Package::from_parts(path.clone(), description, namespace);
+
+        let mut state = DEFAULT_STATE.clone();
+        state.root = Some(SourceRoot::Package(package));
+
+        (state, path)
+    }
+
+    #[test]
+    fn test_undeclared_package() {
+        r_task(|| {
+            let (state, path) = mock_package_state(
+                "Package: mockpkg\nVersion: 1.0.0\nImports: rlang\n",
+                Namespace::default(),
+            );
+
+            let code = "rlang::abort\nbase::stop\nutils::head\n";
+            let document = Document::new(code, None);
+
+            let uri = Url::from_file_path(path.join("R").join("utils.R")).unwrap();
+            let diagnostics: Vec<_> = super::generate_diagnostics(document, state, &uri)
+                .into_iter()
+                .filter(|diagnostic| {
+                    DiagnosticRule::from_diagnostic(diagnostic) ==
+                        Some(DiagnosticRule::UndeclaredPackage)
+                })
+                .collect();
+            assert_eq!(diagnostics.len(), 1);
+
+            let diagnostic = diagnostics.get(0).unwrap();
+            insta::assert_snapshot!(diagnostic.message);
+            assert_eq!(diagnostic.range.start, Position::new(2, 0));
+            assert_eq!(diagnostic.range.end, Position::new(2, 5));
+        })
+    }
+
+    #[test]
+    fn test_unimported_function() {
+        r_task(|| {
+            let code = "
+g <- function(x) x
+f <- function(x) {
+  g(x)
+  paste(x)
+  head(x)
+}
+";
+            let document = Document::new(code, None);
+
+            let (state, path) =
+                mock_package_state("Package: mockpkg\nVersion: 1.0.0\n", Namespace::default());
+            let uri = Url::from_file_path(path.join("R").join("f.R")).unwrap();
+
+            let diagnostics = super::generate_diagnostics(document.clone(), state, &uri);
+            assert_eq!(diagnostics.len(), 1);
+
+            let diagnostic = diagnostics.get(0).unwrap();
+            insta::assert_snapshot!(diagnostic.message);
+            assert_eq!(diagnostic.range.start, Position::new(5, 2));
+
+            // Imported functions are available
+            let namespace = Namespace {
+                imports: vec![String::from("head")],
+                ..Default::default()
+            };
+            let (state, _) = mock_package_state("Package: mockpkg\nVersion: 1.0.0\n", namespace);
+            let diagnostics = super::generate_diagnostics(document, state, &uri);
+            assert!(diagnostics.is_empty());
+        })
+    }
+
+    #[test]
+    fn test_unimported_function_outside_package_folder() {
+        r_task(|| {
+            let _guard = indexer::ResetIndexerGuard;
+
+            let (mut state, path) =
+                mock_package_state("Package: mockpkg\nVersion: 1.0.0\n", Namespace::default());
+
+            // Without an R session
+            state.console_scopes = vec![];
+
+            let util = Url::from_file_path(path.join("R").join("util.R")).unwrap();
+            indexer::update(&Document::new("util <- function() NULL", None), &util).unwrap();
+
+            let helper = path.join("tests").join("testthat").join("helper.R");
+            let helper = Url::from_file_path(helper).unwrap();
+            indexer::update(&Document::new("helper <- function() NULL", None), &helper).unwrap();
+
+            let code = "
+f <- function() {
+  util()
+  helper()
+  paste()
+}
+";
+            let document = Document::new(code, None);
+            let uri = Url::from_file_path(path.join("R").join("f.R")).unwrap();
+
+            let diagnostics: Vec<_> = super::generate_diagnostics(document, state, &uri)
+                .into_iter()
+                .filter(|diagnostic| {
+                    DiagnosticRule::from_diagnostic(diagnostic) ==
+                        Some(DiagnosticRule::UnimportedFunction)
+                })
+                .collect();
+            assert_eq!(diagnostics.len(), 1);
+            assert_eq!(diagnostics[0].range.start, Position::new(3, 2));
+        })
+    }
+
+    #[test]
+    fn test_undefined_export() {
+        r_task(|| {
+            let _guard = indexer::ResetIndexerGuard;
+
+            let (state, path) =
+                mock_package_state("Package: mockpkg\nVersion: 1.0.0\n", Namespace::default());
+
+            let code = "f <- function() NULL";
+            let uri = Url::from_file_path(path.join("R").join("f.R")).unwrap();
+            indexer::update(&Document::new(code, None), &uri).unwrap();
+
+            let namespace = "export(f)\nexport(g)\nimportFrom(utils, head)\n";
+            let document = Document::new(namespace, None);
+
+            let uri = Url::from_file_path(path.join("NAMESPACE")).unwrap();
+            let diagnostics = super::generate_diagnostics(document, state, &uri);
+            assert_eq!(diagnostics.len(), 1);
+
+            let diagnostic = diagnostics.get(0).unwrap();
+            insta::assert_snapshot!(diagnostic.message);
+            assert_eq!(diagnostic.range.start, Position::new(1, 7));
+            assert_eq!(diagnostic.range.end, Position::new(1, 8));
+        })
+    }
+
+    #[test]
+    fn test_undefined_export_defining_calls() {
+        r_task(|| {
+            let _guard = indexer::ResetIndexerGuard;
+
+            let dir = tempfile::tempdir().unwrap();
+            let folder = dir.path().join("R");
+            std::fs::create_dir(&folder).unwrap();
+
+            let description = Description::parse("Package: mockpkg\nVersion: 1.0.0\n").unwrap();
+            let package =
+                Package::from_parts(dir.path().to_path_buf(), description, Namespace::default());
+            let mut state = DEFAULT_STATE.clone();
+            state.root = Some(SourceRoot::Package(package));
+
+            let code = "
+f <- function() NULL
+setGeneric(\"area\", function(shape) standardGeneric(\"area\"))
+methods::setClass(Class = \"Shape\")
+.onLoad <- function(lib, pkg) {
+  delayedAssign(\"lazy\", f())
+  makeActiveBinding(\"active\", f, environment())
+}
+";
+            std::fs::write(folder.join("f.R"), code).unwrap();
+            let uri = Url::from_file_path(folder.join("f.R")).unwrap();
+            indexer::update(&Document::new(code, None), &uri).unwrap();
+
+            let namespace = "export(f, area, Shape, lazy, active, g)\n";
+            let uri = Url::from_file_path(dir.path().join("NAMESPACE")).unwrap();
+
+            let document = Document::new(namespace, None);
+            let diagnostics = super::generate_diagnostics(document, state.clone(), &uri);
+            assert_eq!(diagnostics.len(), 1);
+            assert_eq!(diagnostics[0].range.start, Position::new(0, 37));
+
+            // Names computed at runtime might define any export
+            let code = "for (name in c(\"g\", \"h\")) assign(name, NULL)\n";
+            std::fs::write(folder.join("g.R"), code).unwrap();
+
+            let document = Document::new(namespace, None);
+            let diagnostics = super::generate_diagnostics(document, state, &uri);
+            assert!(diagnostics.is_empty());
+        })
+    }
+
+    #[test]
+    fn test_workspace_diagnostics() {
+        r_task(|| {
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::write(dir.path().join("closed.R"), "foo\n").unwrap();
+            std::fs::write(dir.path().join("open.R"), "bar\n").unwrap();
+
+            let mut state = DEFAULT_STATE.clone();
+            state
+                .workspace
+                .folders
+                .push(Url::from_directory_path(dir.path()).unwrap());
+
+            // Open documents are diagnosed on change and not reported
+            let open = Url::from_file_path(dir.path().join("open.R")).unwrap();
+            state.documents.insert(open, Document::new("bar\n", None));
+
+            let reports = super::workspace_diagnostics(&state, &[], 0);
+            assert_eq!(reports.len(), 1);
+
+            let lsp_types::WorkspaceDocumentDiagnosticReport::Full(report) = &reports[0] else {
+                panic!("Expected a full report");
+            };
+            let closed = Url::from_file_path(dir.path().join("closed.R")).unwrap();
+            assert_eq!(report.uri, closed);
+
+            let items = &report.full_document_diagnostic_report.items;
+            assert_eq!(items.len(), 1);
+            assert_eq!(items[0].range.start, Position::new(0, 0));
+        })
+    }
+
+    #[test]
+    fn test_workspace_diagnostics_unchanged() {
+        r_task(|| {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("test.R");
+            std::fs::write(&path, "foo\n").unwrap();
+            let uri = Url::from_file_path(&path).unwrap();
+
+            let mut state = DEFAULT_STATE.clone();
+            state
+                .workspace
+                .folders
+                .push(Url::from_directory_path(dir.path()).unwrap());
+
+            let full_result_id = |reports: &[lsp_types::WorkspaceDocumentDiagnosticReport]| {
+                let [lsp_types::WorkspaceDocumentDiagnosticReport::Full(report)] = reports else {
+                    panic!("Expected a single full report");
+                };
+                assert_eq!(report.uri, uri);
+                report
+                    .full_document_diagnostic_report
+                    .result_id
+                    .clone()
+                    .unwrap()
+            };
+            let previous = |result_id: &String| {
+                vec![lsp_types::PreviousResultId {
+                    uri: uri.clone(),
+                    value: result_id.clone(),
+                }]
+            };
+
+            let result_id = full_result_id(&super::workspace_diagnostics(&state, &[], 0));
+
+            // Nothing changed since the previous report
+            let reports = super::workspace_diagnostics(&state, &previous(&result_id), 0);
+            let [lsp_types::WorkspaceDocumentDiagnosticReport::Unchanged(report)] =
+                reports.as_slice()
+            else {
+                panic!("Expected a single unchanged report");
+            };
+            assert_eq!(report.uri, uri);
+            assert_eq!(
+                report.unchanged_document_diagnostic_report.result_id,
+                result_id
+            );
+
+            // The index changed
+            let reports = super::workspace_diagnostics(&state, &previous(&result_id), 1);
+            assert_ne!(full_result_id(&reports), result_id);
+
+            // The file changed
+            std::fs::write(&path, "foo\nbar\n").unwrap();
+            let reports = super::workspace_diagnostics(&state, &previous(&result_id), 0);
+            assert_ne!(full_result_id(&reports), result_id);
+        })
+    }
 }
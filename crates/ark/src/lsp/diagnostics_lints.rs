@@ -0,0 +1,961 @@
+//
+// diagnostics_lints.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use anyhow::Result;
+use regex::Regex;
+use stdext::*;
+use tower_lsp::lsp_types::Diagnostic;
+use tree_sitter::Node;
+use tree_sitter::Point;
+use tree_sitter::Range;
+use walkdir::WalkDir;
+
+use crate::lsp::arguments::argument_name;
+use crate::lsp::arguments::match_arguments;
+use crate::lsp::arguments::pipe_placeholder;
+use crate::lsp::diagnostics::DiagnosticContext;
+use crate::lsp::diagnostics::SymbolAccess;
+use crate::lsp::diagnostics::SymbolOccurrence;
+use crate::lsp::diagnostics_rules::DiagnosticRule;
+use crate::lsp::document::Document;
+use crate::lsp::indexer;
+use crate::lsp::inputs::package::Package;
+use crate::lsp::inputs::source_root::SourceRoot;
+use crate::lsp::rename::symbol_name;
+use crate::lsp::roxygen::assigned_function;
+use crate::lsp::roxygen::roxygen_comments;
+use crate::lsp::roxygen::Roxygen;
+use crate::lsp::roxygen::TagLine;
+use crate::lsp::roxygen::INHERIT_TAGS;
+use crate::lsp::traits::node::NodeExt;
+use crate::treesitter::BinaryOperatorType;
+use crate::treesitter::NodeType;
+use crate::treesitter::NodeTypeExt;
+
+/// Calls after which the following statements of a block are never evaluated
+const TERMINAL_FUNCTIONS: &[&str] = &[
+    "return",
+    "stop",
+    "base::stop",
+    "abort",
+    "rlang::abort",
+    "cli_abort",
+    "cli::cli_abort",
+];
+
+/// Functions that access the bindings of the calling frame dynamically. We
+/// don't lint unused bindings in functions calling them.
+const DYNAMIC_ACCESS_FUNCTIONS: &[&str] = &[
+    "environment",
+    "current_env",
+    "sys.frame",
+    "sys.function",
+    "sys.call",
+    "match.call",
+    "ls",
+    "get",
+    "get0",
+    "mget",
+    "exists",
+    "eval",
+    "evalq",
+    "UseMethod",
+    "NextMethod",
+    "standardGeneric",
+    "callNextMethod",
+];
+
+/// Functions that bind an object named by one of their arguments, along with
+/// the name of that argument
+const DEFINING_FUNCTIONS: &[(&str, &str)] = &[
+    ("setGeneric", "name"),
+    ("setClass", "Class"),
+    ("setRefClass", "Class"),
+    ("assign", "x"),
+    ("delayedAssign", "x"),
+    ("makeActiveBinding", "sym"),
+];
+
+/// Functions returning the length of an object, along one of its dimensions
+const LENGTH_FUNCTIONS: &[&str] = &[
+    "length",
+    "base::length",
+    "nrow",
+    "base::nrow",
+    "ncol",
+    "base::ncol",
+    "NROW",
+    "base::NROW",
+    "NCOL",
+    "base::NCOL",
+];
+
+/// Calls where supplying an argument name twice is idiomatic
+const DUPLICATED_ARGUMENTS_FUNCTIONS: &[&str] =
+    &["mutate", "dplyr::mutate", "transmute", "dplyr::transmute"];
+
+/// Interpolated expressions in strings, e.g. for glue or cli
+static RE_INTERPOLATION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{([^{}]+)\}").unwrap());
+static RE_SYMBOL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[A-Za-z.][A-Za-z0-9._]*").unwrap());
+
+/// Flag variables and parameters bound in a function that are never read.
+/// `occurrences` are the symbol occurrences recorded while walking the
+/// function.
+pub(crate) fn check_unused_bindings(
+    function: Node,
+    occurrences: &[SymbolOccurrence],
+    context: &DiagnosticContext,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<()> {
+    let contents = &context.doc.contents;
+
+    let mut reads: HashSet<String> = occurrences
+        .iter()
+        .filter(|occurrence| occurrence.access == SymbolAccess::Read)
+        .map(|occurrence| occurrence.name.clone())
+        .collect();
+
+    if DYNAMIC_ACCESS_FUNCTIONS
+        .iter()
+        .any(|name| reads.contains(*name))
+    {
+        return Ok(());
+    }
+
+    // Variables might be referred to in interpolated strings
+    collect_interpolated_symbols(function, contents, &mut reads)?;
+
+    let mut seen = HashSet::new();
+
+    for occurrence in occurrences {
+        if occurrence.access != SymbolAccess::Write || occurrence.scope != function.id() {
+            continue;
+        }
+
+        let name = occurrence.name.as_str();
+        if reads.contains(name) || !seen.insert(name) {
+            continue;
+        }
+
+        // `...` and `..1` are never reported
+        if name.starts_with("..") {
+            continue;
+        }
+
+        let range = occurrence.range;
+        let Some(node) = function.descendant_for_point_range(range.start_point, range.end_point)
+        else {
+            continue;
+        };
+        let Some(parent) = node.parent() else {
+            continue;
+        };
+
+        let (rule, message) = match parent.node_type() {
+            NodeType::Parameter => (
+                DiagnosticRule::UnusedParameter,
+                format!("Parameter `{name}` is never used."),
+            ),
+            // Loop variables are often unused on purpose
+            NodeType::ForStatement => continue,
+            // The value of super-assignments outlives the function
+            NodeType::BinaryOperator(BinaryOperatorType::LeftSuperAssignment) |
+            NodeType::BinaryOperator(BinaryOperatorType::RightSuperAssignment) => continue,
+            _ => (
+                DiagnosticRule::UnusedVariable,
+                format!("Local variable `{name}` is assigned but never used."),
+            ),
+        };
+
+        let range = context.doc.lsp_range_from_tree_sitter_range(range)?;
+        diagnostics.push(rule.diagnostic(range, message));
+    }
+
+    Ok(())
+}
+
+fn collect_interpolated_symbols(
+    node: Node,
+    contents: &str,
+    symbols: &mut HashSet<String>,
+) -> Result<()> {
+    if node.node_type() == NodeType::String {
+        let text = node.node_as_str(contents)?;
+        for expression in RE_INTERPOLATION.captures_iter(text) {
+            for symbol in RE_SYMBOL.find_iter(&expression[1]) {
+                symbols.insert(symbol.as_str().to_string());
+            }
+        }
+        return Ok(());
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_interpolated_symbols(child, contents, symbols)?;
+    }
+
+    Ok(())
+}
+
+/// Flag statements following a `return()`, an error, `break`, or `next` in a
+/// braced expression
+pub(crate) fn check_unreachable_code(
+    node: Node,
+    context: &DiagnosticContext,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<()> {
+    let contents = &context.doc.contents;
+
+    let mut cursor = node.walk();
+    let body: Vec<Node> = node.children_by_field_name("body", &mut cursor).collect();
+
+    let Some(index) = body.iter().position(|node| is_terminal(node, contents)) else {
+        return Ok(());
+    };
+    let (Some(first), Some(last)) = (body.get(index + 1), body.last()) else {
+        return Ok(());
+    };
+
+    let range = Range {
+        start_byte: first.start_byte(),
+        end_byte: last.end_byte(),
+        start_point: first.start_position(),
+        end_point: last.end_position(),
+    };
+    let range = context.doc.lsp_range_from_tree_sitter_range(range)?;
+    let message = "Code is unreachable.";
+    diagnostics.push(DiagnosticRule::UnreachableCode.diagnostic(range, message.into()));
+
+    Ok(())
+}
+
+fn is_terminal(node: &Node, contents: &str) -> bool {
+    match node.node_type() {
+        NodeType::Break | NodeType::Next => true,
+        NodeType::Call => {
+            callee_text(node, contents).is_some_and(|callee| TERMINAL_FUNCTIONS.contains(&callee))
+        },
+        _ => false,
+    }
+}
+
+/// Flag `T` and `F`, which are regular variables that can be redefined
+pub(crate) fn check_true_false_symbol(
+    node: Node,
+    context: &mut DiagnosticContext,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<bool> {
+    if context.in_formula || !node.is_identifier() {
+        return false.ok();
+    }
+
+    let name = node.node_as_str(&context.doc.contents)?;
+    let replacement = match name {
+        "T" => "TRUE",
+        "F" => "FALSE",
+        _ => return false.ok(),
+    };
+
+    if let Some(parent) = node.parent() {
+        // Skip `x$T` and `T()`
+        if matches!(parent.node_type(), NodeType::ExtractOperator(_)) &&
+            parent.child_by_field_name("rhs") == Some(node)
+        {
+            return false.ok();
+        }
+        if parent.is_call() && parent.child_by_field_name("function") == Some(node) {
+            return false.ok();
+        }
+    }
+
+    // Skip if the document defines the symbol
+    if context
+        .document_symbols
+        .iter()
+        .any(|symbols| symbols.contains_key(name))
+    {
+        return false.ok();
+    }
+
+    let range = context.doc.lsp_range_from_tree_sitter_range(node.range())?;
+    let message = format!("Use `{replacement}` instead of `{name}`, which can be redefined.");
+    diagnostics.push(DiagnosticRule::TrueFalseSymbol.diagnostic(range, message));
+
+    true.ok()
+}
+
+/// Flag `1:length(x)` and the like, which count down to 0 for empty inputs
+pub(crate) fn check_one_to_length(
+    node: Node,
+    context: &mut DiagnosticContext,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<bool> {
+    if node.node_type() != NodeType::BinaryOperator(BinaryOperatorType::Colon) {
+        return false.ok();
+    }
+
+    let contents = &context.doc.contents;
+
+    let (Some(lhs), Some(rhs)) = (
+        node.child_by_field_name("lhs"),
+        node.child_by_field_name("rhs"),
+    ) else {
+        return false.ok();
+    };
+    if !matches!(lhs.node_as_str(contents)?, "1" | "1L") {
+        return false.ok();
+    }
+
+    let Some(sequence) = length_sequence(&rhs, contents)? else {
+        return false.ok();
+    };
+
+    let text = node.node_as_str(contents)?;
+    let length = rhs.node_as_str(contents)?;
+    let range = context.doc.lsp_range_from_tree_sitter_range(node.range())?;
+    let message =
+        format!("`{text}` counts down to `0` when `{length}` is `0`; use `{sequence}` instead.");
+    diagnostics.push(DiagnosticRule::OneToLength.diagnostic(range, message));
+
+    true.ok()
+}
+
+/// Flag sequences created from lengths with the wrong function, e.g.
+/// `seq(length(x))` or `seq_along(length(x))`, and vectors passed to
+/// `seq_len()`
+pub(crate) fn check_seq_misuse(
+    node: Node,
+    context: &mut DiagnosticContext,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<bool> {
+    if !node.is_call() {
+        return false.ok();
+    }
+
+    let contents = &context.doc.contents;
+
+    let Some(callee) = callee_text(&node, contents) else {
+        return false.ok();
+    };
+    let Some(argument) = single_argument(&node) else {
+        return false.ok();
+    };
+
+    let text = node.node_as_str(contents)?;
+
+    let message = match callee {
+        "seq" | "base::seq" => {
+            let Some(sequence) = length_sequence(&argument, contents)? else {
+                return false.ok();
+            };
+            format!("`{text}` returns `c(1, 0)` when the length is `0`; use `{sequence}` instead.")
+        },
+        "seq_along" | "base::seq_along" => {
+            let Some(sequence) = length_sequence(&argument, contents)? else {
+                return false.ok();
+            };
+            format!("`{text}` always returns `1`; use `{sequence}` instead.")
+        },
+        "seq_len" | "base::seq_len" => {
+            let is_vector = argument.is_binary_operator_of_kind(BinaryOperatorType::Colon) ||
+                matches!(
+                    callee_text(&argument, contents),
+                    Some("c" | "seq" | "seq_along" | "seq_len")
+                );
+            if !is_vector {
+                return false.ok();
+            }
+            String::from("`seq_len()` takes the length of the sequence, not a vector.")
+        },
+        _ => return false.ok(),
+    };
+
+    let range = context.doc.lsp_range_from_tree_sitter_range(node.range())?;
+    diagnostics.push(DiagnosticRule::SeqMisuse.diagnostic(range, message));
+
+    true.ok()
+}
+
+/// Returns the safe sequence corresponding to a call to a length function,
+/// i.e. `seq_along(x)` for `length(x)` and `seq_len(nrow(x))` for `nrow(x)`
+fn length_sequence(node: &Node, contents: &str) -> Result<Option<String>> {
+    let Some(callee) = callee_text(node, contents) else {
+        return Ok(None);
+    };
+    if !LENGTH_FUNCTIONS.contains(&callee) {
+        return Ok(None);
+    }
+    let Some(argument) = single_argument(node) else {
+        return Ok(None);
+    };
+
+    if matches!(callee, "length" | "base::length") {
+        let argument = argument.node_as_str(contents)?;
+        Ok(Some(format!("seq_along({argument})")))
+    } else {
+        let length = node.node_as_str(contents)?;
+        Ok(Some(format!("seq_len({length})")))
+    }
+}
+
+/// Flag argument names supplied more than once in a call
+pub(crate) fn check_duplicated_arguments(
+    node: Node,
+    context: &mut DiagnosticContext,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<bool> {
+    if !node.is_call() {
+        return false.ok();
+    }
+
+    let contents = &context.doc.contents;
+
+    if callee_text(&node, contents)
+        .is_some_and(|callee| DUPLICATED_ARGUMENTS_FUNCTIONS.contains(&callee))
+    {
+        return false.ok();
+    }
+
+    let mut names = HashSet::new();
+    let mut found = false;
+
+    for name in node.arguments_names().flatten() {
+        let text = match name.node_type() {
+            NodeType::String => name.get_identifier_or_string_text(contents)?,
+            _ => symbol_name(name.node_as_str(contents)?),
+        };
+
+        if names.insert(text) {
+            continue;
+        }
+
+        let range = context.doc.lsp_range_from_tree_sitter_range(name.range())?;
+        let message = format!("Argument `{text}` is supplied more than once.");
+        diagnostics.push(DiagnosticRule::DuplicatedArgument.diagnostic(range, message));
+        found = true;
+    }
+
+    found.ok()
+}
+
+/// Flag arguments that don't match the formals of the called function. The
+/// formals are looked up in the workspace index for symbols, and in the
+/// library for namespaced calls.
+pub(crate) fn check_unmatched_arguments(
+    node: Node,
+    context: &mut DiagnosticContext,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<bool> {
+    if !node.is_call() {
+        return false.ok();
+    }
+
+    let contents = &context.doc.contents;
+
+    // Forwarded dots might supply any argument
+    if node
+        .arguments_values()
+        .flatten()
+        .any(|value| matches!(value.node_type(), NodeType::Dots | NodeType::DotDotI))
+    {
+        return false.ok();
+    }
+
+    let Some(callee) = node.child_by_field_name("function") else {
+        return false.ok();
+    };
+    let Some(formals) = callee_formals(&callee, context)? else {
+        return false.ok();
+    };
+
+    // Any argument is matched by `...`
+    if formals.iter().any(|formal| formal == "...") {
+        return false.ok();
+    }
+
+    let implicit_first = pipe_placeholder(&node, contents).is_some();
+    let matches = match_arguments(&node, &formals, implicit_first, contents)?;
+
+    let callee = callee.node_as_str(contents)?;
+
+    for name in matches.unmatched_names.iter() {
+        let range = context.doc.lsp_range_from_tree_sitter_range(name.range())?;
+        let name = argument_name(name, contents)?;
+        let message = format!("Argument `{name}` doesn't match a parameter of `{callee}()`.");
+        diagnostics.push(DiagnosticRule::UnmatchedArgument.diagnostic(range, message));
+    }
+
+    if let (Some(first), Some(last)) = (
+        matches.unmatched_positional.first(),
+        matches.unmatched_positional.last(),
+    ) {
+        let range = Range {
+            start_byte: first.start_byte(),
+            end_byte: last.end_byte(),
+            start_point: first.start_position(),
+            end_point: last.end_position(),
+        };
+        let range = context.doc.lsp_range_from_tree_sitter_range(range)?;
+        let message = format!("Too many arguments in call to `{callee}()`.");
+        diagnostics.push(DiagnosticRule::UnmatchedArgument.diagnostic(range, message));
+    }
+
+    (!matches.unmatched_names.is_empty() || !matches.unmatched_positional.is_empty()).ok()
+}
+
+/// Returns `None` for unknown functions, including functions bound in local
+/// scopes as these might shadow workspace functions
+fn callee_formals(callee: &Node, context: &DiagnosticContext) -> Result<Option<Vec<String>>> {
+    let contents = &context.doc.contents;
+
+    match callee.node_type() {
+        NodeType::Identifier => {
+            let name = callee.node_as_str(contents)?;
+
+            if context
+                .document_symbols
+                .iter()
+                .skip(1)
+                .any(|symbols| symbols.contains_key(name))
+            {
+                return Ok(None);
+            }
+
+            let Some((_path, entry)) = indexer::find(name) else {
+                return Ok(None);
+            };
+            match entry.data {
+                indexer::IndexEntryData::Function { arguments, .. } => Ok(Some(arguments)),
+                _ => Ok(None),
+            }
+        },
+        NodeType::NamespaceOperator(_) => {
+            let (Some(lhs), Some(rhs)) = (
+                callee.child_by_field_name("lhs"),
+                callee.child_by_field_name("rhs"),
+            ) else {
+                return Ok(None);
+            };
+            if !rhs.is_identifier_or_string() {
+                return Ok(None);
+            }
+
+            let Some(package) = context.library.get(lhs.node_as_str(contents)?) else {
+                return Ok(None);
+            };
+            let name = rhs.get_identifier_or_string_text(contents)?;
+            Ok(package.function_formals(name).cloned())
+        },
+        _ => Ok(None),
+    }
+}
+
+/// Flag `library()` and `require()` in package code, where dependencies are
+/// declared in `DESCRIPTION` and imported in `NAMESPACE`
+pub(crate) fn check_library_in_package(
+    node: Node,
+    context: &mut DiagnosticContext,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<bool> {
+    if !context.package_code || !node.is_call() {
+        return false.ok();
+    }
+
+    let Some(callee) = callee_text(&node, &context.doc.contents) else {
+        return false.ok();
+    };
+    if !matches!(
+        callee,
+        "library" | "require" | "base::library" | "base::require"
+    ) {
+        return false.ok();
+    }
+
+    let range = context.doc.lsp_range_from_tree_sitter_range(node.range())?;
+    let message = format!(
+        "Avoid `{callee}()` in package code, declare the dependency in `DESCRIPTION` instead."
+    );
+    diagnostics.push(DiagnosticRule::LibraryInPackage.diagnostic(range, message));
+
+    true.ok()
+}
+
+/// Flag `@param` tags of the roxygen block of a top-level function that
+/// don't match its parameters, and parameters that are not documented
+pub(crate) fn check_roxygen_params(
+    function: Node,
+    context: &DiagnosticContext,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<()> {
+    let contents = &context.doc.contents;
+
+    let Some(statement) = function.parent() else {
+        return Ok(());
+    };
+    if !statement.parent().is_some_and(|parent| parent.is_program()) {
+        return Ok(());
+    }
+    let Some((lhs, rhs)) = assigned_function(&statement) else {
+        return Ok(());
+    };
+    if rhs != function {
+        return Ok(());
+    }
+
+    let comments = roxygen_comments(&statement, contents);
+    if comments.is_empty() {
+        return Ok(());
+    }
+
+    let mut tags = Vec::new();
+    for comment in comments.iter() {
+        if let Some(line) = TagLine::parse(comment.node_as_str(contents)?) {
+            tags.push((comment, line));
+        }
+    }
+
+    // Topics shared with other functions document their parameters too
+    if tags
+        .iter()
+        .any(|(_, line)| matches!(line.tag, "rdname" | "describeIn"))
+    {
+        return Ok(());
+    }
+
+    let name = lhs.node_as_str(contents)?;
+    let parameters = indexer::function_parameters(&function, contents)?;
+    let mut documented = HashSet::new();
+
+    for (comment, line) in tags.iter() {
+        for (param, range) in line.param_names() {
+            documented.insert(param);
+
+            if parameters.iter().any(|parameter| parameter == param) {
+                continue;
+            }
+
+            let start = comment.start_position();
+            let range = Range {
+                start_byte: comment.start_byte() + range.start,
+                end_byte: comment.start_byte() + range.end,
+                start_point: Point::new(start.row, start.column + range.start),
+                end_point: Point::new(start.row, start.column + range.end),
+            };
+            let range = context.doc.lsp_range_from_tree_sitter_range(range)?;
+            let message = format!("`{param}` is not a parameter of `{name}()`.");
+            diagnostics.push(DiagnosticRule::UnknownRoxygenParam.diagnostic(range, message));
+        }
+    }
+
+    // Blocks without a title don't generate a help page
+    let lines = comments
+        .iter()
+        .map(|comment| comment.node_as_str(contents))
+        .collect::<Result<Vec<_>>>()?;
+    if Roxygen::parse(lines).title.is_none() {
+        return Ok(());
+    }
+
+    // Parameters might be documented elsewhere
+    if tags.iter().any(|(_, line)| {
+        line.tag == "noRd" || line.tag == "template" || INHERIT_TAGS.contains(&line.tag)
+    }) {
+        return Ok(());
+    }
+
+    let Some(node) = function.child_by_field_name("parameters") else {
+        return Ok(());
+    };
+    let mut cursor = node.walk();
+
+    for child in node.children(&mut cursor) {
+        let Some(parameter) = child.child_by_field_name("name") else {
+            continue;
+        };
+        let parameter_name = parameter.node_as_str(contents)?;
+        if documented.contains(parameter_name) {
+            continue;
+        }
+
+        let range = context
+            .doc
+            .lsp_range_from_tree_sitter_range(parameter.range())?;
+        let message = format!("Parameter `{parameter_name}` is not documented with `@param`.");
+        diagnostics.push(DiagnosticRule::UndocumentedParameter.diagnostic(range, message));
+    }
+
+    Ok(())
+}
+
+/// Flag `pkg::fn` in package code when `pkg` is not declared as a dependency
+/// in `DESCRIPTION`
+pub(crate) fn check_undeclared_package(
+    package: Node,
+    context: &mut DiagnosticContext,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<bool> {
+    if !context.package_code {
+        return false.ok();
+    }
+    let Some(SourceRoot::Package(root)) = context.root else {
+        return false.ok();
+    };
+
+    let name = package.node_as_str(&context.doc.contents)?;
+    let description = &root.description;
+    let dependencies = description.dependencies();
+
+    if name == "base" ||
+        name == description.name ||
+        dependencies.iter().any(|dependency| dependency == name)
+    {
+        return false.ok();
+    }
+
+    let range = context
+        .doc
+        .lsp_range_from_tree_sitter_range(package.range())?;
+    let message = format!(
+        "Package `{name}` is not declared in the `Imports` or `Suggests` field of `DESCRIPTION`."
+    );
+    diagnostics.push(DiagnosticRule::UndeclaredPackage.diagnostic(range, message));
+
+    true.ok()
+}
+
+/// Flag calls in package code to functions that are neither defined in the
+/// `R/` folder of the package nor imported in `NAMESPACE`. Functions that are
+/// available in the session, e.g. from attached packages, or defined
+/// elsewhere in the workspace, e.g. in tests, are not available to the
+/// package.
+pub(crate) fn check_unimported_function(
+    node: Node,
+    context: &mut DiagnosticContext,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<bool> {
+    if !context.package_code || !node.is_call() {
+        return false.ok();
+    }
+
+    let Some(callee) = node.child_by_field_name("function") else {
+        return false.ok();
+    };
+    if !callee.is_identifier() {
+        return false.ok();
+    }
+    let name = callee.node_as_str(&context.doc.contents)?;
+
+    // Symbols that are not in scope at all are reported as such
+    if !context.has_definition(name, callee.start_position()) {
+        return false.ok();
+    }
+
+    let is_visible = context
+        .document_symbols
+        .iter()
+        .any(|symbols| symbols.contains_key(name)) ||
+        context.package_symbols.contains(name) ||
+        context.namespace_symbols.contains(name);
+    if is_visible {
+        return false.ok();
+    }
+
+    let range = context
+        .doc
+        .lsp_range_from_tree_sitter_range(callee.range())?;
+    let message =
+        format!("`{name}()` is neither defined in the package nor imported in `NAMESPACE`.");
+    diagnostics.push(DiagnosticRule::UnimportedFunction.diagnostic(range, message));
+
+    true.ok()
+}
+
+/// Flag the `export()` directives of the NAMESPACE file of a package that
+/// name objects not defined in its `R/` folder. Objects are defined by
+/// assignments or by calls like `setGeneric()` or `assign()`. Nothing is
+/// flagged when one of these calls computes the name at runtime.
+pub(crate) fn check_namespace_exports(
+    doc: &Document,
+    package: &Package,
+) -> Result<Vec<Diagnostic>> {
+    let mut diagnostics = Vec::new();
+
+    let folder = package.path.join("R");
+    let mut defined = HashSet::new();
+
+    indexer::map(|uri, _symbol, entry| {
+        if !uri
+            .to_file_path()
+            .is_ok_and(|path| path.starts_with(&folder))
+        {
+            return;
+        }
+        match &entry.data {
+            indexer::IndexEntryData::Function { name, .. } |
+            indexer::IndexEntryData::Variable { name } => {
+                defined.insert(unquote(name).to_string());
+            },
+            _ => {},
+        }
+    });
+
+    // The package code is not indexed yet
+    if defined.is_empty() {
+        return Ok(diagnostics);
+    }
+
+    // Any export might be defined by a name computed at runtime
+    let Some(defined_by_calls) = defined_by_calls(&folder) else {
+        return Ok(diagnostics);
+    };
+    defined.extend(defined_by_calls);
+
+    let contents = &doc.contents;
+    let root = doc.ast.root_node();
+    let mut cursor = root.walk();
+
+    for node in root.children(&mut cursor) {
+        if callee_text(&node, contents) != Some("export") {
+            continue;
+        }
+
+        for value in node.arguments_values().flatten() {
+            if !value.is_identifier_or_string() {
+                continue;
+            }
+
+            let name = unquote(value.node_as_str(contents)?);
+            if defined.contains(name) {
+                continue;
+            }
+
+            let range = doc.lsp_range_from_tree_sitter_range(value.range())?;
+            let message = format!("`{name}` is exported but not defined in the `R/` folder.");
+            diagnostics.push(DiagnosticRule::UndefinedExport.diagnostic(range, message));
+        }
+    }
+
+    Ok(diagnostics)
+}
+
+/// Collect the names bound by calls to `DEFINING_FUNCTIONS` in the files of
+/// the `R/` folder of a package. Returns `None` when one of these names is
+/// computed at runtime.
+fn defined_by_calls(folder: &Path) -> Option<HashSet<String>> {
+    let mut names = HashSet::new();
+
+    let walker = WalkDir::new(folder);
+    for entry in walker.into_iter().filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        let ext = path.extension().unwrap_or_default();
+        if ext != "r" && ext != "R" {
+            continue;
+        }
+
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) => {
+                log::warn!("Can't read '{}': {err:?}", path.display());
+                continue;
+            },
+        };
+
+        let document = Document::new(&contents, None);
+        if !collect_defined_by_calls(document.ast.root_node(), &contents, &mut names) {
+            return None;
+        }
+    }
+
+    Some(names)
+}
+
+/// Returns `false` when a defining call doesn't name its object with a
+/// string literal
+fn collect_defined_by_calls(node: Node, contents: &str, names: &mut HashSet<String>) -> bool {
+    if let Some(callee) = callee_text(&node, contents) {
+        let function = callee.rsplit("::").next().unwrap_or(callee);
+
+        if let Some((_, parameter)) = DEFINING_FUNCTIONS
+            .iter()
+            .find(|(name, _)| *name == function)
+        {
+            let Some(value) = defining_argument(&node, parameter, contents) else {
+                return false;
+            };
+            if !value.is_string() {
+                return false;
+            }
+            let Ok(name) = value.node_as_str(contents) else {
+                return false;
+            };
+            names.insert(unquote(name).to_string());
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if !collect_defined_by_calls(child, contents, names) {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Returns the argument of a call named `parameter`, or its first unnamed
+/// argument
+fn defining_argument<'tree>(
+    node: &Node<'tree>,
+    parameter: &str,
+    contents: &str,
+) -> Option<Node<'tree>> {
+    let mut unnamed = None;
+
+    for (name, value) in node.arguments() {
+        match name {
+            Some(name) => {
+                if name.node_as_str(contents).ok() == Some(parameter) {
+                    return value;
+                }
+            },
+            None => {
+                if unnamed.is_none() {
+                    unnamed = value;
+                }
+            },
+        }
+    }
+
+    unnamed
+}
+
+/// Strip the quotes of strings and the backticks of non-syntactic names
+fn unquote(name: &str) -> &str {
+    name.trim_matches(|char| matches!(char, '"' | '\'' | '`'))
+}
+
+fn callee_text<'a>(node: &Node, contents: &'a str) -> Option<&'a str> {
+    if !node.is_call() {
+        return None;
+    }
+    let callee = node.child_by_field_name("function")?;
+    callee.node_as_str(contents).ok()
+}
+
+/// Returns the value of the single argument of a call, if unnamed
+fn single_argument<'tree>(node: &Node<'tree>) -> Option<Node<'tree>> {
+    let mut arguments = node.arguments();
+    match (arguments.next(), arguments.next()) {
+        (Some((None, value)), None) => value,
+        _ => None,
+    }
+}
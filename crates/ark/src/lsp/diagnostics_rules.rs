@@ -0,0 +1,662 @@
+//
+// diagnostics_rules.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use tower_lsp::lsp_types::Diagnostic;
+use tower_lsp::lsp_types::DiagnosticSeverity;
+use tower_lsp::lsp_types::DiagnosticTag;
+use tower_lsp::lsp_types::NumberOrString;
+use tower_lsp::lsp_types::Range;
+use tree_sitter::Node;
+use url::Url;
+
+use crate::lsp;
+use crate::lsp::diagnostics::DiagnosticsConfig;
+use crate::lsp::document::Document;
+use crate::lsp::indexer::filter_entry;
+use crate::lsp::state::config_file_candidates;
+use crate::lsp::state::WorldState;
+use crate::lsp::traits::node::NodeExt;
+use crate::treesitter::NodeTypeExt;
+
+/// Names of the ark configuration files, by order of priority
+const ARK_TOML_NAMES: [&str; 2] = ["ark.toml", ".ark.toml"];
+
+/// Suppression comments, e.g. `# ark: ignore[symbol-not-in-scope]`. Without
+/// brackets, all rules are suppressed.
+static RE_SUPPRESSION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^#+\s*ark:\s*(ignore-file|ignore-next-line|ignore)(?:\[([^\]]*)\])?(?:\s|$)")
+        .unwrap()
+});
+
+/// A diagnostic rule. The code of a rule is stable and is used in the
+/// configuration, in suppression comments, and as code of the LSP diagnostics.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DiagnosticRule {
+    SyntaxError,
+    SymbolNotInScope,
+    PackageNotInstalled,
+    InvalidNaComparison,
+    AssignmentInIfCondition,
+    UnusedVariable,
+    UnusedParameter,
+    UnreachableCode,
+    TrueFalseSymbol,
+    OneToLength,
+    SeqMisuse,
+    DuplicatedArgument,
+    UnmatchedArgument,
+    LibraryInPackage,
+    UnknownRoxygenParam,
+    UndocumentedParameter,
+    UndeclaredPackage,
+    UnimportedFunction,
+    UndefinedExport,
+}
+
+/// Severity of a rule. `Off` disables the rule.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RuleSeverity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+    Off,
+}
+
+/// Subset of the ark configuration file that affects diagnostics
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ArkToml {
+    diagnostics: Option<ArkDiagnosticsToml>,
+}
+
+impl ArkToml {
+    fn read(path: &Path) -> Self {
+        let ark_toml: anyhow::Result<ArkToml> = (|| {
+            let contents = std::fs::read_to_string(path)?;
+            Ok(toml::from_str(&contents)?)
+        })();
+
+        ark_toml.unwrap_or_else(|err| {
+            lsp::log_warn!("Can't read `{}`: {err:?}", path.display());
+            ArkToml::default()
+        })
+    }
+}
+
+/// The `ark.toml` files of the workspace folders, parsed once and keyed by
+/// path so that looking up the configuration of a document doesn't hit the
+/// disk. Files that can't be parsed are kept as empty configurations so they
+/// still shadow the files of parent directories.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ArkTomlFiles {
+    files: HashMap<PathBuf, Arc<ArkToml>>,
+}
+
+impl ArkTomlFiles {
+    /// Walk the workspace folders and parse their `ark.toml` files
+    pub(crate) fn load(folders: &[Url]) -> Self {
+        let mut files = Self::default();
+
+        for folder in folders.iter() {
+            let Ok(folder) = folder.to_file_path() else {
+                continue;
+            };
+
+            let walker = walkdir::WalkDir::new(folder);
+            for entry in walker.into_iter().filter_entry(|e| filter_entry(e)) {
+                let Ok(entry) = entry else {
+                    continue;
+                };
+                if entry.file_type().is_file() && Self::is_ark_toml(entry.path()) {
+                    files.update(entry.path());
+                }
+            }
+        }
+
+        files
+    }
+
+    pub(crate) fn is_ark_toml(path: &Path) -> bool {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| ARK_TOML_NAMES.contains(&name))
+    }
+
+    /// Parse the file at `path` again, or forget it if it no longer exists
+    pub(crate) fn update(&mut self, path: &Path) {
+        if path.is_file() {
+            self.files
+                .insert(path.to_path_buf(), Arc::new(ArkToml::read(path)));
+        } else {
+            self.files.remove(path);
+        }
+    }
+
+    fn get(&self, path: &Path) -> Option<&ArkToml> {
+        self.files.get(path).map(|ark_toml| ark_toml.as_ref())
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ArkDiagnosticsToml {
+    enable: Option<bool>,
+    rules: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, PartialEq)]
+enum SuppressionScope {
+    File,
+    Line(u32),
+}
+
+/// A suppression comment. `rules` is `None` when all rules are suppressed.
+#[derive(Debug, PartialEq)]
+struct Suppression {
+    scope: SuppressionScope,
+    rules: Option<Vec<DiagnosticRule>>,
+}
+
+impl DiagnosticRule {
+    pub const ALL: [DiagnosticRule; 19] = [
+        DiagnosticRule::SyntaxError,
+        DiagnosticRule::SymbolNotInScope,
+        DiagnosticRule::PackageNotInstalled,
+        DiagnosticRule::InvalidNaComparison,
+        DiagnosticRule::AssignmentInIfCondition,
+        DiagnosticRule::UnusedVariable,
+        DiagnosticRule::UnusedParameter,
+        DiagnosticRule::UnreachableCode,
+        DiagnosticRule::TrueFalseSymbol,
+        DiagnosticRule::OneToLength,
+        DiagnosticRule::SeqMisuse,
+        DiagnosticRule::DuplicatedArgument,
+        DiagnosticRule::UnmatchedArgument,
+        DiagnosticRule::LibraryInPackage,
+        DiagnosticRule::UnknownRoxygenParam,
+        DiagnosticRule::UndocumentedParameter,
+        DiagnosticRule::UndeclaredPackage,
+        DiagnosticRule::UnimportedFunction,
+        DiagnosticRule::UndefinedExport,
+    ];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            DiagnosticRule::SyntaxError => "syntax-error",
+            DiagnosticRule::SymbolNotInScope => "symbol-not-in-scope",
+            DiagnosticRule::PackageNotInstalled => "package-not-installed",
+            DiagnosticRule::InvalidNaComparison => "invalid-na-comparison",
+            DiagnosticRule::AssignmentInIfCondition => "assignment-in-if-condition",
+            DiagnosticRule::UnusedVariable => "unused-variable",
+            DiagnosticRule::UnusedParameter => "unused-parameter",
+            DiagnosticRule::UnreachableCode => "unreachable-code",
+            DiagnosticRule::TrueFalseSymbol => "true-false-symbol",
+            DiagnosticRule::OneToLength => "one-to-length",
+            DiagnosticRule::SeqMisuse => "seq-misuse",
+            DiagnosticRule::DuplicatedArgument => "duplicated-argument",
+            DiagnosticRule::UnmatchedArgument => "unmatched-argument",
+            DiagnosticRule::LibraryInPackage => "library-in-package",
+            DiagnosticRule::UnknownRoxygenParam => "unknown-roxygen-param",
+            DiagnosticRule::UndocumentedParameter => "undocumented-parameter",
+            DiagnosticRule::UndeclaredPackage => "undeclared-package",
+            DiagnosticRule::UnimportedFunction => "unimported-function",
+            DiagnosticRule::UndefinedExport => "undefined-export",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|rule| rule.code() == code)
+    }
+
+    pub fn default_severity(&self) -> DiagnosticSeverity {
+        match self {
+            DiagnosticRule::SyntaxError => DiagnosticSeverity::ERROR,
+            DiagnosticRule::SymbolNotInScope => DiagnosticSeverity::WARNING,
+            DiagnosticRule::PackageNotInstalled => DiagnosticSeverity::ERROR,
+            DiagnosticRule::InvalidNaComparison => DiagnosticSeverity::INFORMATION,
+            DiagnosticRule::AssignmentInIfCondition => DiagnosticSeverity::ERROR,
+            DiagnosticRule::UnusedVariable => DiagnosticSeverity::WARNING,
+            DiagnosticRule::UnusedParameter => DiagnosticSeverity::HINT,
+            DiagnosticRule::UnreachableCode => DiagnosticSeverity::WARNING,
+            DiagnosticRule::TrueFalseSymbol => DiagnosticSeverity::INFORMATION,
+            DiagnosticRule::OneToLength => DiagnosticSeverity::WARNING,
+            DiagnosticRule::SeqMisuse => DiagnosticSeverity::WARNING,
+            DiagnosticRule::DuplicatedArgument => DiagnosticSeverity::WARNING,
+            DiagnosticRule::UnmatchedArgument => DiagnosticSeverity::WARNING,
+            DiagnosticRule::LibraryInPackage => DiagnosticSeverity::WARNING,
+            DiagnosticRule::UnknownRoxygenParam => DiagnosticSeverity::WARNING,
+            DiagnosticRule::UndocumentedParameter => DiagnosticSeverity::INFORMATION,
+            DiagnosticRule::UndeclaredPackage => DiagnosticSeverity::WARNING,
+            DiagnosticRule::UnimportedFunction => DiagnosticSeverity::WARNING,
+            DiagnosticRule::UndefinedExport => DiagnosticSeverity::WARNING,
+        }
+    }
+
+    /// Tags of the diagnostics of this rule. Unused and unreachable code is
+    /// rendered faded out by editors.
+    pub fn tags(&self) -> Option<Vec<DiagnosticTag>> {
+        match self {
+            DiagnosticRule::UnusedVariable |
+            DiagnosticRule::UnusedParameter |
+            DiagnosticRule::UnreachableCode => Some(vec![DiagnosticTag::UNNECESSARY]),
+            _ => None,
+        }
+    }
+
+    /// Create a diagnostic for this rule, with the default severity
+    pub fn diagnostic(&self, range: Range, message: String) -> Diagnostic {
+        let mut diagnostic = Diagnostic::new_simple(range, message);
+        diagnostic.severity = Some(self.default_severity());
+        diagnostic.code = Some(NumberOrString::String(self.code().to_string()));
+        diagnostic.tags = self.tags();
+        diagnostic
+    }
+
+    pub fn from_diagnostic(diagnostic: &Diagnostic) -> Option<Self> {
+        match diagnostic.code.as_ref()? {
+            NumberOrString::String(code) => Self::from_code(code),
+            NumberOrString::Number(_) => None,
+        }
+    }
+}
+
+impl RuleSeverity {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "error" => Some(RuleSeverity::Error),
+            "warning" => Some(RuleSeverity::Warning),
+            "information" => Some(RuleSeverity::Information),
+            "hint" => Some(RuleSeverity::Hint),
+            "off" => Some(RuleSeverity::Off),
+            _ => None,
+        }
+    }
+
+    fn to_lsp(self) -> Option<DiagnosticSeverity> {
+        match self {
+            RuleSeverity::Error => Some(DiagnosticSeverity::ERROR),
+            RuleSeverity::Warning => Some(DiagnosticSeverity::WARNING),
+            RuleSeverity::Information => Some(DiagnosticSeverity::INFORMATION),
+            RuleSeverity::Hint => Some(DiagnosticSeverity::HINT),
+            RuleSeverity::Off => None,
+        }
+    }
+}
+
+/// Parse a map of rule codes to severities, e.g. from the
+/// `positron.r.diagnostics.rules` setting. Invalid entries are skipped.
+pub(crate) fn rule_severities_from_json(value: &Value) -> HashMap<DiagnosticRule, RuleSeverity> {
+    let Some(object) = value.as_object() else {
+        return HashMap::new();
+    };
+
+    let entries = object
+        .iter()
+        .filter_map(|(code, severity)| Some((code.as_str(), severity.as_str()?)));
+
+    rule_severities(entries)
+}
+
+fn rule_severities<'a>(
+    entries: impl Iterator<Item = (&'a str, &'a str)>,
+) -> HashMap<DiagnosticRule, RuleSeverity> {
+    let mut severities = HashMap::new();
+
+    for (code, severity) in entries {
+        let Some(rule) = DiagnosticRule::from_code(code) else {
+            lsp::log_warn!("Unknown diagnostic rule '{code}'");
+            continue;
+        };
+        let Some(severity) = RuleSeverity::from_name(severity) else {
+            lsp::log_warn!("Invalid severity '{severity}' for diagnostic rule '{code}'");
+            continue;
+        };
+        severities.insert(rule, severity);
+    }
+
+    severities
+}
+
+/// The diagnostics configuration for a document. The editor settings are
+/// overridden by the closest `ark.toml` file in the workspace, if any.
+pub(crate) fn diagnostics_config(uri: &Url, state: &WorldState) -> DiagnosticsConfig {
+    let mut config = state.config.diagnostics.clone();
+
+    let Some(ark_toml) = config_file_candidates(uri, state, &ARK_TOML_NAMES)
+        .iter()
+        .find_map(|path| state.ark_toml.get(path))
+    else {
+        return config;
+    };
+
+    let Some(diagnostics) = &ark_toml.diagnostics else {
+        return config;
+    };
+
+    if let Some(enable) = diagnostics.enable {
+        config.enable = enable;
+    }
+    if let Some(rules) = &diagnostics.rules {
+        let entries = rules
+            .iter()
+            .map(|(code, severity)| (code.as_str(), severity.as_str()));
+        config.rules.extend(rule_severities(entries));
+    }
+
+    config
+}
+
+/// Apply the configured severities of the rules and the suppression comments
+/// of the document
+pub(crate) fn apply_rules(
+    diagnostics: Vec<Diagnostic>,
+    config: &DiagnosticsConfig,
+    doc: &Document,
+) -> Vec<Diagnostic> {
+    let suppressions = suppressions(doc);
+
+    diagnostics
+        .into_iter()
+        .filter_map(|mut diagnostic| {
+            let Some(rule) = DiagnosticRule::from_diagnostic(&diagnostic) else {
+                return Some(diagnostic);
+            };
+
+            // Rules turned off are filtered out
+            if let Some(severity) = config.rules.get(&rule) {
+                diagnostic.severity = Some(severity.to_lsp()?);
+            }
+
+            let line = diagnostic.range.start.line;
+            if suppressions.iter().any(|s| s.suppresses(rule, line)) {
+                return None;
+            }
+
+            Some(diagnostic)
+        })
+        .collect()
+}
+
+impl Suppression {
+    fn suppresses(&self, rule: DiagnosticRule, line: u32) -> bool {
+        let in_scope = match self.scope {
+            SuppressionScope::File => true,
+            SuppressionScope::Line(suppressed) => suppressed == line,
+        };
+
+        match &self.rules {
+            Some(rules) => in_scope && rules.contains(&rule),
+            None => in_scope,
+        }
+    }
+}
+
+/// Collect the suppression comments of a document:
+///
+/// - `# ark: ignore[rule]` suppresses diagnostics starting on the same line.
+/// - `# ark: ignore-next-line[rule]` suppresses diagnostics starting on the
+///   next line.
+/// - `# ark: ignore-file[rule]` suppresses diagnostics in the whole document.
+///
+/// Several rules can be separated by commas. Without brackets, all rules are
+/// suppressed.
+fn suppressions(doc: &Document) -> Vec<Suppression> {
+    let mut suppressions = Vec::new();
+
+    let mut stack = vec![doc.ast.root_node()];
+    while let Some(node) = stack.pop() {
+        if node.is_comment() {
+            if let Some(suppression) = suppression(&node, doc) {
+                suppressions.push(suppression);
+            }
+            continue;
+        }
+
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+
+    suppressions
+}
+
+fn suppression(node: &Node, doc: &Document) -> Option<Suppression> {
+    let text = node.node_as_str(&doc.contents).ok()?;
+    let captures = RE_SUPPRESSION.captures(text)?;
+
+    let line = node.start_position().row as u32;
+    let scope = match &captures[1] {
+        "ignore-file" => SuppressionScope::File,
+        "ignore-next-line" => SuppressionScope::Line(line + 1),
+        _ => SuppressionScope::Line(line),
+    };
+
+    let rules = captures.get(2).map(|rules| {
+        rules
+            .as_str()
+            .split(',')
+            .filter_map(|code| DiagnosticRule::from_code(code.trim()))
+            .collect()
+    });
+
+    Some(Suppression { scope, rules })
+}
+
+#[cfg(test)]
+mod tests {
+    use tower_lsp::lsp_types::Position;
+
+    use super::*;
+
+    fn diagnostic(rule: DiagnosticRule, line: u32) -> Diagnostic {
+        let range = Range::new(Position::new(line, 0), Position::new(line, 1));
+        rule.diagnostic(range, String::from("message"))
+    }
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<(u32, &'static str)> {
+        diagnostics
+            .iter()
+            .map(|d| {
+                let rule = DiagnosticRule::from_diagnostic(d).unwrap();
+                (d.range.start.line, rule.code())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rule_codes_roundtrip() {
+        for rule in DiagnosticRule::ALL {
+            assert_eq!(DiagnosticRule::from_code(rule.code()), Some(rule));
+        }
+        assert_eq!(DiagnosticRule::from_code("foo"), None);
+    }
+
+    #[test]
+    fn test_rule_severities_from_json() {
+        let value = serde_json::json!({
+            "symbol-not-in-scope": "off",
+            "invalid-na-comparison": "error",
+            "unknown-rule": "off",
+            "syntax-error": "invalid",
+        });
+
+        let severities = rule_severities_from_json(&value);
+        assert_eq!(severities.len(), 2);
+        assert_eq!(
+            severities.get(&DiagnosticRule::SymbolNotInScope),
+            Some(&RuleSeverity::Off)
+        );
+        assert_eq!(
+            severities.get(&DiagnosticRule::InvalidNaComparison),
+            Some(&RuleSeverity::Error)
+        );
+
+        assert!(rule_severities_from_json(&Value::Null).is_empty());
+    }
+
+    #[test]
+    fn test_apply_rules_severities() {
+        let doc = Document::new("x\ny\nz\n", None);
+
+        let mut config = DiagnosticsConfig::default();
+        config
+            .rules
+            .insert(DiagnosticRule::SymbolNotInScope, RuleSeverity::Hint);
+        config
+            .rules
+            .insert(DiagnosticRule::InvalidNaComparison, RuleSeverity::Off);
+
+        let diagnostics = vec![
+            diagnostic(DiagnosticRule::SymbolNotInScope, 0),
+            diagnostic(DiagnosticRule::InvalidNaComparison, 1),
+            diagnostic(DiagnosticRule::SyntaxError, 2),
+        ];
+        let diagnostics = apply_rules(diagnostics, &config, &doc);
+
+        assert_eq!(codes(&diagnostics), vec![
+            (0, "symbol-not-in-scope"),
+            (2, "syntax-error")
+        ]);
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::HINT));
+        assert_eq!(diagnostics[1].severity, Some(DiagnosticSeverity::ERROR));
+    }
+
+    #[test]
+    fn test_suppression_comments() {
+        let text = "
+x # ark: ignore[symbol-not-in-scope]
+# ark: ignore-next-line
+y
+z # ark: ignore[invalid-na-comparison, syntax-error]
+w # ark: ignoreed
+";
+        let doc = Document::new(text, None);
+        let config = DiagnosticsConfig::default();
+
+        let diagnostics = vec![
+            diagnostic(DiagnosticRule::SymbolNotInScope, 1),
+            diagnostic(DiagnosticRule::InvalidNaComparison, 1),
+            diagnostic(DiagnosticRule::SymbolNotInScope, 3),
+            diagnostic(DiagnosticRule::SyntaxError, 3),
+            diagnostic(DiagnosticRule::SymbolNotInScope, 4),
+            diagnostic(DiagnosticRule::SyntaxError, 4),
+            diagnostic(DiagnosticRule::SymbolNotInScope, 5),
+        ];
+        let diagnostics = apply_rules(diagnostics, &config, &doc);
+
+        assert_eq!(codes(&diagnostics), vec![
+            (1, "invalid-na-comparison"),
+            (4, "symbol-not-in-scope"),
+            (5, "symbol-not-in-scope"),
+        ]);
+    }
+
+    #[test]
+    fn test_suppression_file_comment() {
+        let text = "x\n# ark: ignore-file[symbol-not-in-scope]\n'# ark: ignore-file'\n";
+        let doc = Document::new(text, None);
+        let config = DiagnosticsConfig::default();
+
+        let diagnostics = vec![
+            diagnostic(DiagnosticRule::SymbolNotInScope, 0),
+            diagnostic(DiagnosticRule::SyntaxError, 0),
+        ];
+        let diagnostics = apply_rules(diagnostics, &config, &doc);
+
+        // The string is not a comment and doesn't suppress anything
+        assert_eq!(codes(&diagnostics), vec![(0, "syntax-error")]);
+    }
+
+    #[test]
+    fn test_diagnostics_config_honours_ark_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("ark.toml"),
+            "[diagnostics.rules]\nsymbol-not-in-scope = \"off\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("R")).unwrap();
+
+        let uri = Url::from_file_path(dir.path().join("R").join("test.R")).unwrap();
+
+        let mut state = WorldState::default();
+        state
+            .config
+            .diagnostics
+            .rules
+            .insert(DiagnosticRule::SymbolNotInScope, RuleSeverity::Error);
+        state
+            .config
+            .diagnostics
+            .rules
+            .insert(DiagnosticRule::SyntaxError, RuleSeverity::Warning);
+
+        // Outside of the workspace, the file is not used
+        let config = diagnostics_config(&uri, &state);
+        assert_eq!(
+            config.rules.get(&DiagnosticRule::SymbolNotInScope),
+            Some(&RuleSeverity::Error)
+        );
+
+        state
+            .workspace
+            .folders
+            .push(Url::from_file_path(dir.path()).unwrap());
+        state.ark_toml = ArkTomlFiles::load(&state.workspace.folders);
+
+        let config = diagnostics_config(&uri, &state);
+        assert!(config.enable);
+        assert_eq!(
+            config.rules.get(&DiagnosticRule::SymbolNotInScope),
+            Some(&RuleSeverity::Off)
+        );
+        assert_eq!(
+            config.rules.get(&DiagnosticRule::SyntaxError),
+            Some(&RuleSeverity::Warning)
+        );
+    }
+
+    #[test]
+    fn test_ark_toml_files_update() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ark.toml");
+        std::fs::write(&path, "[diagnostics]\nenable = false\n").unwrap();
+
+        let uri = Url::from_file_path(dir.path().join("test.R")).unwrap();
+
+        let mut state = WorldState::default();
+        state
+            .workspace
+            .folders
+            .push(Url::from_file_path(dir.path()).unwrap());
+        state.ark_toml = ArkTomlFiles::load(&state.workspace.folders);
+        assert!(!diagnostics_config(&uri, &state).enable);
+
+        // The cached file is used until it is updated
+        std::fs::write(&path, "[diagnostics]\nenable = true\n").unwrap();
+        assert!(!diagnostics_config(&uri, &state).enable);
+
+        state.ark_toml.update(&path);
+        assert!(diagnostics_config(&uri, &state).enable);
+
+        // Deleted files are forgotten
+        std::fs::write(&path, "[diagnostics]\nenable = false\n").unwrap();
+        state.ark_toml.update(&path);
+        std::fs::remove_file(&path).unwrap();
+        state.ark_toml.update(&path);
+        assert!(diagnostics_config(&uri, &state).enable);
+    }
+}
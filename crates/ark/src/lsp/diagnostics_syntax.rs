@@ -10,6 +10,7 @@ use tree_sitter::Node;
 use tree_sitter::Range;
 
 use crate::lsp::diagnostics::DiagnosticContext;
+use crate::lsp::diagnostics_rules::DiagnosticRule;
 use crate::lsp::traits::node::NodeExt;
 use crate::treesitter::node_has_error_or_missing;
 use crate::treesitter::NodeType;
@@ -422,7 +423,7 @@ fn new_syntax_diagnostic(
     context: &DiagnosticContext,
 ) -> anyhow::Result<Diagnostic> {
     let range = context.doc.lsp_range_from_tree_sitter_range(range)?;
-    Ok(Diagnostic::new_simple(range, message))
+    Ok(DiagnosticRule::SyntaxError.diagnostic(range, message))
 }
 
 #[cfg(test)]
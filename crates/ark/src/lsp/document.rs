@@ -13,6 +13,7 @@ use tree_sitter::Parser;
 use tree_sitter::Tree;
 
 use crate::lsp::config::DocumentConfig;
+use crate::lsp::embedded::EmbeddedDocument;
 
 #[derive(Clone)]
 pub struct Document {
@@ -39,6 +40,10 @@ pub struct Document {
 
     /// Configuration of the document, such as indentation settings.
     pub config: DocumentConfig,
+
+    /// For R Markdown and Quarto documents, the host document and its R chunks.
+    /// `contents` is then the virtual R document projected from the chunks.
+    pub embedded: Option<EmbeddedDocument>,
 }
 
 impl std::fmt::Debug for Document {
@@ -83,9 +88,36 @@ impl Document {
             // once/if Ark becomes an independent LSP
             position_encoding: PositionEncoding::Wide(biome_line_index::WideEncoding::Utf16),
             config: Default::default(),
+            embedded: None,
         }
     }
 
+    /// Create a document from an R Markdown or Quarto document
+    pub fn new_embedded(host: &str, version: Option<i32>) -> Self {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_r::LANGUAGE.into())
+            .unwrap();
+
+        Self::new_embedded_with_parser(host, &mut parser, version)
+    }
+
+    pub fn new_embedded_with_parser(host: &str, parser: &mut Parser, version: Option<i32>) -> Self {
+        let (embedded, contents) = EmbeddedDocument::new(host);
+
+        let mut document = Self::new_with_parser(&contents, parser, version);
+        document.embedded = Some(embedded);
+        document
+    }
+
+    /// Whether `row` contains R code. Always the case for R documents, and for
+    /// rows inside R chunks in R Markdown and Quarto documents.
+    pub fn is_r_row(&self, row: usize) -> bool {
+        self.embedded
+            .as_ref()
+            .is_none_or(|embedded| embedded.contains_row(row))
+    }
+
     // --- source
     // authors = ["rust-analyzer team"]
     // license = "MIT OR Apache-2.0"
@@ -111,6 +143,13 @@ impl Document {
             }
         }
 
+        // Changes apply to the host document of R Markdown and Quarto documents,
+        // from which the R contents are projected again
+        let (contents, line_index) = match &mut self.embedded {
+            Some(embedded) => (&mut embedded.host, &mut embedded.host_line_index),
+            None => (&mut self.contents, &mut self.line_index),
+        };
+
         let mut changes = params.content_changes.clone();
 
         // If at least one of the changes is a full document change, use the last of them
@@ -128,7 +167,7 @@ impl Document {
                     let incremental = changes.split_off(idx + 1);
                     // Unwrap: `rposition()` confirmed this index contains a full document change
                     let change = changes.pop().unwrap();
-                    self.contents = change.text;
+                    *contents = change.text;
                     (incremental, 0)
                 },
                 None => (changes, u32::MAX),
@@ -152,18 +191,24 @@ impl Document {
             // the `line_index` needed to apply this change is now invalid, so we have to
             // rebuild it.
             if range.end.line >= last_start_line {
-                self.line_index = biome_line_index::LineIndex::new(&self.contents);
+                *line_index = biome_line_index::LineIndex::new(contents);
             }
             last_start_line = range.start.line;
 
             // This is a panic if we can't convert. It means we can't keep the document up
             // to date and something is very wrong.
             let range: std::ops::Range<usize> =
-                from_proto::text_range(range, &self.line_index, self.position_encoding)
+                from_proto::text_range(range, line_index, self.position_encoding)
                     .expect("Can convert `range` from `Position` to `TextRange`.")
                     .into();
 
-            self.contents.replace_range(range, &change.text);
+            contents.replace_range(range, &change.text);
+        }
+
+        if let Some(embedded) = &mut self.embedded {
+            let (new_embedded, contents) = EmbeddedDocument::new(&embedded.host);
+            *embedded = new_embedded;
+            self.contents = contents;
         }
 
         // Rebuild everything once at the end
@@ -404,4 +449,46 @@ mod tests {
         // The Rowan tree contains the updated document
         assert_eq!(document.syntax().text_with_trivia(), "lib");
     }
+
+    #[test]
+    fn test_embedded_update_projects_host() {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_r::LANGUAGE.into())
+            .unwrap();
+
+        let host = "Text\n\n```{r}\nx <- 1\n```\n";
+        let mut document = Document::new_embedded_with_parser(host, &mut parser, Some(1));
+        assert_eq!(document.contents, "    \n\n      \nx <- 1\n   \n");
+        assert!(!document.is_r_row(0));
+        assert!(document.is_r_row(3));
+
+        // Turn the prose into a second chunk
+        let params = lsp_types::DidChangeTextDocumentParams {
+            text_document: lsp_types::VersionedTextDocumentIdentifier {
+                uri: lsp_types::Url::parse("file:///test.Rmd").unwrap(),
+                version: 2,
+            },
+            content_changes: vec![lsp_types::TextDocumentContentChangeEvent {
+                range: Some(lsp_types::Range::new(
+                    lsp_types::Position::new(0, 0),
+                    lsp_types::Position::new(0, 4),
+                )),
+                range_length: None,
+                text: String::from("```{r}\ny\n```"),
+            }],
+        };
+        document.on_did_change(&mut parser, &params);
+
+        let embedded = document.embedded.as_ref().unwrap();
+        assert_eq!(embedded.host, "```{r}\ny\n```\n\n```{r}\nx <- 1\n```\n");
+        assert_eq!(embedded.chunks.len(), 2);
+        assert_eq!(document.contents, "      \ny\n   \n\n      \nx <- 1\n   \n");
+        assert!(document.is_r_row(1));
+        assert!(document.is_r_row(5));
+
+        // The AST is built from the virtual document
+        let root = document.ast.root_node();
+        assert_eq!(root.named_child_count(), 2);
+    }
 }
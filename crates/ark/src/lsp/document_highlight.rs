@@ -0,0 +1,140 @@
+//
+// document_highlight.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use tower_lsp::lsp_types::DocumentHighlight;
+use tower_lsp::lsp_types::DocumentHighlightKind;
+use tree_sitter::Point;
+
+use crate::lsp::diagnostics::symbol_occurrences;
+use crate::lsp::diagnostics::SymbolAccess;
+use crate::lsp::document::Document;
+use crate::lsp::rename::find_symbol_node;
+use crate::lsp::state::WorldState;
+
+/// Highlight the occurrences of the symbol under the cursor that refer to the
+/// same binding, i.e. within the scope of the enclosing function that defines
+/// it, or at top level.
+pub(crate) fn document_highlights(
+    document: &Document,
+    point: Point,
+    state: &WorldState,
+) -> anyhow::Result<Option<Vec<DocumentHighlight>>> {
+    let Some(node) = find_symbol_node(document, point) else {
+        return Ok(None);
+    };
+
+    let occurrences = symbol_occurrences(document, state);
+
+    let Some(target) = occurrences
+        .iter()
+        .find(|occurrence| occurrence.range == node.range())
+    else {
+        return Ok(None);
+    };
+
+    let mut highlights = Vec::new();
+
+    for occurrence in occurrences.iter() {
+        if occurrence.name != target.name || occurrence.scope != target.scope {
+            continue;
+        }
+
+        let kind = match occurrence.access {
+            SymbolAccess::Read => DocumentHighlightKind::READ,
+            SymbolAccess::Write => DocumentHighlightKind::WRITE,
+        };
+
+        highlights.push(DocumentHighlight {
+            range: document.lsp_range_from_tree_sitter_range(occurrence.range)?,
+            kind: Some(kind),
+        });
+    }
+
+    Ok(Some(highlights))
+}
+
+#[cfg(test)]
+mod tests {
+    use tower_lsp::lsp_types::Position;
+    use tower_lsp::lsp_types::Range;
+
+    use super::*;
+    use crate::fixtures::point_from_cursor;
+
+    fn highlights(code: &str) -> Vec<(Range, DocumentHighlightKind)> {
+        let (code, point) = point_from_cursor(code);
+        let document = Document::new(&code, None);
+        let state = WorldState::default();
+
+        document_highlights(&document, point, &state)
+            .unwrap()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|highlight| (highlight.range, highlight.kind.unwrap()))
+            .collect()
+    }
+
+    fn range(line: u32, start: u32, end: u32) -> Range {
+        Range::new(Position::new(line, start), Position::new(line, end))
+    }
+
+    #[test]
+    fn test_document_highlight_read_write() {
+        let code = "x@ <- 1\nx + 1\n2 -> x\nx = x * 2\n";
+        assert_eq!(highlights(code), vec![
+            (range(0, 0, 1), DocumentHighlightKind::WRITE),
+            (range(1, 0, 1), DocumentHighlightKind::READ),
+            (range(2, 5, 6), DocumentHighlightKind::WRITE),
+            (range(3, 0, 1), DocumentHighlightKind::WRITE),
+            (range(3, 4, 5), DocumentHighlightKind::READ),
+        ]);
+    }
+
+    #[test]
+    fn test_document_highlight_function_scope() {
+        // The parameter `x` shadows the top-level `x`
+        let code = "x <- 1\nf <- function(x) {\n  x@ + 1\n}\nx\n";
+        assert_eq!(highlights(code), vec![
+            (range(1, 14, 15), DocumentHighlightKind::WRITE),
+            (range(2, 2, 3), DocumentHighlightKind::READ),
+        ]);
+
+        // Top-level symbols are highlighted in functions that don't bind them
+        let code = "x@ <- 1\nf <- function(y) {\n  x + y\n}\n";
+        assert_eq!(highlights(code), vec![
+            (range(0, 0, 1), DocumentHighlightKind::WRITE),
+            (range(2, 2, 3), DocumentHighlightKind::READ),
+        ]);
+    }
+
+    #[test]
+    fn test_document_highlight_super_assignment() {
+        let code = "count <- 0\nf <- function() {\n  count <<- count + 1\n}\ncount@\n";
+        assert_eq!(highlights(code), vec![
+            (range(0, 0, 5), DocumentHighlightKind::WRITE),
+            (range(2, 2, 7), DocumentHighlightKind::WRITE),
+            (range(2, 12, 17), DocumentHighlightKind::READ),
+            (range(4, 0, 5), DocumentHighlightKind::READ),
+        ]);
+    }
+
+    #[test]
+    fn test_document_highlight_assign() {
+        let code = "assign(\"x\", 1)\nx@\n";
+        assert_eq!(highlights(code), vec![
+            (range(0, 8, 9), DocumentHighlightKind::WRITE),
+            (range(1, 0, 1), DocumentHighlightKind::READ),
+        ]);
+
+        // Assignments in other environments are not tracked
+        let code = "assign(\"x\", 1, envir = env)\nx@\n";
+        assert_eq!(highlights(code), vec![(
+            range(1, 0, 1),
+            DocumentHighlightKind::READ
+        )]);
+    }
+}
@@ -0,0 +1,385 @@
+//
+// embedded.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+//! R Markdown and Quarto documents
+//!
+//! The R chunks of these documents are projected into a virtual R document
+//! that the LSP features work on. All lines outside of R chunks, including the
+//! chunk fences, are blanked with spaces. Rows and UTF-16 columns of the
+//! virtual document are thus the same as in the host document, and positions
+//! computed within chunks map back to the host document as they are. The chunk
+//! map records which rows belong to R chunks.
+
+use std::ops::Range;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use url::Url;
+
+/// Opening fence of a code chunk, e.g. ```` ```{r label, echo = FALSE} ````
+static RE_OPENING_FENCE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(`{3,})\s*(.*?)\s*$").unwrap());
+
+/// Header of an R chunk, following the opening fence
+static RE_R_HEADER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\{[rR](?:[\s,]+(.*?))?\s*\}$").unwrap());
+
+/// Quarto chunk option, e.g. `#| label: fig-plot`
+static RE_CHUNK_OPTION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*#\|\s*([\w.-]+)\s*:\s*(.*?)\s*$").unwrap());
+
+#[derive(Clone, Debug)]
+pub struct EmbeddedDocument {
+    /// The contents of the host document
+    pub host: String,
+
+    /// Index of new lines in `host`, used to apply changes to the host document
+    pub host_line_index: biome_line_index::LineIndex,
+
+    /// The R chunks of the host document, in order
+    pub chunks: Vec<Chunk>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Chunk {
+    /// Row of the opening fence
+    pub start: usize,
+
+    /// Row of the closing fence, or the number of rows of the document for
+    /// chunks that are not closed
+    pub end: usize,
+
+    /// Label of the chunk, from the chunk header or the `label` option
+    pub label: Option<String>,
+
+    /// Options of the chunk, written as `#|` comments at the top of the chunk
+    pub options: Vec<ChunkOption>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChunkOption {
+    pub name: String,
+    pub value: String,
+    pub row: usize,
+}
+
+/// Whether the document is an R Markdown or Quarto document, based on its
+/// language identifier or its extension
+pub fn is_embedded_document(uri: &Url, language_id: &str) -> bool {
+    if matches!(language_id, "rmd" | "rmarkdown" | "quarto") {
+        return true;
+    }
+
+    let path = uri.path().to_lowercase();
+    path.ends_with(".rmd") || path.ends_with(".qmd")
+}
+
+impl EmbeddedDocument {
+    /// Parse the chunks of a host document. Returns the embedded document
+    /// along with the contents of the virtual R document.
+    pub fn new(host: &str) -> (Self, String) {
+        let (contents, chunks) = project(host);
+
+        let embedded = Self {
+            host: host.to_string(),
+            host_line_index: biome_line_index::LineIndex::new(host),
+            chunks,
+        };
+
+        (embedded, contents)
+    }
+
+    /// The chunk containing `row`, including its fences
+    pub fn chunk_at(&self, row: usize) -> Option<&Chunk> {
+        self.chunks
+            .iter()
+            .find(|chunk| chunk.start <= row && row <= chunk.end)
+    }
+
+    /// Whether `row` contains R code, i.e. is inside the fences of an R chunk
+    pub fn contains_row(&self, row: usize) -> bool {
+        self.chunks
+            .iter()
+            .any(|chunk| chunk.code_rows().contains(&row))
+    }
+}
+
+impl Chunk {
+    /// Rows of the R code of the chunk, between the fences
+    pub fn code_rows(&self) -> Range<usize> {
+        self.start + 1..self.end
+    }
+}
+
+enum State {
+    Text,
+    RChunk { fence: usize, chunk: Chunk },
+    OtherChunk { fence: usize },
+}
+
+/// Blank the lines outside of R chunks and collect the chunks
+fn project(host: &str) -> (String, Vec<Chunk>) {
+    let mut contents = String::with_capacity(host.len());
+    let mut chunks = Vec::new();
+    let mut state = State::Text;
+    let mut n_rows = 0;
+
+    for (row, line) in host.split_inclusive('\n').enumerate() {
+        n_rows = row + 1;
+
+        let (text, ending) = split_line_ending(line);
+        let mut is_code = false;
+
+        state = match state {
+            State::Text => match RE_OPENING_FENCE.captures(text) {
+                Some(captures) => {
+                    let fence = captures[1].len();
+                    match RE_R_HEADER.captures(&captures[2]) {
+                        Some(header) => State::RChunk {
+                            fence,
+                            chunk: Chunk {
+                                start: row,
+                                end: row,
+                                label: header.get(1).and_then(|m| header_label(m.as_str())),
+                                options: Vec::new(),
+                            },
+                        },
+                        None => State::OtherChunk { fence },
+                    }
+                },
+                None => State::Text,
+            },
+
+            State::RChunk { fence, mut chunk } => {
+                if is_closing_fence(text, fence) {
+                    chunk.end = row;
+                    chunks.push(chunk);
+                    State::Text
+                } else {
+                    is_code = true;
+
+                    // Options must come first in the chunk
+                    if chunk.options.len() == row - chunk.start - 1 {
+                        if let Some(captures) = RE_CHUNK_OPTION.captures(text) {
+                            let option = ChunkOption {
+                                name: captures[1].to_string(),
+                                value: unquote(&captures[2]).to_string(),
+                                row,
+                            };
+                            if option.name == "label" && chunk.label.is_none() {
+                                chunk.label = Some(option.value.clone());
+                            }
+                            chunk.options.push(option);
+                        }
+                    }
+
+                    State::RChunk { fence, chunk }
+                }
+            },
+
+            State::OtherChunk { fence } => {
+                if is_closing_fence(text, fence) {
+                    State::Text
+                } else {
+                    State::OtherChunk { fence }
+                }
+            },
+        };
+
+        if is_code {
+            contents.push_str(text);
+        } else {
+            // Preserve UTF-16 columns
+            for char in text.chars() {
+                for _ in 0..char.len_utf16() {
+                    contents.push(' ');
+                }
+            }
+        }
+        contents.push_str(ending);
+    }
+
+    // Chunks that are not closed extend to the end of the document
+    if let State::RChunk { mut chunk, .. } = state {
+        chunk.end = n_rows;
+        chunks.push(chunk);
+    }
+
+    (contents, chunks)
+}
+
+fn split_line_ending(line: &str) -> (&str, &str) {
+    if let Some(text) = line.strip_suffix("\r\n") {
+        (text, "\r\n")
+    } else if let Some(text) = line.strip_suffix('\n') {
+        (text, "\n")
+    } else {
+        (line, "")
+    }
+}
+
+fn is_closing_fence(text: &str, fence: usize) -> bool {
+    let text = text.trim();
+    text.len() >= fence && text.bytes().all(|byte| byte == b'`')
+}
+
+/// The label of an R Markdown chunk header is either the first unnamed
+/// option, e.g. `{r label, echo = FALSE}`, or the `label` option
+fn header_label(options: &str) -> Option<String> {
+    for (i, option) in options.split(',').enumerate() {
+        let option = option.trim();
+
+        match option.split_once('=') {
+            Some((name, value)) if name.trim() == "label" => {
+                return Some(unquote(value.trim()).to_string());
+            },
+            None if i == 0 && !option.is_empty() => {
+                return Some(unquote(option).to_string());
+            },
+            _ => {},
+        }
+    }
+
+    None
+}
+
+fn unquote(value: &str) -> &str {
+    ['"', '\'']
+        .into_iter()
+        .find_map(|quote| {
+            value
+                .strip_prefix(quote)
+                .and_then(|value| value.strip_suffix(quote))
+        })
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_projection() {
+        let host = "---
+title: Test
+---
+
+Some text with `r 1 + 1`.
+
+```{r setup, include = FALSE}
+x <- 1
+```
+
+Überschrift
+
+```{python}
+y = 2
+```
+
+```{R}
+f(x)
+```
+";
+        let (embedded, contents) = EmbeddedDocument::new(host);
+
+        // Rows and UTF-16 columns are preserved
+        let lines: Vec<&str> = contents.lines().collect();
+        let host_lines: Vec<&str> = host.lines().collect();
+        assert_eq!(lines.len(), host_lines.len());
+        for (line, host_line) in lines.iter().zip(host_lines.iter()) {
+            assert_eq!(
+                line.encode_utf16().count(),
+                host_line.encode_utf16().count()
+            );
+        }
+
+        // Only R code is kept
+        assert_eq!(contents.split_whitespace().collect::<Vec<_>>(), vec![
+            "x", "<-", "1", "f(x)"
+        ]);
+
+        assert_eq!(embedded.chunks.len(), 2);
+        assert_eq!(embedded.chunks[0].label, Some(String::from("setup")));
+        assert_eq!(embedded.chunks[0].code_rows(), 7..8);
+        assert_eq!(embedded.chunks[1].label, None);
+        assert_eq!(embedded.chunks[1].code_rows(), 17..18);
+
+        assert!(embedded.contains_row(7));
+        assert!(!embedded.contains_row(6));
+        assert!(!embedded.contains_row(13));
+        assert_eq!(embedded.chunk_at(8), Some(&embedded.chunks[0]));
+        assert_eq!(embedded.chunk_at(10), None);
+    }
+
+    #[test]
+    fn test_chunk_options() {
+        let host = "```{r}
+#| label: fig-plot
+#| echo: false
+#| fig-cap: 'A plot'
+plot(1)
+#| not: an option
+```
+";
+        let (embedded, contents) = EmbeddedDocument::new(host);
+
+        // Options are R comments in the virtual document
+        assert!(contents.contains("#| label: fig-plot"));
+
+        let chunk = &embedded.chunks[0];
+        assert_eq!(chunk.label, Some(String::from("fig-plot")));
+        assert_eq!(chunk.options, vec![
+            ChunkOption {
+                name: String::from("label"),
+                value: String::from("fig-plot"),
+                row: 1,
+            },
+            ChunkOption {
+                name: String::from("echo"),
+                value: String::from("false"),
+                row: 2,
+            },
+            ChunkOption {
+                name: String::from("fig-cap"),
+                value: String::from("A plot"),
+                row: 3,
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_chunk_header_label() {
+        assert_eq!(header_label("setup"), Some(String::from("setup")));
+        assert_eq!(header_label("setup, echo = FALSE"), Some(String::from("setup")));
+        assert_eq!(header_label("echo = FALSE, label = 'x'"), Some(String::from("x")));
+        assert_eq!(header_label("echo = FALSE"), None);
+        assert_eq!(header_label(""), None);
+    }
+
+    #[test]
+    fn test_unclosed_chunk() {
+        let host = "text\n````{r}\nx <- 1\n```\ny <- 2";
+        let (embedded, contents) = EmbeddedDocument::new(host);
+
+        // A shorter fence doesn't close the chunk
+        assert_eq!(embedded.chunks[0].code_rows(), 2..5);
+        assert!(contents.ends_with("```\ny <- 2"));
+    }
+
+    #[test]
+    fn test_is_embedded_document() {
+        let uri = Url::parse("file:///project/report.Rmd").unwrap();
+        assert!(is_embedded_document(&uri, "r"));
+
+        let uri = Url::parse("file:///project/report.qmd").unwrap();
+        assert!(is_embedded_document(&uri, "quarto"));
+
+        let uri = Url::parse("untitled:Untitled-1").unwrap();
+        assert!(is_embedded_document(&uri, "rmd"));
+        assert!(!is_embedded_document(&uri, "r"));
+    }
+}
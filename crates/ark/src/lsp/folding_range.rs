@@ -37,6 +37,14 @@ pub fn folding_range(document: &Document) -> anyhow::Result<Vec<FoldingRange>> {
         &mut None,
     );
 
+    // Chunks of R Markdown and Quarto documents fold from fence to fence
+    if let Some(embedded) = &document.embedded {
+        let last_row = document.contents.lines().count().saturating_sub(1);
+        for chunk in embedded.chunks.iter() {
+            folding_ranges.push(comment_range(chunk.start, chunk.end.min(last_row)));
+        }
+    }
+
     Ok(folding_ranges)
 }
 
@@ -566,6 +574,29 @@ d"
         ));
     }
 
+    #[test]
+    fn test_folding_embedded_chunks() {
+        let host = "# Title
+
+```{r}
+f <- function() {
+  1
+}
+```
+
+```{r}
+x <- 1
+";
+        let doc = Document::new_embedded(host, None);
+        let ranges = sorted_ranges(folding_range(&doc).unwrap());
+
+        let lines: Vec<(u32, u32)> = ranges
+            .iter()
+            .map(|range| (range.start_line, range.end_line))
+            .collect();
+        assert_eq!(lines, vec![(2, 6), (3, 4), (8, 9)]);
+    }
+
     // Test for bracket-based folding
     #[test]
     fn test_folding_brackets() {
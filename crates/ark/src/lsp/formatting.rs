@@ -0,0 +1,331 @@
+//
+// formatting.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use std::path::PathBuf;
+
+use aether_formatter::context::RFormatOptions;
+use aether_formatter::format_node;
+use aether_lsp_utils::proto::to_proto;
+use aether_syntax::RRoot;
+use aether_syntax::RSyntaxNode;
+use aether_workspace::toml::find_air_toml_in_directory;
+use aether_workspace::toml::parse_air_toml;
+use biome_rowan::AstNode;
+use biome_rowan::TextRange;
+use biome_rowan::TextSize;
+use tower_lsp::lsp_types;
+use tower_lsp::lsp_types::TextEdit;
+use url::Url;
+
+use crate::lsp::config::IndentStyle;
+use crate::lsp::document::Document;
+
+/// Format a whole document. Returns `None` if the document is already
+/// formatted or if it has syntax errors.
+pub(crate) fn format_document(
+    uri: &Url,
+    document: &Document,
+) -> anyhow::Result<Option<Vec<TextEdit>>> {
+    if document.parse.has_error() {
+        return Ok(None);
+    }
+
+    // Formatting the virtual R document of R Markdown and Quarto documents
+    // as a whole would discard the prose. Range formatting is still
+    // supported since it only edits the expressions of the chunks.
+    if document.embedded.is_some() {
+        return Ok(None);
+    }
+
+    let options = format_options(uri, document)?;
+    let formatted = format_node(options, &document.syntax())?;
+    let formatted = formatted.print()?.into_code();
+
+    if formatted == document.contents {
+        return Ok(None);
+    }
+
+    let range = TextRange::new(TextSize::from(0), TextSize::of(document.contents.as_str()));
+    let edit = TextEdit::new(lsp_range(document, range)?, formatted);
+
+    Ok(Some(vec![edit]))
+}
+
+/// Format the top-level expressions that overlap with `range`
+///
+/// Formatting is performed on whole top-level expressions so that we don't
+/// need to infer the indentation context of nested expressions. The range is
+/// effectively widened to the expressions it touches.
+pub(crate) fn format_range(
+    uri: &Url,
+    document: &Document,
+    range: lsp_types::Range,
+) -> anyhow::Result<Option<Vec<TextEdit>>> {
+    if document.parse.has_error() {
+        return Ok(None);
+    }
+
+    let range = document.tree_sitter_range_from_lsp_range(range)?;
+    let range = TextRange::new(
+        TextSize::from(range.start_byte as u32),
+        TextSize::from(range.end_byte as u32),
+    );
+
+    let options = format_options(uri, document)?;
+
+    let Some(root) = RRoot::cast(document.syntax()) else {
+        return Ok(None);
+    };
+
+    let mut edits = Vec::new();
+
+    for expression in root.expressions() {
+        let node: &RSyntaxNode = expression.syntax();
+        let node_range = trivia_range(document, node);
+
+        if node_range.end() < range.start() || node_range.start() > range.end() {
+            continue;
+        }
+
+        // In R Markdown and Quarto documents, expressions that continue in
+        // another chunk span blanked prose that formatting would discard
+        if !is_r_range(document, node_range) {
+            continue;
+        }
+
+        let formatted = format_node(options.clone(), node)?;
+        let formatted = formatted.print()?.into_code();
+
+        // The formatter adds a trailing newline, which is already part of the
+        // surrounding trivia
+        let formatted = formatted.trim();
+
+        if formatted == &document.contents[node_range] {
+            continue;
+        }
+
+        edits.push(TextEdit::new(
+            lsp_range(document, node_range)?,
+            formatted.to_string(),
+        ));
+    }
+
+    if edits.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(edits))
+}
+
+/// The range of a top-level expression including its comments, which the
+/// formatter prints along with the expression. The whitespace and newlines
+/// separating the expression from its neighbours are excluded.
+fn trivia_range(document: &Document, node: &RSyntaxNode) -> TextRange {
+    let range = node.text_range();
+    let text = &document.contents[range];
+
+    let leading = TextSize::of(&text[..text.len() - text.trim_start().len()]);
+    let trailing = TextSize::of(&text[text.trim_end().len()..]);
+
+    TextRange::new(range.start() + leading, range.end() - trailing)
+}
+
+/// Formatting options are taken from the closest `air.toml` file, loaded with
+/// air's own settings loader. Without one, they are taken from the document
+/// configuration, which is synchronised with the editor settings.
+fn format_options(uri: &Url, document: &Document) -> anyhow::Result<RFormatOptions> {
+    if let Some(air_toml) = find_air_toml(uri) {
+        let settings = parse_air_toml(&air_toml)
+            .map_err(|err| anyhow::anyhow!("Can't parse `{}`: {err}", air_toml.display()))?;
+        return Ok(settings.format.to_format_options(&document.contents));
+    }
+
+    let indent = &document.config.indent;
+
+    let (indent_style, indent_width) = match indent.indent_style {
+        IndentStyle::Space => (aether_settings::IndentStyle::Space, indent.indent_size),
+        IndentStyle::Tab => (aether_settings::IndentStyle::Tab, indent.tab_width),
+    };
+
+    // Fall back to the default width for widths that air doesn't support
+    let indent_width = u8::try_from(indent_width)
+        .ok()
+        .and_then(|width| aether_settings::IndentWidth::try_from(width).ok())
+        .unwrap_or_default();
+
+    Ok(RFormatOptions::new()
+        .with_indent_style(indent_style)
+        .with_indent_width(indent_width))
+}
+
+/// Find the closest `air.toml` file from the document's directory up to the
+/// root of the file system, as air does. Documents outside of the workspace
+/// folders are formatted with the settings of their project too.
+fn find_air_toml(uri: &Url) -> Option<PathBuf> {
+    let path = uri.to_file_path().ok()?;
+    path.ancestors()
+        .skip(1)
+        .find_map(find_air_toml_in_directory)
+}
+
+/// Whether all the rows of `range` contain R code
+fn is_r_range(document: &Document, range: TextRange) -> bool {
+    let line = |offset: TextSize| document.line_index.line_col(offset).map(|pos| pos.line);
+
+    let (Some(start), Some(end)) = (line(range.start()), line(range.end())) else {
+        return false;
+    };
+
+    (start..=end).all(|row| document.is_r_row(row as usize))
+}
+
+fn lsp_range(document: &Document, range: TextRange) -> anyhow::Result<lsp_types::Range> {
+    let position = |offset: TextSize| {
+        let line_col = document
+            .line_index
+            .line_col(offset)
+            .ok_or_else(|| anyhow::anyhow!("Can't convert offset {offset:?} to a position"))?;
+        to_proto::position_from_line_col(line_col, &document.line_index, document.position_encoding)
+    };
+
+    Ok(lsp_types::Range::new(
+        position(range.start())?,
+        position(range.end())?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsp::config::IndentationConfig;
+    use crate::lsp::util::test_path;
+
+    fn apply_edits(document: &Document, edits: Vec<TextEdit>) -> String {
+        let mut contents = document.contents.clone();
+
+        let mut edits = edits;
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.range.start));
+
+        for edit in edits {
+            let range = document
+                .tree_sitter_range_from_lsp_range(edit.range)
+                .unwrap();
+            contents.replace_range(range.start_byte..range.end_byte, &edit.new_text);
+        }
+
+        contents
+    }
+
+    #[test]
+    fn test_format_document() {
+        let uri = test_path("test.R");
+
+        let document = Document::new("x<-1\nif(x){y}\n", None);
+        let edits = format_document(&uri, &document).unwrap().unwrap();
+        assert_eq!(apply_edits(&document, edits), "x <- 1\nif (x) {\n  y\n}\n");
+
+        // Already formatted
+        let document = Document::new("x <- 1\n", None);
+        assert!(format_document(&uri, &document).unwrap().is_none());
+
+        // Syntax errors
+        let document = Document::new("x <- (\n", None);
+        assert!(format_document(&uri, &document).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_format_document_honours_indentation_config() {
+        let uri = test_path("test.R");
+
+        let mut document = Document::new("if(x){y}\n", None);
+        document.config.indent = IndentationConfig {
+            indent_style: IndentStyle::Space,
+            indent_size: 4,
+            tab_width: 4,
+        };
+
+        let edits = format_document(&uri, &document).unwrap().unwrap();
+        assert_eq!(apply_edits(&document, edits), "if (x) {\n    y\n}\n");
+    }
+
+    #[test]
+    fn test_format_document_honours_air_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("air.toml"), "[format]\nindent-width = 3\n").unwrap();
+        std::fs::create_dir(dir.path().join("R")).unwrap();
+
+        // The file doesn't need to be in a workspace folder
+        let uri = Url::from_file_path(dir.path().join("R").join("test.R")).unwrap();
+
+        let document = Document::new("if(x){y}\n", None);
+        let edits = format_document(&uri, &document).unwrap().unwrap();
+        assert_eq!(apply_edits(&document, edits), "if (x) {\n   y\n}\n");
+    }
+
+    #[test]
+    fn test_format_range() {
+        let uri = test_path("test.R");
+
+        let document = Document::new("x<-1\ny<-2\nz<-3\n", None);
+        let range = lsp_types::Range::new(
+            lsp_types::Position::new(1, 1),
+            lsp_types::Position::new(1, 2),
+        );
+
+        let edits = format_range(&uri, &document, range).unwrap().unwrap();
+        assert_eq!(apply_edits(&document, edits), "x<-1\ny <- 2\nz<-3\n");
+    }
+
+    #[test]
+    fn test_format_range_skips_expressions_spanning_prose() {
+        let uri = test_path("test.Rmd");
+
+        let host = "```{r}\nx<-1\ny<-c(1,\n```\n\nSome prose\n\n```{r}\n2)\n```\n";
+        let document = Document::new_embedded(host, None);
+
+        let range = lsp_types::Range::new(
+            lsp_types::Position::new(0, 0),
+            lsp_types::Position::new(9, 0),
+        );
+
+        let edits = format_range(&uri, &document, range).unwrap().unwrap();
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].new_text, "x <- 1");
+    }
+
+    #[test]
+    fn test_format_range_keeps_comments() {
+        let uri = test_path("test.R");
+
+        let document = Document::new("x<-1\n\n# Comment\ny<-2 # Trailing\nz<-3\n", None);
+        let range = lsp_types::Range::new(
+            lsp_types::Position::new(3, 1),
+            lsp_types::Position::new(3, 2),
+        );
+
+        let edits = format_range(&uri, &document, range).unwrap().unwrap();
+        assert_eq!(
+            apply_edits(&document, edits),
+            "x<-1\n\n# Comment\ny <- 2 # Trailing\nz<-3\n"
+        );
+    }
+
+    #[test]
+    fn test_format_options_falls_back_on_invalid_indent_width() {
+        let uri = test_path("test.R");
+
+        let mut document = Document::new("if(x){y}\n", None);
+        document.config.indent = IndentationConfig {
+            indent_style: IndentStyle::Space,
+            indent_size: 300,
+            tab_width: 300,
+        };
+
+        let edits = format_document(&uri, &document).unwrap().unwrap();
+        assert_eq!(apply_edits(&document, edits), "if (x) {\n  y\n}\n");
+    }
+}
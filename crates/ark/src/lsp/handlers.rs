@@ -9,31 +9,62 @@ use anyhow::anyhow;
 use serde_json::Value;
 use stdext::result::ResultExt;
 use stdext::unwrap;
+use tower_lsp::lsp_types::CallHierarchyIncomingCall;
+use tower_lsp::lsp_types::CallHierarchyIncomingCallsParams;
+use tower_lsp::lsp_types::CallHierarchyItem;
+use tower_lsp::lsp_types::CallHierarchyOutgoingCall;
+use tower_lsp::lsp_types::CallHierarchyOutgoingCallsParams;
+use tower_lsp::lsp_types::CallHierarchyPrepareParams;
 use tower_lsp::lsp_types::CodeActionParams;
 use tower_lsp::lsp_types::CodeActionResponse;
+use tower_lsp::lsp_types::CodeLens;
+use tower_lsp::lsp_types::CodeLensParams;
 use tower_lsp::lsp_types::CompletionItem;
 use tower_lsp::lsp_types::CompletionParams;
 use tower_lsp::lsp_types::CompletionResponse;
+use tower_lsp::lsp_types::DidChangeWatchedFilesRegistrationOptions;
+use tower_lsp::lsp_types::DocumentDiagnosticParams;
+use tower_lsp::lsp_types::DocumentDiagnosticReport;
+use tower_lsp::lsp_types::DocumentDiagnosticReportResult;
+use tower_lsp::lsp_types::DocumentFormattingParams;
+use tower_lsp::lsp_types::DocumentHighlight;
+use tower_lsp::lsp_types::DocumentHighlightParams;
 use tower_lsp::lsp_types::DocumentOnTypeFormattingParams;
+use tower_lsp::lsp_types::DocumentRangeFormattingParams;
 use tower_lsp::lsp_types::DocumentSymbolParams;
 use tower_lsp::lsp_types::DocumentSymbolResponse;
+use tower_lsp::lsp_types::FileSystemWatcher;
 use tower_lsp::lsp_types::FoldingRange;
 use tower_lsp::lsp_types::FoldingRangeParams;
+use tower_lsp::lsp_types::GlobPattern;
 use tower_lsp::lsp_types::GotoDefinitionParams;
 use tower_lsp::lsp_types::GotoDefinitionResponse;
 use tower_lsp::lsp_types::Hover;
 use tower_lsp::lsp_types::HoverContents;
 use tower_lsp::lsp_types::HoverParams;
+use tower_lsp::lsp_types::InlayHint;
+use tower_lsp::lsp_types::InlayHintParams;
 use tower_lsp::lsp_types::Location;
 use tower_lsp::lsp_types::MessageType;
+use tower_lsp::lsp_types::PrepareRenameResponse;
 use tower_lsp::lsp_types::ReferenceParams;
 use tower_lsp::lsp_types::Registration;
+use tower_lsp::lsp_types::RelatedFullDocumentDiagnosticReport;
+use tower_lsp::lsp_types::RenameParams;
 use tower_lsp::lsp_types::SelectionRange;
 use tower_lsp::lsp_types::SelectionRangeParams;
+use tower_lsp::lsp_types::SemanticTokensDeltaParams;
+use tower_lsp::lsp_types::SemanticTokensFullDeltaResult;
+use tower_lsp::lsp_types::SemanticTokensParams;
+use tower_lsp::lsp_types::SemanticTokensResult;
 use tower_lsp::lsp_types::SignatureHelp;
 use tower_lsp::lsp_types::SignatureHelpParams;
 use tower_lsp::lsp_types::SymbolInformation;
+use tower_lsp::lsp_types::TextDocumentPositionParams;
 use tower_lsp::lsp_types::TextEdit;
+use tower_lsp::lsp_types::WorkspaceDiagnosticParams;
+use tower_lsp::lsp_types::WorkspaceDiagnosticReport;
+use tower_lsp::lsp_types::WorkspaceDiagnosticReportResult;
 use tower_lsp::lsp_types::WorkspaceEdit;
 use tower_lsp::lsp_types::WorkspaceSymbolParams;
 use tower_lsp::Client;
@@ -43,24 +74,43 @@ use crate::analysis::input_boundaries::input_boundaries;
 use crate::lsp;
 use crate::lsp::backend::LspError;
 use crate::lsp::backend::LspResult;
+use crate::lsp::call_hierarchy::incoming_calls;
+use crate::lsp::call_hierarchy::outgoing_calls;
+use crate::lsp::call_hierarchy::prepare_call_hierarchy;
 use crate::lsp::code_action::code_actions;
+use crate::lsp::code_lens::code_lenses;
+use crate::lsp::code_lens::resolve_code_lens;
 use crate::lsp::completions::provide_completions;
+use crate::lsp::completions::provide_static_completions;
 use crate::lsp::completions::resolve_completion;
 use crate::lsp::definitions::goto_definition;
+use crate::lsp::diagnostics::workspace_diagnostics;
 use crate::lsp::document_context::DocumentContext;
+use crate::lsp::document_highlight::document_highlights;
 use crate::lsp::folding_range::folding_range;
+use crate::lsp::formatting::format_document;
+use crate::lsp::formatting::format_range;
 use crate::lsp::help_topic::help_topic;
 use crate::lsp::help_topic::HelpTopicParams;
 use crate::lsp::help_topic::HelpTopicResponse;
 use crate::lsp::hover::r_hover;
+use crate::lsp::hover::workspace_hover;
 use crate::lsp::indent::indent_edit;
+use crate::lsp::indexer;
+use crate::lsp::inlay_hint::inlay_hints;
 use crate::lsp::input_boundaries::InputBoundariesParams;
 use crate::lsp::input_boundaries::InputBoundariesResponse;
 use crate::lsp::main_loop::LspState;
 use crate::lsp::references::find_references;
+use crate::lsp::rename::prepare_rename;
+use crate::lsp::rename::rename;
 use crate::lsp::selection_range::convert_selection_range_from_tree_sitter_to_lsp;
 use crate::lsp::selection_range::selection_range;
+use crate::lsp::semantic_tokens::semantic_tokens;
+use crate::lsp::signature_help::add_roxygen_documentation;
 use crate::lsp::signature_help::r_signature_help;
+use crate::lsp::signature_help::workspace_signature_help;
+use crate::lsp::standalone::is_standalone;
 use crate::lsp::state::WorldState;
 use crate::lsp::statement_range::statement_range;
 use crate::lsp::statement_range::StatementRangeParams;
@@ -117,6 +167,25 @@ pub(crate) async fn handle_initialized(
         }
     }
 
+    if lsp_state
+        .capabilities
+        .dynamic_registration_for_did_change_watched_files()
+    {
+        // Watch the `ark.toml` files so that the cached diagnostics
+        // configuration is reloaded when they change on disk
+        let options = DidChangeWatchedFilesRegistrationOptions {
+            watchers: vec![FileSystemWatcher {
+                glob_pattern: GlobPattern::String(String::from("**/{ark.toml,.ark.toml}")),
+                kind: None,
+            }],
+        };
+        regs.push(Registration {
+            id: uuid::Uuid::new_v4().to_string(),
+            method: String::from("workspace/didChangeWatchedFiles"),
+            register_options: Some(serde_json::to_value(options)?),
+        });
+    }
+
     client
         .register_capability(regs)
         .instrument(span.exit())
@@ -184,18 +253,41 @@ pub(crate) fn handle_completion(
 ) -> LspResult<Option<CompletionResponse>> {
     // Get reference to document.
     let uri = params.text_document_position.text_document.uri;
-    let document = state.get_document(&uri)?;
-
     let position = params.text_document_position.position;
+
+    // Notebook cells are completed within the R cells of their notebook
+    let notebook = state.get_notebook_of_cell(&uri);
+    let (document, position) = match notebook {
+        Some(notebook) => (
+            &notebook.document,
+            notebook.to_notebook_position(&uri, position)?,
+        ),
+        None => (state.get_document(&uri)?, position),
+    };
+
     let point = document.tree_sitter_point_from_lsp_position(position)?;
 
+    // No completions in the prose of R Markdown and Quarto documents
+    if !document.is_r_row(point.row) {
+        return Ok(None);
+    }
+
     let trigger = params.context.and_then(|ctxt| ctxt.trigger_character);
 
     // Build the document context.
     let context = DocumentContext::new(&document, point, trigger);
     lsp::log_info!("Completion context: {:#?}", context);
 
-    let completions = r_task(|| provide_completions(&context, state))?;
+    // Without an R session, we can only offer completions from static sources
+    let mut completions = if is_standalone() {
+        provide_static_completions(&context, state)?
+    } else {
+        r_task(|| provide_completions(&context, state))?
+    };
+
+    if let Some(notebook) = notebook {
+        notebook.to_cell_completions(&uri, &mut completions);
+    }
 
     if !completions.is_empty() {
         Ok(Some(CompletionResponse::Array(completions)))
@@ -206,6 +298,9 @@ pub(crate) fn handle_completion(
 
 #[tracing::instrument(level = "info", skip_all)]
 pub(crate) fn handle_completion_resolve(mut item: CompletionItem) -> LspResult<CompletionItem> {
+    if is_standalone() {
+        return Ok(item);
+    }
     r_task(|| resolve_completion(&mut item))?;
     Ok(item)
 }
@@ -221,14 +316,24 @@ pub(crate) fn handle_hover(params: HoverParams, state: &WorldState) -> LspResult
     // build document context
     let context = DocumentContext::new(&document, point, None);
 
-    // request hover information
-    let result = r_task(|| r_hover(&context));
+    // Help pages are fetched from the R session
+    let result = if is_standalone() {
+        None
+    } else {
+        match r_task(|| r_hover(&context)) {
+            Ok(result) => result,
+            Err(err) => {
+                lsp::log_error!("{err:?}");
+                None
+            },
+        }
+    };
 
-    // unwrap errors
-    let result = unwrap!(result, Err(err) => {
-        lsp::log_error!("{err:?}");
-        return Ok(None);
-    });
+    // Fall back to the documentation of functions defined in the workspace
+    let result = match result {
+        Some(result) => Some(result),
+        None => workspace_hover(&context, &uri, state).log_err().flatten(),
+    };
 
     // unwrap empty options
     let result = unwrap!(result, None => {
@@ -255,8 +360,26 @@ pub(crate) fn handle_signature_help(
 
     let context = DocumentContext::new(&document, point, None);
 
-    // request signature help
-    let result = r_task(|| r_signature_help(&context));
+    // Signatures are fetched from the R session when there is one
+    if !is_standalone() {
+        // request signature help
+        let result = r_task(|| r_signature_help(&context));
+
+        // unwrap errors
+        let result = unwrap!(result, Err(err) => {
+            lsp::log_error!("{err:?}");
+            return Ok(None);
+        });
+
+        if let Some(mut result) = result {
+            // Functions defined in the workspace have no help page
+            add_roxygen_documentation(&mut result, &uri, state);
+            return Ok(Some(result));
+        }
+    }
+
+    // Otherwise fall back to functions defined in the workspace
+    let result = workspace_signature_help(&context, &uri, state);
 
     // unwrap errors
     let result = unwrap!(result, Err(err) => {
@@ -264,12 +387,7 @@ pub(crate) fn handle_signature_help(
         return Ok(None);
     });
 
-    // unwrap empty options
-    let result = unwrap!(result, None => {
-        return Ok(None);
-    });
-
-    Ok(Some(result))
+    Ok(result)
 }
 
 #[tracing::instrument(level = "info", skip_all)]
@@ -278,6 +396,11 @@ pub(crate) fn handle_goto_definition(
     state: &WorldState,
 ) -> LspResult<Option<GotoDefinitionResponse>> {
     let uri = &params.text_document_position_params.text_document.uri;
+
+    if let Some(notebook) = state.get_notebook_of_cell(uri) {
+        return Ok(notebook.goto_definition(params).log_err().flatten());
+    }
+
     let document = state.get_document(uri)?;
     Ok(goto_definition(&document, params).log_err().flatten())
 }
@@ -360,6 +483,128 @@ pub(crate) fn handle_indent(
     indent_edit(doc, point.row)
 }
 
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_prepare_rename(
+    params: TextDocumentPositionParams,
+    state: &WorldState,
+) -> LspResult<Option<PrepareRenameResponse>> {
+    Ok(prepare_rename(params, state)?)
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_rename(
+    params: RenameParams,
+    lsp_state: &LspState,
+    state: &WorldState,
+) -> LspResult<Option<WorkspaceEdit>> {
+    Ok(rename(params, state, &lsp_state.capabilities)?)
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_semantic_tokens_full(
+    params: SemanticTokensParams,
+    lsp_state: &mut LspState,
+    state: &WorldState,
+) -> LspResult<Option<SemanticTokensResult>> {
+    let uri = params.text_document.uri;
+    let document = state.get_document(&uri)?;
+
+    let data = semantic_tokens(document, state)?;
+    let tokens = lsp_state.semantic_tokens.insert(uri, data);
+
+    Ok(Some(SemanticTokensResult::Tokens(tokens)))
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_semantic_tokens_full_delta(
+    params: SemanticTokensDeltaParams,
+    lsp_state: &mut LspState,
+    state: &WorldState,
+) -> LspResult<Option<SemanticTokensFullDeltaResult>> {
+    let uri = params.text_document.uri;
+    let document = state.get_document(&uri)?;
+
+    let data = semantic_tokens(document, state)?;
+    let result = lsp_state
+        .semantic_tokens
+        .insert_delta(uri, &params.previous_result_id, data);
+
+    Ok(Some(result))
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_inlay_hint(
+    params: InlayHintParams,
+    state: &WorldState,
+) -> LspResult<Option<Vec<InlayHint>>> {
+    let document = state.get_document(&params.text_document.uri)?;
+    let range = document.tree_sitter_range_from_lsp_range(params.range)?;
+
+    let hints = inlay_hints(document, range, &state.config.inlay_hints)?;
+
+    if hints.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(hints))
+    }
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_formatting(
+    params: DocumentFormattingParams,
+    state: &WorldState,
+) -> LspResult<Option<Vec<TextEdit>>> {
+    let uri = params.text_document.uri;
+    let document = state.get_document(&uri)?;
+    Ok(format_document(&uri, document)?)
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_range_formatting(
+    params: DocumentRangeFormattingParams,
+    state: &WorldState,
+) -> LspResult<Option<Vec<TextEdit>>> {
+    let uri = params.text_document.uri;
+    let document = state.get_document(&uri)?;
+    Ok(format_range(&uri, document, params.range)?)
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_prepare_call_hierarchy(
+    params: CallHierarchyPrepareParams,
+    state: &WorldState,
+) -> LspResult<Option<Vec<CallHierarchyItem>>> {
+    Ok(prepare_call_hierarchy(params, state)?)
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_incoming_calls(
+    params: CallHierarchyIncomingCallsParams,
+    state: &WorldState,
+) -> LspResult<Option<Vec<CallHierarchyIncomingCall>>> {
+    Ok(incoming_calls(params, state)?)
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_outgoing_calls(
+    params: CallHierarchyOutgoingCallsParams,
+    state: &WorldState,
+) -> LspResult<Option<Vec<CallHierarchyOutgoingCall>>> {
+    Ok(outgoing_calls(params, state)?)
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_document_highlight(
+    params: DocumentHighlightParams,
+    state: &WorldState,
+) -> LspResult<Option<Vec<DocumentHighlight>>> {
+    let uri = params.text_document_position_params.text_document.uri;
+    let document = state.get_document(&uri)?;
+    let position = params.text_document_position_params.position;
+    let point = document.tree_sitter_point_from_lsp_position(position)?;
+    Ok(document_highlights(document, point, state)?)
+}
+
 #[tracing::instrument(level = "info", skip_all)]
 pub(crate) fn handle_code_action(
     params: CodeActionParams,
@@ -370,7 +615,14 @@ pub(crate) fn handle_code_action(
     let doc = state.get_document(&uri)?;
     let range = doc.tree_sitter_range_from_lsp_range(params.range)?;
 
-    let code_actions = code_actions(&uri, doc, range, &lsp_state.capabilities);
+    let code_actions = code_actions(
+        &uri,
+        doc,
+        range,
+        &params.context.diagnostics,
+        state,
+        &lsp_state.capabilities,
+    );
 
     if code_actions.is_empty() {
         Ok(None)
@@ -379,6 +631,54 @@ pub(crate) fn handle_code_action(
     }
 }
 
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_code_lens(
+    params: CodeLensParams,
+    state: &WorldState,
+) -> LspResult<Option<Vec<CodeLens>>> {
+    let uri = params.text_document.uri;
+    let document = state.get_document(&uri)?;
+
+    let lenses = code_lenses(&uri, document)?;
+
+    if lenses.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(lenses))
+    }
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_code_lens_resolve(
+    params: CodeLens,
+    state: &WorldState,
+) -> LspResult<CodeLens> {
+    Ok(resolve_code_lens(params, state)?)
+}
+
+/// Open documents are diagnosed as they change and their diagnostics are
+/// published by the server, so pulled document reports are empty. This
+/// replaces the report of the workspace pull once a document is opened.
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_document_diagnostic(
+    _params: DocumentDiagnosticParams,
+) -> LspResult<DocumentDiagnosticReportResult> {
+    Ok(DocumentDiagnosticReportResult::Report(
+        DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport::default()),
+    ))
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_workspace_diagnostic(
+    params: WorkspaceDiagnosticParams,
+    state: WorldState,
+) -> LspResult<WorkspaceDiagnosticReportResult> {
+    let items = workspace_diagnostics(&state, &params.previous_result_ids, indexer::generation());
+    Ok(WorkspaceDiagnosticReportResult::Report(
+        WorkspaceDiagnosticReport { items },
+    ))
+}
+
 pub(crate) fn handle_virtual_document(
     params: VirtualDocumentParams,
     state: &WorldState,
@@ -396,6 +696,12 @@ pub(crate) fn handle_virtual_document(
 pub(crate) fn handle_input_boundaries(
     params: InputBoundariesParams,
 ) -> LspResult<InputBoundariesResponse> {
+    if is_standalone() {
+        return Err(LspError::Anyhow(anyhow!(
+            "Input boundaries require an R session"
+        )));
+    }
+
     let boundaries = r_task(|| input_boundaries(&params.text))?;
     Ok(InputBoundariesResponse { boundaries })
 }
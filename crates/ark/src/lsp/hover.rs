@@ -11,9 +11,13 @@ use stdext::unwrap::IntoResult;
 use tower_lsp::lsp_types::MarkupContent;
 use tower_lsp::lsp_types::MarkupKind;
 use tree_sitter::Node;
+use url::Url;
 
 use crate::lsp::document_context::DocumentContext;
 use crate::lsp::help::RHtmlHelp;
+use crate::lsp::roxygen::find_workspace_function;
+use crate::lsp::roxygen::render_rd;
+use crate::lsp::state::WorldState;
 use crate::lsp::traits::node::NodeExt;
 use crate::treesitter::NodeTypeExt;
 
@@ -100,3 +104,128 @@ pub(crate) fn r_hover(context: &DocumentContext) -> anyhow::Result<Option<Markup
         value: markdown,
     }))
 }
+
+/// Hover documentation for functions defined in the workspace, rendered from
+/// their signature and roxygen comments. On the argument name of a call, only
+/// the documentation of that parameter is shown.
+pub(crate) fn workspace_hover(
+    context: &DocumentContext,
+    uri: &Url,
+    state: &WorldState,
+) -> anyhow::Result<Option<MarkupContent>> {
+    let node = context.closest_node;
+    let contents = context.document.contents.as_str();
+
+    if !node.is_identifier() {
+        return Ok(None);
+    }
+
+    // Argument name of a call, e.g. `x` in `f(x = 1)`
+    if let Some(call) = argument_name_call(&node) {
+        let Some(callee) = call.child_by_field_name("function") else {
+            return Ok(None);
+        };
+        if !callee.is_identifier() {
+            return Ok(None);
+        }
+
+        let Some(function) = find_workspace_function(callee.node_as_str(contents)?, uri, state)?
+        else {
+            return Ok(None);
+        };
+
+        let name = node.node_as_str(contents)?;
+        let description = function
+            .roxygen
+            .as_ref()
+            .and_then(|roxygen| roxygen.param(name));
+        let Some(description) = description else {
+            return Ok(None);
+        };
+
+        return Ok(Some(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: format!("`{name}`: {}", render_rd(description)),
+        }));
+    }
+
+    // Function of a call
+    let Some(call) = node.parent() else {
+        return Ok(None);
+    };
+    if !call.is_call() || call.child_by_field_name("function") != Some(node) {
+        return Ok(None);
+    }
+
+    let Some(function) = find_workspace_function(node.node_as_str(contents)?, uri, state)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(MarkupContent {
+        kind: MarkupKind::Markdown,
+        value: function.markdown(),
+    }))
+}
+
+/// The call of which `node` is an argument name
+fn argument_name_call<'tree>(node: &Node<'tree>) -> Option<Node<'tree>> {
+    let argument = node.parent()?;
+    if !argument.is_argument() || argument.child_by_field_name("name") != Some(*node) {
+        return None;
+    }
+
+    let call = argument.parent()?.parent()?;
+    call.is_call().then_some(call)
+}
+
+#[cfg(test)]
+mod tests {
+    use tree_sitter::Point;
+
+    use super::*;
+    use crate::lsp::document::Document;
+    use crate::lsp::indexer;
+    use crate::lsp::indexer::ResetIndexerGuard;
+    use crate::lsp::util::test_path;
+
+    fn hover_test(code: &str, point: Point) -> Option<String> {
+        let uri = test_path("test.R");
+        let document = Document::new(code, None);
+        indexer::update(&document, &uri).unwrap();
+
+        let mut state = WorldState::default();
+        state.documents.insert(uri.clone(), document.clone());
+
+        let context = DocumentContext::new(&document, point, None);
+        workspace_hover(&context, &uri, &state)
+            .unwrap()
+            .map(|markup| markup.value)
+    }
+
+    #[test]
+    fn test_workspace_hover() {
+        let _guard = ResetIndexerGuard;
+
+        let code = "
+#' Scale a vector
+#' @param x A vector.
+#' @param factor The \\code{numeric} factor.
+scale_by <- function(x, factor = 2) x * factor
+
+scale_by(1, factor = 3)
+";
+
+        // On the function
+        let hover = hover_test(code, Point::new(6, 2)).unwrap();
+        assert!(hover.starts_with("``` r\nscale_by(x, factor = 2)\n```"));
+        assert!(hover.contains("**Scale a vector**"));
+        assert!(hover.contains("- `x`: A vector."));
+
+        // On an argument name
+        let hover = hover_test(code, Point::new(6, 14)).unwrap();
+        assert_eq!(hover, "`factor`: The `numeric` factor.");
+
+        // On an argument value
+        assert!(hover_test(code, Point::new(6, 9)).is_none());
+    }
+}
@@ -6,12 +6,18 @@
 //
 
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::PathBuf;
 use std::result::Result::Ok;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::sync::LazyLock;
 use std::sync::Mutex;
 
 use regex::Regex;
+use serde::Deserialize;
+use serde::Serialize;
 use stdext::unwrap;
 use stdext::unwrap::IntoResult;
 use tower_lsp::lsp_types::Range;
@@ -22,6 +28,9 @@ use walkdir::DirEntry;
 use walkdir::WalkDir;
 
 use crate::lsp;
+use crate::lsp::cache::file_modified;
+use crate::lsp::cache::CachedFile;
+use crate::lsp::cache::IndexCache;
 use crate::lsp::document::Document;
 use crate::lsp::traits::node::NodeExt;
 use crate::treesitter::BinaryOperatorType;
@@ -51,7 +60,7 @@ impl FileId {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum IndexEntryData {
     Variable {
         name: String,
@@ -70,7 +79,7 @@ pub enum IndexEntryData {
     },
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct IndexEntry {
     pub key: String,
     pub range: Range,
@@ -82,6 +91,15 @@ type DocumentSymbolIndex = HashMap<DocumentSymbol, IndexEntry>;
 type WorkspaceIndex = Arc<Mutex<HashMap<FileId, DocumentSymbolIndex>>>;
 
 static WORKSPACE_INDEX: LazyLock<WorkspaceIndex> = LazyLock::new(|| Default::default());
+
+/// Incremented whenever the index changes. Results derived from the index,
+/// such as workspace diagnostics, are outdated once the generation changes.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// On-disk cache of the index, loaded on startup when indexing the workspace
+/// folders
+static INDEX_CACHE: Mutex<Option<IndexCache>> = Mutex::new(None);
+
 pub static RE_COMMENT_SECTION: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^\s*(#+)\s*(.*?)\s*[#=-]{4,}\s*$").unwrap());
 
@@ -154,19 +172,25 @@ pub fn map(mut callback: impl FnMut(&Url, &String, &IndexEntry)) {
     }
 }
 
+/// The current generation of the index
+pub(crate) fn generation() -> u64 {
+    GENERATION.load(Ordering::Relaxed)
+}
+
 #[tracing::instrument(level = "trace", skip_all, fields(uri = %uri))]
 pub fn update(document: &Document, uri: &Url) -> anyhow::Result<()> {
     // Defensive, callers are expected to filter virtual doc URIs before queuing
     if !ExtUrl::is_indexable(uri) {
         return Ok(());
     }
-    delete(uri)?;
+    clear(uri);
     index_document(document, uri);
     Ok(())
 }
 
 fn insert(uri: &Url, entry: IndexEntry) -> anyhow::Result<()> {
     let mut index = WORKSPACE_INDEX.lock().unwrap();
+    GENERATION.fetch_add(1, Ordering::Relaxed);
     let file_id = FileId::from_uri(uri.clone());
 
     let file_index = index.entry(file_id).or_default();
@@ -193,20 +217,30 @@ fn index_insert(index: &mut HashMap<String, IndexEntry>, entry: IndexEntry) {
 
 #[tracing::instrument(level = "trace")]
 pub(crate) fn delete(uri: &Url) -> anyhow::Result<()> {
+    clear(uri);
+
+    if let Ok(path) = uri.to_file_path() {
+        with_cache(|cache| cache.remove(&path));
+    }
+
+    Ok(())
+}
+
+fn clear(uri: &Url) {
     let file_id = FileId::from_uri(uri.clone());
     let mut index = WORKSPACE_INDEX.lock().unwrap();
+    GENERATION.fetch_add(1, Ordering::Relaxed);
 
     // Only clears if the key exists
     index.entry(file_id).and_modify(|index| {
         index.clear();
     });
-
-    Ok(())
 }
 
 #[tracing::instrument(level = "trace")]
 pub(crate) fn rename(old_uri: &Url, new_uri: &Url) -> anyhow::Result<()> {
     let mut index = WORKSPACE_INDEX.lock().unwrap();
+    GENERATION.fetch_add(1, Ordering::Relaxed);
 
     let old_file_id = FileId::from_uri(old_uri.clone());
     let new_file_id = FileId::from_uri(new_uri.clone());
@@ -214,13 +248,62 @@ pub(crate) fn rename(old_uri: &Url, new_uri: &Url) -> anyhow::Result<()> {
     if let Some(entries) = index.remove(&old_file_id) {
         index.insert(new_file_id, entries);
     }
+    drop(index);
+
+    if let (Ok(old_path), Ok(new_path)) = (old_uri.to_file_path(), new_uri.to_file_path()) {
+        with_cache(|cache| cache.rename(&old_path, new_path));
+    }
 
     Ok(())
 }
 
+/// Load the on-disk cache of the index of the workspace `folders`. Files of
+/// the cache that are not among the workspace files `uris` are dropped.
+pub(crate) fn load_cache(folders: &[String], uris: &[Url]) {
+    let mut cache = IndexCache::load(folders);
+
+    let paths: HashSet<PathBuf> = uris
+        .iter()
+        .filter_map(|uri| uri.to_file_path().ok())
+        .collect();
+    cache.retain(|path| paths.contains(path));
+
+    *INDEX_CACHE.lock().unwrap() = Some(cache);
+}
+
+/// Index a workspace file from the on-disk cache if it wasn't modified since
+/// it was cached. Returns `false` if the file needs to be indexed.
+pub(crate) fn restore(uri: &Url) -> bool {
+    let Ok(path) = uri.to_file_path() else {
+        return false;
+    };
+    let Some(modified) = file_modified(&path) else {
+        return false;
+    };
+
+    let Some(entries) = with_cache(|cache| cache.get(&path, modified).cloned()).flatten() else {
+        return false;
+    };
+
+    index_entries(uri, entries);
+    true
+}
+
+/// Write the on-disk cache if files were indexed since it was last saved
+pub(crate) fn save_cache() {
+    if let Some(Err(err)) = with_cache(|cache| cache.save()) {
+        lsp::log_warn!("Can't save index cache: {err:?}");
+    }
+}
+
+fn with_cache<T>(f: impl FnOnce(&mut IndexCache) -> T) -> Option<T> {
+    INDEX_CACHE.lock().unwrap().as_mut().map(f)
+}
+
 #[cfg(test)]
 pub(crate) fn indexer_clear() {
     let mut index = WORKSPACE_INDEX.lock().unwrap();
+    GENERATION.fetch_add(1, Ordering::Relaxed);
     index.clear();
 }
 
@@ -277,16 +360,51 @@ pub(crate) fn create(uri: &Url) -> anyhow::Result<()> {
 
     // TODO: Handle document encodings here.
     // TODO: Check if there's an up-to-date buffer to be used.
-    let contents = std::fs::read(path)?;
+    let contents = std::fs::read(&path)?;
     let contents = String::from_utf8(contents)?;
-    let document = Document::new(contents.as_str(), None);
 
-    index_document(&document, uri);
+    let modified = file_modified(&path).unwrap_or_default();
+    let hash = blake3::hash(contents.as_bytes()).to_hex().to_string();
+
+    // Files that were touched without being changed are not parsed again
+    let cached = with_cache(|cache| cache.get_unchanged(&path, &hash, modified).cloned()).flatten();
+
+    let entries = match cached {
+        Some(entries) => entries,
+        None => {
+            let document = Document::new(contents.as_str(), None);
+            let entries = document_entries(&document);
+
+            let file = CachedFile {
+                modified,
+                hash,
+                entries: entries.clone(),
+            };
+            with_cache(|cache| cache.insert(path, file));
+
+            entries
+        },
+    };
+
+    index_entries(uri, entries);
 
     Ok(())
 }
 
 fn index_document(doc: &Document, uri: &Url) {
+    index_entries(uri, document_entries(doc));
+}
+
+fn index_entries(uri: &Url, entries: Vec<IndexEntry>) {
+    for entry in entries {
+        if let Err(err) = insert(uri, entry) {
+            lsp::log_error!("Can't insert index entry: {err:?}");
+        }
+    }
+}
+
+/// The index entries of a document, in document order
+pub(crate) fn document_entries(doc: &Document) -> Vec<IndexEntry> {
     let ast = &doc.ast;
     let root = ast.root_node();
     let mut cursor = root.walk();
@@ -298,11 +416,7 @@ fn index_document(doc: &Document, uri: &Url) {
         }
     }
 
-    for entry in entries {
-        if let Err(err) = insert(uri, entry) {
-            lsp::log_error!("Can't insert index entry: {err:?}");
-        }
-    }
+    entries
 }
 
 fn index_node(doc: &Document, node: &Node, entries: &mut Vec<IndexEntry>) -> anyhow::Result<()> {
@@ -354,17 +468,7 @@ fn index_assignment(
 
     if rhs.is_function_definition() {
         // If RHS is a function definition, emit a function symbol
-        let mut arguments = Vec::new();
-        if let Some(parameters) = rhs.child_by_field_name("parameters") {
-            let mut cursor = parameters.walk();
-            for child in parameters.children(&mut cursor) {
-                let name = unwrap!(child.child_by_field_name("name"), None => continue);
-                if name.is_identifier() {
-                    let name = name.node_to_string(&doc.contents)?;
-                    arguments.push(name);
-                }
-            }
-        }
+        let arguments = function_parameters(&rhs, &doc.contents)?;
 
         // Note that unlike document symbols whose ranges cover the whole entity
         // they represent, the range of workspace symbols only cover the identifers
@@ -393,6 +497,25 @@ fn index_assignment(
     Ok(())
 }
 
+/// Names of the parameters of a function definition, including `...`
+pub(crate) fn function_parameters(function: &Node, contents: &str) -> anyhow::Result<Vec<String>> {
+    let mut parameters = Vec::new();
+
+    let Some(node) = function.child_by_field_name("parameters") else {
+        return Ok(parameters);
+    };
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        let name = unwrap!(child.child_by_field_name("name"), None => continue);
+        if name.is_identifier() || name.node_type() == NodeType::Dots {
+            parameters.push(name.node_to_string(contents)?);
+        }
+    }
+
+    Ok(parameters)
+}
+
 fn index_r6_class_methods(
     doc: &Document,
     node: &Node,
@@ -0,0 +1,314 @@
+//
+// inlay_hint.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use harp::eval::RParseEvalOptions;
+use harp::utils::r_formals;
+use harp::utils::r_is_function;
+use tower_lsp::lsp_types::InlayHint;
+use tower_lsp::lsp_types::InlayHintKind;
+use tower_lsp::lsp_types::InlayHintLabel;
+use tree_sitter::Node;
+use tree_sitter::Range;
+
+use crate::console;
+use crate::lsp::arguments::match_arguments;
+use crate::lsp::arguments::pipe_placeholder;
+use crate::lsp::config::InlayHintsConfig;
+use crate::lsp::document::Document;
+use crate::lsp::indexer;
+use crate::lsp::standalone::is_standalone;
+use crate::lsp::traits::node::NodeExt;
+use crate::r_task;
+use crate::treesitter::NodeTypeExt;
+
+/// Formal parameter names of the functions called in a document, keyed by
+/// the text of the callee, e.g. `"paste"` or `"ggplot2::aes"`
+pub(crate) type Formals = HashMap<String, Vec<String>>;
+
+pub(crate) fn inlay_hints(
+    document: &Document,
+    range: Range,
+    config: &InlayHintsConfig,
+) -> anyhow::Result<Vec<InlayHint>> {
+    if !config.parameter_names && !config.pipe_placeholder {
+        return Ok(Vec::new());
+    }
+
+    let contents = document.contents.as_str();
+
+    let mut calls = Vec::new();
+    collect_calls(document.ast.root_node(), range, &mut calls);
+
+    let callees: HashSet<String> = calls
+        .iter()
+        .filter_map(|call| callee_text(call, contents))
+        .map(String::from)
+        .collect();
+
+    let formals = resolve_formals(callees);
+
+    call_hints(document, &calls, &formals, config)
+}
+
+/// Look up formals of callees, first in the session as it represents the
+/// current state of the world, then in the workspace index. Same lookup as for
+/// argument completions.
+fn resolve_formals(callees: HashSet<String>) -> Formals {
+    let mut formals: Formals = if is_standalone() {
+        Formals::new()
+    } else {
+        r_task(|| {
+            callees
+                .iter()
+                .filter_map(|callee| Some((callee.clone(), session_formals(callee)?)))
+                .collect()
+        })
+    };
+
+    for callee in callees {
+        if formals.contains_key(&callee) {
+            continue;
+        }
+        if let Some((_path, entry)) = indexer::find(&callee) {
+            if let indexer::IndexEntryData::Function { arguments, .. } = entry.data {
+                formals.insert(callee, arguments);
+            }
+        }
+    }
+
+    formals
+}
+
+fn session_formals(callee: &str) -> Option<Vec<String>> {
+    let object = harp::parse_eval(callee, RParseEvalOptions {
+        forbid_function_calls: true,
+        env: console::selected_env(),
+    })
+    .ok()?;
+
+    if !r_is_function(object.sexp) {
+        return None;
+    }
+
+    let formals = r_formals(object.sexp).ok()?;
+    Some(formals.into_iter().map(|formal| formal.name).collect())
+}
+
+fn collect_calls<'tree>(node: Node<'tree>, range: Range, calls: &mut Vec<Node<'tree>>) {
+    if node.end_position() < range.start_point || node.start_position() > range.end_point {
+        return;
+    }
+
+    if node.is_call() {
+        calls.push(node);
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_calls(child, range, calls);
+    }
+}
+
+/// Returns the text of the callee if it's a symbol or a namespaced symbol.
+/// Other callees such as `fns[[1]]()` are not looked up.
+fn callee_text<'a>(call: &Node, contents: &'a str) -> Option<&'a str> {
+    let callee = call.child_by_field_name("function")?;
+
+    if !callee.is_identifier() && !callee.is_namespace_operator() {
+        return None;
+    }
+
+    callee.node_as_str(contents).ok()
+}
+
+pub(crate) fn call_hints(
+    document: &Document,
+    calls: &[Node],
+    formals: &Formals,
+    config: &InlayHintsConfig,
+) -> anyhow::Result<Vec<InlayHint>> {
+    let contents = document.contents.as_str();
+    let mut hints = Vec::new();
+
+    for call in calls {
+        let Some(callee) = callee_text(call, contents) else {
+            continue;
+        };
+        let Some(formals) = formals.get(callee) else {
+            continue;
+        };
+        let Some(arguments) = call.child_by_field_name("arguments") else {
+            continue;
+        };
+
+        let placeholder = pipe_placeholder(call, contents);
+        let matches = match_arguments(call, formals, placeholder.is_some(), contents)?;
+
+        if config.pipe_placeholder {
+            if let (Some(placeholder), Some(formal)) = (placeholder, &matches.implicit) {
+                let Some(open) = arguments.child_by_field_name("open") else {
+                    continue;
+                };
+                let has_arguments = call.arguments().next().is_some();
+                let label = if has_arguments {
+                    format!("{formal} = {placeholder},")
+                } else {
+                    format!("{formal} = {placeholder}")
+                };
+
+                hints.push(InlayHint {
+                    position: document.lsp_position_from_tree_sitter_point(open.end_position())?,
+                    label: InlayHintLabel::String(label),
+                    kind: Some(InlayHintKind::PARAMETER),
+                    text_edits: None,
+                    tooltip: None,
+                    padding_left: None,
+                    padding_right: Some(has_arguments),
+                    data: None,
+                });
+            }
+        }
+
+        if config.parameter_names {
+            for (value, formal) in matches.positional {
+                // Redundant with the argument itself
+                if value.node_as_str(contents)? == formal {
+                    continue;
+                }
+
+                hints.push(InlayHint {
+                    position: document.lsp_position_from_tree_sitter_point(value.start_position())?,
+                    label: InlayHintLabel::String(format!("{formal} =")),
+                    kind: Some(InlayHintKind::PARAMETER),
+                    text_edits: None,
+                    tooltip: None,
+                    padding_left: None,
+                    padding_right: Some(true),
+                    data: None,
+                });
+            }
+        }
+    }
+
+    Ok(hints)
+}
+
+#[cfg(test)]
+mod tests {
+    use tower_lsp::lsp_types::Position;
+
+    use super::*;
+
+    fn formals(entries: &[(&str, &[&str])]) -> Formals {
+        entries
+            .iter()
+            .map(|(callee, formals)| {
+                let formals = formals.iter().map(|formal| formal.to_string()).collect();
+                (callee.to_string(), formals)
+            })
+            .collect()
+    }
+
+    fn hints(code: &str, formals: &Formals, config: &InlayHintsConfig) -> Vec<(Position, String)> {
+        let document = Document::new(code, None);
+        let root = document.ast.root_node();
+
+        let mut calls = Vec::new();
+        collect_calls(root, root.range(), &mut calls);
+
+        call_hints(&document, &calls, formals, config)
+            .unwrap()
+            .into_iter()
+            .map(|hint| {
+                let InlayHintLabel::String(label) = hint.label else {
+                    panic!("Unexpected label");
+                };
+                (hint.position, label)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_inlay_hints_positional_arguments() {
+        let formals = formals(&[("f", &["x", "y", "z"])]);
+        let config = InlayHintsConfig::default();
+
+        assert_eq!(hints("f(1, 2)", &formals, &config), vec![
+            (Position::new(0, 2), String::from("x =")),
+            (Position::new(0, 5), String::from("y =")),
+        ]);
+
+        // Named arguments are matched first, exactly or partially
+        assert_eq!(hints("f(1, x = 2)", &formals, &config), vec![(
+            Position::new(0, 2),
+            String::from("y =")
+        )]);
+        assert_eq!(hints("f(y = 1, 2)", &formals, &config), vec![(
+            Position::new(0, 9),
+            String::from("x =")
+        )]);
+
+        // Arguments named like the formal are not hinted
+        assert_eq!(hints("f(x, 2)", &formals, &config), vec![(
+            Position::new(0, 5),
+            String::from("y =")
+        )]);
+
+        // Unknown functions are not hinted
+        assert_eq!(hints("g(1)", &formals, &config), vec![]);
+    }
+
+    #[test]
+    fn test_inlay_hints_dots() {
+        let formals = formals(&[
+            ("paste", &["...", "sep", "collapse"]),
+            ("f", &["x", "...", "na.rm"]),
+        ]);
+        let config = InlayHintsConfig::default();
+
+        assert_eq!(hints("paste(a, b)", &formals, &config), vec![]);
+        assert_eq!(hints("f(a, b, n = 1)", &formals, &config), vec![(
+            Position::new(0, 2),
+            String::from("x =")
+        )]);
+    }
+
+    #[test]
+    fn test_inlay_hints_pipe_placeholder() {
+        let formals = formals(&[("filter", &[".data", "...", ".by"]), ("head", &["x", "n"])]);
+        let config = InlayHintsConfig {
+            parameter_names: true,
+            pipe_placeholder: true,
+        };
+
+        assert_eq!(hints("df |> head(5)", &formals, &config), vec![
+            (Position::new(0, 11), String::from("x = _,")),
+            (Position::new(0, 11), String::from("n =")),
+        ]);
+        assert_eq!(hints("df %>% head()", &formals, &config), vec![(
+            Position::new(0, 12),
+            String::from("x = .")
+        )]);
+
+        // Explicit placeholder
+        assert_eq!(hints("df %>% head(., 5)", &formals, &config), vec![
+            (Position::new(0, 12), String::from("x =")),
+            (Position::new(0, 15), String::from("n =")),
+        ]);
+
+        // Without the option, the implicit argument still shifts positions
+        let config = InlayHintsConfig::default();
+        assert_eq!(hints("df |> head(5)", &formals, &config), vec![(
+            Position::new(0, 11),
+            String::from("n =")
+        )]);
+        assert_eq!(hints("df |> filter(x > 1)", &formals, &config), vec![]);
+    }
+}
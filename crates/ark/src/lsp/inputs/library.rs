@@ -7,10 +7,13 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::Mutex;
 use std::sync::RwLock;
 
 use super::package::Package;
+use super::package_namespace::Namespace;
 use crate::lsp;
+use crate::lsp::cache::LibraryCache;
 
 /// Lazily manages a list of known R packages by name
 #[derive(Default, Clone, Debug)]
@@ -19,6 +22,23 @@ pub struct Library {
     pub library_paths: Arc<Vec<PathBuf>>,
 
     packages: Arc<RwLock<HashMap<String, Option<Arc<Package>>>>>,
+
+    /// On-disk cache of the metadata of installed packages, shared across
+    /// sessions
+    cache: Option<Arc<Mutex<LibraryCache>>>,
+
+    /// Index of the packages exporting each symbol, built in the background
+    exports: Arc<RwLock<ExportsIndex>>,
+}
+
+/// Installed packages exporting each symbol
+#[derive(Default, Debug)]
+struct ExportsIndex {
+    /// The installed packages the index is built from
+    packages: Vec<String>,
+    /// The installed packages the index is being built from
+    building: Option<Vec<String>>,
+    symbols: HashMap<String, Vec<String>>,
 }
 
 impl Library {
@@ -26,6 +46,25 @@ impl Library {
         Self {
             packages: Arc::new(RwLock::new(HashMap::new())),
             library_paths: Arc::new(library_paths),
+            cache: None,
+            exports: Arc::new(RwLock::new(ExportsIndex::default())),
+        }
+    }
+
+    /// Use an on-disk cache for the metadata of packages
+    pub fn with_cache(mut self, cache: LibraryCache) -> Self {
+        self.cache = Some(Arc::new(Mutex::new(cache)));
+        self
+    }
+
+    /// Write the packages loaded since the last save to the on-disk cache
+    pub fn save_cache(&self) {
+        let Some(cache) = &self.cache else {
+            return;
+        };
+
+        if let Err(err) = cache.lock().unwrap().save() {
+            lsp::log_warn!("Can't save library cache: {err:?}");
         }
     }
 
@@ -56,6 +95,107 @@ impl Library {
         pkg
     }
 
+    /// The installed packages exporting `symbol`, in the order of the
+    /// installed packages. Empty until the exports index is built.
+    pub fn exporting_packages(&self, symbol: &str) -> Vec<String> {
+        self.exports
+            .read()
+            .unwrap()
+            .symbols
+            .get(symbol)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Build the exports index of the installed `packages` in the background,
+    /// unless it was already built or is being built for the same packages.
+    /// Reading the NAMESPACE file of every installed package is too slow to
+    /// be done on request.
+    pub fn update_exports_index(&self, packages: Vec<String>) {
+        {
+            let mut exports = self.exports.write().unwrap();
+            if exports.packages == packages || exports.building.as_ref() == Some(&packages) {
+                return;
+            }
+            exports.building = Some(packages.clone());
+        }
+
+        let library = self.clone();
+        std::thread::spawn(move || {
+            let symbols = library.collect_exports(&packages);
+
+            // Discard the index if the installed packages changed while it was
+            // being built
+            let mut exports = library.exports.write().unwrap();
+            if exports.building.as_ref() == Some(&packages) {
+                exports.building = None;
+                exports.packages = packages;
+                exports.symbols = symbols;
+            }
+        });
+    }
+
+    /// Build the exports index synchronously for testing purposes.
+    #[cfg(test)]
+    pub fn with_exports_index(self, packages: Vec<String>) -> Self {
+        let symbols = self.collect_exports(&packages);
+        *self.exports.write().unwrap() = ExportsIndex {
+            packages,
+            building: None,
+            symbols,
+        };
+        self
+    }
+
+    fn collect_exports(&self, packages: &[String]) -> HashMap<String, Vec<String>> {
+        let mut symbols: HashMap<String, Vec<String>> = HashMap::new();
+
+        for name in packages {
+            for symbol in self.package_exports(name) {
+                symbols.entry(symbol).or_default().push(name.clone());
+            }
+        }
+
+        symbols
+    }
+
+    /// The objects exported in the NAMESPACE file of a package. Packages that
+    /// are loaded already are not read again.
+    fn package_exports(&self, name: &str) -> Vec<String> {
+        if let Some(entry) = self.packages.read().unwrap().get(name) {
+            return entry
+                .as_ref()
+                .map(|package| package.namespace.exports.clone())
+                .unwrap_or_default();
+        }
+
+        let Some(package_path) = self
+            .library_paths
+            .iter()
+            .map(|lib_path| lib_path.join(name))
+            .find(|package_path| package_path.join("DESCRIPTION").is_file())
+        else {
+            return Vec::new();
+        };
+
+        let namespace_path = package_path.join("NAMESPACE");
+        if !namespace_path.is_file() {
+            return Vec::new();
+        }
+
+        let namespace = std::fs::read_to_string(&namespace_path)
+            .map_err(anyhow::Error::from)
+            .and_then(|contents| Namespace::parse(&contents));
+
+        match namespace {
+            Ok(namespace) => namespace.exports,
+            Err(err) => {
+                lsp::log_warn!("Can't read NAMESPACE file of package `{name}`: {err:?}");
+                Vec::new()
+            },
+        }
+    }
+
     /// Insert a package in the library for testing purposes.
     #[cfg(test)]
     pub fn insert(self, name: &str, package: Package) -> Self {
@@ -68,7 +208,7 @@ impl Library {
 
     fn load_package(&self, name: &str) -> anyhow::Result<Option<Package>> {
         for lib_path in self.library_paths.iter() {
-            match Package::load_from_library(&lib_path, name)? {
+            match Package::load_from_library(&lib_path, name, self.cache.as_deref())? {
                 Some(pkg) => return Ok(Some(pkg)),
                 None => (),
             }
@@ -144,4 +284,26 @@ importFrom(pkg, baz)
         assert_eq!(pkg.namespace.exports, vec!["bar", "foo"]);
         assert_eq!(pkg.namespace.imports, vec!["baz"]);
     }
+
+    #[test]
+    fn test_exports_index() {
+        let description = r#"
+Package: mypkg
+Version: 1.0
+        "#;
+        let (temp_dir, _pkg_dir) = create_temp_package("mypkg", description, "export(foo)\n");
+
+        let lib = Library::new(vec![temp_dir.path().to_path_buf()]);
+        assert!(lib.exporting_packages("foo").is_empty());
+
+        let lib = lib.with_exports_index(vec![String::from("mypkg"), String::from("notapkg")]);
+        assert_eq!(lib.exporting_packages("foo"), vec!["mypkg"]);
+        assert!(lib.exporting_packages("bar").is_empty());
+
+        // The index is built from the NAMESPACE files without loading packages
+        assert!(lib.packages.read().unwrap().is_empty());
+
+        // Clones share the index
+        assert_eq!(lib.clone().exporting_packages("foo"), vec!["mypkg"]);
+    }
 }
@@ -8,6 +8,8 @@
 pub mod library;
 pub mod package;
 pub mod package_description;
+pub mod package_help;
 pub mod package_index;
 pub mod package_namespace;
+pub mod rds;
 pub mod source_root;
@@ -5,12 +5,25 @@
 //
 //
 
+use std::collections::HashMap;
 use std::fs;
+use std::path::Path;
 use std::path::PathBuf;
-
+use std::sync::Mutex;
+use std::sync::OnceLock;
+
+use crate::lsp::cache::file_modified;
+use crate::lsp::cache::CachedPackage;
+use crate::lsp::cache::LibraryCache;
+use crate::lsp::document::Document;
+use crate::lsp::indexer::function_parameters;
 use crate::lsp::inputs::package_description::Description;
+use crate::lsp::inputs::package_help::help_function_formals;
 use crate::lsp::inputs::package_index::Index;
 use crate::lsp::inputs::package_namespace::Namespace;
+use crate::treesitter::BinaryOperatorType;
+use crate::treesitter::NodeType;
+use crate::treesitter::NodeTypeExt;
 
 /// Represents an R package and its metadata relevant for static analysis.
 #[derive(Clone, Debug)]
@@ -27,6 +40,10 @@ pub struct Package {
     // support exported datasets and prevent spurious diagnostics (we accept
     // false negatives to avoid annoying false positives).
     pub exported_symbols: Vec<String>,
+
+    // Formal arguments of the functions defined in the package, lazily
+    // computed from the R sources or the help database.
+    function_formals: OnceLock<HashMap<String, Vec<String>>>,
 }
 
 impl Package {
@@ -51,9 +68,34 @@ impl Package {
             description,
             namespace,
             exported_symbols,
+            function_formals: OnceLock::new(),
         }
     }
 
+    /// Get the formal arguments of a function defined in the package.
+    ///
+    /// Package sources are read from their `R/` folder. Installed packages
+    /// store their R code in a lazy-load database that can't be read
+    /// statically, so their formals are read from the usage sections of
+    /// their help database instead. Undocumented functions of installed
+    /// packages return `None`.
+    pub fn function_formals(&self, name: &str) -> Option<&Vec<String>> {
+        self.function_formals
+            .get_or_init(|| {
+                if self.is_installed() {
+                    load_installed_function_formals(&self.path, &self.description.name)
+                } else {
+                    load_function_formals(&self.path)
+                }
+            })
+            .get(name)
+    }
+
+    /// Whether this is an installed package rather than a package source
+    fn is_installed(&self) -> bool {
+        self.path.join("Meta").join("package.rds").is_file()
+    }
+
     #[cfg(test)]
     pub fn from_parts(path: PathBuf, description: Description, namespace: Namespace) -> Self {
         Self::new(path, description, namespace, Index::default())
@@ -61,6 +103,23 @@ impl Package {
 
     /// Load a package from a given path.
     pub fn load_from_folder(package_path: &std::path::Path) -> anyhow::Result<Option<Self>> {
+        let Some(description) = Self::load_description(package_path)? else {
+            return Ok(None);
+        };
+
+        let (namespace, index) = Self::load_metadata(package_path, &description)?;
+
+        Ok(Some(Self::new(
+            package_path.to_path_buf(),
+            description,
+            namespace,
+            index,
+        )))
+    }
+
+    /// Load the DESCRIPTION file of a package. Returns `None` if the folder
+    /// doesn't contain one.
+    pub fn load_description(package_path: &Path) -> anyhow::Result<Option<Description>> {
         let description_path = package_path.join("DESCRIPTION");
 
         // Only consider directories that contain a description file
@@ -71,8 +130,14 @@ impl Package {
         // This fails if there is no `Package` field, so we're never loading
         // folders like bookdown projects as package
         let description_contents = fs::read_to_string(&description_path)?;
-        let description = Description::parse(&description_contents)?;
+        Ok(Some(Description::parse(&description_contents)?))
+    }
 
+    /// Load the NAMESPACE and INDEX files of a package
+    pub fn load_metadata(
+        package_path: &Path,
+        description: &Description,
+    ) -> anyhow::Result<(Namespace, Index)> {
         let namespace_path = package_path.join("NAMESPACE");
         let namespace = if namespace_path.is_file() {
             let namespace_contents = fs::read_to_string(&namespace_path)?;
@@ -96,35 +161,136 @@ impl Package {
             },
         };
 
-        Ok(Some(Self::new(
-            package_path.to_path_buf(),
-            description,
-            namespace,
-            index,
-        )))
+        Ok((namespace, index))
     }
 
-    /// Load a package from the given library path and name.
+    /// Load a package from the given library path and name. The NAMESPACE
+    /// and INDEX files are taken from `cache` when it has an entry for the
+    /// installed version of the package.
     pub fn load_from_library(
         lib_path: &std::path::Path,
         name: &str,
+        cache: Option<&Mutex<LibraryCache>>,
     ) -> anyhow::Result<Option<Self>> {
         let package_path = lib_path.join(name);
 
+        let Some(description) = Self::load_description(&package_path)? else {
+            return Ok(None);
+        };
+
         // For library packages, ensure the invariant that the package name
         // matches the folder name
-        if let Some(pkg) = Self::load_from_folder(&package_path)? {
-            if pkg.description.name != name {
-                return Err(anyhow::anyhow!(
-                    "`Package` field in `DESCRIPTION` doesn't match folder name '{name}'"
-                ));
+        if description.name != name {
+            return Err(anyhow::anyhow!(
+                "`Package` field in `DESCRIPTION` doesn't match folder name '{name}'"
+            ));
+        }
+
+        let modified = installation_modified(&package_path);
+
+        let cached = cache.and_then(|cache| {
+            let cache = cache.lock().unwrap();
+            cache
+                .get(&package_path, &description.version, modified)
+                .cloned()
+        });
+
+        let (namespace, index) = match cached {
+            Some(cached) => (cached.namespace, cached.index),
+            None => {
+                let (namespace, index) = Self::load_metadata(&package_path, &description)?;
+
+                if let Some(cache) = cache {
+                    cache.lock().unwrap().insert(package_path.clone(), CachedPackage {
+                        version: description.version.clone(),
+                        modified,
+                        namespace: namespace.clone(),
+                        index: index.clone(),
+                    });
+                }
+
+                (namespace, index)
+            },
+        };
+
+        Ok(Some(Self::new(package_path, description, namespace, index)))
+    }
+}
+
+/// Latest modification time of the DESCRIPTION and NAMESPACE files of a
+/// package. These are rewritten when the package is reinstalled.
+fn installation_modified(package_path: &Path) -> u128 {
+    ["DESCRIPTION", "NAMESPACE"]
+        .iter()
+        .filter_map(|file| file_modified(&package_path.join(file)))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Collect the formals of the documented functions of an installed package
+fn load_installed_function_formals(
+    package_path: &Path,
+    package: &str,
+) -> HashMap<String, Vec<String>> {
+    help_function_formals(package_path, package).unwrap_or_else(|err| {
+        tracing::warn!("Can't read the help database of package `{package}`: {err:?}");
+        HashMap::new()
+    })
+}
+
+/// Collect the formals of top-level functions defined in the `R/` folder
+fn load_function_formals(package_path: &Path) -> HashMap<String, Vec<String>> {
+    let mut formals = HashMap::new();
+
+    let Ok(entries) = fs::read_dir(package_path.join("R")) else {
+        return formals;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        let is_r_file = path.extension().is_some_and(|ext| ext == "R" || ext == "r");
+        if !is_r_file {
+            continue;
+        }
+
+        let Ok(contents) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let document = Document::new(&contents, None);
+
+        let root = document.ast.root_node();
+        let mut cursor = root.walk();
+
+        for node in root.children(&mut cursor) {
+            if !matches!(
+                node.node_type(),
+                NodeType::BinaryOperator(BinaryOperatorType::LeftAssignment) |
+                    NodeType::BinaryOperator(BinaryOperatorType::EqualsAssignment)
+            ) {
+                continue;
             }
 
-            Ok(Some(pkg))
-        } else {
-            Ok(None)
+            let (Some(lhs), Some(rhs)) = (
+                node.child_by_field_name("lhs"),
+                node.child_by_field_name("rhs"),
+            ) else {
+                continue;
+            };
+            if !lhs.is_identifier_or_string() || !rhs.is_function_definition() {
+                continue;
+            }
+
+            let Ok(name) = lhs.get_identifier_or_string_text(&contents) else {
+                continue;
+            };
+            if let Ok(parameters) = function_parameters(&rhs, &contents) {
+                formals.insert(name.to_string(), parameters);
+            }
         }
     }
+
+    formals
 }
 
 #[cfg(test)]
@@ -180,6 +346,26 @@ mod tests {
         ]);
         assert_eq!(pkg.description.name, "penguins");
     }
+
+    #[test]
+    fn function_formals_are_read_from_sources() {
+        let dir = temp_palmerpenguin();
+        let r_dir = dir.path().join("R");
+        std::fs::create_dir(&r_dir).unwrap();
+        std::fs::write(
+            r_dir.join("utils.R"),
+            "path_to_file <- function(path = NULL, ...) NULL\nhelper = function() NULL\n",
+        )
+        .unwrap();
+
+        let pkg = Package::load_from_folder(dir.path()).unwrap().unwrap();
+        assert_eq!(pkg.function_formals("path_to_file").unwrap(), &vec![
+            "path".to_string(),
+            "...".to_string()
+        ]);
+        assert_eq!(pkg.function_formals("helper").unwrap(), &Vec::<String>::new());
+        assert!(pkg.function_formals("penguins").is_none());
+    }
 }
 
 #[cfg(test)]
@@ -96,6 +96,20 @@ impl Description {
     }
 }
 
+impl Description {
+    /// Packages declared in the `Depends`, `Imports`, `Suggests` and
+    /// `Enhances` fields, i.e. the packages that the code of the package may
+    /// refer to
+    pub fn dependencies(&self) -> Vec<String> {
+        ["Depends", "Imports", "Suggests", "Enhances"]
+            .into_iter()
+            .filter_map(|field| self.fields.get(field))
+            .flat_map(parse_comma_separated)
+            .filter(|pkg| pkg != "R")
+            .collect()
+    }
+}
+
 /// Parse a DCF (Debian Control File) format string into a key-value map.
 /// https://www.debian.org/doc/debian-policy/ch-controlfields.html
 fn parse_dcf(input: &str) -> std::collections::HashMap<String, String> {
@@ -199,6 +213,21 @@ Description: This is a long description
         assert_eq!(parsed.version, "1.0.0");
     }
 
+    #[test]
+    fn parses_description_dependencies() {
+        let desc = r#"Package: mypackage
+Version: 1.0.0
+Depends: R (>= 3.5.0), utils
+Imports:
+    dplyr (>= 1.0.0),
+    rlang
+Suggests: testthat"#;
+        let parsed = Description::parse(desc).unwrap();
+        assert_eq!(parsed.dependencies(), vec![
+            "utils", "dplyr", "rlang", "testthat"
+        ]);
+    }
+
     #[test]
     fn parses_dcf_basic() {
         let dcf = r#"Package: mypackage
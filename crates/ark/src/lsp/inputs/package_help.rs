@@ -0,0 +1,407 @@
+//
+// package_help.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::anyhow;
+use anyhow::bail;
+use flate2::read::GzDecoder;
+use flate2::read::ZlibDecoder;
+
+use crate::lsp::document::Document;
+use crate::lsp::inputs::rds::unserialize;
+use crate::lsp::inputs::rds::Data;
+use crate::lsp::inputs::rds::Value;
+use crate::lsp::traits::node::NodeExt;
+use crate::treesitter::NodeType;
+use crate::treesitter::NodeTypeExt;
+
+/// Collect the formals of the functions of an installed package from the
+/// `\usage` sections of its help database, i.e. the `help/<package>.rdb`
+/// lazy-load database of Rd objects. Installed packages store their R code in
+/// a lazy-load database too, but reading it statically would require
+/// evaluating closures. Only documented functions are covered.
+pub fn help_function_formals(
+    package_path: &Path,
+    package: &str,
+) -> anyhow::Result<HashMap<String, Vec<String>>> {
+    let help_path = package_path.join("help");
+
+    let index = fs::read(help_path.join(format!("{package}.rdx")))?;
+    let index = unserialize(&decompress_rds(index)?)?;
+
+    let Some(variables) = index.get("variables") else {
+        bail!("Help index of `{package}` doesn't have variables");
+    };
+    let compression = match index.get("compressed").map(|value| &value.data) {
+        Some(Data::Integer(values)) => values.first().copied().unwrap_or(1),
+        Some(Data::Double(values)) => values.first().copied().unwrap_or(1.0) as i32,
+        _ => 1,
+    };
+
+    let database = fs::read(help_path.join(format!("{package}.rdb")))?;
+
+    let mut formals = HashMap::new();
+
+    for entry in variables.as_list() {
+        let (offset, length) = match &entry.data {
+            Data::Integer(position) if position.len() == 2 => {
+                (position[0] as usize, position[1] as usize)
+            },
+            Data::Double(position) if position.len() == 2 => {
+                (position[0] as usize, position[1] as usize)
+            },
+            _ => continue,
+        };
+        let Some(bytes) = database.get(offset..offset + length) else {
+            continue;
+        };
+
+        let rd = unserialize(&decompress_entry(bytes, compression)?)?;
+
+        for (name, arguments) in usage_formals(&rd) {
+            formals.entry(name).or_insert(arguments);
+        }
+    }
+
+    Ok(formals)
+}
+
+/// `saveRDS()` compresses files with gzip by default
+fn decompress_rds(bytes: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+    if !bytes.starts_with(&[0x1f, 0x8b]) {
+        return Ok(bytes);
+    }
+
+    let mut out = Vec::new();
+    GzDecoder::new(bytes.as_slice()).read_to_end(&mut out)?;
+    Ok(out)
+}
+
+/// Entries of lazy-load databases start with their decompressed length. With
+/// the newer compression schemes, this is followed by a byte indicating the
+/// compression type. Only zlib and uncompressed entries are supported.
+fn decompress_entry(bytes: &[u8], compression: i32) -> anyhow::Result<Vec<u8>> {
+    let Some(length) = bytes.get(0..4) else {
+        bail!("Truncated lazy-load entry");
+    };
+    let length = u32::from_be_bytes(length.try_into()?) as usize;
+
+    let (kind, data) = match compression {
+        1 => (b'1', &bytes[4..]),
+        _ => {
+            let Some(kind) = bytes.get(4) else {
+                bail!("Truncated lazy-load entry");
+            };
+            (*kind, &bytes[5..])
+        },
+    };
+
+    match kind {
+        b'0' => Ok(data.to_vec()),
+        b'1' => {
+            let mut out = Vec::with_capacity(length);
+            ZlibDecoder::new(data).read_to_end(&mut out)?;
+            Ok(out)
+        },
+        kind => Err(anyhow!(
+            "Unsupported lazy-load compression type `{kind}`",
+            kind = kind as char
+        )),
+    }
+}
+
+/// Formals of the functions called in the `\usage` section of an Rd object.
+/// Only functions that are aliases of the topic are included, so that calls
+/// to other functions in examples of usage, e.g. `data(penguins)`, are not
+/// taken as their definition.
+fn usage_formals(rd: &Value) -> Vec<(String, Vec<String>)> {
+    let mut aliases = Vec::new();
+    let mut usage = String::new();
+
+    for section in rd.as_list() {
+        match rd_tag(section) {
+            Some("\\alias") => aliases.push(rd_text(section).trim().to_string()),
+            Some("\\usage") => usage.push_str(&rd_text(section)),
+            _ => {},
+        }
+    }
+
+    let document = Document::new(&usage, None);
+    let contents = document.contents.as_str();
+    let root = document.ast.root_node();
+
+    let mut formals = Vec::new();
+
+    let mut cursor = root.walk();
+    for node in root.children(&mut cursor) {
+        if !node.is_call() {
+            continue;
+        }
+        let Some(callee) = node.child_by_field_name("function") else {
+            continue;
+        };
+        if !callee.is_identifier_or_string() {
+            continue;
+        }
+        let Ok(name) = callee.get_identifier_or_string_text(contents) else {
+            continue;
+        };
+        if !aliases.iter().any(|alias| alias == name) {
+            continue;
+        }
+
+        formals.push((name.to_string(), call_formals(&node, contents)));
+    }
+
+    formals
+}
+
+/// Usage calls list the formals of the function, either as `name` or as
+/// `name = default`
+fn call_formals(call: &tree_sitter::Node, contents: &str) -> Vec<String> {
+    let mut formals = Vec::new();
+
+    let Some(arguments) = call.child_by_field_name("arguments") else {
+        return formals;
+    };
+
+    let mut cursor = arguments.walk();
+    for argument in arguments.children_by_field_name("argument", &mut cursor) {
+        let name = argument
+            .child_by_field_name("name")
+            .or_else(|| argument.child_by_field_name("value"));
+        let Some(name) = name else {
+            continue;
+        };
+        if !name.is_identifier() && name.node_type() != NodeType::Dots {
+            continue;
+        }
+        if let Ok(name) = name.node_to_string(contents) {
+            formals.push(name);
+        }
+    }
+
+    formals
+}
+
+fn rd_tag(value: &Value) -> Option<&str> {
+    value.attribute("Rd_tag")?.as_str()
+}
+
+/// Deparse an Rd element to text, keeping only what is shown for R code
+fn rd_text(value: &Value) -> String {
+    match rd_tag(value) {
+        // Rd comments
+        Some("COMMENT") => return String::new(),
+        // Dots are written as macros in usage sections
+        Some("\\dots" | "\\ldots") => return String::from("..."),
+        // `\method{generic}{class}(x)` is shown as `generic(x)`
+        Some("\\method" | "\\S3method" | "\\S4method") => {
+            return value.as_list().first().map(rd_text).unwrap_or_default();
+        },
+        // The first element of conditionals is the platform
+        Some("#ifdef" | "#ifndef") => {
+            return value.as_list().iter().skip(1).map(rd_text).collect();
+        },
+        _ => {},
+    }
+
+    match &value.data {
+        Data::Character(strings) => strings.iter().flatten().map(String::as_str).collect(),
+        Data::List(values) => values.iter().map(rd_text).collect(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+
+    use super::*;
+
+    fn text(string: &str, tag: &str) -> Value {
+        Value {
+            data: Data::Character(vec![Some(string.to_string())]),
+            attributes: vec![(String::from("Rd_tag"), text_untagged(tag))],
+        }
+    }
+
+    fn text_untagged(string: &str) -> Value {
+        Value {
+            data: Data::Character(vec![Some(string.to_string())]),
+            attributes: vec![],
+        }
+    }
+
+    fn section(tag: &str, values: Vec<Value>) -> Value {
+        Value {
+            data: Data::List(values),
+            attributes: vec![(String::from("Rd_tag"), text_untagged(tag))],
+        }
+    }
+
+    // Serialize with the subset of the XDR format read by `unserialize()`
+    fn serialize(value: &Value) -> Vec<u8> {
+        let mut out = b"X\n".to_vec();
+        for int in [2, 0x00040300, 0x00020300] {
+            out.extend(i32::to_be_bytes(int));
+        }
+        write_value(&mut out, value);
+        out
+    }
+
+    fn write_value(out: &mut Vec<u8>, value: &Value) {
+        let attributes = if value.attributes.is_empty() {
+            0
+        } else {
+            1 << 9
+        };
+
+        match &value.data {
+            Data::Character(strings) => {
+                out.extend(i32::to_be_bytes(16 | attributes));
+                out.extend(i32::to_be_bytes(strings.len() as i32));
+                for string in strings.iter().flatten() {
+                    out.extend(i32::to_be_bytes(9));
+                    out.extend(i32::to_be_bytes(string.len() as i32));
+                    out.extend(string.as_bytes());
+                }
+            },
+            Data::Integer(ints) => {
+                out.extend(i32::to_be_bytes(13 | attributes));
+                out.extend(i32::to_be_bytes(ints.len() as i32));
+                for int in ints {
+                    out.extend(i32::to_be_bytes(*int));
+                }
+            },
+            Data::List(values) => {
+                out.extend(i32::to_be_bytes(19 | attributes));
+                out.extend(i32::to_be_bytes(values.len() as i32));
+                for value in values {
+                    write_value(out, value);
+                }
+            },
+            _ => unreachable!(),
+        }
+
+        if !value.attributes.is_empty() {
+            for (name, value) in value.attributes.iter() {
+                out.extend(i32::to_be_bytes(2 | 1 << 10));
+                out.extend(i32::to_be_bytes(1));
+                out.extend(i32::to_be_bytes(9));
+                out.extend(i32::to_be_bytes(name.len() as i32));
+                out.extend(name.as_bytes());
+                write_value(out, value);
+            }
+            out.extend(i32::to_be_bytes(254));
+        }
+    }
+
+    fn rd_sd() -> Value {
+        section("Rd", vec![
+            section("\\alias", vec![text("sd", "VERB")]),
+            section("\\usage", vec![
+                text("\n", "RCODE"),
+                text("sd(x, na.rm = FALSE, ", "RCODE"),
+                section("\\dots", vec![]),
+                text(")\n", "RCODE"),
+                text("data(penguins)\n", "RCODE"),
+            ]),
+        ])
+    }
+
+    #[test]
+    fn test_usage_formals() {
+        let rd = section("Rd", vec![
+            section("\\alias", vec![text("print.foo", "VERB")]),
+            section("\\alias", vec![text("foo", "VERB")]),
+            section("\\usage", vec![
+                text("foo(x, ", "RCODE"),
+                text("% A comment", "COMMENT"),
+                text("y = 1)\n", "RCODE"),
+                section("\\method", vec![
+                    section("", vec![text("print", "TEXT")]),
+                    section("", vec![text("foo", "TEXT")]),
+                ]),
+                text("(x, ", "RCODE"),
+                section("\\dots", vec![]),
+                text(")\n", "RCODE"),
+            ]),
+        ]);
+
+        // The `print()` generic is not an alias of the topic
+        assert_eq!(usage_formals(&rd), vec![(String::from("foo"), vec![
+            String::from("x"),
+            String::from("y")
+        ])]);
+
+        assert_eq!(usage_formals(&rd_sd()), vec![(String::from("sd"), vec![
+            String::from("x"),
+            String::from("na.rm"),
+            String::from("...")
+        ])]);
+    }
+
+    #[test]
+    fn test_help_function_formals() {
+        let dir = tempfile::tempdir().unwrap();
+        let help_path = dir.path().join("help");
+        fs::create_dir(&help_path).unwrap();
+
+        // A single zlib-compressed entry, prefixed by its length
+        let rd = serialize(&rd_sd());
+        let mut entry = (rd.len() as u32).to_be_bytes().to_vec();
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&rd).unwrap();
+        entry.extend(encoder.finish().unwrap());
+        fs::write(help_path.join("stats.rdb"), &entry).unwrap();
+
+        let index = Value {
+            data: Data::List(vec![
+                Value {
+                    data: Data::List(vec![Value {
+                        data: Data::Integer(vec![0, entry.len() as i32]),
+                        attributes: vec![],
+                    }]),
+                    attributes: vec![(String::from("names"), text_untagged("sd"))],
+                },
+                Value {
+                    data: Data::Integer(vec![1]),
+                    attributes: vec![],
+                },
+            ]),
+            attributes: vec![(String::from("names"), Value {
+                data: Data::Character(vec![
+                    Some(String::from("variables")),
+                    Some(String::from("compressed")),
+                ]),
+                attributes: vec![],
+            })],
+        };
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&serialize(&index)).unwrap();
+        fs::write(help_path.join("stats.rdx"), encoder.finish().unwrap()).unwrap();
+
+        let formals = help_function_formals(dir.path(), "stats").unwrap();
+        assert_eq!(formals.len(), 1);
+        assert_eq!(formals.get("sd").unwrap(), &vec![
+            String::from("x"),
+            String::from("na.rm"),
+            String::from("...")
+        ]);
+
+        assert!(help_function_formals(dir.path(), "unknown").is_err());
+    }
+}
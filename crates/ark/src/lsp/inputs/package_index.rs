@@ -7,13 +7,16 @@
 
 use std::path::Path;
 
+use serde::Deserialize;
+use serde::Serialize;
+
 /// This represents an INDEX file.
 ///
 /// We use it to complement the list of exported symbols in NAMESPACE, in
 /// particular for exported datasets. This is a stopgap approach that has known
 /// shortcomings (false negatives as we will treat actually non-exported symbols
 /// as exported in some cases).
-#[derive(Default, Clone, Debug)]
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct Index {
     pub names: Vec<String>,
 }
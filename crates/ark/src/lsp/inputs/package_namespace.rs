@@ -6,6 +6,8 @@
 
 use std::sync::LazyLock;
 
+use serde::Deserialize;
+use serde::Serialize;
 use tree_sitter::Parser;
 use tree_sitter::Query;
 
@@ -13,7 +15,7 @@ use crate::lsp::traits::node::NodeExt;
 use crate::treesitter::TsQuery;
 
 /// Parsed NAMESPACE file
-#[derive(Default, Clone, Debug)]
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct Namespace {
     /// Names of objects exported with `export()`
     pub exports: Vec<String>,
@@ -0,0 +1,457 @@
+//
+// rds.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+//! Minimal reader of R's serialization format
+//!
+//! Installed packages store their metadata as serialized R objects, e.g. the
+//! Rd objects of their help database. This reads the XDR format written by
+//! `serialize()` and `saveRDS()` without an R session. Only the data needed for
+//! static analysis is kept: vectors, lists, pairlists and their attributes.
+//! Other objects such as environments and closures are read and discarded.
+
+use anyhow::anyhow;
+use anyhow::bail;
+
+const SYMSXP: u8 = 1;
+const LISTSXP: u8 = 2;
+const CLOSXP: u8 = 3;
+const ENVSXP: u8 = 4;
+const PROMSXP: u8 = 5;
+const LANGSXP: u8 = 6;
+const SPECIALSXP: u8 = 7;
+const BUILTINSXP: u8 = 8;
+const CHARSXP: u8 = 9;
+const LGLSXP: u8 = 10;
+const INTSXP: u8 = 13;
+const REALSXP: u8 = 14;
+const CPLXSXP: u8 = 15;
+const STRSXP: u8 = 16;
+const DOTSXP: u8 = 17;
+const VECSXP: u8 = 19;
+const EXPRSXP: u8 = 20;
+const EXTPTRSXP: u8 = 22;
+const WEAKREFSXP: u8 = 23;
+const RAWSXP: u8 = 24;
+const S4SXP: u8 = 25;
+
+const ALTREP_SXP: u8 = 238;
+const EMPTYENV_SXP: u8 = 242;
+const BASEENV_SXP: u8 = 241;
+const PERSISTSXP: u8 = 247;
+const PACKAGESXP: u8 = 248;
+const NAMESPACESXP: u8 = 249;
+const BASENAMESPACE_SXP: u8 = 250;
+const MISSINGARG_SXP: u8 = 251;
+const UNBOUNDVALUE_SXP: u8 = 252;
+const GLOBALENV_SXP: u8 = 253;
+const NILVALUE_SXP: u8 = 254;
+const REFSXP: u8 = 255;
+
+const HAS_ATTRIBUTES: i32 = 1 << 9;
+const HAS_TAG: i32 = 1 << 10;
+
+/// An unserialized R object
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Value {
+    pub data: Data,
+    pub attributes: Vec<(String, Value)>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Data {
+    #[default]
+    Null,
+    Symbol(String),
+    /// Character vectors, `None` elements are `NA`
+    Character(Vec<Option<String>>),
+    /// Integer and logical vectors
+    Integer(Vec<i32>),
+    Double(Vec<f64>),
+    /// Lists and expression vectors
+    List(Vec<Value>),
+    /// Pairlists with their tags
+    Pairlist(Vec<(Option<String>, Value)>),
+    /// Objects we don't need to inspect, e.g. environments or calls
+    Other,
+}
+
+impl Value {
+    fn new(data: Data) -> Self {
+        Self {
+            data,
+            attributes: Vec::new(),
+        }
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&Value> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value)
+    }
+
+    /// The first element of a character vector
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.data {
+            Data::Character(strings) => strings.first()?.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> &[Value] {
+        match &self.data {
+            Data::List(values) => values,
+            _ => &[],
+        }
+    }
+
+    /// The element of a named list called `name`
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        let Data::Character(names) = &self.attribute("names")?.data else {
+            return None;
+        };
+        let index = names
+            .iter()
+            .position(|element| element.as_deref() == Some(name))?;
+        self.as_list().get(index)
+    }
+}
+
+/// Unserialize an R object from the XDR format. The bytes must not be
+/// compressed.
+pub fn unserialize(bytes: &[u8]) -> anyhow::Result<Value> {
+    let mut reader = Reader {
+        bytes,
+        position: 0,
+        references: Vec::new(),
+    };
+
+    if reader.read_bytes(2)? != b"X\n" {
+        bail!("Only the XDR serialization format is supported");
+    }
+
+    let version = reader.read_int()?;
+    let _writer_version = reader.read_int()?;
+    let _reader_version = reader.read_int()?;
+
+    match version {
+        2 => {},
+        3 => {
+            // Native encoding of the writer
+            let length = reader.read_length()?;
+            reader.read_bytes(length)?;
+        },
+        version => bail!("Unsupported serialization version {version}"),
+    }
+
+    reader.read_item()
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+
+    /// Symbols and environments, which are serialized once and then referred
+    /// to by index
+    references: Vec<Value>,
+}
+
+impl<'a> Reader<'a> {
+    fn read_bytes(&mut self, length: usize) -> anyhow::Result<&'a [u8]> {
+        let end = self.position + length;
+        let Some(bytes) = self.bytes.get(self.position..end) else {
+            bail!("Unexpected end of serialized data");
+        };
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn read_int(&mut self) -> anyhow::Result<i32> {
+        let bytes = self.read_bytes(4)?;
+        Ok(i32::from_be_bytes(bytes.try_into()?))
+    }
+
+    fn read_double(&mut self) -> anyhow::Result<f64> {
+        let bytes = self.read_bytes(8)?;
+        Ok(f64::from_be_bytes(bytes.try_into()?))
+    }
+
+    fn read_length(&mut self) -> anyhow::Result<usize> {
+        match self.read_int()? {
+            -1 => {
+                let upper = self.read_int()? as u64;
+                let lower = self.read_int()? as u32 as u64;
+                Ok(((upper << 32) + lower) as usize)
+            },
+            length if length < 0 => bail!("Invalid vector length {length}"),
+            length => Ok(length as usize),
+        }
+    }
+
+    fn read_item(&mut self) -> anyhow::Result<Value> {
+        let flags = self.read_int()?;
+        self.read_item_with(flags)
+    }
+
+    fn read_item_with(&mut self, flags: i32) -> anyhow::Result<Value> {
+        let kind = (flags & 0xFF) as u8;
+        let has_attributes = flags & HAS_ATTRIBUTES != 0;
+
+        let data = match kind {
+            NILVALUE_SXP => return Ok(Value::default()),
+
+            EMPTYENV_SXP | BASEENV_SXP | GLOBALENV_SXP | UNBOUNDVALUE_SXP | MISSINGARG_SXP |
+            BASENAMESPACE_SXP => return Ok(Value::new(Data::Other)),
+
+            REFSXP => {
+                let index = match flags >> 8 {
+                    0 => self.read_int()?,
+                    index => index,
+                };
+                let reference = (index as usize)
+                    .checked_sub(1)
+                    .and_then(|index| self.references.get(index));
+                return reference
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Unknown reference {index}"));
+            },
+
+            PERSISTSXP | PACKAGESXP | NAMESPACESXP => {
+                self.read_string_vector()?;
+                let value = Value::new(Data::Other);
+                self.references.push(value.clone());
+                return Ok(value);
+            },
+
+            SYMSXP => {
+                let name = self.read_item()?;
+                let name = name.as_str().unwrap_or_default().to_string();
+                let value = Value::new(Data::Symbol(name));
+                self.references.push(value.clone());
+                return Ok(value);
+            },
+
+            ENVSXP => {
+                let _locked = self.read_int()?;
+                self.references.push(Value::new(Data::Other));
+
+                // Enclosure, frame, hash table, and attributes
+                for _ in 0..4 {
+                    self.read_item()?;
+                }
+                return Ok(Value::new(Data::Other));
+            },
+
+            LISTSXP | LANGSXP | CLOSXP | PROMSXP | DOTSXP => {
+                return self.read_pairlist(flags);
+            },
+
+            ALTREP_SXP => {
+                // Class information, state, and attributes
+                for _ in 0..3 {
+                    self.read_item()?;
+                }
+                return Ok(Value::new(Data::Other));
+            },
+
+            EXTPTRSXP => {
+                self.references.push(Value::new(Data::Other));
+
+                // Protected value and tag
+                self.read_item()?;
+                self.read_item()?;
+                Data::Other
+            },
+
+            WEAKREFSXP => {
+                self.references.push(Value::new(Data::Other));
+                Data::Other
+            },
+
+            SPECIALSXP | BUILTINSXP => {
+                let length = self.read_length()?;
+                self.read_bytes(length)?;
+                Data::Other
+            },
+
+            CHARSXP => match self.read_int()? {
+                -1 => Data::Character(vec![None]),
+                length if length < 0 => bail!("Invalid string length {length}"),
+                length => {
+                    let bytes = self.read_bytes(length as usize)?;
+                    let string = String::from_utf8_lossy(bytes).into_owned();
+                    Data::Character(vec![Some(string)])
+                },
+            },
+
+            LGLSXP | INTSXP => {
+                let length = self.read_length()?;
+                let values = (0..length)
+                    .map(|_| self.read_int())
+                    .collect::<anyhow::Result<_>>()?;
+                Data::Integer(values)
+            },
+
+            REALSXP => {
+                let length = self.read_length()?;
+                let values = (0..length)
+                    .map(|_| self.read_double())
+                    .collect::<anyhow::Result<_>>()?;
+                Data::Double(values)
+            },
+
+            CPLXSXP => {
+                let length = self.read_length()?;
+                self.read_bytes(length * 16)?;
+                Data::Other
+            },
+
+            STRSXP => {
+                let length = self.read_length()?;
+                let mut strings = Vec::with_capacity(length);
+                for _ in 0..length {
+                    let string = self.read_item()?;
+                    strings.push(string.as_str().map(String::from));
+                }
+                Data::Character(strings)
+            },
+
+            VECSXP | EXPRSXP => {
+                let length = self.read_length()?;
+                let values = (0..length)
+                    .map(|_| self.read_item())
+                    .collect::<anyhow::Result<_>>()?;
+                Data::List(values)
+            },
+
+            RAWSXP => {
+                let length = self.read_length()?;
+                self.read_bytes(length)?;
+                Data::Other
+            },
+
+            S4SXP => Data::Other,
+
+            kind => bail!("Unsupported serialized type {kind}"),
+        };
+
+        let attributes = if has_attributes {
+            self.read_attributes()?
+        } else {
+            Vec::new()
+        };
+
+        // Strings are not attributed
+        if kind == CHARSXP {
+            return Ok(Value::new(data));
+        }
+
+        Ok(Value { data, attributes })
+    }
+
+    /// Read the nodes of a pairlist iteratively, since long pairlists would
+    /// otherwise overflow the stack
+    fn read_pairlist(&mut self, flags: i32) -> anyhow::Result<Value> {
+        let kind = (flags & 0xFF) as u8;
+
+        let mut flags = flags;
+        let mut attributes = Vec::new();
+        let mut nodes = Vec::new();
+
+        loop {
+            let node_attributes = if flags & HAS_ATTRIBUTES != 0 {
+                self.read_attributes()?
+            } else {
+                Vec::new()
+            };
+            if nodes.is_empty() {
+                attributes = node_attributes;
+            }
+
+            let tag = if flags & HAS_TAG != 0 {
+                match self.read_item()?.data {
+                    Data::Symbol(tag) => Some(tag),
+                    _ => None,
+                }
+            } else {
+                None
+            };
+
+            nodes.push((tag, self.read_item()?));
+
+            // The CDR is either the next node or the end of the pairlist
+            flags = self.read_int()?;
+            if (flags & 0xFF) as u8 != LISTSXP {
+                self.read_item_with(flags)?;
+                break;
+            }
+        }
+
+        let data = match kind {
+            LISTSXP => Data::Pairlist(nodes),
+            _ => Data::Other,
+        };
+
+        Ok(Value { data, attributes })
+    }
+
+    fn read_attributes(&mut self) -> anyhow::Result<Vec<(String, Value)>> {
+        let attributes = self.read_item()?;
+
+        let Data::Pairlist(nodes) = attributes.data else {
+            return Ok(Vec::new());
+        };
+
+        Ok(nodes
+            .into_iter()
+            .filter_map(|(tag, value)| Some((tag?, value)))
+            .collect())
+    }
+
+    /// Read the string vectors of persistent references and namespaces
+    fn read_string_vector(&mut self) -> anyhow::Result<()> {
+        if self.read_int()? != 0 {
+            bail!("Invalid string vector");
+        }
+
+        let length = self.read_length()?;
+        for _ in 0..length {
+            self.read_item()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unserialize_named_list() {
+        // `serialize(list(a = 1L), NULL, version = 2)`
+        let bytes = [
+            0x58, 0x0a, 0x00, 0x00, 0x00, 0x02, 0x00, 0x04, 0x03, 0x00, 0x00, 0x02, 0x03, 0x00,
+            0x00, 0x00, 0x02, 0x13, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x0d, 0x00, 0x00,
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x04, 0x02, 0x00, 0x00, 0x00, 0x01,
+            0x00, 0x04, 0x00, 0x09, 0x00, 0x00, 0x00, 0x05, 0x6e, 0x61, 0x6d, 0x65, 0x73, 0x00,
+            0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x01, 0x00, 0x04, 0x00, 0x09, 0x00, 0x00, 0x00,
+            0x01, 0x61, 0x00, 0x00, 0x00, 0xfe,
+        ];
+
+        let value = unserialize(&bytes).unwrap();
+        assert_eq!(value.as_list().len(), 1);
+        assert_eq!(value.get("a").unwrap().data, Data::Integer(vec![1]));
+        assert!(value.get("b").is_none());
+    }
+
+    #[test]
+    fn test_unserialize_rejects_other_formats() {
+        assert!(unserialize(b"A\n1\n").is_err());
+        assert!(unserialize(b"X\n").is_err());
+    }
+}
@@ -0,0 +1,598 @@
+//
+// lint.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+// `ark lint <paths...>` runs the LSP diagnostics on files on disk, without an
+// editor and without an R session. This is meant for CI, e.g. to fail on
+// syntax errors, or with `--fail-on warning` on symbols not in scope in a
+// package.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde_json::json;
+use tower_lsp::lsp_types::Diagnostic;
+use tower_lsp::lsp_types::DiagnosticSeverity;
+use tower_lsp::lsp_types::NumberOrString;
+use url::Url;
+use walkdir::WalkDir;
+
+use crate::lsp::diagnostics::generate_diagnostics;
+use crate::lsp::diagnostics_rules::diagnostics_config;
+use crate::lsp::diagnostics_rules::ArkTomlFiles;
+use crate::lsp::document::Document;
+use crate::lsp::indexer;
+use crate::lsp::indexer::filter_entry;
+use crate::lsp::inputs::library::Library;
+use crate::lsp::inputs::package::Package;
+use crate::lsp::inputs::source_root::SourceRoot;
+use crate::lsp::standalone;
+use crate::lsp::state::WorldState;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LintFormat {
+    Human,
+    Json,
+    Sarif,
+}
+
+#[derive(Debug)]
+pub struct LintOptions {
+    pub paths: Vec<PathBuf>,
+    pub format: LintFormat,
+    /// Lowest severity that causes a non-zero exit code
+    pub fail_on: DiagnosticSeverity,
+}
+
+/// Diagnostics of a single file
+#[derive(Debug)]
+pub(crate) struct FileDiagnostics {
+    /// Path as found when walking the linted paths, i.e. relative if the
+    /// user supplied relative paths
+    pub(crate) path: PathBuf,
+    pub(crate) diagnostics: Vec<Diagnostic>,
+}
+
+fn print_usage() {
+    print!(
+        r#"
+Usage: ark lint [OPTIONS] [PATHS]...
+
+Report syntax and semantic diagnostics for the R files in PATHS (defaults to
+the current directory). Directories are searched recursively.
+
+Available options:
+
+--format FORMAT              Output format: "human" (the default), "json", or "sarif"
+--fail-on SEVERITY           Exit with a non-zero status if any diagnostic is at least
+                             as severe as SEVERITY: "error" (the default), "warning",
+                             "information", or "hint"
+--help                       Print this help message
+
+"#
+    );
+}
+
+/// Entry point of `ark lint`. Returns `false` if diagnostics were reported
+/// at or above the `--fail-on` severity.
+pub fn start_lint(args: impl Iterator<Item = String>) -> anyhow::Result<bool> {
+    let Some(options) = parse_args(args)? else {
+        print_usage();
+        return Ok(true);
+    };
+
+    // There is no R session to evaluate code in
+    standalone::set_standalone();
+
+    let files = collect_files(&options.paths)?;
+    let state = world_state(&options.paths);
+
+    index_files(&files, &state);
+    let results = lint_files(&files, &state);
+
+    let output = match options.format {
+        LintFormat::Human => format_human(&results),
+        LintFormat::Json => serde_json::to_string_pretty(&format_json(&results))?,
+        LintFormat::Sarif => serde_json::to_string_pretty(&format_sarif(&results))?,
+    };
+    println!("{output}");
+
+    Ok(!has_failures(&results, options.fail_on))
+}
+
+/// Returns `None` when help was requested
+fn parse_args(mut args: impl Iterator<Item = String>) -> anyhow::Result<Option<LintOptions>> {
+    let mut options = LintOptions {
+        paths: Vec::new(),
+        format: LintFormat::Human,
+        fail_on: DiagnosticSeverity::ERROR,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                let Some(format) = args.next() else {
+                    return Err(anyhow::anyhow!(
+                        "A format must be specified when using the `--format` argument."
+                    ));
+                };
+                options.format = match format.as_str() {
+                    "human" => LintFormat::Human,
+                    "json" => LintFormat::Json,
+                    "sarif" => LintFormat::Sarif,
+                    _ => {
+                        return Err(anyhow::anyhow!(
+                            "Invalid format: '{format}'. Expected `human`, `json`, or `sarif`."
+                        ));
+                    },
+                };
+            },
+            "--fail-on" => {
+                let Some(severity) = args.next() else {
+                    return Err(anyhow::anyhow!(
+                        "A severity must be specified when using the `--fail-on` argument."
+                    ));
+                };
+                options.fail_on = match severity.as_str() {
+                    "error" => DiagnosticSeverity::ERROR,
+                    "warning" => DiagnosticSeverity::WARNING,
+                    "information" => DiagnosticSeverity::INFORMATION,
+                    "hint" => DiagnosticSeverity::HINT,
+                    _ => {
+                        return Err(anyhow::anyhow!(
+                            "Invalid severity: '{severity}'. Expected `error`, `warning`, `information`, or `hint`."
+                        ));
+                    },
+                };
+            },
+            "--help" => return Ok(None),
+            other if other.starts_with("--") => {
+                return Err(anyhow::anyhow!("Argument '{other}' unknown."));
+            },
+            path => options.paths.push(PathBuf::from(path)),
+        }
+    }
+
+    if options.paths.is_empty() {
+        options.paths.push(PathBuf::from("."));
+    }
+
+    Ok(Some(options))
+}
+
+/// Collect the R files to lint. Files passed explicitly are always included,
+/// directories are walked with the same ignores as the workspace indexer.
+/// Files reached through several paths are included once, and files are
+/// sorted by canonical path.
+fn collect_files(paths: &[PathBuf]) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = BTreeMap::new();
+    let mut insert = |path: PathBuf| {
+        let key = path.canonicalize().unwrap_or_else(|_| path.clone());
+        files.entry(key).or_insert(path);
+    };
+
+    for path in paths {
+        if path.is_file() {
+            insert(path.clone());
+            continue;
+        }
+        if !path.is_dir() {
+            return Err(anyhow::anyhow!("Can't find path '{}'.", path.display()));
+        }
+
+        let walker = WalkDir::new(path).sort_by_file_name();
+        for entry in walker.into_iter().filter_entry(|e| filter_entry(e)) {
+            let Ok(entry) = entry else {
+                continue;
+            };
+            if entry.file_type().is_file() && is_r_file(entry.path()) {
+                insert(entry.into_path());
+            }
+        }
+    }
+
+    Ok(files.into_values().collect())
+}
+
+fn is_r_file(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("r" | "R")
+    )
+}
+
+/// Create the world state from the R installation, as in standalone mode.
+/// If the linted paths are inside a package, the package is the source root.
+fn world_state(paths: &[PathBuf]) -> WorldState {
+    let mut state = WorldState::default();
+
+    state.library = Library::new(standalone::library_paths());
+
+    let inputs = standalone::console_inputs(&state.library);
+    state.console_scopes = inputs.console_scopes;
+    state.installed_packages = inputs.installed_packages;
+
+    state.root = paths
+        .iter()
+        .filter_map(|path| std::path::absolute(path).ok())
+        .find_map(|path| find_package(&path))
+        .map(SourceRoot::Package);
+
+    // The workspace folders determine where `ark.toml` files are looked up
+    state.workspace.folders = match &state.root {
+        Some(SourceRoot::Package(package)) => Url::from_directory_path(&package.path)
+            .into_iter()
+            .collect(),
+        None => paths
+            .iter()
+            .filter_map(|path| std::path::absolute(path).ok())
+            .filter(|path| path.is_dir())
+            .filter_map(|path| Url::from_directory_path(path).ok())
+            .collect(),
+    };
+    state.ark_toml = ArkTomlFiles::load(&state.workspace.folders);
+
+    state
+}
+
+/// Find the package containing `path`, if any
+fn find_package(path: &Path) -> Option<Package> {
+    path.ancestors()
+        .find_map(|dir| match Package::load_from_folder(dir) {
+            Ok(package) => package,
+            Err(err) => {
+                log::warn!("Can't load package at {}: {err:?}", dir.display());
+                None
+            },
+        })
+}
+
+/// Index the linted files so that their top-level definitions are in scope in
+/// other files. For packages, all files of the `R/` folder are indexed even
+/// if only a subset is linted.
+fn index_files(files: &[PathBuf], state: &WorldState) {
+    let mut indexed = files.to_vec();
+
+    if let Some(SourceRoot::Package(package)) = &state.root {
+        let r_folder = package.path.join("R");
+        let walker = WalkDir::new(r_folder).into_iter();
+        indexed.extend(
+            walker
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_file() && is_r_file(entry.path()))
+                .map(|entry| entry.into_path()),
+        );
+    }
+
+    for path in indexed.iter() {
+        let Some((uri, document)) = read_document(path) else {
+            continue;
+        };
+        if let Err(err) = indexer::update(&document, &uri) {
+            log::warn!("Can't index file {}: {err:?}", path.display());
+        }
+    }
+}
+
+fn lint_files(files: &[PathBuf], state: &WorldState) -> Vec<FileDiagnostics> {
+    let mut results = Vec::new();
+
+    for path in files {
+        let Some((uri, document)) = read_document(path) else {
+            eprintln!("Can't read file '{}'.", path.display());
+            continue;
+        };
+
+        let mut state = state.clone();
+        state.config.diagnostics = diagnostics_config(&uri, &state);
+
+        let diagnostics = generate_diagnostics(document, state, &uri);
+
+        results.push(FileDiagnostics {
+            path: path.clone(),
+            diagnostics,
+        });
+    }
+
+    results
+}
+
+fn read_document(path: &Path) -> Option<(Url, Document)> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    let uri = Url::from_file_path(std::path::absolute(path).ok()?).ok()?;
+    Some((uri, Document::new(&contents, None)))
+}
+
+fn has_failures(results: &[FileDiagnostics], fail_on: DiagnosticSeverity) -> bool {
+    results
+        .iter()
+        .flat_map(|result| result.diagnostics.iter())
+        .any(|diagnostic| severity(diagnostic) <= fail_on)
+}
+
+// Diagnostics without severity are errors, as in most LSP clients. Note that
+// more severe diagnostics have lower values.
+fn severity(diagnostic: &Diagnostic) -> DiagnosticSeverity {
+    diagnostic.severity.unwrap_or(DiagnosticSeverity::ERROR)
+}
+
+fn severity_name(severity: DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::ERROR => "error",
+        DiagnosticSeverity::WARNING => "warning",
+        DiagnosticSeverity::INFORMATION => "information",
+        _ => "hint",
+    }
+}
+
+fn code(diagnostic: &Diagnostic) -> Option<String> {
+    match diagnostic.code.as_ref()? {
+        NumberOrString::Number(code) => Some(code.to_string()),
+        NumberOrString::String(code) => Some(code.clone()),
+    }
+}
+
+/// Paths with forward slashes, as expected in URIs and for stable output
+/// across platforms
+fn display_path(path: &Path) -> String {
+    let path = path.strip_prefix(".").unwrap_or(path);
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// One `path:line:column: severity: message` line per diagnostic, with 1-based
+/// positions, followed by a summary
+fn format_human(results: &[FileDiagnostics]) -> String {
+    let mut out = String::new();
+    let mut n_errors = 0;
+    let mut n_warnings = 0;
+    let mut n_other = 0;
+
+    for result in results {
+        for diagnostic in result.diagnostics.iter() {
+            let severity = severity(diagnostic);
+            match severity {
+                DiagnosticSeverity::ERROR => n_errors += 1,
+                DiagnosticSeverity::WARNING => n_warnings += 1,
+                _ => n_other += 1,
+            }
+
+            let start = diagnostic.range.start;
+            out.push_str(&format!(
+                "{}:{}:{}: {}: {}",
+                display_path(&result.path),
+                start.line + 1,
+                start.character + 1,
+                severity_name(severity),
+                diagnostic.message
+            ));
+            if let Some(code) = code(diagnostic) {
+                out.push_str(&format!(" [{code}]"));
+            }
+            out.push('\n');
+        }
+    }
+
+    let n_files = results.len();
+    let files = if n_files == 1 { "file" } else { "files" };
+    out.push_str(&format!(
+        "Found {n_errors} error(s), {n_warnings} warning(s), and {n_other} other diagnostic(s) in {n_files} {files}."
+    ));
+
+    out
+}
+
+/// A flat array of diagnostics. Positions are 0-based, as in the LSP.
+fn format_json(results: &[FileDiagnostics]) -> serde_json::Value {
+    let diagnostics: Vec<serde_json::Value> = results
+        .iter()
+        .flat_map(|result| {
+            result.diagnostics.iter().map(|diagnostic| {
+                json!({
+                    "path": display_path(&result.path),
+                    "range": diagnostic.range,
+                    "severity": severity_name(severity(diagnostic)),
+                    "code": code(diagnostic),
+                    "message": diagnostic.message,
+                })
+            })
+        })
+        .collect();
+
+    serde_json::Value::Array(diagnostics)
+}
+
+/// A SARIF 2.1.0 log, e.g. for code scanning on GitHub. Positions are 1-based
+/// and columns are counted in UTF-16 code units, as in the LSP.
+fn format_sarif(results: &[FileDiagnostics]) -> serde_json::Value {
+    let sarif_results: Vec<serde_json::Value> = results
+        .iter()
+        .flat_map(|result| {
+            result.diagnostics.iter().map(|diagnostic| {
+                let level = match severity(diagnostic) {
+                    DiagnosticSeverity::ERROR => "error",
+                    DiagnosticSeverity::WARNING => "warning",
+                    _ => "note",
+                };
+                let range = diagnostic.range;
+
+                let mut sarif_result = json!({
+                    "level": level,
+                    "message": { "text": diagnostic.message },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": display_path(&result.path) },
+                            "region": {
+                                "startLine": range.start.line + 1,
+                                "startColumn": range.start.character + 1,
+                                "endLine": range.end.line + 1,
+                                "endColumn": range.end.character + 1,
+                            },
+                        },
+                    }],
+                });
+                if let Some(code) = code(diagnostic) {
+                    sarif_result["ruleId"] = json!(code);
+                }
+
+                sarif_result
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "ark",
+                    "version": crate::ARK_VERSION,
+                    "informationUri": "https://github.com/posit-dev/ark",
+                },
+            },
+            "columnKind": "utf16CodeUnits",
+            "results": sarif_results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsp::indexer::ResetIndexerGuard;
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn lint_dir(dir: &Path) -> Vec<FileDiagnostics> {
+        let files = collect_files(&[dir.to_path_buf()]).unwrap();
+        let state = WorldState::default();
+        index_files(&files, &state);
+        lint_files(&files, &state)
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse_args(args(&[])).unwrap().unwrap();
+        assert_eq!(options.paths, vec![PathBuf::from(".")]);
+        assert_eq!(options.format, LintFormat::Human);
+        assert_eq!(options.fail_on, DiagnosticSeverity::ERROR);
+
+        let argv = args(&["R", "--format", "sarif", "--fail-on", "warning", "tests"]);
+        let options = parse_args(argv).unwrap().unwrap();
+        let paths = vec![PathBuf::from("R"), PathBuf::from("tests")];
+        assert_eq!(options.paths, paths);
+        assert_eq!(options.format, LintFormat::Sarif);
+        assert_eq!(options.fail_on, DiagnosticSeverity::WARNING);
+
+        assert!(parse_args(args(&["--help"])).unwrap().is_none());
+        assert!(parse_args(args(&["--format", "xml"])).is_err());
+        assert!(parse_args(args(&["--format"])).is_err());
+        assert!(parse_args(args(&["--foo"])).is_err());
+    }
+
+    #[test]
+    fn test_collect_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("R")).unwrap();
+        std::fs::create_dir_all(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join("R").join("b.R"), "").unwrap();
+        std::fs::write(dir.path().join("R").join("a.r"), "").unwrap();
+        std::fs::write(dir.path().join("R").join("notes.md"), "").unwrap();
+        std::fs::write(dir.path().join(".git").join("ignored.R"), "").unwrap();
+
+        let files = collect_files(&[dir.path().to_path_buf()]).unwrap();
+        assert_eq!(files, vec![
+            dir.path().join("R").join("a.r"),
+            dir.path().join("R").join("b.R"),
+        ]);
+
+        // Explicit files are included whatever their extension
+        let file = dir.path().join("R").join("notes.md");
+        assert_eq!(collect_files(&[file.clone()]).unwrap(), vec![file]);
+
+        // Files reached through several paths are included once
+        let paths = [
+            dir.path().join("R").join("b.R"),
+            dir.path().to_path_buf(),
+            dir.path().join("R").join("..").join("R").join("a.r"),
+        ];
+        assert_eq!(collect_files(&paths).unwrap(), vec![
+            dir.path().join("R").join("a.r"),
+            dir.path().join("R").join("b.R"),
+        ]);
+
+        assert!(collect_files(&[dir.path().join("missing")]).is_err());
+    }
+
+    #[test]
+    fn test_lint_files() {
+        let _guard = ResetIndexerGuard;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.R"), "helper <- function() 1\n").unwrap();
+        std::fs::write(dir.path().join("b.R"), "helper()\nundefined\nx <- (\n").unwrap();
+
+        let results = lint_dir(dir.path());
+        assert_eq!(results.len(), 2);
+        assert!(results[0].diagnostics.is_empty());
+
+        // `helper` is defined in another file and is in scope
+        let messages: Vec<&str> = results[1]
+            .diagnostics
+            .iter()
+            .map(|d| d.message.as_str())
+            .collect();
+        assert!(messages.contains(&"No symbol named 'undefined' in scope."));
+        assert!(!messages.iter().any(|message| message.contains("'helper'")));
+        assert!(results[1]
+            .diagnostics
+            .iter()
+            .any(|d| d.severity == Some(DiagnosticSeverity::ERROR)));
+
+        assert!(has_failures(&results, DiagnosticSeverity::ERROR));
+        assert!(!has_failures(&results[..1], DiagnosticSeverity::HINT));
+    }
+
+    #[test]
+    fn test_output_formats() {
+        let _guard = ResetIndexerGuard;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.R"), "x <- 1\ny\n").unwrap();
+
+        let mut results = lint_dir(dir.path());
+        results[0].path = PathBuf::from("./R/a.R");
+
+        let human = format_human(&results);
+        assert_eq!(
+            human,
+            "R/a.R:2:1: warning: No symbol named 'y' in scope. [symbol-not-in-scope]\nFound 0 error(s), 1 warning(s), and 0 other diagnostic(s) in 1 file."
+        );
+
+        let json = format_json(&results);
+        assert_eq!(json[0]["path"], "R/a.R");
+        assert_eq!(json[0]["severity"], "warning");
+        assert_eq!(json[0]["code"], "symbol-not-in-scope");
+        assert_eq!(json[0]["range"]["start"]["line"], 1);
+
+        let sarif = format_sarif(&results);
+        assert_eq!(sarif["version"], "2.1.0");
+        let result = &sarif["runs"][0]["results"][0];
+        assert_eq!(result["ruleId"], "symbol-not-in-scope");
+        assert_eq!(result["level"], "warning");
+        assert_eq!(result["message"]["text"], "No symbol named 'y' in scope.");
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "R/a.R");
+        assert_eq!(location["region"]["startLine"], 2);
+        assert_eq!(location["region"]["startColumn"], 1);
+    }
+}
@@ -6,14 +6,16 @@
 //
 
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::future;
-use std::path::Path;
 use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::sync::LazyLock;
+use std::sync::Mutex;
 use std::sync::RwLock;
+use std::time::Duration;
 
 use anyhow::anyhow;
 use futures::stream::FuturesUnordered;
@@ -36,12 +38,16 @@ use crate::lsp::backend::LspNotification;
 use crate::lsp::backend::LspRequest;
 use crate::lsp::backend::LspResponse;
 use crate::lsp::backend::LspResult;
+use crate::lsp::cache::LibraryCache;
 use crate::lsp::capabilities::Capabilities;
 use crate::lsp::diagnostics::generate_diagnostics;
+use crate::lsp::diagnostics_rules::diagnostics_config;
 use crate::lsp::document::Document;
 use crate::lsp::handlers;
 use crate::lsp::indexer;
 use crate::lsp::inputs::library::Library;
+use crate::lsp::semantic_tokens::SemanticTokensCache;
+use crate::lsp::standalone;
 use crate::lsp::state::WorldState;
 use crate::lsp::state_handlers;
 use crate::lsp::state_handlers::ConsoleInputs;
@@ -115,6 +121,9 @@ pub(crate) enum AuxiliaryEvent {
     Log(lsp_types::MessageType, String),
     PublishDiagnostics(Url, Vec<Diagnostic>, Option<i32>),
     SpawnedTask(JoinHandle<anyhow::Result<Option<AuxiliaryEvent>>>),
+    BeginProgress(lsp_types::NumberOrString, lsp_types::WorkDoneProgressBegin),
+    Progress(lsp_types::ProgressParams),
+    RefreshWorkspaceDiagnostics,
     Shutdown,
 }
 
@@ -158,6 +167,9 @@ pub(crate) struct LspState {
 
     /// Channel for sending notifications to Console (e.g., document changes for DAP)
     pub(crate) console_notification_tx: TokioUnboundedSender<ConsoleNotification>,
+
+    /// Last semantic tokens sent for each document, used to compute deltas
+    pub(crate) semantic_tokens: SemanticTokensCache,
 }
 
 /// State for the auxiliary loop
@@ -169,6 +181,7 @@ pub(crate) struct LspState {
 /// The auxiliary loop currently handles:
 /// - Log messages.
 /// - Joining of spawned blocking tasks to relay any errors or panics to the LSP log.
+/// - Progress notifications and diagnostics refresh requests.
 struct AuxiliaryState {
     client: Client,
     auxiliary_event_rx: TokioUnboundedReceiver<AuxiliaryEvent>,
@@ -194,6 +207,7 @@ impl GlobalState {
             parsers: HashMap::new(),
             capabilities: Capabilities::default(),
             console_notification_tx,
+            semantic_tokens: SemanticTokensCache::default(),
         };
 
         let mut state = Self {
@@ -204,6 +218,22 @@ impl GlobalState {
             events_rx,
         };
 
+        if standalone::is_standalone() {
+            let paths = standalone::library_paths();
+            log::info!("Using library paths: {paths:#?}");
+            state.world.library = Library::new(paths).with_cache(LibraryCache::load());
+
+            let inputs = standalone::console_inputs(&state.world.library);
+            state.world.console_scopes = inputs.console_scopes;
+            state.world.installed_packages = inputs.installed_packages;
+            state
+                .world
+                .library
+                .update_exports_index(state.world.installed_packages.clone());
+
+            return state;
+        }
+
         // FIXME: We shouldn't call R code in the kernel to figure this out
         if let Err(err) = crate::r_task(|| -> anyhow::Result<()> {
             let paths: Vec<String> = harp::RFunction::new("base", ".libPaths")
@@ -212,7 +242,7 @@ impl GlobalState {
 
             log::info!("Using library paths: {paths:#?}");
             let paths: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
-            state.world.library = Library::new(paths);
+            state.world.library = Library::new(paths).with_cache(LibraryCache::load());
 
             Ok(())
         }) {
@@ -294,8 +324,10 @@ impl GlobalState {
                         LspNotification::DidChangeConfiguration(params) => {
                             state_handlers::did_change_configuration(params, &self.client, &mut self.world).await?;
                         },
-                        LspNotification::DidChangeWatchedFiles(_params) => {
+                        LspNotification::DidChangeWatchedFiles(params) => {
+                            // Only `ark.toml` files are watched for now.
                             // TODO: Re-index the changed files.
+                            state_handlers::did_change_watched_files(params, &mut self.world)?;
                         },
                         LspNotification::DidOpenTextDocument(params) => {
                             state_handlers::did_open(params, &mut self.lsp_state, &mut self.world)?;
@@ -318,6 +350,15 @@ impl GlobalState {
                         LspNotification::DidRenameFiles(params) => {
                             state_handlers::did_rename_files(params, &mut self.world)?;
                         },
+                        LspNotification::DidOpenNotebookDocument(params) => {
+                            state_handlers::did_open_notebook(params, &mut self.lsp_state, &mut self.world)?;
+                        },
+                        LspNotification::DidChangeNotebookDocument(params) => {
+                            state_handlers::did_change_notebook(params, &mut self.lsp_state, &mut self.world)?;
+                        },
+                        LspNotification::DidCloseNotebookDocument(params) => {
+                            state_handlers::did_close_notebook(params, &mut self.lsp_state, &mut self.world)?;
+                        },
                     }
                 },
 
@@ -366,6 +407,21 @@ impl GlobalState {
                         LspRequest::References(params) => {
                             respond(tx, || handlers::handle_references(params, &self.world), LspResponse::References)?;
                         },
+                        LspRequest::PrepareRename(params) => {
+                            respond(tx, || handlers::handle_prepare_rename(params, &self.world), LspResponse::PrepareRename)?;
+                        },
+                        LspRequest::Rename(params) => {
+                            respond(tx, || handlers::handle_rename(params, &self.lsp_state, &self.world), LspResponse::Rename)?;
+                        },
+                        LspRequest::SemanticTokensFull(params) => {
+                            respond(tx, || handlers::handle_semantic_tokens_full(params, &mut self.lsp_state, &self.world), LspResponse::SemanticTokensFull)?;
+                        },
+                        LspRequest::SemanticTokensFullDelta(params) => {
+                            respond(tx, || handlers::handle_semantic_tokens_full_delta(params, &mut self.lsp_state, &self.world), LspResponse::SemanticTokensFullDelta)?;
+                        },
+                        LspRequest::InlayHint(params) => {
+                            respond(tx, || handlers::handle_inlay_hint(params, &self.world), LspResponse::InlayHint)?;
+                        },
                         LspRequest::StatementRange(params) => {
                             respond(tx, || handlers::handle_statement_range(params, &self.world), LspResponse::StatementRange)?;
                         },
@@ -376,9 +432,43 @@ impl GlobalState {
                             state_handlers::did_change_formatting_options(&params.text_document_position.text_document.uri, &params.options, &mut self.world);
                             respond(tx, || handlers::handle_indent(params, &self.world), LspResponse::OnTypeFormatting)?;
                         },
+                        LspRequest::Formatting(params) => {
+                            state_handlers::did_change_formatting_options(&params.text_document.uri, &params.options, &mut self.world);
+                            respond(tx, || handlers::handle_formatting(params, &self.world), LspResponse::Formatting)?;
+                        },
+                        LspRequest::RangeFormatting(params) => {
+                            state_handlers::did_change_formatting_options(&params.text_document.uri, &params.options, &mut self.world);
+                            respond(tx, || handlers::handle_range_formatting(params, &self.world), LspResponse::RangeFormatting)?;
+                        },
+                        LspRequest::PrepareCallHierarchy(params) => {
+                            respond(tx, || handlers::handle_prepare_call_hierarchy(params, &self.world), LspResponse::PrepareCallHierarchy)?;
+                        },
+                        LspRequest::IncomingCalls(params) => {
+                            respond(tx, || handlers::handle_incoming_calls(params, &self.world), LspResponse::IncomingCalls)?;
+                        },
+                        LspRequest::OutgoingCalls(params) => {
+                            respond(tx, || handlers::handle_outgoing_calls(params, &self.world), LspResponse::OutgoingCalls)?;
+                        },
+                        LspRequest::DocumentHighlight(params) => {
+                            respond(tx, || handlers::handle_document_highlight(params, &self.world), LspResponse::DocumentHighlight)?;
+                        },
                         LspRequest::CodeAction(params) => {
                             respond(tx, || handlers::handle_code_action(params, &self.lsp_state, &self.world), LspResponse::CodeAction)?;
                         },
+                        LspRequest::CodeLens(params) => {
+                            respond(tx, || handlers::handle_code_lens(params, &self.world), LspResponse::CodeLens)?;
+                        },
+                        LspRequest::CodeLensResolve(params) => {
+                            respond(tx, || handlers::handle_code_lens_resolve(params, &self.world), LspResponse::CodeLensResolve)?;
+                        },
+                        LspRequest::DocumentDiagnostic(params) => {
+                            respond(tx, || handlers::handle_document_diagnostic(params), LspResponse::DocumentDiagnostic)?;
+                        },
+                        LspRequest::WorkspaceDiagnostic(params) => {
+                            // Diagnosing all files of the workspace takes a while
+                            let state = self.world.clone();
+                            Self::spawn_handler(tx, move || handlers::handle_workspace_diagnostic(params, state), LspResponse::WorkspaceDiagnostic);
+                        },
                         LspRequest::VirtualDocument(params) => {
                             respond(tx, || handlers::handle_virtual_document(params, &self.world), LspResponse::VirtualDocument)?;
                         },
@@ -414,7 +504,6 @@ impl GlobalState {
         Ok(())
     }
 
-    #[allow(dead_code)] // Currently unused
     /// Spawn blocking thread for LSP request handler
     ///
     /// Use this for handlers that might take too long to handle on the main
@@ -556,6 +645,24 @@ impl AuxiliaryState {
                         .publish_diagnostics(uri, diagnostics, version)
                         .await
                 },
+                AuxiliaryEvent::BeginProgress(token, begin) => {
+                    self.begin_progress(token, begin).await
+                },
+                AuxiliaryEvent::Progress(params) => {
+                    self.client
+                        .send_notification::<lsp_types::notification::Progress>(params)
+                        .await
+                },
+                AuxiliaryEvent::RefreshWorkspaceDiagnostics => {
+                    let result = self
+                        .client
+                        .send_request::<lsp_types::request::WorkspaceDiagnosticRefresh>(())
+                        .await;
+                    if let Err(err) = result {
+                        self.log_error(format!("Can't refresh workspace diagnostics: {err:?}"))
+                            .await;
+                    }
+                },
                 AuxiliaryEvent::Shutdown => break,
             }
         }
@@ -589,6 +696,37 @@ impl AuxiliaryState {
         }
     }
 
+    /// Create a work done progress token on the frontend and begin reporting
+    /// progress with it
+    async fn begin_progress(
+        &self,
+        token: lsp_types::NumberOrString,
+        begin: lsp_types::WorkDoneProgressBegin,
+    ) {
+        let params = lsp_types::WorkDoneProgressCreateParams {
+            token: token.clone(),
+        };
+        let result = self
+            .client
+            .send_request::<lsp_types::request::WorkDoneProgressCreate>(params)
+            .await;
+        if let Err(err) = result {
+            self.log_error(format!("Can't create progress token: {err:?}"))
+                .await;
+            return;
+        }
+
+        let params = lsp_types::ProgressParams {
+            token,
+            value: lsp_types::ProgressParamsValue::WorkDone(lsp_types::WorkDoneProgress::Begin(
+                begin,
+            )),
+        };
+        self.client
+            .send_notification::<lsp_types::notification::Progress>(params)
+            .await
+    }
+
     async fn log(&self, level: MessageType, message: String) {
         self.client.log_message(level, message).await
     }
@@ -633,14 +771,24 @@ pub(crate) fn log(level: lsp_types::MessageType, message: String) {
 
     // Check that channel is still alive in case the LSP was closed.
     // If closed, fallthrough.
-    if let Ok(_) = with_auxiliary_tx(|auxiliary_event_tx| {
-        auxiliary_event_tx.send(AuxiliaryEvent::Log(level, message.clone()))
-    }) {
-        return;
-    }
+    let auxiliary_event_tx = AUXILIARY_EVENT_TX
+        .read()
+        .expect("Can lock auxiliary event sender.");
+
+    match auxiliary_event_tx.as_ref() {
+        Some(tx) => {
+            if let Ok(_) = tx.send(AuxiliaryEvent::Log(level, message.clone())) {
+                return;
+            }
 
-    // Log to the kernel as fallback
-    log::warn!("LSP channel is closed, redirecting messages to Jupyter kernel");
+            // Log to the kernel as fallback
+            log::warn!("LSP channel is closed, redirecting messages to Jupyter kernel");
+        },
+        None => {
+            // The LSP was never started, e.g. when analysing files with
+            // `ark lint`
+        },
+    }
 
     match level {
         MessageType::ERROR => log::error!("{message}"),
@@ -754,6 +902,10 @@ static INDEXER_QUEUE: LazyLock<tokio::sync::mpsc::UnboundedSender<IndexerQueueTa
         tx
     });
 
+/// Delay of inactivity of the indexer queue after which the on-disk index
+/// cache is saved
+const INDEX_CACHE_SAVE_DELAY: Duration = Duration::from_secs(2);
+
 /// Process indexer and diagnostics tasks
 ///
 /// Diagnostics need an up-to-date index to be accurate, so we synchronise
@@ -763,6 +915,8 @@ static INDEXER_QUEUE: LazyLock<tokio::sync::mpsc::UnboundedSender<IndexerQueueTa
 /// - Indexer tasks are batched together, same for diagnostics tasks.
 /// - Cancellation is simply dealt with by deduplicating tasks for the same URI,
 ///   retaining only the most recent one.
+/// - The on-disk index cache is saved once the queue goes idle rather than
+///   after every batch, since it is rewritten as a whole.
 ///
 /// Ideally we'd process indexer tasks continually without making them dependent
 /// on diagnostics tasks. The current setup blocks the queue loop while
@@ -779,7 +933,18 @@ async fn process_indexer_queue(mut rx: mpsc::UnboundedReceiver<IndexerQueueTask>
     let mut diagnostics_batch = Vec::new();
     let mut indexer_batch = Vec::new();
 
-    while let Some(task) = rx.recv().await {
+    loop {
+        let task = match tokio::time::timeout(INDEX_CACHE_SAVE_DELAY, rx.recv()).await {
+            Ok(task) => task,
+            Err(_) => {
+                indexer::save_cache();
+                rx.recv().await
+            },
+        };
+        let Some(task) = task else {
+            break;
+        };
+
         let mut tasks = vec![task];
 
         // Process diagnostics at least every 10 iterations if indexer tasks
@@ -847,6 +1012,10 @@ async fn process_indexer_batch(batch: Vec<IndexerTask>) {
         })()
         .await;
 
+        if let IndexerTask::Create { uri } = &task {
+            advance_indexing_progress(uri);
+        }
+
         if let Err(err) = result {
             tracing::warn!("Can't process indexer task: {err}");
             continue;
@@ -865,44 +1034,209 @@ async fn process_diagnostics_batch(batch: Vec<RefreshDiagnosticsTask>) {
         .map(|task| (task.uri, task.state))
         .collect();
 
+    // Packages loaded while diagnosing are saved to the on-disk cache
+    let library = batch.values().next().map(|state| state.library.clone());
+
     let mut futures = FuturesUnordered::new();
 
-    for (uri, state) in batch {
+    for (uri, mut state) in batch {
         futures.push(task::spawn_blocking(move || {
             let _span = tracing::info_span!("diagnostics_refresh", uri = %uri).entered();
 
+            // Take the workspace configuration file into account
+            state.config.diagnostics = diagnostics_config(&uri, &state);
+
+            // The R cells of notebooks are diagnosed together and the
+            // diagnostics are published for each cell
+            if let Some(notebook) = state.notebooks.get(&uri) {
+                let diagnostics =
+                    generate_diagnostics(notebook.document.clone(), state.clone(), &uri);
+
+                return notebook
+                    .cell_diagnostics(diagnostics)
+                    .into_iter()
+                    .map(|(uri, diagnostics)| RefreshDiagnosticsResult {
+                        version: state.documents.get(&uri).and_then(|doc| doc.version),
+                        uri,
+                        diagnostics,
+                    })
+                    .collect();
+            }
+
             if let Some(document) = state.documents.get(&uri) {
-                // Special case testthat-specific behaviour. This is a simple
-                // stopgap approach that has some false positives (e.g. when we
-                // work on testthat itself the flag will always be true), but
-                // that shouldn't have much practical impact.
-                let testthat = Path::new(uri.path())
-                    .components()
-                    .any(|c| c.as_os_str() == "testthat");
-
-                let diagnostics = generate_diagnostics(document.clone(), state.clone(), testthat);
-                Some(RefreshDiagnosticsResult {
+                let diagnostics = generate_diagnostics(document.clone(), state.clone(), &uri);
+                vec![RefreshDiagnosticsResult {
                     uri,
                     diagnostics,
                     version: document.version,
-                })
+                }]
             } else {
-                None
+                Vec::new()
             }
         }));
     }
 
     // Publish results as they complete
-    while let Some(Ok(Some(result))) = futures.next().await {
-        publish_diagnostics(result.uri, result.diagnostics, result.version);
+    while let Some(Ok(results)) = futures.next().await {
+        for result in results {
+            publish_diagnostics(result.uri, result.diagnostics, result.version);
+        }
     }
+
+    if let Some(library) = library {
+        library.save_cache();
+    }
+}
+
+/// Token of the `$/progress` notifications sent during the initial indexing
+const INDEXING_PROGRESS_TOKEN: &str = "ark/indexing";
+
+/// Progress of the initial indexing of the workspace folders
+///
+/// The files are indexed on the indexer queue, along with other indexer tasks.
+/// We keep track of the files that are still pending and report progress as
+/// they get indexed.
+#[derive(Debug)]
+struct IndexingProgress {
+    /// Files queued for indexing that are not indexed yet
+    pending: HashSet<Url>,
+
+    /// Number of files to index
+    total: usize,
+
+    /// Last percentage reported to the frontend
+    percentage: u32,
+
+    /// Whether the frontend supports `$/progress` notifications
+    report: bool,
+
+    /// Whether the frontend supports `workspace/diagnostic/refresh` requests.
+    /// Workspace diagnostics are pulled again once indexing is complete since
+    /// they depend on the index.
+    refresh_diagnostics: bool,
 }
 
-pub(crate) fn index_start(folders: Vec<String>, state: WorldState) {
+static INDEXING_PROGRESS: Mutex<Option<IndexingProgress>> = Mutex::new(None);
+
+impl IndexingProgress {
+    fn new(uris: &[Url], capabilities: &Capabilities) -> Self {
+        Self {
+            pending: uris.iter().cloned().collect(),
+            total: uris.len(),
+            percentage: 0,
+            report: capabilities.work_done_progress(),
+            refresh_diagnostics: capabilities.workspace_diagnostic_refresh(),
+        }
+    }
+
+    fn begin(&self, send: &mut impl FnMut(AuxiliaryEvent)) {
+        if !self.report {
+            return;
+        }
+
+        send(AuxiliaryEvent::BeginProgress(
+            progress_token(),
+            lsp_types::WorkDoneProgressBegin {
+                title: String::from("Indexing"),
+                cancellable: Some(false),
+                message: Some(format!("0/{} files", self.total)),
+                percentage: Some(0),
+            },
+        ));
+    }
+
+    /// Mark `uri` as indexed. Returns `true` once all files are indexed.
+    fn advance(&mut self, uri: &Url, send: &mut impl FnMut(AuxiliaryEvent)) -> bool {
+        if !self.pending.remove(uri) {
+            return false;
+        }
+        if self.pending.is_empty() {
+            return true;
+        }
+
+        self.report(send);
+        false
+    }
+
+    fn report(&mut self, send: &mut impl FnMut(AuxiliaryEvent)) {
+        let done = self.total - self.pending.len();
+        let percentage = (done * 100 / self.total) as u32;
+
+        // Only report when the percentage changes to avoid flooding the
+        // frontend in large workspaces
+        if !self.report || percentage == self.percentage {
+            return;
+        }
+        self.percentage = percentage;
+
+        send(progress_event(lsp_types::WorkDoneProgress::Report(
+            lsp_types::WorkDoneProgressReport {
+                cancellable: Some(false),
+                message: Some(format!("{done}/{} files", self.total)),
+                percentage: Some(percentage),
+            },
+        )));
+    }
+
+    fn end(self, send: &mut impl FnMut(AuxiliaryEvent)) {
+        if self.report {
+            send(progress_event(lsp_types::WorkDoneProgress::End(
+                lsp_types::WorkDoneProgressEnd {
+                    message: Some(format!("Indexed {} files", self.total)),
+                },
+            )));
+        }
+
+        if self.refresh_diagnostics {
+            send(AuxiliaryEvent::RefreshWorkspaceDiagnostics);
+        }
+    }
+}
+
+fn progress_token() -> lsp_types::NumberOrString {
+    lsp_types::NumberOrString::String(String::from(INDEXING_PROGRESS_TOKEN))
+}
+
+fn progress_event(progress: lsp_types::WorkDoneProgress) -> AuxiliaryEvent {
+    AuxiliaryEvent::Progress(lsp_types::ProgressParams {
+        token: progress_token(),
+        value: lsp_types::ProgressParamsValue::WorkDone(progress),
+    })
+}
+
+fn start_indexing_progress(uris: &[Url], capabilities: &Capabilities) {
+    let progress = IndexingProgress::new(uris, capabilities);
+    progress.begin(&mut send_auxiliary);
+
+    if progress.pending.is_empty() {
+        progress.end(&mut send_auxiliary);
+        return;
+    }
+
+    let mut guard = INDEXING_PROGRESS.lock().unwrap();
+    *guard = Some(progress);
+}
+
+/// Called when `uri` has been indexed by the indexer queue
+fn advance_indexing_progress(uri: &Url) {
+    let mut guard = INDEXING_PROGRESS.lock().unwrap();
+
+    let Some(progress) = guard.as_mut() else {
+        return;
+    };
+
+    if progress.advance(uri, &mut send_auxiliary) {
+        if let Some(progress) = guard.take() {
+            progress.end(&mut send_auxiliary);
+        }
+    }
+}
+
+pub(crate) fn index_start(folders: Vec<String>, state: WorldState, capabilities: &Capabilities) {
     lsp::log_info!("Initial indexing started");
 
     let uris: Vec<Url> = folders
-        .into_iter()
+        .iter()
         .flat_map(|folder| {
             walkdir::WalkDir::new(folder)
                 .into_iter()
@@ -934,6 +1268,15 @@ pub(crate) fn index_start(folders: Vec<String>, state: WorldState) {
         })
         .collect();
 
+    // Files that weren't modified since the last session are indexed from the
+    // on-disk cache right away, the others are queued for indexing
+    indexer::load_cache(&folders, &uris);
+    let uris: Vec<Url> = uris
+        .into_iter()
+        .filter(|uri| !indexer::restore(uri))
+        .collect();
+
+    start_indexing_progress(&uris, capabilities);
     index_create(uris, state);
 }
 
@@ -1007,7 +1350,14 @@ pub(crate) fn diagnostics_refresh_all(state: WorldState) {
         n = state.documents.len()
     );
 
-    for (uri, _document) in state.documents.iter() {
+    // Notebook cells are diagnosed with their notebook
+    let uris = state
+        .documents
+        .keys()
+        .filter(|uri| state.get_notebook_of_cell(uri).is_none())
+        .chain(state.notebooks.keys());
+
+    for uri in uris {
         if !ExtUrl::should_diagnose(uri) {
             continue;
         }
@@ -1020,3 +1370,97 @@ pub(crate) fn diagnostics_refresh_all(state: WorldState) {
             .unwrap_or_else(|err| lsp::log_error!("Failed to queue diagnostics refresh: {err}"));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tower_lsp::lsp_types;
+    use url::Url;
+
+    use super::AuxiliaryEvent;
+    use super::IndexingProgress;
+    use crate::lsp::capabilities::Capabilities;
+
+    fn progress_values(events: &[AuxiliaryEvent]) -> Vec<lsp_types::WorkDoneProgress> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                AuxiliaryEvent::Progress(params) => {
+                    let lsp_types::ProgressParamsValue::WorkDone(value) = &params.value;
+                    Some(value.clone())
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_indexing_progress() {
+        let uris: Vec<Url> = (1..=4)
+            .map(|i| Url::parse(&format!("file:///test{i}.R")).unwrap())
+            .collect();
+        let capabilities = Capabilities::default()
+            .with_work_done_progress(true)
+            .with_workspace_diagnostic_refresh(true);
+
+        let mut events = Vec::new();
+        let mut send = |event: AuxiliaryEvent| events.push(event);
+
+        let mut progress = IndexingProgress::new(&uris, &capabilities);
+        progress.begin(&mut send);
+
+        assert!(!progress.advance(&uris[0], &mut send));
+
+        // Unknown and already indexed files don't advance the progress
+        assert!(!progress.advance(&uris[0], &mut send));
+        assert!(!progress.advance(&Url::parse("file:///other.R").unwrap(), &mut send));
+
+        assert!(!progress.advance(&uris[1], &mut send));
+        assert!(!progress.advance(&uris[2], &mut send));
+        assert!(progress.advance(&uris[3], &mut send));
+        progress.end(&mut send);
+
+        let AuxiliaryEvent::BeginProgress(_, begin) = &events[0] else {
+            panic!("Expected a begin event");
+        };
+        assert_eq!(begin.message.as_deref(), Some("0/4 files"));
+        assert_eq!(begin.percentage, Some(0));
+
+        let values = progress_values(&events);
+        assert_eq!(values.len(), 4);
+
+        let percentages: Vec<_> = values[..3]
+            .iter()
+            .map(|value| match value {
+                lsp_types::WorkDoneProgress::Report(report) => report.percentage.unwrap(),
+                _ => panic!("Expected a report"),
+            })
+            .collect();
+        assert_eq!(percentages, vec![25, 50, 75]);
+
+        let lsp_types::WorkDoneProgress::End(end) = &values[3] else {
+            panic!("Expected an end");
+        };
+        assert_eq!(end.message.as_deref(), Some("Indexed 4 files"));
+
+        // Workspace diagnostics are pulled again once indexing is complete
+        assert!(matches!(
+            events.last(),
+            Some(AuxiliaryEvent::RefreshWorkspaceDiagnostics)
+        ));
+    }
+
+    #[test]
+    fn test_indexing_progress_without_client_support() {
+        let uris = vec![Url::parse("file:///test.R").unwrap()];
+
+        let mut events = Vec::new();
+        let mut send = |event: AuxiliaryEvent| events.push(event);
+
+        let mut progress = IndexingProgress::new(&uris, &Capabilities::default());
+        progress.begin(&mut send);
+        assert!(progress.advance(&uris[0], &mut send));
+        progress.end(&mut send);
+
+        assert!(events.is_empty());
+    }
+}
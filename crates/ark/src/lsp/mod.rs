@@ -5,20 +5,30 @@
 //
 //
 
+pub mod arguments;
 pub mod backend;
+mod base_symbols;
+pub mod cache;
+pub mod call_hierarchy;
 pub mod capabilities;
 pub mod code_action;
+pub mod code_lens;
 pub mod comm;
 pub mod completions;
 mod config;
 mod declarations;
 pub mod definitions;
 pub mod diagnostics;
+pub mod diagnostics_lints;
+pub mod diagnostics_rules;
 pub mod diagnostics_syntax;
 pub mod document_context;
+pub mod document_highlight;
 pub mod document;
+pub mod embedded;
 pub mod events;
 pub mod folding_range;
+pub mod formatting;
 pub mod handler;
 pub mod handlers;
 pub mod help;
@@ -26,14 +36,22 @@ pub mod help_topic;
 pub mod hover;
 pub mod indent;
 pub mod indexer;
+pub mod inlay_hint;
 pub mod input_boundaries;
 pub mod inputs;
+pub mod lint;
 pub mod main_loop;
 pub mod markdown;
+pub mod notebook;
 
 pub mod references;
+pub mod rename;
+pub mod roxygen;
+pub mod search_path;
 pub mod selection_range;
+pub mod semantic_tokens;
 pub mod signature_help;
+pub mod standalone;
 pub mod state;
 pub mod state_handlers;
 pub mod statement_range;
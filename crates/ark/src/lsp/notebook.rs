@@ -0,0 +1,402 @@
+//
+// notebook.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+//! Notebook documents
+//!
+//! The cells of a notebook are synchronised as separate text documents, but
+//! the R cells are analysed as a single unit so that objects defined in a cell
+//! are in scope in the following ones. The R cells are concatenated in order
+//! into the notebook document, and positions are mapped between a cell and
+//! the notebook document by offsetting rows. Columns are the same in both.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use tower_lsp::lsp_types;
+use tower_lsp::lsp_types::CompletionItem;
+use tower_lsp::lsp_types::CompletionTextEdit;
+use tower_lsp::lsp_types::Diagnostic;
+use tower_lsp::lsp_types::GotoDefinitionParams;
+use tower_lsp::lsp_types::GotoDefinitionResponse;
+use tower_lsp::lsp_types::LocationLink;
+use tower_lsp::lsp_types::NotebookCellArrayChange;
+use url::Url;
+
+use crate::lsp::document::Document;
+use crate::lsp::indexer;
+use crate::lsp::traits::node::NodeExt;
+use crate::treesitter::NodeTypeExt;
+
+pub(crate) const NOTEBOOK_DID_OPEN: &str = "notebookDocument/didOpen";
+pub(crate) const NOTEBOOK_DID_CHANGE: &str = "notebookDocument/didChange";
+pub(crate) const NOTEBOOK_DID_CLOSE: &str = "notebookDocument/didClose";
+
+#[derive(Clone, Debug)]
+pub(crate) struct Notebook {
+    pub(crate) version: i32,
+
+    /// The cells of the notebook, in order
+    pub(crate) cells: Vec<NotebookCell>,
+
+    /// The R cells concatenated in order
+    pub(crate) document: Document,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct NotebookCell {
+    /// URI of the text document of the cell
+    pub(crate) uri: Url,
+
+    /// Rows of the cell in the notebook document, for R cells
+    pub(crate) rows: Option<Range<u32>>,
+}
+
+/// Whether a cell text document contains R code
+pub(crate) fn is_r_cell(item: &lsp_types::TextDocumentItem) -> bool {
+    item.language_id.eq_ignore_ascii_case("r")
+}
+
+impl Notebook {
+    /// Create a notebook from its cells. `documents` contains the documents of
+    /// the R cells.
+    pub(crate) fn new(version: i32, cells: Vec<Url>, documents: &HashMap<Url, Document>) -> Self {
+        let cells = cells
+            .into_iter()
+            .map(|uri| NotebookCell { uri, rows: None })
+            .collect();
+
+        let mut notebook = Self {
+            version,
+            cells,
+            document: Document::new("", None),
+        };
+        notebook.update(documents);
+
+        notebook
+    }
+
+    /// Apply a change to the array of cells. The documents of new cells are
+    /// opened separately.
+    pub(crate) fn splice(&mut self, change: NotebookCellArrayChange) {
+        let start = (change.start as usize).min(self.cells.len());
+        let end = (start + change.delete_count as usize).min(self.cells.len());
+
+        let cells = change
+            .cells
+            .unwrap_or_default()
+            .into_iter()
+            .map(|cell| NotebookCell {
+                uri: cell.document,
+                rows: None,
+            });
+
+        self.cells.splice(start..end, cells);
+    }
+
+    /// Concatenate the R cells again after a change to the notebook or to the
+    /// contents of its cells
+    pub(crate) fn update(&mut self, documents: &HashMap<Url, Document>) {
+        let mut contents = String::new();
+        let mut row = 0;
+
+        for cell in self.cells.iter_mut() {
+            let Some(document) = documents.get(&cell.uri) else {
+                cell.rows = None;
+                continue;
+            };
+
+            contents.push_str(&document.contents);
+            if !document.contents.ends_with('\n') {
+                contents.push('\n');
+            }
+
+            let start = row;
+            row = contents.matches('\n').count() as u32;
+            cell.rows = Some(start..row);
+        }
+
+        self.document = Document::new(&contents, Some(self.version));
+    }
+
+    /// Whether `uri` is the text document of one of the cells
+    pub(crate) fn contains_cell(&self, uri: &Url) -> bool {
+        self.cells.iter().any(|cell| &cell.uri == uri)
+    }
+
+    /// The R cells of the notebook
+    pub(crate) fn r_cells(&self) -> impl Iterator<Item = (&Url, &Range<u32>)> {
+        self.cells
+            .iter()
+            .filter_map(|cell| Some((&cell.uri, cell.rows.as_ref()?)))
+    }
+
+    /// First row of an R cell in the notebook document
+    pub(crate) fn cell_offset(&self, uri: &Url) -> anyhow::Result<u32> {
+        self.r_cells()
+            .find(|(cell, _)| *cell == uri)
+            .map(|(_, rows)| rows.start)
+            .ok_or_else(|| anyhow::anyhow!("Can't find R cell {uri} in notebook"))
+    }
+
+    /// Map a position in a cell to the notebook document
+    pub(crate) fn to_notebook_position(
+        &self,
+        uri: &Url,
+        position: lsp_types::Position,
+    ) -> anyhow::Result<lsp_types::Position> {
+        let offset = self.cell_offset(uri)?;
+        Ok(lsp_types::Position::new(
+            position.line + offset,
+            position.character,
+        ))
+    }
+
+    /// Map a range of the notebook document to the cell containing its start
+    pub(crate) fn to_cell_range(&self, range: lsp_types::Range) -> Option<(Url, lsp_types::Range)> {
+        let (uri, rows) = self
+            .r_cells()
+            .find(|(_, rows)| rows.contains(&range.start.line))?;
+
+        let range = lsp_types::Range::new(
+            lsp_types::Position::new(range.start.line - rows.start, range.start.character),
+            lsp_types::Position::new(
+                range.end.line.saturating_sub(rows.start),
+                range.end.character,
+            ),
+        );
+
+        Some((uri.clone(), range))
+    }
+
+    /// Split the diagnostics of the notebook document by cell. All R cells
+    /// are included so that outdated diagnostics are cleared.
+    pub(crate) fn cell_diagnostics(
+        &self,
+        diagnostics: Vec<Diagnostic>,
+    ) -> HashMap<Url, Vec<Diagnostic>> {
+        let mut out: HashMap<Url, Vec<Diagnostic>> = self
+            .r_cells()
+            .map(|(uri, _)| (uri.clone(), Vec::new()))
+            .collect();
+
+        for mut diagnostic in diagnostics {
+            let Some((uri, range)) = self.to_cell_range(diagnostic.range) else {
+                continue;
+            };
+            diagnostic.range = range;
+            out.entry(uri).or_default().push(diagnostic);
+        }
+
+        out
+    }
+
+    /// Map the edits of completion items computed on the notebook document
+    /// back to the cell `uri`
+    pub(crate) fn to_cell_completions(&self, uri: &Url, items: &mut [CompletionItem]) {
+        let Ok(offset) = self.cell_offset(uri) else {
+            return;
+        };
+
+        let shift = |range: &mut lsp_types::Range| {
+            range.start.line = range.start.line.saturating_sub(offset);
+            range.end.line = range.end.line.saturating_sub(offset);
+        };
+
+        for item in items.iter_mut() {
+            match &mut item.text_edit {
+                Some(CompletionTextEdit::Edit(edit)) => shift(&mut edit.range),
+                Some(CompletionTextEdit::InsertAndReplace(edit)) => {
+                    shift(&mut edit.insert);
+                    shift(&mut edit.replace);
+                },
+                None => {},
+            }
+
+            for edit in item.additional_text_edits.iter_mut().flatten() {
+                shift(&mut edit.range);
+            }
+        }
+    }
+
+    /// Find the definition of the symbol at a cell position. Definitions in
+    /// the R cells of the notebook take precedence over the workspace.
+    pub(crate) fn goto_definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> anyhow::Result<Option<GotoDefinitionResponse>> {
+        let uri = &params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+        let position = self.to_notebook_position(uri, position)?;
+
+        let document = &self.document;
+        let point = document.tree_sitter_point_from_lsp_position(position)?;
+
+        let Some(node) = document.ast.root_node().find_closest_node_to_point(point) else {
+            return Ok(None);
+        };
+        if !node.is_identifier() {
+            return Ok(None);
+        }
+        let symbol = node.node_as_str(&document.contents)?;
+
+        let origin = document.lsp_range_from_tree_sitter_range(node.range())?;
+        let origin = self.to_cell_range(origin).map(|(_, range)| range);
+
+        let target = indexer::document_entries(document)
+            .into_iter()
+            .find(|entry| entry.key == symbol)
+            .and_then(|entry| self.to_cell_range(entry.range))
+            .or_else(|| {
+                indexer::find(symbol)
+                    .map(|(file_id, entry)| (file_id.as_uri().clone(), entry.range))
+            });
+
+        let Some((target_uri, target_range)) = target else {
+            return Ok(None);
+        };
+
+        let link = LocationLink {
+            origin_selection_range: origin,
+            target_uri,
+            target_range,
+            target_selection_range: target_range,
+        };
+        Ok(Some(GotoDefinitionResponse::Link(vec![link])))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tower_lsp::lsp_types::NotebookCell as LspNotebookCell;
+    use tower_lsp::lsp_types::NotebookCellKind;
+    use tower_lsp::lsp_types::Position;
+    use tower_lsp::lsp_types::TextDocumentIdentifier;
+    use tower_lsp::lsp_types::TextDocumentPositionParams;
+
+    use super::*;
+
+    fn cell_uri(i: usize) -> Url {
+        Url::parse(&format!("vscode-notebook-cell:/notebook.ipynb#cell{i}")).unwrap()
+    }
+
+    /// Notebook of R cells, and of a markdown cell for `None` contents
+    fn test_notebook(cells: &[Option<&str>]) -> (Notebook, HashMap<Url, Document>) {
+        let mut documents = HashMap::new();
+
+        for (i, contents) in cells.iter().enumerate() {
+            if let Some(contents) = contents {
+                documents.insert(cell_uri(i), Document::new(contents, None));
+            }
+        }
+
+        let uris = (0..cells.len()).map(cell_uri).collect();
+        (Notebook::new(1, uris, &documents), documents)
+    }
+
+    #[test]
+    fn test_notebook_concatenation() {
+        let (notebook, _) = test_notebook(&[Some("x <- 1\ny <- 2"), None, Some("f(x)\n")]);
+
+        assert_eq!(notebook.document.contents, "x <- 1\ny <- 2\nf(x)\n");
+        assert_eq!(notebook.cells[0].rows, Some(0..2));
+        assert_eq!(notebook.cells[1].rows, None);
+        assert_eq!(notebook.cells[2].rows, Some(2..3));
+
+        assert_eq!(
+            notebook
+                .to_notebook_position(&cell_uri(2), Position::new(0, 2))
+                .unwrap(),
+            Position::new(2, 2)
+        );
+        assert!(notebook
+            .to_notebook_position(&cell_uri(1), Position::new(0, 0))
+            .is_err());
+
+        let range = lsp_types::Range::new(Position::new(2, 2), Position::new(2, 3));
+        assert_eq!(
+            notebook.to_cell_range(range),
+            Some((
+                cell_uri(2),
+                lsp_types::Range::new(Position::new(0, 2), Position::new(0, 3))
+            ))
+        );
+    }
+
+    #[test]
+    fn test_notebook_splice() {
+        let (mut notebook, mut documents) = test_notebook(&[Some("x <- 1"), Some("f(x)")]);
+
+        // Replace the second cell by two cells
+        documents.remove(&cell_uri(1));
+        documents.insert(cell_uri(2), Document::new("y <- x", None));
+        documents.insert(cell_uri(3), Document::new("g(y)", None));
+
+        let cell = |i| LspNotebookCell {
+            kind: NotebookCellKind::Code,
+            document: cell_uri(i),
+            metadata: None,
+            execution_summary: None,
+        };
+        notebook.splice(NotebookCellArrayChange {
+            start: 1,
+            delete_count: 1,
+            cells: Some(vec![cell(2), cell(3)]),
+        });
+        notebook.update(&documents);
+
+        let uris: Vec<Url> = notebook.cells.iter().map(|cell| cell.uri.clone()).collect();
+        assert_eq!(uris, vec![cell_uri(0), cell_uri(2), cell_uri(3)]);
+        assert_eq!(notebook.document.contents, "x <- 1\ny <- x\ng(y)\n");
+    }
+
+    #[test]
+    fn test_notebook_cell_diagnostics() {
+        let (notebook, _) = test_notebook(&[Some("x <- 1"), Some("f(x)\ng(y)")]);
+
+        let range = lsp_types::Range::new(Position::new(2, 2), Position::new(2, 3));
+        let diagnostics = vec![Diagnostic::new_simple(range, String::from("Not in scope"))];
+
+        let diagnostics = notebook.cell_diagnostics(diagnostics);
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics[&cell_uri(0)].is_empty());
+        assert_eq!(
+            diagnostics[&cell_uri(1)][0].range,
+            lsp_types::Range::new(Position::new(1, 2), Position::new(1, 3))
+        );
+    }
+
+    #[test]
+    fn test_notebook_goto_definition() {
+        let (notebook, _) = test_notebook(&[Some("foo <- function() 1"), None, Some("foo()")]);
+
+        let params = GotoDefinitionParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: cell_uri(2) },
+                position: Position::new(0, 1),
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+
+        let Some(GotoDefinitionResponse::Link(links)) = notebook.goto_definition(params).unwrap()
+        else {
+            panic!("Expected a definition");
+        };
+        assert_eq!(links[0].target_uri, cell_uri(0));
+        assert_eq!(
+            links[0].target_range,
+            lsp_types::Range::new(Position::new(0, 0), Position::new(0, 3))
+        );
+        assert_eq!(
+            links[0].origin_selection_range,
+            Some(lsp_types::Range::new(
+                Position::new(0, 0),
+                Position::new(0, 3)
+            ))
+        );
+    }
+}
@@ -0,0 +1,617 @@
+//
+// rename.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+
+use std::collections::HashSet;
+
+use anyhow::anyhow;
+use harp::syntax::sym_quote_invalid;
+use tower_lsp::lsp_types::PrepareRenameResponse;
+use tower_lsp::lsp_types::RenameParams;
+use tower_lsp::lsp_types::TextDocumentPositionParams;
+use tower_lsp::lsp_types::TextEdit;
+use tower_lsp::lsp_types::WorkspaceEdit;
+use tree_sitter::Node;
+use tree_sitter::Point;
+use url::Url;
+
+use crate::lsp;
+use crate::lsp::capabilities::Capabilities;
+use crate::lsp::code_action::workspace_text_edits;
+use crate::lsp::document::Document;
+use crate::lsp::indexer;
+use crate::lsp::state::for_each_document;
+use crate::lsp::state::WorldState;
+use crate::lsp::traits::node::NodeExt;
+use crate::treesitter::BinaryOperatorType;
+use crate::treesitter::NodeType;
+use crate::treesitter::NodeTypeExt;
+
+/// The scope in which a symbol is bound
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Scope<'tree> {
+    /// Bound by the parameters or the body of a function. Such symbols are
+    /// only visible from within the function and its nested functions.
+    Function(Node<'tree>),
+
+    /// Bound at top level, e.g. in the global environment or in a package
+    /// namespace. These symbols are shared by all files of the workspace.
+    TopLevel,
+}
+
+pub(crate) fn prepare_rename(
+    params: TextDocumentPositionParams,
+    state: &WorldState,
+) -> anyhow::Result<Option<PrepareRenameResponse>> {
+    let document = state.get_document(&params.text_document.uri)?;
+    let point = document.tree_sitter_point_from_lsp_position(params.position)?;
+
+    let Some(node) = find_symbol_node(document, point) else {
+        return Ok(None);
+    };
+
+    let contents = document.contents.as_str();
+    let name = symbol_name(node.node_as_str(contents)?);
+    check_defined(document, node, name)?;
+
+    let range = document.lsp_range_from_tree_sitter_range(node.range())?;
+
+    Ok(Some(PrepareRenameResponse::RangeWithPlaceholder {
+        range,
+        placeholder: name.to_string(),
+    }))
+}
+
+pub(crate) fn rename(
+    params: RenameParams,
+    state: &WorldState,
+    capabilities: &Capabilities,
+) -> anyhow::Result<Option<WorkspaceEdit>> {
+    let uri = params.text_document_position.text_document.uri;
+    let position = params.text_document_position.position;
+
+    let document = state.get_document(&uri)?;
+    let point = document.tree_sitter_point_from_lsp_position(position)?;
+
+    let Some(node) = find_symbol_node(document, point) else {
+        return Ok(None);
+    };
+
+    let contents = document.contents.as_str();
+    let name = symbol_name(node.node_as_str(contents)?).to_string();
+    check_defined(document, node, &name)?;
+
+    let new_name = new_symbol_name(&params.new_name)?;
+
+    let mut document_edits = Vec::new();
+
+    match resolve_scope(node, &name, contents) {
+        Scope::Function(function) => {
+            // Local symbols can only be referenced from within the function
+            // that binds them, so we only need to look in this document
+            let scope = Scope::Function(function);
+            let edits = rename_edits(document, function, scope, &name, &new_name)?;
+            document_edits.push((uri.clone(), document.version, edits));
+        },
+
+        Scope::TopLevel => {
+            // Top-level symbols may be referenced from any file of the
+            // workspace and from open documents
+            for_each_document(state, |doc_uri, doc| {
+                let root = doc.ast.root_node();
+                match rename_edits(doc, root, Scope::TopLevel, &name, &new_name) {
+                    Ok(edits) if edits.is_empty() => {},
+                    Ok(edits) => document_edits.push((doc_uri.clone(), doc.version, edits)),
+                    Err(err) => lsp::log_warn!("Can't rename in {doc_uri}: {err:?}"),
+                }
+            });
+        },
+    }
+
+    if document_edits.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(workspace_text_edits(document_edits, capabilities)))
+}
+
+/// Refuse to rename symbols we don't know the definition of, such as functions
+/// from installed packages. Renaming them would only rename the call sites in
+/// the workspace. Clients may skip `prepareRename`, so this is checked again
+/// on rename.
+fn check_defined(document: &Document, node: Node, name: &str) -> anyhow::Result<()> {
+    let contents = document.contents.as_str();
+
+    if resolve_scope(node, name, contents) != Scope::TopLevel {
+        return Ok(());
+    }
+
+    let root = document.ast.root_node();
+    if node_binds(root, name, contents) || indexer::find(name).is_some() {
+        return Ok(());
+    }
+
+    Err(anyhow!(
+        "Can't rename `{name}` because it is not defined in the workspace."
+    ))
+}
+
+/// Collect the edits renaming all occurrences of `name` bound in `scope`,
+/// searching the subtree starting at `root`.
+fn rename_edits(
+    document: &Document,
+    root: Node,
+    scope: Scope,
+    name: &str,
+    new_name: &str,
+) -> anyhow::Result<Vec<TextEdit>> {
+    let contents = document.contents.as_str();
+
+    let mut nodes = Vec::new();
+    collect_symbol_nodes(root, name, contents, &mut nodes);
+
+    let mut edits = Vec::new();
+
+    for node in nodes {
+        if resolve_scope(node, name, contents) != scope {
+            continue;
+        }
+        let range = document.lsp_range_from_tree_sitter_range(node.range())?;
+        edits.push(TextEdit::new(range, new_name.to_string()));
+    }
+
+    Ok(edits)
+}
+
+fn collect_symbol_nodes<'tree>(
+    node: Node<'tree>,
+    name: &str,
+    contents: &str,
+    nodes: &mut Vec<Node<'tree>>,
+) {
+    if is_symbol_node(&node) {
+        if let Ok(text) = node.node_as_str(contents) {
+            if symbol_name(text) == name {
+                nodes.push(node);
+            }
+        }
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_symbol_nodes(child, name, contents, nodes);
+    }
+}
+
+/// Find the symbol under the cursor
+///
+/// Like in `references.rs`, we also look one character to the left so that
+/// a cursor placed just after an identifier still selects it.
+pub(crate) fn find_symbol_node(document: &Document, point: Point) -> Option<Node<'_>> {
+    let root = document.ast.root_node();
+
+    let node = root.descendant_for_point_range(point, point)?;
+    if is_symbol_node(&node) {
+        return Some(node);
+    }
+
+    if point.column == 0 {
+        return None;
+    }
+
+    let point = Point::new(point.row, point.column - 1);
+    let node = root.descendant_for_point_range(point, point)?;
+    is_symbol_node(&node).then_some(node)
+}
+
+/// Is this node an identifier referring to a lexical binding? Field names
+/// following `$` or `@`, namespace-qualified names, and argument names in
+/// calls are identifiers that don't refer to bindings.
+pub(crate) fn is_symbol_node(node: &Node) -> bool {
+    if !node.is_identifier() {
+        return false;
+    }
+
+    let Some(parent) = node.parent() else {
+        return true;
+    };
+
+    match parent.node_type() {
+        NodeType::ExtractOperator(_) => parent.child_by_field_name("rhs") != Some(*node),
+        NodeType::NamespaceOperator(_) => false,
+        NodeType::Argument => parent.child_by_field_name("name") != Some(*node),
+        _ => true,
+    }
+}
+
+/// Returns the name of a symbol, without the backticks of non-syntactic names
+pub(crate) fn symbol_name(text: &str) -> &str {
+    text.strip_prefix('`')
+        .and_then(|text| text.strip_suffix('`'))
+        .unwrap_or(text)
+}
+
+fn new_symbol_name(new_name: &str) -> anyhow::Result<String> {
+    let new_name = new_name.trim();
+
+    if symbol_name(new_name).is_empty() {
+        return Err(anyhow!("Can't rename to an empty name."));
+    }
+
+    // Already quoted by the user
+    if new_name.len() > 1 && new_name.starts_with('`') && new_name.ends_with('`') {
+        return Ok(new_name.to_string());
+    }
+
+    Ok(sym_quote_invalid(new_name))
+}
+
+/// Find the scope that binds `name`, as seen from `node`
+///
+/// We walk up the enclosing functions and return the first one that binds
+/// `name`, either as a parameter or through a local assignment. If no function
+/// binds it, the symbol refers to a top-level binding.
+pub(crate) fn resolve_scope<'tree>(node: Node<'tree>, name: &str, contents: &str) -> Scope<'tree> {
+    // The target of a super assignment is looked up starting from the parent
+    // of the enclosing function
+    let mut skip = is_super_assignment_target(&node);
+
+    for ancestor in node.ancestors().skip(1) {
+        if !ancestor.is_function_definition() {
+            continue;
+        }
+        if skip {
+            skip = false;
+            continue;
+        }
+        if function_binds(ancestor, name, contents) {
+            return Scope::Function(ancestor);
+        }
+    }
+
+    Scope::TopLevel
+}
+
+/// Does this function bind `name` in its own scope?
+pub(crate) fn function_binds(function: Node, name: &str, contents: &str) -> bool {
+    if let Some(parameters) = function.child_by_field_name("parameters") {
+        let mut cursor = parameters.walk();
+        for parameter in parameters.children_by_field_name("parameter", &mut cursor) {
+            let Some(parameter_name) = parameter.child_by_field_name("name") else {
+                continue;
+            };
+            if binding_name(&parameter_name, contents) == Some(name) {
+                return true;
+            }
+        }
+    }
+
+    match function.child_by_field_name("body") {
+        Some(body) => node_binds(body, name, contents),
+        None => false,
+    }
+}
+
+/// Does this subtree assign `name` in the current scope? Nested functions
+/// have their own scope and are not inspected.
+fn node_binds(node: Node, name: &str, contents: &str) -> bool {
+    if let Some(target) = assignment_target(&node) {
+        if binding_name(&target, contents) == Some(name) {
+            return true;
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.is_function_definition() {
+            continue;
+        }
+        if node_binds(child, name, contents) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Collect all names assigned by this subtree in the current scope. Like
+/// `node_binds()`, nested functions are not inspected.
+pub(crate) fn collect_bindings(node: Node, contents: &str, bindings: &mut HashSet<String>) {
+    if let Some(target) = assignment_target(&node) {
+        if let Some(name) = binding_name(&target, contents) {
+            bindings.insert(name.to_string());
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.is_function_definition() {
+            continue;
+        }
+        collect_bindings(child, contents, bindings);
+    }
+}
+
+/// Returns the node assigned to by a local assignment or a `for` loop
+pub(crate) fn assignment_target<'tree>(node: &Node<'tree>) -> Option<Node<'tree>> {
+    match node.node_type() {
+        NodeType::BinaryOperator(
+            BinaryOperatorType::LeftAssignment | BinaryOperatorType::EqualsAssignment,
+        ) => node.child_by_field_name("lhs"),
+        NodeType::BinaryOperator(BinaryOperatorType::RightAssignment) => {
+            node.child_by_field_name("rhs")
+        },
+        NodeType::ForStatement => node.child_by_field_name("variable"),
+        _ => None,
+    }
+}
+
+pub(crate) fn is_super_assignment_target(node: &Node) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+
+    let target = match parent.node_type() {
+        NodeType::BinaryOperator(BinaryOperatorType::LeftSuperAssignment) => {
+            parent.child_by_field_name("lhs")
+        },
+        NodeType::BinaryOperator(BinaryOperatorType::RightSuperAssignment) => {
+            parent.child_by_field_name("rhs")
+        },
+        _ => None,
+    };
+
+    target == Some(*node)
+}
+
+fn binding_name<'a>(node: &Node, contents: &'a str) -> Option<&'a str> {
+    if !node.is_identifier() {
+        return None;
+    }
+    node.node_as_str(contents).ok().map(symbol_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use tower_lsp::lsp_types;
+    use tower_lsp::lsp_types::DocumentChanges;
+    use tower_lsp::lsp_types::OneOf;
+
+    use super::*;
+    use crate::fixtures::point_from_cursor;
+    use crate::lsp::util::test_path;
+
+    fn rename_params(uri: &Url, point: Point, new_name: &str) -> RenameParams {
+        RenameParams {
+            text_document_position: TextDocumentPositionParams {
+                text_document: lsp_types::TextDocumentIdentifier { uri: uri.clone() },
+                position: lsp_types::Position::new(point.row as u32, point.column as u32),
+            },
+            new_name: new_name.to_string(),
+            work_done_progress_params: Default::default(),
+        }
+    }
+
+    // Returns the renamed contents of each document, in order of `documents`
+    fn apply_rename(documents: Vec<(&str, &str)>, cursor_doc: &str, new_name: &str) -> Vec<String> {
+        let mut state = WorldState::default();
+        let mut cursor = None;
+
+        for (file, text) in documents.iter() {
+            let uri = test_path(file);
+            let text = if *file == cursor_doc {
+                let (text, point) = point_from_cursor(text);
+                cursor = Some((uri.clone(), point));
+                text
+            } else {
+                text.to_string()
+            };
+            state.documents.insert(uri, Document::new(&text, None));
+        }
+
+        let (uri, point) = cursor.unwrap();
+        let capabilities = Capabilities::default();
+
+        let edit = rename(rename_params(&uri, point, new_name), &state, &capabilities)
+            .unwrap()
+            .unwrap();
+        let changes = edit.changes.unwrap();
+
+        documents
+            .iter()
+            .map(|(file, _)| {
+                let uri = test_path(file);
+                let document = state.get_document(&uri).unwrap();
+                let mut contents = document.contents.clone();
+
+                let mut edits = changes.get(&uri).cloned().unwrap_or_default();
+                edits.sort_by_key(|edit| std::cmp::Reverse(edit.range.start));
+
+                for edit in edits {
+                    let start = document
+                        .tree_sitter_point_from_lsp_position(edit.range.start)
+                        .unwrap();
+                    let end = document
+                        .tree_sitter_point_from_lsp_position(edit.range.end)
+                        .unwrap();
+                    let start = offset(&contents, start);
+                    let end = offset(&contents, end);
+                    contents.replace_range(start..end, &edit.new_text);
+                }
+
+                contents
+            })
+            .collect()
+    }
+
+    fn offset(contents: &str, point: Point) -> usize {
+        let line_start: usize = contents
+            .split_inclusive('\n')
+            .take(point.row)
+            .map(|line| line.len())
+            .sum();
+        line_start + point.column
+    }
+
+    #[test]
+    fn test_rename_parameter_is_local_to_function() {
+        let text = "
+x <- 1
+f <- function(x) {
+  y <- x@ + 1
+  g <- function(x) x
+  y
+}
+x
+";
+        let out = apply_rename(vec![("test.R", text)], "test.R", "value");
+        let expected = "
+x <- 1
+f <- function(value) {
+  y <- value + 1
+  g <- function(x) x
+  y
+}
+x
+";
+        assert_eq!(out[0], expected);
+    }
+
+    #[test]
+    fn test_rename_local_variable_seen_by_nested_function() {
+        let text = "
+f <- function() {
+  count@ <- 0
+  g <- function() count <<- count + 1
+  count
+}
+count
+";
+        let out = apply_rename(vec![("test.R", text)], "test.R", "n");
+        let expected = "
+f <- function() {
+  n <- 0
+  g <- function() n <<- n + 1
+  n
+}
+count
+";
+        assert_eq!(out[0], expected);
+    }
+
+    #[test]
+    fn test_rename_super_assignment_targets_top_level() {
+        let text = "
+counter <- 0
+f <- function() {
+  counter <<- counter@ + 1
+}
+";
+        let out = apply_rename(vec![("test.R", text)], "test.R", "total");
+        let expected = "
+total <- 0
+f <- function() {
+  total <<- total + 1
+}
+";
+        assert_eq!(out[0], expected);
+    }
+
+    #[test]
+    fn test_rename_top_level_function_across_documents() {
+        let helpers = "
+help@er <- function(x) x + 1
+";
+        let script = "
+helper(1)
+local_fn <- function(helper) helper
+list$helper
+pkg::helper
+list(helper = helper)
+";
+        let out = apply_rename(
+            vec![("helpers.R", helpers), ("script.R", script)],
+            "helpers.R",
+            "assist",
+        );
+        let expected = "
+assist <- function(x) x + 1
+";
+        assert_eq!(out[0], expected);
+        let expected = "
+assist(1)
+local_fn <- function(helper) helper
+list$helper
+pkg::helper
+list(helper = assist)
+";
+        assert_eq!(out[1], expected);
+    }
+
+    #[test]
+    fn test_rename_quotes_non_syntactic_names() {
+        let text = "
+fo@o <- 1
+foo
+";
+        let out = apply_rename(vec![("test.R", text)], "test.R", "my var");
+        let expected = "
+`my var` <- 1
+`my var`
+";
+        assert_eq!(out[0], expected);
+    }
+
+    #[test]
+    fn test_rename_uses_document_changes_when_supported() {
+        let (text, point) = point_from_cursor("fo@o <- 1\nfoo");
+        let uri = test_path("test.R");
+
+        let mut state = WorldState::default();
+        state
+            .documents
+            .insert(uri.clone(), Document::new(&text, Some(3)));
+
+        let capabilities = Capabilities::default().with_workspace_edit_document_changes(true);
+        let edit = rename(rename_params(&uri, point, "bar"), &state, &capabilities)
+            .unwrap()
+            .unwrap();
+
+        assert!(edit.changes.is_none());
+        let Some(DocumentChanges::Edits(edits)) = edit.document_changes else {
+            panic!("Unexpected");
+        };
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].text_document.uri, uri);
+        assert_eq!(edits[0].text_document.version, Some(3));
+        assert_eq!(edits[0].edits.len(), 2);
+        assert!(matches!(edits[0].edits[0], OneOf::Left(_)));
+    }
+
+    #[test]
+    fn test_rename_refuses_unknown_symbols() {
+        let _guard = indexer::ResetIndexerGuard;
+
+        let (text, point) = point_from_cursor("pri@nt(1)");
+        let uri = test_path("test.R");
+
+        let mut state = WorldState::default();
+        state
+            .documents
+            .insert(uri.clone(), Document::new(&text, None));
+
+        let params = TextDocumentPositionParams {
+            text_document: lsp_types::TextDocumentIdentifier { uri },
+            position: lsp_types::Position::new(point.row as u32, point.column as u32),
+        };
+        assert!(prepare_rename(params, &state).is_err());
+
+        let params = rename_params(&test_path("test.R"), point, "show");
+        assert!(rename(params, &state, &Capabilities::default()).is_err());
+    }
+}
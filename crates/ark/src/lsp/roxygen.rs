@@ -0,0 +1,640 @@
+//
+// roxygen.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use std::ops::Range;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use stdext::unwrap::IntoResult;
+use tree_sitter::Node;
+use url::Url;
+
+use crate::lsp::document::Document;
+use crate::lsp::indexer;
+use crate::lsp::indexer::IndexEntryData;
+use crate::lsp::markdown::md_bold;
+use crate::lsp::markdown::md_codeblock;
+use crate::lsp::state::with_document;
+use crate::lsp::state::WorldState;
+use crate::lsp::traits::node::NodeExt;
+use crate::treesitter::BinaryOperatorType;
+use crate::treesitter::NodeTypeExt;
+
+/// Rd markup commonly found in roxygen comments, e.g. `\code{x}`
+static RE_RD_MARKUP: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\\(code|link|var|env|pkg|emph|strong)\{([^{}]*)\}").unwrap());
+
+/// A roxygen line starting with a tag, e.g. `#' @param x`
+static RE_TAG_LINE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*#'\s*@([A-Za-z]\w*)(?:\s+|$)").unwrap());
+
+/// Tags whose first argument is a topic to inherit documentation from
+pub(crate) const INHERIT_TAGS: &[&str] = &["inherit", "inheritParams", "inheritDotParams"];
+
+/// A parsed roxygen block
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Roxygen {
+    /// The first paragraph of the block
+    pub(crate) title: Option<String>,
+
+    /// The following paragraphs, or the `@description` tag
+    pub(crate) description: Option<String>,
+
+    /// The `@param` tags, in order. A tag documenting several parameters,
+    /// e.g. `@param x,y`, is recorded for each of them.
+    pub(crate) params: Vec<(String, String)>,
+
+    /// The `@return` or `@returns` tag
+    pub(crate) returns: Option<String>,
+
+    /// The `@examples` tag, verbatim
+    pub(crate) examples: Option<String>,
+
+    /// All tags in order, with their contents
+    pub(crate) tags: Vec<(String, String)>,
+}
+
+/// A function defined in the workspace
+#[derive(Clone, Debug)]
+pub(crate) struct WorkspaceFunction {
+    pub(crate) name: String,
+
+    /// The function signature, e.g. `f(x, y = 2)`
+    pub(crate) signature: String,
+
+    /// The parameters of the function, e.g. `x` and `y = 2`
+    pub(crate) parameters: Vec<String>,
+
+    /// The roxygen block above the definition, if any
+    pub(crate) roxygen: Option<Roxygen>,
+}
+
+impl Roxygen {
+    /// Parse the lines of a roxygen block, including their `#'` prefix
+    pub(crate) fn parse<'a>(lines: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut intro: Vec<&str> = Vec::new();
+        let mut tags: Vec<(String, Vec<&str>)> = Vec::new();
+
+        for line in lines {
+            let line = strip_prefix(line);
+
+            if let Some(tagged) = line.trim_start().strip_prefix('@') {
+                let (tag, rest) = tagged
+                    .split_once(char::is_whitespace)
+                    .unwrap_or((tagged, ""));
+                tags.push((tag.to_string(), vec![rest]));
+            } else if let Some((_, contents)) = tags.last_mut() {
+                contents.push(line);
+            } else {
+                intro.push(line);
+            }
+        }
+
+        let mut out = Self::default();
+
+        let mut paragraphs = paragraphs(&intro).into_iter();
+        out.title = paragraphs.next();
+        let description = paragraphs.collect::<Vec<_>>().join("\n\n");
+        if !description.is_empty() {
+            out.description = Some(description);
+        }
+
+        for (tag, contents) in tags {
+            // Examples are code and are kept verbatim
+            let text = if tag == "examples" {
+                contents
+                    .join("\n")
+                    .trim_matches('\n')
+                    .trim_end()
+                    .to_string()
+            } else {
+                paragraphs(&contents).join("\n\n")
+            };
+
+            match tag.as_str() {
+                "param" => {
+                    let (names, description) = text
+                        .split_once(char::is_whitespace)
+                        .unwrap_or((text.as_str(), ""));
+                    for name in names.split(',') {
+                        out.params
+                            .push((name.trim().to_string(), description.trim().to_string()));
+                    }
+                },
+                "return" | "returns" => out.returns = Some(text.clone()),
+                "examples" => out.examples = Some(text.clone()),
+                "description" => out.description = Some(text.clone()),
+                "title" => out.title = Some(text.clone()),
+                _ => {},
+            }
+
+            out.tags.push((tag, text));
+        }
+
+        out
+    }
+
+    /// The description of a parameter
+    pub(crate) fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, description)| description.as_str())
+    }
+
+    /// Render the block as markdown, below a signature
+    pub(crate) fn markdown(&self, signature: &str) -> String {
+        let mut sections = vec![md_codeblock("r", signature)];
+
+        if let Some(title) = &self.title {
+            sections.push(md_bold(&render_rd(title)));
+        }
+        if let Some(description) = &self.description {
+            sections.push(render_rd(description));
+        }
+
+        if !self.params.is_empty() {
+            let params = self
+                .params
+                .iter()
+                .map(|(name, description)| format!("- `{name}`: {}", render_rd(description)))
+                .collect::<Vec<_>>()
+                .join("\n");
+            sections.push(format!("{}\n\n{params}", md_bold("Parameters")));
+        }
+
+        if let Some(returns) = &self.returns {
+            sections.push(format!("{}\n\n{}", md_bold("Returns"), render_rd(returns)));
+        }
+
+        if let Some(examples) = &self.examples {
+            sections.push(format!(
+                "{}\n\n{}",
+                md_bold("Examples"),
+                md_codeblock("r", examples)
+            ));
+        }
+
+        sections
+            .iter()
+            .map(|section| section.trim_end())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+impl WorkspaceFunction {
+    pub(crate) fn markdown(&self) -> String {
+        match &self.roxygen {
+            Some(roxygen) => roxygen.markdown(&self.signature),
+            None => md_codeblock("r", &self.signature).trim_end().to_string(),
+        }
+    }
+}
+
+/// Render the markup of a parameter description
+pub(crate) fn render_rd(text: &str) -> String {
+    RE_RD_MARKUP
+        .replace_all(text, |captures: &regex::Captures| match &captures[1] {
+            "code" | "var" | "env" => format!("`{}`", &captures[2]),
+            "emph" => format!("_{}_", &captures[2]),
+            "strong" => format!("**{}**", &captures[2]),
+            _ => captures[2].to_string(),
+        })
+        .into_owned()
+}
+
+/// Find a function in the workspace index, preferring definitions in the
+/// document `uri`, and collect its signature and roxygen documentation
+pub(crate) fn find_workspace_function(
+    name: &str,
+    uri: &Url,
+    state: &WorldState,
+) -> anyhow::Result<Option<WorkspaceFunction>> {
+    let Some((file_id, entry)) = indexer::find_in_file(name, uri).or_else(|| indexer::find(name))
+    else {
+        return Ok(None);
+    };
+
+    if !matches!(entry.data, IndexEntryData::Function { .. }) {
+        return Ok(None);
+    }
+
+    let callback = |document: &Document| -> anyhow::Result<Option<WorkspaceFunction>> {
+        let point = document.tree_sitter_point_from_lsp_position(entry.range.start)?;
+        let Some(lhs) = document
+            .ast
+            .root_node()
+            .named_descendant_for_point_range(point, point)
+        else {
+            return Ok(None);
+        };
+        function_documentation(document, &lhs)
+    };
+
+    let target = file_id.as_uri();
+    match state.get_document(target) {
+        Ok(document) => callback(document),
+        Err(_) => {
+            let Ok(path) = target.to_file_path() else {
+                return Ok(None);
+            };
+            with_document(&path, state, callback)
+        },
+    }
+}
+
+/// Signature and roxygen documentation of the function assigned to `lhs`
+pub(crate) fn function_documentation(
+    document: &Document,
+    lhs: &Node,
+) -> anyhow::Result<Option<WorkspaceFunction>> {
+    let Some(assignment) = lhs.parent() else {
+        return Ok(None);
+    };
+    if !assignment.is_binary_operator_of_kind(BinaryOperatorType::LeftAssignment) &&
+        !assignment.is_binary_operator_of_kind(BinaryOperatorType::EqualsAssignment)
+    {
+        return Ok(None);
+    }
+
+    let Some(function) = assignment.child_by_field_name("rhs") else {
+        return Ok(None);
+    };
+    if !function.is_function_definition() {
+        return Ok(None);
+    }
+
+    let contents = &document.contents;
+    let name = lhs.node_as_str(contents)?.to_string();
+
+    let (signature, parameters) = match function.child_by_field_name("parameters") {
+        Some(node) => {
+            let signature = node.node_as_str(contents)?;
+            let signature = signature.split_whitespace().collect::<Vec<_>>().join(" ");
+            let signature = signature.replace("( ", "(").replace(" )", ")");
+
+            let mut cursor = node.walk();
+            let parameters = node
+                .children_by_field_name("parameter", &mut cursor)
+                .map(|parameter| parameter_label(&parameter, contents))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            (signature, parameters)
+        },
+        None => (String::from("()"), Vec::new()),
+    };
+
+    Ok(Some(WorkspaceFunction {
+        signature: format!("{name}{signature}"),
+        parameters,
+        name,
+        roxygen: roxygen_block(document, &assignment),
+    }))
+}
+
+/// The label of a parameter in a signature, e.g. `x` or `y = 2`
+fn parameter_label(parameter: &Node, contents: &str) -> anyhow::Result<String> {
+    let name = parameter.child_by_field_name("name").into_result()?;
+    let name = name.node_as_str(contents)?;
+
+    match parameter.child_by_field_name("default") {
+        Some(default) => {
+            let default = default.node_as_str(contents)?;
+            let default = default.split_whitespace().collect::<Vec<_>>().join(" ");
+            Ok(format!("{name} = {default}"))
+        },
+        None => Ok(name.to_string()),
+    }
+}
+
+/// The roxygen block directly above the statement containing `node`
+pub(crate) fn roxygen_block(document: &Document, node: &Node) -> Option<Roxygen> {
+    let statement = node.ancestors().find(|node| {
+        node.parent()
+            .is_some_and(|parent| parent.is_program() || parent.is_braced_expression())
+    })?;
+
+    let contents = &document.contents;
+    let comments = roxygen_comments(&statement, contents);
+
+    if comments.is_empty() {
+        return None;
+    }
+
+    let lines = comments
+        .iter()
+        .map(|comment| comment.node_as_str(contents))
+        .collect::<anyhow::Result<Vec<_>>>()
+        .ok()?;
+    Some(Roxygen::parse(lines))
+}
+
+/// The comments of the roxygen block directly above `statement`, in order
+pub(crate) fn roxygen_comments<'tree>(statement: &Node<'tree>, contents: &str) -> Vec<Node<'tree>> {
+    let mut comments = Vec::new();
+    let mut row = statement.start_position().row;
+    let mut sibling = statement.prev_sibling();
+
+    while let Some(comment) = sibling {
+        if !is_roxygen_comment(&comment, contents) || comment.end_position().row + 1 != row {
+            break;
+        }
+
+        comments.push(comment);
+        row = comment.start_position().row;
+        sibling = comment.prev_sibling();
+    }
+
+    comments.reverse();
+    comments
+}
+
+/// The statement documented by the roxygen block that contains `comment`
+pub(crate) fn documented_statement<'tree>(
+    comment: &Node<'tree>,
+    contents: &str,
+) -> Option<Node<'tree>> {
+    let mut row = comment.end_position().row;
+    let mut sibling = comment.next_sibling();
+
+    while let Some(node) = sibling {
+        if node.start_position().row != row + 1 {
+            return None;
+        }
+        if !node.is_comment() {
+            return Some(node);
+        }
+        if !is_roxygen_comment(&node, contents) {
+            return None;
+        }
+
+        row = node.end_position().row;
+        sibling = node.next_sibling();
+    }
+
+    None
+}
+
+/// The function definition assigned by `statement`, along with the
+/// assignment target
+pub(crate) fn assigned_function<'tree>(
+    statement: &Node<'tree>,
+) -> Option<(Node<'tree>, Node<'tree>)> {
+    if !statement.is_binary_operator_of_kind(BinaryOperatorType::LeftAssignment) &&
+        !statement.is_binary_operator_of_kind(BinaryOperatorType::EqualsAssignment)
+    {
+        return None;
+    }
+
+    let lhs = statement.child_by_field_name("lhs")?;
+    let rhs = statement.child_by_field_name("rhs")?;
+
+    rhs.is_function_definition().then_some((lhs, rhs))
+}
+
+pub(crate) fn is_roxygen_comment(node: &Node, contents: &str) -> bool {
+    node.is_comment() &&
+        node
+            .node_as_str(contents)
+            .is_ok_and(|text| text.starts_with("#'"))
+}
+
+/// A roxygen line starting with a tag, e.g. `#' @param x A number.`
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct TagLine<'a> {
+    /// The tag name, without `@`
+    pub(crate) tag: &'a str,
+
+    /// The text following the tag and its whitespace
+    pub(crate) argument: &'a str,
+
+    /// Byte offset of `argument` in the line
+    pub(crate) offset: usize,
+}
+
+impl<'a> TagLine<'a> {
+    pub(crate) fn parse(line: &'a str) -> Option<Self> {
+        let captures = RE_TAG_LINE.captures(line)?;
+        let offset = captures.get(0)?.end();
+
+        Some(Self {
+            tag: captures.get(1)?.as_str(),
+            argument: &line[offset..],
+            offset,
+        })
+    }
+
+    /// The first word of the argument, e.g. the topic of `@inheritParams`,
+    /// with its byte range in the line
+    pub(crate) fn first_word(&self) -> (&'a str, Range<usize>) {
+        let end = self
+            .argument
+            .find(char::is_whitespace)
+            .unwrap_or(self.argument.len());
+        (&self.argument[..end], self.offset..self.offset + end)
+    }
+
+    /// The parameters documented by a `@param` tag, e.g. `@param x,y`, with
+    /// their byte ranges in the line
+    pub(crate) fn param_names(&self) -> Vec<(&'a str, Range<usize>)> {
+        if self.tag != "param" {
+            return Vec::new();
+        }
+
+        let (names, range) = self.first_word();
+        let mut start = range.start;
+
+        names
+            .split(',')
+            .filter_map(|name| {
+                let range = start..start + name.len();
+                start = range.end + 1;
+                (!name.is_empty()).then_some((name, range))
+            })
+            .collect()
+    }
+}
+
+/// The topic referenced by an `@inherit` tag of a roxygen comment when
+/// `column` is on it, with its byte range in the comment
+pub(crate) fn inherited_topic(comment: &str, column: usize) -> Option<(&str, Range<usize>)> {
+    let line = TagLine::parse(comment)?;
+    if !INHERIT_TAGS.contains(&line.tag) {
+        return None;
+    }
+
+    let (topic, range) = line.first_word();
+    if topic.is_empty() || column < range.start || column > range.end {
+        return None;
+    }
+
+    Some((topic, range))
+}
+
+/// Strip the `#'` prefix of a roxygen line, along with a single space
+pub(crate) fn strip_prefix(line: &str) -> &str {
+    let line = line.trim_start();
+    let line = line.strip_prefix("#'").unwrap_or(line);
+    line.strip_prefix(' ').unwrap_or(line)
+}
+
+/// Paragraphs of lines, separated by empty lines. Lines within a paragraph
+/// are joined with spaces.
+fn paragraphs(lines: &[&str]) -> Vec<String> {
+    lines
+        .split(|line| line.trim().is_empty())
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| {
+            paragraph
+                .iter()
+                .map(|line| line.trim())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsp::indexer::ResetIndexerGuard;
+    use crate::lsp::util::test_path;
+
+    #[test]
+    fn test_roxygen_parse() {
+        let roxygen = Roxygen::parse([
+            "#' Add numbers",
+            "#'",
+            "#' Adds two numbers",
+            "#' together.",
+            "#'",
+            "#' @param x,y Numbers to",
+            "#'   add.",
+            "#' @param z Unused.",
+            "#' @returns The sum.",
+            "#' @export",
+            "#' @examples",
+            "#' add(1, 2)",
+            "#'",
+            "#' add(3, 4)",
+        ]);
+
+        assert_eq!(roxygen.title, Some(String::from("Add numbers")));
+        assert_eq!(
+            roxygen.description,
+            Some(String::from("Adds two numbers together."))
+        );
+        assert_eq!(roxygen.param("x"), Some("Numbers to add."));
+        assert_eq!(roxygen.param("y"), Some("Numbers to add."));
+        assert_eq!(roxygen.param("z"), Some("Unused."));
+        assert_eq!(roxygen.param("w"), None);
+        assert_eq!(roxygen.returns, Some(String::from("The sum.")));
+        assert_eq!(
+            roxygen.examples,
+            Some(String::from("add(1, 2)\n\nadd(3, 4)"))
+        );
+
+        let tags: Vec<&str> = roxygen.tags.iter().map(|(tag, _)| tag.as_str()).collect();
+        assert_eq!(tags, vec!["param", "param", "returns", "export", "examples"]);
+    }
+
+    #[test]
+    fn test_tag_line() {
+        let line = TagLine::parse("#' @param x,y Numbers.").unwrap();
+        assert_eq!(line.tag, "param");
+        assert_eq!(line.argument, "x,y Numbers.");
+        assert_eq!(line.first_word(), ("x,y", 10..13));
+        assert_eq!(line.param_names(), vec![("x", 10..11), ("y", 12..13)]);
+
+        let line = TagLine::parse("#' @export").unwrap();
+        assert_eq!(line.tag, "export");
+        assert_eq!(line.argument, "");
+        assert!(line.param_names().is_empty());
+
+        assert_eq!(TagLine::parse("#' Title"), None);
+        assert_eq!(TagLine::parse("# @param x"), None);
+
+        assert_eq!(
+            inherited_topic("#' @inheritParams add", 19),
+            Some(("add", 18..21))
+        );
+        assert_eq!(inherited_topic("#' @inheritParams add", 10), None);
+        assert_eq!(inherited_topic("#' @seealso add", 13), None);
+    }
+
+    #[test]
+    fn test_roxygen_markdown() {
+        let roxygen = Roxygen::parse([
+            "#' Add numbers",
+            "#' @param x A \\code{numeric} vector.",
+            "#' @return The sum.",
+        ]);
+
+        assert_eq!(
+            roxygen.markdown("add(x)"),
+            "``` r
+add(x)
+```
+
+**Add numbers**
+
+**Parameters**
+
+- `x`: A `numeric` vector.
+
+**Returns**
+
+The sum."
+        );
+    }
+
+    #[test]
+    fn test_find_workspace_function() {
+        let _guard = ResetIndexerGuard;
+
+        let code = "
+#' Add numbers
+#'
+#' @param x A number.
+#' @param y Another
+#'   number.
+add <- function(x,
+                y = 2) {
+  x + y
+}
+
+# Not roxygen
+other <- function() NULL
+";
+        let uri = test_path("test.R");
+        let document = Document::new(code, None);
+        indexer::update(&document, &uri).unwrap();
+
+        let mut state = WorldState::default();
+        state.documents.insert(uri.clone(), document);
+
+        let function = find_workspace_function("add", &uri, &state)
+            .unwrap()
+            .unwrap();
+        assert_eq!(function.signature, "add(x, y = 2)");
+
+        let roxygen = function.roxygen.unwrap();
+        assert_eq!(roxygen.title, Some(String::from("Add numbers")));
+        assert_eq!(roxygen.param("y"), Some("Another number."));
+
+        let function = find_workspace_function("other", &uri, &state)
+            .unwrap()
+            .unwrap();
+        assert_eq!(function.signature, "other()");
+        assert!(function.roxygen.is_none());
+
+        assert!(find_workspace_function("missing", &uri, &state)
+            .unwrap()
+            .is_none());
+    }
+}
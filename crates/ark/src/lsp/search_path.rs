@@ -0,0 +1,102 @@
+//
+// search_path.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use tree_sitter::Node;
+
+use crate::lsp::document::Document;
+use crate::lsp::inputs::source_root::SourceRoot;
+use crate::lsp::state::WorldState;
+use crate::lsp::traits::node::NodeExt;
+use crate::treesitter::NodeTypeExt;
+
+/// Packages attached in a vanilla R session, from innermost to outermost
+/// scope of the search path
+pub(crate) const DEFAULT_PACKAGES: [&str; 7] = [
+    "stats",
+    "graphics",
+    "grDevices",
+    "utils",
+    "datasets",
+    "methods",
+    "base",
+];
+
+/// The packages providing the functions called in a document, in search
+/// order. Packages attached in the document with `library()` or `require()`
+/// mask the packages imported by the workspace package, which mask the
+/// default packages.
+pub(crate) fn search_path(document: &Document, state: &WorldState) -> Vec<String> {
+    let mut packages = Vec::new();
+    collect_attached_packages(
+        document.ast.root_node(),
+        document.contents.as_str(),
+        &mut packages,
+    );
+    packages.reverse();
+
+    if let Some(SourceRoot::Package(root)) = &state.root {
+        packages.extend(root.namespace.package_imports.iter().cloned());
+    }
+
+    packages.extend(DEFAULT_PACKAGES.iter().map(|package| package.to_string()));
+    packages
+}
+
+/// Collect the packages attached with `library()` or `require()`, in order of
+/// attachment
+fn collect_attached_packages(node: Node, contents: &str, packages: &mut Vec<String>) {
+    if let Some(package) = attached_package(&node, contents) {
+        packages.push(package.to_string());
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_attached_packages(child, contents, packages);
+    }
+}
+
+fn attached_package<'a>(node: &Node, contents: &'a str) -> Option<&'a str> {
+    if !node.is_call() {
+        return None;
+    }
+
+    let callee = node.child_by_field_name("function")?;
+    if !matches!(callee.node_as_str(contents), Ok("library" | "require")) {
+        return None;
+    }
+
+    // Same heuristics as in diagnostics: no argument matching, and we bail if
+    // `character.only` is supplied
+    if node
+        .arguments_names_as_string(contents)
+        .flatten()
+        .any(|name| name == "character.only")
+    {
+        return None;
+    }
+
+    let package = node.arguments_values().flatten().nth(0)?;
+    package.get_identifier_or_string_text(contents).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_path() {
+        let document = Document::new(
+            "library(dplyr)\nf <- function() require(\"tidyr\")\nlibrary(x, character.only = TRUE)\n",
+            None,
+        );
+        let state = WorldState::default();
+
+        let packages = search_path(&document, &state);
+        assert_eq!(packages[..3], ["tidyr", "dplyr", "stats"]);
+        assert_eq!(packages.len(), 2 + DEFAULT_PACKAGES.len());
+    }
+}
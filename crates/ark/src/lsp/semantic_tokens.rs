@@ -0,0 +1,815 @@
+//
+// semantic_tokens.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use tower_lsp::lsp_types::SemanticToken;
+use tower_lsp::lsp_types::SemanticTokenModifier;
+use tower_lsp::lsp_types::SemanticTokenType;
+use tower_lsp::lsp_types::SemanticTokens;
+use tower_lsp::lsp_types::SemanticTokensDelta;
+use tower_lsp::lsp_types::SemanticTokensEdit;
+use tower_lsp::lsp_types::SemanticTokensFullDeltaResult;
+use tower_lsp::lsp_types::SemanticTokensLegend;
+use tree_sitter::Node;
+use tree_sitter::Point;
+use url::Url;
+
+use crate::lsp::document::Document;
+use crate::lsp::indexer;
+use crate::lsp::rename::collect_bindings;
+use crate::lsp::rename::symbol_name;
+use crate::lsp::search_path::search_path;
+use crate::lsp::state::WorldState;
+use crate::lsp::traits::node::NodeExt;
+use crate::treesitter::node_is_pipe_rhs;
+use crate::treesitter::BinaryOperatorType;
+use crate::treesitter::NodeType;
+use crate::treesitter::NodeTypeExt;
+
+/// Token types, in the order of the legend sent to the client
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u32)]
+pub(crate) enum TokenType {
+    Namespace = 0,
+    Function = 1,
+    Parameter = 2,
+    Variable = 3,
+    Property = 4,
+    Decorator = 5,
+    Comment = 6,
+}
+
+const TOKEN_TYPES: [SemanticTokenType; 7] = [
+    SemanticTokenType::NAMESPACE,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::DECORATOR,
+    SemanticTokenType::COMMENT,
+];
+
+/// Token modifiers, as bits of the modifiers set. The order must match the
+/// legend sent to the client.
+pub(crate) const MODIFIER_DECLARATION: u32 = 1 << 0;
+pub(crate) const MODIFIER_DEFAULT_LIBRARY: u32 = 1 << 1;
+pub(crate) const MODIFIER_DOCUMENTATION: u32 = 1 << 2;
+
+const TOKEN_MODIFIERS: [SemanticTokenModifier; 3] = [
+    SemanticTokenModifier::DECLARATION,
+    SemanticTokenModifier::DEFAULT_LIBRARY,
+    SemanticTokenModifier::DOCUMENTATION,
+];
+
+pub(crate) fn semantic_tokens_legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TOKEN_TYPES.to_vec(),
+        token_modifiers: TOKEN_MODIFIERS.to_vec(),
+    }
+}
+
+/// The package providing the data-masking verbs of `NSE_VERBS`
+const NSE_PACKAGE: &str = "dplyr";
+
+/// dplyr verbs whose arguments refer to data frame columns through
+/// non-standard evaluation
+const NSE_VERBS: &[&str] = &[
+    "arrange",
+    "count",
+    "distinct",
+    "filter",
+    "group_by",
+    "mutate",
+    "pull",
+    "relocate",
+    "rename",
+    "select",
+    "slice_max",
+    "slice_min",
+    "summarise",
+    "summarize",
+    "transmute",
+];
+
+static RE_ROXYGEN_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^#+'\s*(@\w+)(?:\s+([\w.,]+))?").unwrap());
+
+/// The last tokens sent for each document, so we can compute deltas
+#[derive(Debug, Default)]
+pub(crate) struct SemanticTokensCache {
+    next_result_id: u64,
+    documents: HashMap<Url, SemanticTokens>,
+}
+
+impl SemanticTokensCache {
+    /// Store the tokens of a document and return them tagged with a fresh
+    /// result ID
+    pub(crate) fn insert(&mut self, uri: Url, data: Vec<SemanticToken>) -> SemanticTokens {
+        self.next_result_id += 1;
+
+        let tokens = SemanticTokens {
+            result_id: Some(self.next_result_id.to_string()),
+            data,
+        };
+        self.documents.insert(uri, tokens.clone());
+
+        tokens
+    }
+
+    /// Store the tokens of a document and return the edits from the tokens
+    /// identified by `previous_result_id`. If these tokens are no longer
+    /// cached, the full set of tokens is returned instead.
+    pub(crate) fn insert_delta(
+        &mut self,
+        uri: Url,
+        previous_result_id: &str,
+        data: Vec<SemanticToken>,
+    ) -> SemanticTokensFullDeltaResult {
+        let previous = self
+            .documents
+            .get(&uri)
+            .filter(|tokens| tokens.result_id.as_deref() == Some(previous_result_id))
+            .map(|tokens| tokens.data.clone());
+
+        let tokens = self.insert(uri, data);
+
+        let Some(previous) = previous else {
+            return SemanticTokensFullDeltaResult::Tokens(tokens);
+        };
+
+        SemanticTokensFullDeltaResult::TokensDelta(SemanticTokensDelta {
+            result_id: tokens.result_id,
+            edits: semantic_tokens_edits(&previous, &tokens.data),
+        })
+    }
+
+    pub(crate) fn remove(&mut self, uri: &Url) {
+        self.documents.remove(uri);
+    }
+}
+
+/// Compute a single edit replacing the tokens that differ between `old` and
+/// `new`. Edits are expressed in terms of the flattened integer array, where
+/// each token takes up 5 integers.
+fn semantic_tokens_edits(old: &[SemanticToken], new: &[SemanticToken]) -> Vec<SemanticTokensEdit> {
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|(old, new)| old == new)
+        .count();
+
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+
+    let deleted = old.len() - prefix - suffix;
+    let inserted = &new[prefix..new.len() - suffix];
+
+    if deleted == 0 && inserted.is_empty() {
+        return Vec::new();
+    }
+
+    vec![SemanticTokensEdit {
+        start: (prefix * 5) as u32,
+        delete_count: (deleted * 5) as u32,
+        data: Some(inserted.to_vec()),
+    }]
+}
+
+/// Compute the semantic tokens of a document, encoded relative to each other
+/// as required by the protocol
+pub(crate) fn semantic_tokens(
+    document: &Document,
+    state: &WorldState,
+) -> anyhow::Result<Vec<SemanticToken>> {
+    let mut collector = TokenCollector::new(document, state);
+    collector.collect()?;
+
+    let mut tokens = collector.tokens;
+    tokens.sort_by_key(|token| (token.start.row, token.start.column));
+
+    let mut data = Vec::with_capacity(tokens.len());
+    let mut previous_line = 0;
+    let mut previous_start = 0;
+
+    for token in tokens {
+        // Multiline tokens are not supported by all clients
+        if token.start.row != token.end.row {
+            continue;
+        }
+
+        let start = document.lsp_position_from_tree_sitter_point(token.start)?;
+        let end = document.lsp_position_from_tree_sitter_point(token.end)?;
+
+        let delta_line = start.line - previous_line;
+        let delta_start = if delta_line == 0 {
+            start.character - previous_start
+        } else {
+            start.character
+        };
+
+        data.push(SemanticToken {
+            delta_line,
+            delta_start,
+            length: end.character - start.character,
+            token_type: token.token_type as u32,
+            token_modifiers_bitset: token.modifiers,
+        });
+
+        previous_line = start.line;
+        previous_start = start.character;
+    }
+
+    Ok(data)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RawToken {
+    start: Point,
+    end: Point,
+    token_type: TokenType,
+    modifiers: u32,
+}
+
+/// Symbols bound in a scope
+#[derive(Debug, Default)]
+struct Frame {
+    parameters: HashSet<String>,
+    locals: HashSet<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Binding {
+    Parameter,
+    Local,
+}
+
+struct TokenCollector<'a> {
+    document: &'a Document,
+    contents: &'a str,
+    state: &'a WorldState,
+    search_path: Vec<String>,
+    frames: Vec<Frame>,
+    tokens: Vec<RawToken>,
+}
+
+impl<'a> TokenCollector<'a> {
+    fn new(document: &'a Document, state: &'a WorldState) -> Self {
+        Self {
+            document,
+            contents: document.contents.as_str(),
+            state,
+            search_path: search_path(document, state),
+            frames: Vec::new(),
+            tokens: Vec::new(),
+        }
+    }
+
+    fn collect(&mut self) -> anyhow::Result<()> {
+        let root = self.document.ast.root_node();
+
+        let mut frame = Frame::default();
+        collect_bindings(root, self.contents, &mut frame.locals);
+        self.frames.push(frame);
+
+        self.visit(root, false)
+    }
+
+    fn push(&mut self, node: Node, token_type: TokenType, modifiers: u32) {
+        self.tokens.push(RawToken {
+            start: node.start_position(),
+            end: node.end_position(),
+            token_type,
+            modifiers,
+        });
+    }
+
+    fn lookup(&self, name: &str) -> Option<Binding> {
+        for frame in self.frames.iter().rev() {
+            if frame.parameters.contains(name) {
+                return Some(Binding::Parameter);
+            }
+            if frame.locals.contains(name) {
+                return Some(Binding::Local);
+            }
+        }
+        None
+    }
+
+    /// Visit a node. `nse` is set when the node is inside an argument of a
+    /// data-masking function, where unbound symbols refer to columns.
+    fn visit(&mut self, node: Node, nse: bool) -> anyhow::Result<()> {
+        match node.node_type() {
+            NodeType::Comment => self.visit_comment(node),
+            NodeType::Identifier => self.visit_identifier(node, nse),
+            NodeType::FunctionDefinition => self.visit_function_definition(node),
+            NodeType::Call => self.visit_call(node, nse),
+            NodeType::NamespaceOperator(_) => self.visit_namespace_operator(node),
+            NodeType::ExtractOperator(_) => self.visit_extract_operator(node, nse),
+            NodeType::BinaryOperator(
+                BinaryOperatorType::LeftAssignment |
+                BinaryOperatorType::LeftSuperAssignment |
+                BinaryOperatorType::EqualsAssignment,
+            ) => self.visit_assignment(node, "lhs", "rhs", nse),
+            NodeType::BinaryOperator(
+                BinaryOperatorType::RightAssignment | BinaryOperatorType::RightSuperAssignment,
+            ) => self.visit_assignment(node, "rhs", "lhs", nse),
+            _ => self.visit_children(node, nse),
+        }
+    }
+
+    fn visit_children(&mut self, node: Node, nse: bool) -> anyhow::Result<()> {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.visit(child, nse)?;
+        }
+        Ok(())
+    }
+
+    fn visit_identifier(&mut self, node: Node, nse: bool) -> anyhow::Result<()> {
+        let name = symbol_name(node.node_as_str(self.contents)?);
+
+        match self.lookup(name) {
+            Some(Binding::Parameter) => self.push(node, TokenType::Parameter, 0),
+            Some(Binding::Local) => self.push(node, TokenType::Variable, 0),
+            None if nse => self.push(node, TokenType::Property, 0),
+            None => self.push(node, TokenType::Variable, 0),
+        }
+
+        Ok(())
+    }
+
+    fn visit_assignment(
+        &mut self,
+        node: Node,
+        target: &str,
+        value: &str,
+        nse: bool,
+    ) -> anyhow::Result<()> {
+        let target = node.child_by_field_name(target);
+        let value = node.child_by_field_name(value);
+
+        // Highlight `fn <- function() {}` as a function definition
+        if let (Some(target), Some(value)) = (target, value) {
+            if target.is_identifier() && value.is_function_definition() {
+                self.push(target, TokenType::Function, MODIFIER_DECLARATION);
+                return self.visit(value, nse);
+            }
+        }
+
+        self.visit_children(node, nse)
+    }
+
+    fn visit_function_definition(&mut self, node: Node) -> anyhow::Result<()> {
+        let mut frame = Frame::default();
+
+        let parameters = node.child_by_field_name("parameters");
+        let mut parameter_names = Vec::new();
+
+        if let Some(parameters) = parameters {
+            let mut cursor = parameters.walk();
+            for parameter in parameters.children_by_field_name("parameter", &mut cursor) {
+                let Some(name) = parameter.child_by_field_name("name") else {
+                    continue;
+                };
+                if name.is_identifier() {
+                    let text = symbol_name(name.node_as_str(self.contents)?);
+                    frame.parameters.insert(text.to_string());
+                    parameter_names.push(name);
+                }
+            }
+        }
+
+        if let Some(body) = node.child_by_field_name("body") {
+            collect_bindings(body, self.contents, &mut frame.locals);
+        }
+
+        self.frames.push(frame);
+        let result = self.visit_function_scope(node, parameter_names);
+        self.frames.pop();
+
+        result
+    }
+
+    fn visit_function_scope(
+        &mut self,
+        node: Node,
+        parameter_names: Vec<Node>,
+    ) -> anyhow::Result<()> {
+        for name in parameter_names {
+            self.push(name, TokenType::Parameter, MODIFIER_DECLARATION);
+        }
+
+        // Default values, evaluated in the function scope
+        if let Some(parameters) = node.child_by_field_name("parameters") {
+            let mut cursor = parameters.walk();
+            for parameter in parameters.children_by_field_name("parameter", &mut cursor) {
+                if let Some(default) = parameter.child_by_field_name("default") {
+                    self.visit(default, false)?;
+                }
+            }
+        }
+
+        if let Some(body) = node.child_by_field_name("body") {
+            self.visit(body, false)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_call(&mut self, node: Node, nse: bool) -> anyhow::Result<()> {
+        let Some(callee) = node.child_by_field_name("function") else {
+            return self.visit_children(node, nse);
+        };
+
+        // The called function and the package it comes from, if any
+        let mut verb = None;
+        let mut package = None;
+
+        if callee.is_identifier() {
+            let name = symbol_name(callee.node_as_str(self.contents)?);
+            let modifiers = if self.is_local_function(name) {
+                0
+            } else {
+                verb = Some(name);
+                package = self.resolve_package(name);
+                MODIFIER_DEFAULT_LIBRARY
+            };
+            self.push(callee, TokenType::Function, modifiers);
+        } else {
+            if callee.is_namespace_operator() {
+                verb = callee
+                    .child_by_field_name("rhs")
+                    .and_then(|rhs| rhs.node_as_str(self.contents).ok());
+                package = callee
+                    .child_by_field_name("lhs")
+                    .and_then(|lhs| lhs.node_to_string(self.contents).ok());
+            }
+            self.visit(callee, nse)?;
+        }
+
+        // Verbs of the same name in other packages, e.g. `stats::filter()`,
+        // evaluate their arguments normally
+        let is_nse_verb = package.as_deref() == Some(NSE_PACKAGE) &&
+            verb.is_some_and(|verb| NSE_VERBS.contains(&verb));
+
+        let Some(arguments) = node.child_by_field_name("arguments") else {
+            return Ok(());
+        };
+
+        // When the call is not the right-hand side of a pipe, the first
+        // unnamed argument is the data frame rather than a column expression
+        let mut skip_data = is_nse_verb && !node_is_pipe_rhs(&node, self.contents);
+
+        let mut cursor = arguments.walk();
+        for argument in arguments.children(&mut cursor) {
+            if !argument.is_argument() {
+                self.visit(argument, nse)?;
+                continue;
+            }
+
+            let name = argument.child_by_field_name("name");
+
+            if let Some(name) = name {
+                if is_nse_verb && name.is_identifier() {
+                    self.push(name, TokenType::Property, 0);
+                }
+            }
+
+            let Some(value) = argument.child_by_field_name("value") else {
+                continue;
+            };
+
+            if skip_data && name.is_none() {
+                skip_data = false;
+                self.visit(value, nse)?;
+                continue;
+            }
+
+            self.visit(value, nse || is_nse_verb)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_namespace_operator(&mut self, node: Node) -> anyhow::Result<()> {
+        if let Some(lhs) = node.child_by_field_name("lhs") {
+            self.push(lhs, TokenType::Namespace, 0);
+        }
+
+        if let Some(rhs) = node.child_by_field_name("rhs") {
+            let callee = node
+                .parent()
+                .filter(|parent| parent.is_call())
+                .and_then(|parent| parent.child_by_field_name("function"));
+
+            let token_type = if callee == Some(node) {
+                TokenType::Function
+            } else {
+                TokenType::Variable
+            };
+            self.push(rhs, token_type, MODIFIER_DEFAULT_LIBRARY);
+        }
+
+        Ok(())
+    }
+
+    fn visit_extract_operator(&mut self, node: Node, nse: bool) -> anyhow::Result<()> {
+        if let Some(lhs) = node.child_by_field_name("lhs") {
+            self.visit(lhs, nse)?;
+        }
+
+        if let Some(rhs) = node.child_by_field_name("rhs") {
+            if rhs.is_identifier() {
+                self.push(rhs, TokenType::Property, 0);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn visit_comment(&mut self, node: Node) -> anyhow::Result<()> {
+        let comment = node.node_as_str(self.contents)?;
+        let start = node.start_position();
+
+        let point = |offset: usize| Point::new(start.row, start.column + offset);
+
+        if let Some(captures) = RE_ROXYGEN_TAG.captures(comment) {
+            if let Some(tag) = captures.get(1) {
+                self.tokens.push(RawToken {
+                    start: point(tag.start()),
+                    end: point(tag.end()),
+                    token_type: TokenType::Decorator,
+                    modifiers: MODIFIER_DOCUMENTATION,
+                });
+
+                // Documented parameters, possibly several of them separated
+                // by commas
+                if let (Some(names), "@param") = (captures.get(2), tag.as_str()) {
+                    let mut offset = names.start();
+                    for name in names.as_str().split(',') {
+                        if !name.is_empty() {
+                            self.tokens.push(RawToken {
+                                start: point(offset),
+                                end: point(offset + name.len()),
+                                token_type: TokenType::Parameter,
+                                modifiers: MODIFIER_DOCUMENTATION,
+                            });
+                        }
+                        offset += name.len() + 1;
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        if let Some(captures) = indexer::RE_COMMENT_SECTION.captures(comment) {
+            // Skip things that look like knitr output
+            let is_section = captures
+                .get(2)
+                .is_some_and(|title| !title.as_str().starts_with("----"));
+
+            if is_section {
+                self.push(node, TokenType::Comment, MODIFIER_DECLARATION);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Find the package exporting `name` on the search path of the document
+    fn resolve_package(&self, name: &str) -> Option<String> {
+        self.search_path
+            .iter()
+            .find(|package| {
+                self.state.library.get(package).is_some_and(|package| {
+                    package.exported_symbols.iter().any(|symbol| symbol == name)
+                })
+            })
+            .cloned()
+    }
+
+    /// Is this function defined in the document or in the workspace, rather
+    /// than in a package?
+    fn is_local_function(&self, name: &str) -> bool {
+        if self.lookup(name).is_some() {
+            return true;
+        }
+
+        matches!(
+            indexer::find(name),
+            Some((_, entry)) if matches!(
+                entry.data,
+                indexer::IndexEntryData::Function { .. } | indexer::IndexEntryData::Variable { .. }
+            )
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::lsp::indexer::ResetIndexerGuard;
+    use crate::lsp::inputs::library::Library;
+    use crate::lsp::inputs::package::Package;
+    use crate::lsp::inputs::package_description::Dcf;
+    use crate::lsp::inputs::package_description::Description;
+    use crate::lsp::inputs::package_namespace::Namespace;
+
+    fn mock_package(name: &str, exports: &[&str]) -> Package {
+        let namespace = Namespace {
+            exports: exports.iter().map(|export| export.to_string()).collect(),
+            imports: vec![],
+            package_imports: vec![],
+        };
+        let description = Description {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            depends: vec![],
+            fields: Dcf::new(),
+        };
+        let path = PathBuf::from(format!("/mock/{name}"));
+        Package::from_parts(path, description, namespace)
+    }
+
+    // `filter()` is exported by both dplyr and stats
+    fn mock_state() -> WorldState {
+        let mut state = WorldState::default();
+        state.library = Library::default()
+            .insert("dplyr", mock_package("dplyr", &["filter", "mutate"]))
+            .insert("stats", mock_package("stats", &["filter"]));
+        state
+    }
+
+    // Decode tokens into `(text, type, modifiers)` triples
+    fn tokens(code: &str) -> Vec<(String, TokenType, u32)> {
+        let _guard = ResetIndexerGuard;
+
+        let document = Document::new(code, None);
+        let data = semantic_tokens(&document, &mock_state()).unwrap();
+
+        let lines: Vec<&str> = code.lines().collect();
+        let mut line = 0;
+        let mut start = 0;
+
+        data.into_iter()
+            .map(|token| {
+                line += token.delta_line as usize;
+                if token.delta_line == 0 {
+                    start += token.delta_start as usize;
+                } else {
+                    start = token.delta_start as usize;
+                }
+
+                let text = lines[line][start..start + token.length as usize].to_string();
+                let token_type = TOKEN_TYPES_ORDER[token.token_type as usize];
+                (text, token_type, token.token_modifiers_bitset)
+            })
+            .collect()
+    }
+
+    const TOKEN_TYPES_ORDER: [TokenType; 7] = [
+        TokenType::Namespace,
+        TokenType::Function,
+        TokenType::Parameter,
+        TokenType::Variable,
+        TokenType::Property,
+        TokenType::Decorator,
+        TokenType::Comment,
+    ];
+
+    fn token(text: &str, token_type: TokenType, modifiers: u32) -> (String, TokenType, u32) {
+        (text.to_string(), token_type, modifiers)
+    }
+
+    #[test]
+    fn test_semantic_tokens_function_definitions_and_parameters() {
+        let code = "f <- function(x, y = x) {\n  z <- x + y\n  g(z)\n}";
+
+        assert_eq!(tokens(code), vec![
+            token("f", TokenType::Function, MODIFIER_DECLARATION),
+            token("x", TokenType::Parameter, MODIFIER_DECLARATION),
+            token("y", TokenType::Parameter, MODIFIER_DECLARATION),
+            token("x", TokenType::Parameter, 0),
+            token("z", TokenType::Variable, 0),
+            token("x", TokenType::Parameter, 0),
+            token("y", TokenType::Parameter, 0),
+            token("g", TokenType::Function, MODIFIER_DEFAULT_LIBRARY),
+            token("z", TokenType::Variable, 0),
+        ]);
+    }
+
+    #[test]
+    fn test_semantic_tokens_local_vs_package_calls() {
+        let code = "helper <- function() 1\nhelper()\nfilter(x)\nstats::filter(x)";
+
+        assert_eq!(tokens(code), vec![
+            token("helper", TokenType::Function, MODIFIER_DECLARATION),
+            token("helper", TokenType::Function, 0),
+            token("filter", TokenType::Function, MODIFIER_DEFAULT_LIBRARY),
+            token("x", TokenType::Variable, 0),
+            token("stats", TokenType::Namespace, 0),
+            token("filter", TokenType::Function, MODIFIER_DEFAULT_LIBRARY),
+            token("x", TokenType::Variable, 0),
+        ]);
+    }
+
+    #[test]
+    fn test_semantic_tokens_nse_columns() {
+        let code = "
+library(dplyr)
+threshold <- 1
+df |> filter(value > threshold)
+mutate(df, new = old * 2)
+";
+
+        assert_eq!(tokens(code), vec![
+            token("library", TokenType::Function, MODIFIER_DEFAULT_LIBRARY),
+            token("dplyr", TokenType::Variable, 0),
+            token("threshold", TokenType::Variable, 0),
+            token("df", TokenType::Variable, 0),
+            token("filter", TokenType::Function, MODIFIER_DEFAULT_LIBRARY),
+            token("value", TokenType::Property, 0),
+            token("threshold", TokenType::Variable, 0),
+            token("mutate", TokenType::Function, MODIFIER_DEFAULT_LIBRARY),
+            token("df", TokenType::Variable, 0),
+            token("new", TokenType::Property, 0),
+            token("old", TokenType::Property, 0),
+        ]);
+    }
+
+    #[test]
+    fn test_semantic_tokens_nse_columns_namespaced() {
+        // Without dplyr attached, `filter()` is `stats::filter()`
+        let code = "filter(df, value)\nstats::filter(df, value)\ndplyr::filter(df, value)";
+
+        assert_eq!(tokens(code), vec![
+            token("filter", TokenType::Function, MODIFIER_DEFAULT_LIBRARY),
+            token("df", TokenType::Variable, 0),
+            token("value", TokenType::Variable, 0),
+            token("stats", TokenType::Namespace, 0),
+            token("filter", TokenType::Function, MODIFIER_DEFAULT_LIBRARY),
+            token("df", TokenType::Variable, 0),
+            token("value", TokenType::Variable, 0),
+            token("dplyr", TokenType::Namespace, 0),
+            token("filter", TokenType::Function, MODIFIER_DEFAULT_LIBRARY),
+            token("df", TokenType::Variable, 0),
+            token("value", TokenType::Property, 0),
+        ]);
+    }
+
+    #[test]
+    fn test_semantic_tokens_comments() {
+        let code = "# Section ----\n#' Title\n#' @param x,y Values.\n#' @export\n# Plain comment";
+
+        assert_eq!(tokens(code), vec![
+            token("# Section ----", TokenType::Comment, MODIFIER_DECLARATION),
+            token("@param", TokenType::Decorator, MODIFIER_DOCUMENTATION),
+            token("x", TokenType::Parameter, MODIFIER_DOCUMENTATION),
+            token("y", TokenType::Parameter, MODIFIER_DOCUMENTATION),
+            token("@export", TokenType::Decorator, MODIFIER_DOCUMENTATION),
+        ]);
+    }
+
+    #[test]
+    fn test_semantic_tokens_delta() {
+        let uri = crate::lsp::util::test_path("test.R");
+        let mut cache = SemanticTokensCache::default();
+        let state = WorldState::default();
+
+        let old = semantic_tokens(&Document::new("a <- 1\nb <- 2\nc <- 3", None), &state).unwrap();
+        let new = semantic_tokens(&Document::new("a <- 1\nbb <- 2\nc <- 3", None), &state).unwrap();
+
+        let tokens = cache.insert(uri.clone(), old);
+        let result_id = tokens.result_id.unwrap();
+
+        let SemanticTokensFullDeltaResult::TokensDelta(delta) =
+            cache.insert_delta(uri.clone(), &result_id, new.clone())
+        else {
+            panic!("Expected a delta");
+        };
+
+        assert_ne!(delta.result_id, Some(result_id));
+        assert_eq!(delta.edits.len(), 1);
+        assert_eq!(delta.edits[0].start, 5);
+        assert_eq!(delta.edits[0].delete_count, 5);
+        assert_eq!(delta.edits[0].data, Some(vec![new[1]]));
+
+        // Unknown result IDs fall back to full tokens
+        let result = cache.insert_delta(uri, "unknown", new);
+        assert!(matches!(result, SemanticTokensFullDeltaResult::Tokens(_)));
+    }
+}
@@ -20,15 +20,21 @@ use log::info;
 use stdext::unwrap;
 use stdext::unwrap::IntoResult;
 use tower_lsp::lsp_types::Documentation;
+use tower_lsp::lsp_types::MarkupContent;
+use tower_lsp::lsp_types::MarkupKind;
 use tower_lsp::lsp_types::ParameterInformation;
 use tower_lsp::lsp_types::ParameterLabel;
 use tower_lsp::lsp_types::SignatureHelp;
 use tower_lsp::lsp_types::SignatureInformation;
 use tree_sitter::Node;
 use tree_sitter::Point;
+use url::Url;
 
 use crate::lsp::document_context::DocumentContext;
 use crate::lsp::help::RHtmlHelp;
+use crate::lsp::roxygen::find_workspace_function;
+use crate::lsp::roxygen::render_rd;
+use crate::lsp::state::WorldState;
 use crate::lsp::traits::node::NodeExt;
 use crate::lsp::traits::point::PointExt;
 use crate::treesitter::NodeType;
@@ -39,8 +45,24 @@ use crate::treesitter::NodeTypeExt;
 // is the accumulation of a number of smaller changes that have resulted in something
 // that is a bit hard to follow.
 
-/// SAFETY: Requires access to the R runtime.
-pub(crate) fn r_signature_help(context: &DocumentContext) -> anyhow::Result<Option<SignatureHelp>> {
+/// The call surrounding the cursor and the arguments supplied to it so far
+struct CallArguments<'tree> {
+    /// The left-hand side of the call
+    callee: Node<'tree>,
+
+    /// The arguments that have been explicitly specified by name
+    explicit_parameters: Vec<String>,
+
+    /// The number of unnamed arguments that have been supplied
+    num_unnamed_arguments: i32,
+
+    /// The argument the cursor lies upon, if named
+    active_argument: Option<String>,
+}
+
+fn call_arguments<'tree>(
+    context: &DocumentContext<'tree>,
+) -> anyhow::Result<Option<CallArguments<'tree>>> {
     // Get document AST + completion position.
     let ast = &context.document.ast;
 
@@ -98,9 +120,6 @@ pub(crate) fn r_signature_help(context: &DocumentContext) -> anyhow::Result<Opti
     // Whether we've found the child node we were looking for.
     let mut found_child = false;
 
-    // The computed argument offset.
-    let mut offset: Option<u32> = None;
-
     let call = loop {
         // If we found an 'arguments' node, then use that to infer the current offset.
         if parent.node_type() == NodeType::Arguments {
@@ -161,6 +180,60 @@ pub(crate) fn r_signature_help(context: &DocumentContext) -> anyhow::Result<Opti
         return Ok(None);
     });
 
+    Ok(Some(CallArguments {
+        callee,
+        explicit_parameters,
+        num_unnamed_arguments,
+        active_argument,
+    }))
+}
+
+/// The index of the active parameter among the parameters `names`
+fn active_parameter(names: &[&str], call: &CallArguments) -> u32 {
+    // If we had an explicit name, and this name matches a parameter, then
+    // that's the active one
+    if let Some(index) = names
+        .iter()
+        .position(|name| call.active_argument.as_deref() == Some(*name))
+    {
+        return index as u32;
+    }
+
+    let mut num_unnamed_arguments = call.num_unnamed_arguments;
+
+    for (index, name) in names.iter().enumerate() {
+        // Was this argument explicitly provided? If so, skip it.
+        if call
+            .explicit_parameters
+            .iter()
+            .any(|explicit| explicit == name)
+        {
+            continue;
+        }
+
+        // Otherwise, check and see if we have any remaining commas.
+        if num_unnamed_arguments > 0 {
+            num_unnamed_arguments -= 1;
+            continue;
+        }
+
+        // This is the argument.
+        return index as u32;
+    }
+
+    // NOTE: It seems like the frontend still tries to highlight the first
+    // parameter when the offset is set to none, so here we just force it to
+    // match no available argument.
+    (names.len() + 1).try_into().unwrap_or_default()
+}
+
+/// SAFETY: Requires access to the R runtime.
+pub(crate) fn r_signature_help(context: &DocumentContext) -> anyhow::Result<Option<SignatureHelp>> {
+    let Some(call) = call_arguments(context)? else {
+        return Ok(None);
+    };
+    let callee = call.callee;
+
     // TODO: Should we search the document and / or the workspace index
     // before asking the R session for a definition? Which should take precedence?
 
@@ -226,7 +299,7 @@ pub(crate) fn r_signature_help(context: &DocumentContext) -> anyhow::Result<Opti
     let mut parameters = vec![];
 
     // Iterate over the documentation for each parameter, and add the relevant information.
-    for (index, argument) in formals.iter().enumerate() {
+    for argument in formals.iter() {
         // Get argument components.
         let argument_name = &argument.name;
         let argument_value = &argument.value;
@@ -240,12 +313,6 @@ pub(crate) fn r_signature_help(context: &DocumentContext) -> anyhow::Result<Opti
         label.push_str(argument_label.as_str());
         label.push_str(", ");
 
-        // If we had an explicit name, and this name matches the argument,
-        // then update the offset now.
-        if active_argument.as_ref() == Some(argument_name) {
-            offset = Some(index as u32);
-        }
-
         // Get documentation, if any.
         let mut documentation = None;
         if let Ok(Some(ref help)) = help {
@@ -271,32 +338,11 @@ pub(crate) fn r_signature_help(context: &DocumentContext) -> anyhow::Result<Opti
     // Add a closing parenthesis.
     label.push(')');
 
-    // Finally, if we don't have an offset, figure it out now.
-    if offset.is_none() {
-        for (index, argument) in formals.iter().enumerate() {
-            // Was this argument explicitly provided? If so, skip it.
-            if explicit_parameters.contains(&argument.name) {
-                continue;
-            }
-
-            // Otherwise, check and see if we have any remaining commas.
-            if num_unnamed_arguments > 0 {
-                num_unnamed_arguments -= 1;
-                continue;
-            }
-
-            // This is the argument.
-            offset = Some(index as u32);
-            break;
-        }
-    }
-
-    // NOTE: It seems like the frontend still tries to highlight the first
-    // parameter when the offset is set to none, so here we just force it to
-    // match no available argument.
-    if offset.is_none() {
-        offset = Some((formals.len() + 1).try_into().unwrap_or_default());
-    }
+    let names: Vec<&str> = formals
+        .iter()
+        .map(|argument| argument.name.as_str())
+        .collect();
+    let offset = Some(active_parameter(&names, &call));
 
     let signature = SignatureInformation {
         label,
@@ -315,6 +361,115 @@ pub(crate) fn r_signature_help(context: &DocumentContext) -> anyhow::Result<Opti
     Ok(Some(help))
 }
 
+/// Signature help for functions defined in the workspace, documented with the
+/// `@param` descriptions of their roxygen comments. Used when the function
+/// can't be resolved in the R session, e.g. without a session.
+pub(crate) fn workspace_signature_help(
+    context: &DocumentContext,
+    uri: &Url,
+    state: &WorldState,
+) -> anyhow::Result<Option<SignatureHelp>> {
+    let Some(call) = call_arguments(context)? else {
+        return Ok(None);
+    };
+    if !call.callee.is_identifier() {
+        return Ok(None);
+    }
+
+    let name = call.callee.node_as_str(&context.document.contents)?;
+    let Some(function) = find_workspace_function(name, uri, state)? else {
+        return Ok(None);
+    };
+
+    let mut label = format!("{name}(");
+    let mut parameters = vec![];
+
+    for parameter in function.parameters.iter() {
+        let start = label.len() as u32;
+        label.push_str(parameter);
+        let end = label.len() as u32;
+        label.push_str(", ");
+
+        parameters.push(ParameterInformation {
+            label: ParameterLabel::LabelOffsets([start, end]),
+            documentation: None,
+        });
+    }
+
+    if label.ends_with(", ") {
+        label.truncate(label.len() - 2);
+    }
+    label.push(')');
+
+    // Labels are either `name` or `name = default`
+    let names: Vec<&str> = function
+        .parameters
+        .iter()
+        .map(|parameter| parameter.split(" = ").next().unwrap_or(parameter))
+        .collect();
+    let offset = Some(active_parameter(&names, &call));
+
+    let mut help = SignatureHelp {
+        signatures: vec![SignatureInformation {
+            label,
+            documentation: None,
+            parameters: Some(parameters),
+            active_parameter: offset,
+        }],
+        active_signature: None,
+        active_parameter: offset,
+    };
+    add_roxygen_documentation(&mut help, uri, state);
+
+    Ok(Some(help))
+}
+
+/// Document the parameters of functions defined in the workspace with the
+/// `@param` descriptions of their roxygen comments, when there is no help
+/// page to document them
+pub(crate) fn add_roxygen_documentation(help: &mut SignatureHelp, uri: &Url, state: &WorldState) {
+    for signature in help.signatures.iter_mut() {
+        let Some(parameters) = signature.parameters.as_mut() else {
+            continue;
+        };
+        if parameters
+            .iter()
+            .any(|parameter| parameter.documentation.is_some())
+        {
+            continue;
+        }
+
+        let Some((name, _)) = signature.label.split_once('(') else {
+            continue;
+        };
+        let Ok(Some(function)) = find_workspace_function(name, uri, state) else {
+            continue;
+        };
+        let Some(roxygen) = function.roxygen else {
+            continue;
+        };
+
+        for parameter in parameters.iter_mut() {
+            let ParameterLabel::LabelOffsets([start, end]) = parameter.label else {
+                continue;
+            };
+            let Some(label) = signature.label.get(start as usize..end as usize) else {
+                continue;
+            };
+
+            // Labels are either `name` or `name = default`
+            let name = label.split(" = ").next().unwrap_or(label);
+
+            if let Some(description) = roxygen.param(name) {
+                parameter.documentation = Some(Documentation::MarkupContent(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: render_rd(description),
+                }));
+            }
+        }
+    }
+}
+
 fn is_within_call_parentheses(x: &Point, node: &Node) -> bool {
     if node.node_type() != NodeType::Call {
         // This would be very weird
@@ -499,6 +654,7 @@ fn call_label(x: SEXP) -> String {
 
 #[cfg(test)]
 mod tests {
+    use assert_matches::assert_matches;
     use harp::call::RCall;
     use harp::object::*;
     use harp::r_char;
@@ -506,13 +662,23 @@ mod tests {
     use harp::r_symbol;
     use harp::RObject;
     use libr::R_xlen_t;
+    use tower_lsp::lsp_types::Documentation;
+    use tower_lsp::lsp_types::ParameterInformation;
     use tower_lsp::lsp_types::ParameterLabel;
+    use tower_lsp::lsp_types::SignatureHelp;
+    use tower_lsp::lsp_types::SignatureInformation;
 
     use crate::fixtures::point_from_cursor;
     use crate::lsp::document::Document;
     use crate::lsp::document_context::DocumentContext;
+    use crate::lsp::indexer;
+    use crate::lsp::indexer::ResetIndexerGuard;
+    use crate::lsp::signature_help::add_roxygen_documentation;
     use crate::lsp::signature_help::argument_label;
     use crate::lsp::signature_help::r_signature_help;
+    use crate::lsp::signature_help::workspace_signature_help;
+    use crate::lsp::state::WorldState;
+    use crate::lsp::util::test_path;
 
     #[test]
     fn test_basic_signature_help() {
@@ -532,6 +698,96 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_signature_help_roxygen_documentation() {
+        let _guard = ResetIndexerGuard;
+
+        let code = "
+#' @param x A vector.
+#' @param by The \\code{step}.
+my_seq <- function(x, by = 1) NULL
+";
+        let uri = test_path("test.R");
+        let document = Document::new(code, None);
+        indexer::update(&document, &uri).unwrap();
+
+        let mut state = WorldState::default();
+        state.documents.insert(uri.clone(), document);
+
+        let parameter = |start, end| ParameterInformation {
+            label: ParameterLabel::LabelOffsets([start, end]),
+            documentation: None,
+        };
+        let mut help = SignatureHelp {
+            signatures: vec![SignatureInformation {
+                label: String::from("my_seq(x, by = 1)"),
+                documentation: None,
+                parameters: Some(vec![parameter(7, 8), parameter(10, 16)]),
+                active_parameter: None,
+            }],
+            active_signature: None,
+            active_parameter: None,
+        };
+
+        add_roxygen_documentation(&mut help, &uri, &state);
+
+        let documentation: Vec<String> = help.signatures[0]
+            .parameters
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|parameter| match &parameter.documentation {
+                Some(Documentation::MarkupContent(markup)) => markup.value.clone(),
+                _ => String::new(),
+            })
+            .collect();
+        assert_eq!(documentation, vec!["A vector.", "The `step`."]);
+    }
+
+    #[test]
+    fn test_workspace_signature_help() {
+        let _guard = ResetIndexerGuard;
+
+        let code = "
+#' @param x A vector.
+my_seq <- function(x, by = 1) NULL
+";
+        let uri = test_path("test.R");
+        let document = Document::new(code, None);
+        indexer::update(&document, &uri).unwrap();
+
+        let mut state = WorldState::default();
+        state.documents.insert(uri.clone(), document);
+
+        let (text, point) = point_from_cursor("my_seq(1, @)");
+        let document = Document::new(&text, None);
+        let context = DocumentContext::new(&document, point, None);
+
+        // No R session involved
+        let help = workspace_signature_help(&context, &uri, &state);
+        let help = help.unwrap().unwrap();
+        assert_eq!(help.active_parameter, Some(1));
+
+        let signature = &help.signatures[0];
+        assert_eq!(signature.label, "my_seq(x, by = 1)");
+
+        let parameters = signature.parameters.as_ref().unwrap();
+        assert_eq!(parameters[0].label, ParameterLabel::LabelOffsets([7, 8]));
+        assert_eq!(parameters[1].label, ParameterLabel::LabelOffsets([10, 16]));
+        assert_matches!(
+            &parameters[0].documentation,
+            Some(Documentation::MarkupContent(markup)) if markup.value == "A vector."
+        );
+        assert!(parameters[1].documentation.is_none());
+
+        // Unknown functions get no help
+        let (text, point) = point_from_cursor("unknown(@)");
+        let document = Document::new(&text, None);
+        let context = DocumentContext::new(&document, point, None);
+        let help = workspace_signature_help(&context, &uri, &state).unwrap();
+        assert!(help.is_none());
+    }
+
     #[test]
     fn test_no_signature_help_outside_parentheses() {
         crate::r_task(|| {
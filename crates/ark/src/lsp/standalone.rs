@@ -0,0 +1,152 @@
+//
+// standalone.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+// When the LSP runs without an R session (`ark --lsp`), the inputs that are
+// normally sent by the kernel (library paths, console scopes, installed
+// packages) are computed statically from the R installation instead.
+
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use harp::command::r_command;
+use harp::command::r_home_setup;
+
+use crate::lsp::inputs::library::Library;
+use crate::lsp::search_path::DEFAULT_PACKAGES;
+use crate::lsp::state_handlers::ConsoleInputs;
+
+/// Whether the LSP runs without an R session. Set once at startup.
+static LSP_STANDALONE: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn set_standalone() {
+    LSP_STANDALONE.store(true, Ordering::Release);
+}
+
+/// Features that need to evaluate R code must check this and degrade
+/// gracefully, since `r_task()` would block forever without an R session.
+pub(crate) fn is_standalone() -> bool {
+    LSP_STANDALONE.load(Ordering::Acquire)
+}
+
+/// Library paths, in the same order as `.libPaths()`
+pub(crate) fn library_paths() -> Vec<PathBuf> {
+    let paths = match r_library_paths() {
+        Ok(paths) => paths,
+        Err(err) => {
+            log::warn!("Can't get library paths from R, using environment variables: {err:?}");
+            env_library_paths()
+        },
+    };
+
+    let mut out: Vec<PathBuf> = Vec::new();
+    for path in paths {
+        if path.is_dir() && !out.contains(&path) {
+            out.push(path);
+        }
+    }
+
+    out
+}
+
+/// Ask a separate R process for its library paths. Unlike environment
+/// variables, this takes `.Renviron` and `.Rprofile` files into account.
+fn r_library_paths() -> anyhow::Result<Vec<PathBuf>> {
+    r_home_setup()?;
+
+    let output = r_command(|command| {
+        command
+            .arg("--no-echo")
+            .arg("--no-save")
+            .arg("--no-restore")
+            .arg("-e")
+            .arg("cat(.libPaths(), sep = '\\n')");
+    })?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!("R exited with status {}", output.status));
+    }
+
+    let stdout = String::from_utf8(output.stdout)?;
+    Ok(stdout.lines().map(PathBuf::from).collect())
+}
+
+/// Fallback when R can't be run
+fn env_library_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+
+    for var in ["R_LIBS", "R_LIBS_USER", "R_LIBS_SITE"] {
+        if let Some(value) = std::env::var_os(var) {
+            paths.extend(std::env::split_paths(&value));
+        }
+    }
+
+    if let Some(home) = std::env::var_os("R_HOME") {
+        paths.push(PathBuf::from(home).join("library"));
+    }
+
+    paths
+}
+
+/// Inputs standing in for the ones sent by the kernel after each top-level
+/// evaluation. The console scopes are the exports of the default packages.
+pub(crate) fn console_inputs(library: &Library) -> ConsoleInputs {
+    let console_scopes = DEFAULT_PACKAGES
+        .iter()
+        .filter_map(|name| library.get(name))
+        .map(|package| package.exported_symbols.clone())
+        .collect();
+
+    ConsoleInputs {
+        console_scopes,
+        installed_packages: installed_packages(library),
+    }
+}
+
+fn installed_packages(library: &Library) -> Vec<String> {
+    let mut packages = Vec::new();
+
+    for path in library.library_paths.iter() {
+        let Ok(entries) = std::fs::read_dir(path) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            if !entry.path().join("DESCRIPTION").is_file() {
+                continue;
+            }
+            if let Some(name) = entry.file_name().to_str() {
+                packages.push(name.to_string());
+            }
+        }
+    }
+
+    packages.sort();
+    packages.dedup();
+    packages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_installed_packages() {
+        let dir = tempfile::tempdir().unwrap();
+
+        for name in ["b", "a"] {
+            std::fs::create_dir(dir.path().join(name)).unwrap();
+            std::fs::write(dir.path().join(name).join("DESCRIPTION"), "").unwrap();
+        }
+
+        // Not a package
+        std::fs::create_dir(dir.path().join("c")).unwrap();
+
+        let library = Library::new(vec![dir.path().to_path_buf(), dir.path().to_path_buf()]);
+        assert_eq!(installed_packages(&library), vec!["a", "b"]);
+    }
+}
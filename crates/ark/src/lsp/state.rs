@@ -1,13 +1,21 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::path::Path;
+use std::path::PathBuf;
 
 use anyhow::anyhow;
 use url::Url;
+use walkdir::WalkDir;
 
+use crate::lsp;
 use crate::lsp::config::LspConfig;
+use crate::lsp::diagnostics_rules::ArkTomlFiles;
 use crate::lsp::document::Document;
+use crate::lsp::indexer::filter_entry;
 use crate::lsp::inputs::library::Library;
 use crate::lsp::inputs::source_root::SourceRoot;
+use crate::lsp::notebook::Notebook;
+use crate::url::ExtUrl;
 
 #[derive(Clone, Default, Debug)]
 /// The world state, i.e. all the inputs necessary for analysing or refactoring
@@ -17,6 +25,9 @@ pub(crate) struct WorldState {
     /// Watched documents
     pub(crate) documents: HashMap<Url, Document>,
 
+    /// Open notebooks, whose R cells are watched as documents
+    pub(crate) notebooks: HashMap<Url, Notebook>,
+
     /// Watched folders
     pub(crate) workspace: Workspace,
 
@@ -57,6 +68,10 @@ pub(crate) struct WorldState {
     pub(crate) library: Library,
 
     pub(crate) config: LspConfig,
+
+    /// Parsed `ark.toml` files of the workspace folders. Kept up to date by
+    /// the file watcher.
+    pub(crate) ark_toml: ArkTomlFiles,
 }
 
 #[derive(Clone, Default, Debug)]
@@ -80,6 +95,13 @@ impl WorldState {
             Err(anyhow!("Can't find document for URI {uri}"))
         }
     }
+
+    /// The notebook containing the cell `uri`, if any
+    pub(crate) fn get_notebook_of_cell(&self, uri: &Url) -> Option<&Notebook> {
+        self.notebooks
+            .values()
+            .find(|notebook| notebook.contains_cell(uri))
+    }
 }
 
 pub(crate) fn with_document<T, F>(
@@ -115,6 +137,104 @@ where
     return callback(document);
 }
 
+/// The paths where configuration files named after one of `names` are looked
+/// up for a document, from the closest to the farthest
+pub(crate) fn config_file_candidates(
+    uri: &Url,
+    state: &WorldState,
+    names: &[&str],
+) -> Vec<PathBuf> {
+    let Ok(path) = uri.to_file_path() else {
+        return Vec::new();
+    };
+
+    let Some(root) = state
+        .workspace
+        .folders
+        .iter()
+        .filter_map(|folder| folder.to_file_path().ok())
+        .find(|folder| path.starts_with(folder))
+    else {
+        return Vec::new();
+    };
+
+    path.ancestors()
+        .skip(1)
+        .take_while(|dir| dir.starts_with(&root))
+        .flat_map(|dir| names.iter().map(|name| dir.join(name)))
+        .collect()
+}
+
+/// Call `callback` on every R file of the workspace folders, then on open
+/// documents living outside of the workspace folders (e.g. untitled
+/// documents). Open documents are preferred over their on-disk contents.
+pub(crate) fn for_each_document<F>(state: &WorldState, mut callback: F)
+where
+    F: FnMut(&Url, &Document),
+{
+    let mut visited: HashSet<Url> = HashSet::new();
+
+    for_each_workspace_file(state, |path, uri| {
+        visited.insert(uri.clone());
+
+        let result = with_document(path, state, |document| {
+            callback(uri, document);
+            Ok(())
+        });
+        if let Err(err) = result {
+            lsp::log_warn!("Can't read file {}: {err:?}", path.display());
+        }
+    });
+
+    for (uri, document) in state.documents.iter() {
+        if ExtUrl::is_ark_virtual_doc(uri) || visited.contains(uri) {
+            continue;
+        }
+        callback(uri, document);
+    }
+}
+
+/// Call `callback` on the path and URI of every R file of the workspace
+/// folders, without reading them
+pub(crate) fn for_each_workspace_file<F>(state: &WorldState, mut callback: F)
+where
+    F: FnMut(&Path, &Url),
+{
+    let mut visited: HashSet<Url> = HashSet::new();
+
+    for folder in state.workspace.folders.iter() {
+        let Ok(folder) = folder.to_file_path() else {
+            continue;
+        };
+
+        let walker = WalkDir::new(folder);
+        for entry in walker.into_iter().filter_entry(|e| filter_entry(e)) {
+            let Ok(entry) = entry else {
+                continue;
+            };
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let path = entry.path();
+            let Some(ext) = path.extension() else {
+                continue;
+            };
+            if ext != "r" && ext != "R" {
+                continue;
+            }
+
+            let Ok(uri) = Url::from_file_path(path) else {
+                continue;
+            };
+            if !visited.insert(uri.clone()) {
+                continue;
+            }
+
+            callback(path, &uri);
+        }
+    }
+}
+
 pub(crate) fn workspace_uris(state: &WorldState) -> Vec<Url> {
     let uris: Vec<Url> = state.documents.iter().map(|elt| elt.0.clone()).collect();
     uris
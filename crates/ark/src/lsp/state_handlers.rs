@@ -8,13 +8,21 @@
 use anyhow::anyhow;
 use stdext::result::ResultExt;
 use tower_lsp::lsp_types;
+use tower_lsp::lsp_types::CallHierarchyServerCapability;
+use tower_lsp::lsp_types::CodeLensOptions;
 use tower_lsp::lsp_types::CompletionOptions;
 use tower_lsp::lsp_types::CompletionOptionsCompletionItem;
 use tower_lsp::lsp_types::CreateFilesParams;
 use tower_lsp::lsp_types::DeleteFilesParams;
+use tower_lsp::lsp_types::DiagnosticOptions;
+use tower_lsp::lsp_types::DiagnosticServerCapabilities;
 use tower_lsp::lsp_types::DidChangeConfigurationParams;
+use tower_lsp::lsp_types::DidChangeNotebookDocumentParams;
 use tower_lsp::lsp_types::DidChangeTextDocumentParams;
+use tower_lsp::lsp_types::DidChangeWatchedFilesParams;
+use tower_lsp::lsp_types::DidCloseNotebookDocumentParams;
 use tower_lsp::lsp_types::DidCloseTextDocumentParams;
+use tower_lsp::lsp_types::DidOpenNotebookDocumentParams;
 use tower_lsp::lsp_types::DidOpenTextDocumentParams;
 use tower_lsp::lsp_types::DocumentOnTypeFormattingOptions;
 use tower_lsp::lsp_types::ExecuteCommandOptions;
@@ -28,9 +36,15 @@ use tower_lsp::lsp_types::HoverProviderCapability;
 use tower_lsp::lsp_types::ImplementationProviderCapability;
 use tower_lsp::lsp_types::InitializeParams;
 use tower_lsp::lsp_types::InitializeResult;
+use tower_lsp::lsp_types::NotebookCellSelector;
+use tower_lsp::lsp_types::NotebookDocumentSyncOptions;
+use tower_lsp::lsp_types::NotebookSelector;
 use tower_lsp::lsp_types::OneOf;
 use tower_lsp::lsp_types::RenameFilesParams;
 use tower_lsp::lsp_types::SelectionRangeProviderCapability;
+use tower_lsp::lsp_types::SemanticTokensFullOptions;
+use tower_lsp::lsp_types::SemanticTokensOptions;
+use tower_lsp::lsp_types::SemanticTokensServerCapabilities;
 use tower_lsp::lsp_types::ServerCapabilities;
 use tower_lsp::lsp_types::ServerInfo;
 use tower_lsp::lsp_types::SignatureHelpOptions;
@@ -50,12 +64,17 @@ use crate::lsp::capabilities::Capabilities;
 use crate::lsp::config::indent_style_from_lsp;
 use crate::lsp::config::DOCUMENT_SETTINGS;
 use crate::lsp::config::GLOBAL_SETTINGS;
+use crate::lsp::diagnostics_rules::ArkTomlFiles;
 use crate::lsp::document::Document;
+use crate::lsp::embedded::is_embedded_document;
 use crate::lsp::inputs::package::Package;
 use crate::lsp::inputs::source_root::SourceRoot;
 use crate::lsp::main_loop::DidCloseVirtualDocumentParams;
 use crate::lsp::main_loop::DidOpenVirtualDocumentParams;
 use crate::lsp::main_loop::LspState;
+use crate::lsp::notebook::is_r_cell;
+use crate::lsp::notebook::Notebook;
+use crate::lsp::semantic_tokens::semantic_tokens_legend;
 use crate::lsp::state::workspace_uris;
 use crate::lsp::state::WorldState;
 use crate::url::ExtUrl;
@@ -127,8 +146,12 @@ pub(crate) fn initialize(
         }
     }
 
+    // Parse the `ark.toml` files once, they are then reloaded by the file
+    // watcher registered in `handle_initialized()`
+    state.ark_toml = ArkTomlFiles::load(&state.workspace.folders);
+
     // Start first round of indexing
-    lsp::main_loop::index_start(folders, state.clone());
+    lsp::main_loop::index_start(folders, state.clone(), &lsp_state.capabilities);
 
     Ok(InitializeResult {
         server_info: Some(ServerInfo {
@@ -165,6 +188,20 @@ pub(crate) fn initialize(
             type_definition_provider: None,
             implementation_provider: Some(ImplementationProviderCapability::Simple(true)),
             references_provider: Some(OneOf::Left(true)),
+            rename_provider: lsp_state.capabilities.rename_provider_capability(),
+            inlay_hint_provider: Some(OneOf::Left(true)),
+            document_formatting_provider: Some(OneOf::Left(true)),
+            document_range_formatting_provider: Some(OneOf::Left(true)),
+            call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
+            document_highlight_provider: Some(OneOf::Left(true)),
+            semantic_tokens_provider: Some(
+                SemanticTokensServerCapabilities::SemanticTokensOptions(SemanticTokensOptions {
+                    legend: semantic_tokens_legend(),
+                    full: Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                    range: None,
+                    work_done_progress_options: Default::default(),
+                }),
+            ),
             document_symbol_provider: Some(OneOf::Left(true)),
             folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
             workspace_symbol_provider: Some(OneOf::Left(true)),
@@ -173,6 +210,15 @@ pub(crate) fn initialize(
                 work_done_progress_options: Default::default(),
             }),
             code_action_provider: lsp_state.capabilities.code_action_provider_capability(),
+            code_lens_provider: Some(CodeLensOptions {
+                resolve_provider: Some(true),
+            }),
+            diagnostic_provider: Some(DiagnosticServerCapabilities::Options(DiagnosticOptions {
+                identifier: Some(String::from("ark")),
+                inter_file_dependencies: true,
+                workspace_diagnostics: true,
+                work_done_progress_options: Default::default(),
+            })),
             workspace: Some(WorkspaceServerCapabilities {
                 workspace_folders: Some(WorkspaceFoldersServerCapabilities {
                     supported: Some(true),
@@ -205,6 +251,15 @@ pub(crate) fn initialize(
                 first_trigger_character: String::from("\n"),
                 more_trigger_character: None,
             }),
+            notebook_document_sync: Some(OneOf::Left(NotebookDocumentSyncOptions {
+                notebook_selector: vec![NotebookSelector::ByCells {
+                    notebook: None,
+                    cells: vec![NotebookCellSelector {
+                        language: String::from("r"),
+                    }],
+                }],
+                save: None,
+            })),
             ..ServerCapabilities::default()
         },
     })
@@ -225,7 +280,11 @@ pub(crate) fn did_open(
         .set_language(&tree_sitter_r::LANGUAGE.into())
         .unwrap();
 
-    let document = Document::new_with_parser(contents, &mut parser, Some(version));
+    let document = if is_embedded_document(&uri, &params.text_document.language_id) {
+        Document::new_embedded_with_parser(contents, &mut parser, Some(version))
+    } else {
+        Document::new_with_parser(contents, &mut parser, Some(version))
+    };
 
     lsp_state.parsers.insert(uri.clone(), parser);
     state.documents.insert(uri.clone(), document.clone());
@@ -289,11 +348,141 @@ pub(crate) fn did_close(
         .remove(&uri)
         .ok_or(anyhow!("Failed to remove parser for URI: {uri}"))?;
 
+    lsp_state.semantic_tokens.remove(&uri);
+
     lsp::log_info!("did_close(): closed document with URI: '{uri}'.");
 
     Ok(())
 }
 
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn did_open_notebook(
+    params: DidOpenNotebookDocumentParams,
+    lsp_state: &mut LspState,
+    state: &mut WorldState,
+) -> anyhow::Result<()> {
+    let uri = params.notebook_document.uri;
+
+    for item in params.cell_text_documents {
+        open_cell(item, lsp_state, state);
+    }
+
+    let cells = params
+        .notebook_document
+        .cells
+        .into_iter()
+        .map(|cell| cell.document)
+        .collect();
+    let notebook = Notebook::new(params.notebook_document.version, cells, &state.documents);
+    state.notebooks.insert(uri, notebook);
+
+    lsp::main_loop::diagnostics_refresh_all(state.clone());
+
+    Ok(())
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn did_change_notebook(
+    params: DidChangeNotebookDocumentParams,
+    lsp_state: &mut LspState,
+    state: &mut WorldState,
+) -> anyhow::Result<()> {
+    let uri = &params.notebook_document.uri;
+
+    let mut notebook = state
+        .notebooks
+        .remove(uri)
+        .ok_or(anyhow!("Can't find notebook for URI {uri}"))?;
+    notebook.version = params.notebook_document.version;
+
+    if let Some(cells) = params.change.cells {
+        if let Some(structure) = cells.structure {
+            for item in structure.did_open.unwrap_or_default() {
+                open_cell(item, lsp_state, state);
+            }
+            for cell in structure.did_close.unwrap_or_default() {
+                close_cell(&cell.uri, lsp_state, state);
+            }
+            notebook.splice(structure.array);
+        }
+
+        for content in cells.text_content.unwrap_or_default() {
+            let cell_uri = &content.document.uri;
+
+            // Non-R cells are not watched
+            let (Some(document), Some(parser)) = (
+                state.documents.get_mut(cell_uri),
+                lsp_state.parsers.get_mut(cell_uri),
+            ) else {
+                continue;
+            };
+
+            document.on_did_change(parser, &DidChangeTextDocumentParams {
+                text_document: content.document,
+                content_changes: content.changes,
+            });
+        }
+    }
+
+    notebook.update(&state.documents);
+    state.notebooks.insert(uri.clone(), notebook);
+
+    lsp::main_loop::diagnostics_refresh_all(state.clone());
+
+    Ok(())
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn did_close_notebook(
+    params: DidCloseNotebookDocumentParams,
+    lsp_state: &mut LspState,
+    state: &mut WorldState,
+) -> anyhow::Result<()> {
+    let uri = params.notebook_document.uri;
+
+    if let Some(notebook) = state.notebooks.remove(&uri) {
+        for cell in notebook.cells {
+            close_cell(&cell.uri, lsp_state, state);
+        }
+    }
+    for cell in params.cell_text_documents {
+        close_cell(&cell.uri, lsp_state, state);
+    }
+
+    lsp::log_info!("did_close_notebook(): closed notebook with URI: '{uri}'.");
+
+    Ok(())
+}
+
+/// Watch the text document of a notebook cell if it contains R code
+fn open_cell(item: lsp_types::TextDocumentItem, lsp_state: &mut LspState, state: &mut WorldState) {
+    if !is_r_cell(&item) {
+        return;
+    }
+
+    let mut parser = Parser::new();
+    parser
+        .set_language(&tree_sitter_r::LANGUAGE.into())
+        .unwrap();
+
+    let document = Document::new_with_parser(&item.text, &mut parser, Some(item.version));
+
+    lsp_state.parsers.insert(item.uri.clone(), parser);
+    state.documents.insert(item.uri, document);
+}
+
+fn close_cell(uri: &Url, lsp_state: &mut LspState, state: &mut WorldState) {
+    if state.documents.remove(uri).is_none() {
+        return;
+    }
+
+    // Publish empty set of diagnostics to clear them
+    lsp::publish_diagnostics(uri.clone(), Vec::new(), None);
+
+    lsp_state.parsers.remove(uri);
+    lsp_state.semantic_tokens.remove(uri);
+}
+
 #[tracing::instrument(level = "info", skip_all)]
 pub(crate) fn did_create_files(
     params: CreateFilesParams,
@@ -372,6 +561,32 @@ pub(crate) async fn did_change_configuration(
         .await
 }
 
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn did_change_watched_files(
+    params: DidChangeWatchedFilesParams,
+    state: &mut WorldState,
+) -> anyhow::Result<()> {
+    let mut changed = false;
+
+    for change in params.changes.iter() {
+        let Ok(path) = change.uri.to_file_path() else {
+            continue;
+        };
+        if !ArkTomlFiles::is_ark_toml(&path) {
+            continue;
+        }
+        state.ark_toml.update(&path);
+        changed = true;
+    }
+
+    if changed {
+        tracing::info!("Refreshing diagnostics after `ark.toml` changed");
+        lsp::main_loop::diagnostics_refresh_all(state.clone());
+    }
+
+    Ok(())
+}
+
 #[tracing::instrument(level = "info", skip_all)]
 pub(crate) fn did_change_formatting_options(
     uri: &Url,
@@ -492,6 +707,9 @@ pub(crate) fn did_change_console_inputs(
 ) -> anyhow::Result<()> {
     state.console_scopes = inputs.console_scopes;
     state.installed_packages = inputs.installed_packages;
+    state
+        .library
+        .update_exports_index(state.installed_packages.clone());
 
     // We currently rely on global console scopes for diagnostics, in particular
     // during package development in conjunction with `devtools::load_all()`.
@@ -133,6 +133,11 @@ pub(crate) fn statement_range(
     document: &Document,
     point: Point,
 ) -> LspResult<Option<StatementRangeResponse>> {
+    // Prose of R Markdown and Quarto documents is not executable
+    if !document.is_r_row(point.row) {
+        return Ok(None);
+    }
+
     let root = document.ast.root_node();
     let contents = &document.contents;
 
@@ -212,7 +217,10 @@ fn as_roxygen_comment_text(node: &Node, contents: &str) -> Option<String> {
     Some(text)
 }
 
-fn find_roxygen_examples_section(node: Node, contents: &str) -> Option<tree_sitter::Range> {
+pub(crate) fn find_roxygen_examples_section(
+    node: Node,
+    contents: &str,
+) -> Option<tree_sitter::Range> {
     // Check that the `node` we start on is a valid roxygen comment line.
     // We check this `node` specially because the loops below start on the previous/next
     // sibling, and this one would go unchecked.
@@ -322,27 +330,11 @@ fn find_roxygen_examples_range(
     // Anchor row that we adjust relative to
     let row_adjustment = range.start_point.row;
 
-    // Slice out the `@examples` or `@examplesIf` code block (with leading roxygen comments)
-    let Some(slice) = contents.get(range.start_byte..range.end_byte) else {
+    // Trim out leading roxygen comments so we are left with a subdocument of actual code
+    let Some(subcontents) = roxygen_examples_code(range, contents) else {
         return Ok(None);
     };
 
-    // Trim out leading roxygen comments so we are left with a subdocument of actual code
-    let subcontents = slice.to_string();
-    let subcontents: Vec<String> = subcontents
-        .lines()
-        .map(|line| {
-            // Trim `#'` and at most 1 leading whitespace character. Don't trim more
-            // whitespace because that would trim intentional indentation and whitespace
-            // in multiline strings.
-            let line = RE_ROXYGEN2_COMMENT.replace(line, "");
-            line.strip_prefix(" ")
-                .map(str::to_string)
-                .unwrap_or_else(|| line.to_string())
-        })
-        .collect();
-    let subcontents = subcontents.join("\n");
-
     // Parse the subdocument
     let subdocument = Document::new(&subcontents, None);
     let subdocument_root = subdocument.ast.root_node();
@@ -375,6 +367,28 @@ fn find_roxygen_examples_range(
     })
 }
 
+/// The code of an `@examples` or `@examplesIf` section found by
+/// `find_roxygen_examples_section()`, stripped of its roxygen comment prefixes
+pub(crate) fn roxygen_examples_code(range: tree_sitter::Range, contents: &str) -> Option<String> {
+    // Slice out the `@examples` or `@examplesIf` code block (with leading roxygen comments)
+    let slice = contents.get(range.start_byte..range.end_byte)?;
+
+    let lines: Vec<String> = slice
+        .lines()
+        .map(|line| {
+            // Trim `#'` and at most 1 leading whitespace character. Don't trim more
+            // whitespace because that would trim intentional indentation and whitespace
+            // in multiline strings.
+            let line = RE_ROXYGEN2_COMMENT.replace(line, "");
+            line.strip_prefix(" ")
+                .map(str::to_string)
+                .unwrap_or_else(|| line.to_string())
+        })
+        .collect();
+
+    Some(lines.join("\n"))
+}
+
 fn adjust_roxygen_examples_success(
     subdocument_statement_range: ArkStatementRangeSuccess,
     subdocument: &Document,
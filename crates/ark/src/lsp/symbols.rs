@@ -18,8 +18,10 @@ use tower_lsp::lsp_types::SymbolInformation;
 use tower_lsp::lsp_types::SymbolKind;
 use tower_lsp::lsp_types::WorkspaceSymbolParams;
 use tree_sitter::Node;
+use tree_sitter::Point;
 
 use crate::lsp::document::Document;
+use crate::lsp::embedded::EmbeddedDocument;
 use crate::lsp::indexer;
 use crate::lsp::indexer::IndexEntryData;
 use crate::lsp::state::WorldState;
@@ -175,9 +177,72 @@ pub(crate) fn document_symbols(
         return Ok(Vec::new());
     }
 
+    // Group the symbols of R Markdown and Quarto documents by chunk
+    if let Some(embedded) = &doc.embedded {
+        return collect_chunks(embedded, doc, result);
+    }
+
     Ok(result)
 }
 
+/// Nest symbols in the chunks of an R Markdown or Quarto document. Chunks are
+/// named after their label and list their options first.
+fn collect_chunks(
+    embedded: &EmbeddedDocument,
+    doc: &Document,
+    symbols: Vec<DocumentSymbol>,
+) -> anyhow::Result<Vec<DocumentSymbol>> {
+    let mut symbols = symbols.into_iter().peekable();
+    let mut chunks = Vec::new();
+
+    for (i, chunk) in embedded.chunks.iter().enumerate() {
+        let mut children = Vec::new();
+
+        for option in chunk.options.iter() {
+            let mut symbol = new_symbol(
+                option.name.clone(),
+                SymbolKind::PROPERTY,
+                line_range(doc, option.row)?,
+            );
+            symbol.detail = Some(option.value.clone());
+            children.push(symbol);
+        }
+
+        // Symbols are in document order, so the symbols of this chunk are the
+        // ones starting before its closing fence
+        while let Some(symbol) =
+            symbols.next_if(|symbol| symbol.range.start.line as usize <= chunk.end)
+        {
+            children.push(symbol);
+        }
+
+        let name = match &chunk.label {
+            Some(label) => label.clone(),
+            None => format!("Chunk {}", i + 1),
+        };
+        let range = Range {
+            start: line_range(doc, chunk.start)?.start,
+            end: line_range(doc, chunk.end)?.end,
+        };
+
+        chunks.push(new_symbol_node(name, SymbolKind::MODULE, range, children));
+    }
+
+    Ok(chunks)
+}
+
+/// The range of a whole line, clamped to the last line of the document
+fn line_range(doc: &Document, row: usize) -> anyhow::Result<Range> {
+    let n_rows = doc.contents.lines().count();
+    let row = row.min(n_rows.saturating_sub(1));
+    let line = doc.contents.lines().nth(row).unwrap_or_default();
+
+    Ok(Range {
+        start: doc.lsp_position_from_tree_sitter_point(Point::new(row, 0))?,
+        end: doc.lsp_position_from_tree_sitter_point(Point::new(row, line.len()))?,
+    })
+}
+
 /// Collect all document symbols from a node recursively
 fn collect_symbols(
     ctx: &mut CollectContext,
@@ -1275,4 +1340,54 @@ function(
 "
         ));
     }
+
+    #[test]
+    fn test_symbol_embedded_chunks() {
+        let host = "---
+title: Test
+---
+
+```{r setup}
+#| echo: false
+f <- function() 1
+```
+
+Text
+
+```{r}
+x <- 1
+```
+";
+        let doc = Document::new_embedded(host, None);
+        let uri = test_path("test.Rmd");
+
+        let mut state = WorldState::default();
+        state.documents.insert(uri.clone(), doc);
+
+        let params = DocumentSymbolParams {
+            text_document: tower_lsp::lsp_types::TextDocumentIdentifier { uri },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let symbols = document_symbols(&state, &params).unwrap();
+
+        let names: Vec<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["setup", "Chunk 2"]);
+
+        assert_eq!(symbols[0].kind, SymbolKind::MODULE);
+        assert_eq!(symbols[0].range.start, Position::new(4, 0));
+        assert_eq!(symbols[0].range.end, Position::new(7, 3));
+
+        let children = symbols[0].children.as_ref().unwrap();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].name, "echo");
+        assert_eq!(children[0].kind, SymbolKind::PROPERTY);
+        assert_eq!(children[0].detail, Some(String::from("false")));
+        assert_eq!(children[1].name, "f");
+        assert_eq!(children[1].kind, SymbolKind::FUNCTION);
+
+        let children = symbols[1].children.as_ref().unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].name, "x");
+    }
 }
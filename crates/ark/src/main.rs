@@ -33,6 +33,7 @@ fn print_usage() {
     print!(
         r#"
 Usage: ark [OPTIONS]
+       ark lint [OPTIONS] [PATHS]...   (see `ark lint --help`)
 
 Available options:
 
@@ -51,6 +52,8 @@ Available options:
                              containing a list of named repositories (`name = url`)
 --default-ppm-repo           Set the default repositories to a custom Posit Package Manager URL.
 --default-cran-repo          Set the default CRAN repository to a custom URL.
+--lsp                        Run the language server on stdin/stdout, without
+                             starting R
 --version                    Print the version of Ark
 --log FILE                   Log to the given file (if not specified, stdout/stderr
                              will be used)
@@ -87,6 +90,17 @@ fn main() -> anyhow::Result<()> {
     // Skip the first "argument" as it's the path/name to this executable
     argv.next();
 
+    // `ark lint` is a subcommand with its own arguments. It analyses files on
+    // disk and doesn't start R.
+    let mut argv = argv.peekable();
+    if argv.peek().is_some_and(|arg| arg == "lint") {
+        argv.next();
+        if !ark::lsp::lint::start_lint(argv)? {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     let mut connection_file: Option<String> = None;
     let mut startup_file: Option<String> = None;
     let mut session_mode = SessionMode::Console;
@@ -96,6 +110,7 @@ fn main() -> anyhow::Result<()> {
     let mut startup_delay: Option<std::time::Duration> = None;
     let mut r_args: Vec<String> = Vec::new();
     let mut has_action = false;
+    let mut lsp = false;
     let mut capture_streams = true;
     let mut default_repos = DefaultRepos::Auto;
     #[cfg(target_os = "windows")]
@@ -141,6 +156,10 @@ fn main() -> anyhow::Result<()> {
                     ));
                 }
             },
+            "--lsp" => {
+                lsp = true;
+                has_action = true;
+            },
             "--version" => {
                 println!("Ark {}", env!("CARGO_PKG_VERSION"));
                 return Ok(());
@@ -349,6 +368,12 @@ fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
+    // The standalone language server doesn't start R. Logs go to stderr or to
+    // the `--log` file, leaving stdout to the protocol.
+    if lsp {
+        return ark::lsp::backend::start_lsp_stdio();
+    }
+
     // Register segfault handler to get a backtrace. Should be after
     // initialising `log!`. Note that R will not override this handler
     // because we set `R_SignalHandlers` to 0 before startup.
@@ -513,6 +513,19 @@ pub(crate) fn node_is_namespaced_call(
     node_namespace == namespace && node_name == name
 }
 
+/// Is this node the right-hand side of a native or magrittr pipe?
+pub(crate) fn node_is_pipe_rhs(node: &Node, contents: &str) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+
+    if !parent.is_pipe_operator(contents).unwrap_or(false) {
+        return false;
+    }
+
+    parent.child_by_field_name("rhs") == Some(*node)
+}
+
 /// This function takes a [Node] that you suspect might be in a call argument position
 /// and walks up the tree, looking for the containing call node
 ///
@@ -57,6 +57,7 @@ use libr::*;
 use stdext::local;
 use stdext::unwrap;
 
+use crate::data_explorer::table_backend::is_lazy_table;
 use crate::methods::ArkGenerics;
 use crate::modules::ARK_ENVS;
 
@@ -607,7 +608,7 @@ fn has_viewer(value: SEXP) -> bool {
         // The viewer method was found, use its result
         Ok(Some(val)) => val,
         // No method found, fall back to default logic for data frames/matrices
-        Ok(None) => r_is_data_frame(value) || r_is_matrix(value),
+        Ok(None) => r_is_data_frame(value) || r_is_matrix(value) || is_lazy_table(value),
     }
 }
 
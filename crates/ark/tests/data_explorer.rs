@@ -6,6 +6,7 @@
 //
 use amalthea::comm::comm_channel::CommMsg;
 use amalthea::comm::data_explorer_comm::ArraySelection;
+use amalthea::comm::data_explorer_comm::CodeSyntaxName;
 use amalthea::comm::data_explorer_comm::ColumnDisplayType;
 use amalthea::comm::data_explorer_comm::ColumnFilter;
 use amalthea::comm::data_explorer_comm::ColumnFilterParams;
@@ -23,6 +24,7 @@ use amalthea::comm::data_explorer_comm::ColumnProfileType;
 use amalthea::comm::data_explorer_comm::ColumnSelection;
 use amalthea::comm::data_explorer_comm::ColumnSortKey;
 use amalthea::comm::data_explorer_comm::ColumnValue;
+use amalthea::comm::data_explorer_comm::ConvertToCodeParams;
 use amalthea::comm::data_explorer_comm::DataExplorerBackendReply;
 use amalthea::comm::data_explorer_comm::DataExplorerBackendRequest;
 use amalthea::comm::data_explorer_comm::DataExplorerFrontendEvent;
@@ -42,6 +44,7 @@ use amalthea::comm::data_explorer_comm::GetColumnProfilesParams;
 use amalthea::comm::data_explorer_comm::GetDataValuesParams;
 use amalthea::comm::data_explorer_comm::GetRowLabelsParams;
 use amalthea::comm::data_explorer_comm::GetSchemaParams;
+use amalthea::comm::data_explorer_comm::OpenDatasetParams;
 use amalthea::comm::data_explorer_comm::RowFilter;
 use amalthea::comm::data_explorer_comm::RowFilterCondition;
 use amalthea::comm::data_explorer_comm::RowFilterParams;
@@ -50,6 +53,7 @@ use amalthea::comm::data_explorer_comm::SearchSchemaParams;
 use amalthea::comm::data_explorer_comm::SearchSchemaResult;
 use amalthea::comm::data_explorer_comm::SearchSchemaSortOrder;
 use amalthea::comm::data_explorer_comm::Selection;
+use amalthea::comm::data_explorer_comm::SetColumnFiltersParams;
 use amalthea::comm::data_explorer_comm::SetRowFiltersParams;
 use amalthea::comm::data_explorer_comm::SetSortColumnsParams;
 use amalthea::comm::data_explorer_comm::SummaryStatsBoolean;
@@ -245,6 +249,10 @@ impl RequestBuilder {
         })
     }
 
+    fn set_column_filters(filters: Vec<ColumnFilter>) -> DataExplorerBackendRequest {
+        DataExplorerBackendRequest::SetColumnFilters(SetColumnFiltersParams { filters })
+    }
+
     fn set_sort_columns(sort_keys: Vec<ColumnSortKey>) -> DataExplorerBackendRequest {
         DataExplorerBackendRequest::SetSortColumns(SetSortColumnsParams { sort_keys })
     }
@@ -284,6 +292,21 @@ impl RequestBuilder {
             selection,
         })
     }
+
+    fn convert_to_code(
+        code_syntax_name: &str,
+        row_filters: Vec<RowFilter>,
+        sort_keys: Vec<ColumnSortKey>,
+    ) -> DataExplorerBackendRequest {
+        DataExplorerBackendRequest::ConvertToCode(ConvertToCodeParams {
+            column_filters: vec![],
+            row_filters,
+            sort_keys,
+            code_syntax_name: CodeSyntaxName {
+                code_syntax_name: code_syntax_name.to_string(),
+            },
+        })
+    }
 }
 
 /// Test assertion helpers with enhanced error reporting
@@ -591,6 +614,44 @@ impl RowFilterBuilder {
         }
     }
 
+    fn not_empty(column_schema: amalthea::comm::data_explorer_comm::ColumnSchema) -> RowFilter {
+        RowFilter {
+            column_schema,
+            filter_type: RowFilterType::NotEmpty,
+            params: None,
+            filter_id: uuid::Uuid::new_v4().to_string(),
+            condition: RowFilterCondition::And,
+            is_valid: None,
+            error_message: None,
+        }
+    }
+
+    fn between(
+        column_schema: amalthea::comm::data_explorer_comm::ColumnSchema,
+        left_value: &str,
+        right_value: &str,
+        inclusive: bool,
+    ) -> RowFilter {
+        RowFilter {
+            column_schema,
+            filter_type: if inclusive {
+                RowFilterType::Between
+            } else {
+                RowFilterType::NotBetween
+            },
+            params: Some(RowFilterParams::Between(
+                amalthea::comm::data_explorer_comm::FilterBetween {
+                    left_value: left_value.to_string(),
+                    right_value: right_value.to_string(),
+                },
+            )),
+            filter_id: uuid::Uuid::new_v4().to_string(),
+            condition: RowFilterCondition::And,
+            is_valid: None,
+            error_message: None,
+        }
+    }
+
     fn is_true(column_schema: amalthea::comm::data_explorer_comm::ColumnSchema) -> RowFilter {
         RowFilter {
             column_schema,
@@ -985,6 +1046,222 @@ fn test_data_table_support() {
     });
 }
 
+#[test]
+fn test_lazy_table_support() {
+    let has_dbplyr = r_task(|| {
+        harp::parse_eval_global(
+            "lazy_tbl <- dbplyr::memdb_frame(x = c(3L, 1L, 2L, NA), y = c('c', 'a', 'b', 'a'))",
+        )
+        .is_ok()
+    });
+    if !has_dbplyr {
+        return;
+    }
+
+    let setup = TestSetup::new("lazy_tbl");
+
+    TestAssertions::assert_state(&setup, |state| {
+        assert_eq!(state.table_shape.num_rows, 4);
+        assert_eq!(state.table_shape.num_columns, 2);
+        assert!(!state.has_row_labels);
+    });
+
+    // Pages are collected from the database
+    TestAssertions::assert_data_values(&setup, 1, 2, vec![0, 1], |columns| {
+        assert_eq!(columns[0], vec![
+            ColumnValue::FormattedValue(String::from("1")),
+            ColumnValue::FormattedValue(String::from("2")),
+        ]);
+        assert_eq!(columns[1], vec![
+            ColumnValue::FormattedValue(String::from("a")),
+            ColumnValue::FormattedValue(String::from("b")),
+        ]);
+    });
+
+    // Profiles are computed by the database
+    let req = RequestBuilder::get_column_profiles(String::from("lazy_null_count"), vec![
+        ProfileBuilder::null_count(0),
+    ]);
+    expect_column_profile_results(&setup, req, |data| {
+        assert_eq!(data[0].null_count, Some(1));
+    });
+
+    let req = RequestBuilder::get_column_profiles(String::from("lazy_freq_table"), vec![
+        ProfileBuilder::small_frequency_table(1, 1),
+    ]);
+    expect_column_profile_results(&setup, req, |data| {
+        let freq_table = data[0].small_frequency_table.clone().unwrap();
+        assert_eq!(freq_table.counts, vec![2]);
+        assert_eq!(freq_table.other_count, Some(2));
+    });
+
+    // Filter values are passed as data and never parsed as code
+    let count: f64 = r_task(|| {
+        harp::parse_eval_global(
+            "local({
+                filter <- list(
+                    filter_type = 'compare',
+                    column_schema = list(column_name = 'y', type_display = 'string'),
+                    params = list(op = '=', value = 'a\") | (1 == 1')
+                )
+                .ps.lazy_count(lazy_tbl, list(filter))
+            })",
+        )
+        .unwrap()
+        .try_into()
+        .unwrap()
+    });
+    assert_eq!(count, 0.0);
+
+    // Filter and sort in the database
+    let req = RequestBuilder::get_schema(vec![0]);
+    let schema = match setup.rpc(req) {
+        DataExplorerBackendReply::GetSchemaReply(schema) => schema,
+        _ => panic!("Expected schema reply"),
+    };
+    let filters = vec![RowFilterBuilder::not_null(schema.columns[0].clone())];
+    TestAssertions::assert_row_filters_applied(&setup, filters, 3, Some(false));
+
+    let sort_keys = vec![SelectionBuilder::column_sort_key(0, false)];
+    TestAssertions::assert_sort_columns_applied(&setup, sort_keys);
+
+    TestAssertions::assert_data_values(&setup, 0, 10, vec![0], |columns| {
+        assert_eq!(columns[0], vec![
+            ColumnValue::FormattedValue(String::from("3")),
+            ColumnValue::FormattedValue(String::from("2")),
+            ColumnValue::FormattedValue(String::from("1")),
+        ]);
+    });
+
+    // Pages after the first one follow the sort order
+    TestAssertions::assert_data_values(&setup, 1, 2, vec![0], |columns| {
+        assert_eq!(columns[0], vec![
+            ColumnValue::FormattedValue(String::from("2")),
+            ColumnValue::FormattedValue(String::from("1")),
+        ]);
+    });
+
+    r_task(|| {
+        harp::parse_eval_global("rm(lazy_tbl)").unwrap();
+    });
+}
+
+#[test]
+fn test_lazy_table_text_search() {
+    let has_duckdb = r_task(|| {
+        harp::parse_eval_global(
+            "local({
+                con <- DBI::dbConnect(duckdb::duckdb())
+                people <- data.frame(name = c('Alice', 'bob', 'Carl_a', '50%'))
+                DBI::dbWriteTable(con, 'people', people)
+                lazy_people <<- dplyr::tbl(con, 'people')
+            })",
+        )
+        .is_ok()
+    });
+    if !has_duckdb {
+        return;
+    }
+
+    let setup = TestSetup::new("lazy_people");
+    let schema = TestAssertions::get_column_schema(&setup, vec![0]);
+    let name = schema.columns[0].clone();
+
+    let search = |search_type, term: &str, case_sensitive| {
+        RowFilterBuilder::text_search(name.clone(), search_type, term, case_sensitive)
+    };
+
+    // Each search is translated to SQL and evaluated by DuckDB
+    let cases = vec![
+        (search(TextSearchType::Contains, "a", false), 2),
+        (search(TextSearchType::Contains, "a", true), 1),
+        (search(TextSearchType::Contains, "_", true), 1),
+        (search(TextSearchType::Contains, "%", true), 1),
+        (search(TextSearchType::NotContains, "l", true), 2),
+        (search(TextSearchType::StartsWith, "B", false), 1),
+        (search(TextSearchType::EndsWith, "_A", false), 1),
+        (search(TextSearchType::RegexMatch, "^[ac]", false), 2),
+    ];
+    for (filter, expected_rows) in cases {
+        TestAssertions::assert_row_filters_applied(
+            &setup,
+            vec![filter],
+            expected_rows,
+            Some(false),
+        );
+    }
+
+    // SQL code selects from the database table rather than the R object
+    let req = RequestBuilder::convert_to_code("SQL", vec![], vec![]);
+    assert_match!(setup.rpc(req),
+        DataExplorerBackendReply::ConvertToCodeReply(code) => {
+            assert_eq!(code.converted_code, vec!["SELECT *\nFROM people"]);
+        }
+    );
+
+    r_task(|| {
+        harp::parse_eval_global(
+            "DBI::dbDisconnect(dbplyr::remote_con(lazy_people)); rm(lazy_people)",
+        )
+        .unwrap();
+    });
+}
+
+#[test]
+fn test_lazy_arrow_pages_are_sliced() {
+    let has_arrow = r_task(|| {
+        harp::parse_eval_global("arrow_tbl <- arrow::arrow_table(x = 1:100000, y = 100000:1)")
+            .is_ok()
+    });
+    if !has_arrow {
+        return;
+    }
+
+    r_task(|| {
+        // A deep page is sliced in Arrow: only the rows of the page are
+        // converted to R
+        let num_rows: i32 = harp::parse_eval_global(
+            "local({
+                page <- .ps.lazy_arrow_page(arrow_tbl, NULL, NULL, NULL, 'x', 99990, 5)
+                as.integer(page$num_rows)
+            })",
+        )
+        .unwrap()
+        .try_into()
+        .unwrap();
+        assert_eq!(num_rows, 5);
+
+        // Sorted queries are computed once and sliced too
+        let values: Vec<i32> = harp::parse_eval_global(
+            "local({
+                filter <- list(
+                    filter_type = 'compare',
+                    column_schema = list(column_name = 'x', type_display = 'integer'),
+                    params = list(op = '>', value = '10')
+                )
+                page <- .ps.lazy_arrow_page(arrow_tbl, list(filter), 'y', TRUE, 'x', 2, 3)
+                as.data.frame(page)$x
+            })",
+        )
+        .unwrap()
+        .try_into()
+        .unwrap();
+        assert_eq!(values, vec![99998, 99997, 99996]);
+    });
+
+    let setup = TestSetup::new("arrow_tbl");
+    TestAssertions::assert_data_values(&setup, 99998, 5, vec![0], |columns| {
+        assert_eq!(columns[0], vec![
+            ColumnValue::FormattedValue(String::from("99999")),
+            ColumnValue::FormattedValue(String::from("100000")),
+        ]);
+    });
+
+    r_task(|| {
+        harp::parse_eval_global("rm(arrow_tbl)").unwrap();
+    });
+}
+
 #[test]
 fn test_null_counts() {
     let setup = TestSetup::from_expression(
@@ -2272,6 +2549,84 @@ fn test_search_schema_no_matches() {
     TestAssertions::assert_search_matches(&setup, req, vec![] as Vec<i64>);
 }
 
+#[test]
+fn test_set_column_filters() {
+    let setup = TestDataBuilder::create_search_test_dataframe().unwrap();
+
+    // Schema: user_name(0), user_age(1), user_id(2), email_address(3), admin_email(4),
+    //         score(5), bonus_score(6), is_active(7), is_premium(8), registration_date(9), last_login(10)
+
+    // Keep the columns whose name matches a pattern
+    let filters = vec![ColumnFilter {
+        filter_type: ColumnFilterType::TextSearch,
+        params: ColumnFilterParams::TextSearch(FilterTextSearch {
+            search_type: TextSearchType::RegexMatch,
+            term: String::from("^(user|is)_"),
+            case_sensitive: false,
+        }),
+    }];
+    assert_match!(setup.rpc(RequestBuilder::set_column_filters(filters)),
+        DataExplorerBackendReply::SetColumnFiltersReply() => {}
+    );
+
+    TestAssertions::assert_state(&setup, |state| {
+        assert_eq!(state.table_shape.num_columns, 5);
+        assert_eq!(state.table_unfiltered_shape.num_columns, 11);
+        assert_eq!(state.column_filters.len(), 1);
+    });
+
+    // Schema indices are relative to the filtered columns
+    assert_match!(setup.rpc(RequestBuilder::get_schema(vec![0, 1, 2, 3, 4, 5])),
+        DataExplorerBackendReply::GetSchemaReply(schema) => {
+            let names: Vec<&str> = schema.columns.iter().map(|c| c.column_name.as_str()).collect();
+            assert_eq!(names, vec!["user_name", "user_age", "user_id", "is_active", "is_premium"]);
+
+            let indices: Vec<i64> = schema.columns.iter().map(|c| c.column_index).collect();
+            assert_eq!(indices, vec![0, 1, 2, 7, 8]);
+        }
+    );
+
+    // Searches only consider the filtered columns
+    let req = RequestBuilder::search_schema_data_types(
+        vec![ColumnDisplayType::Boolean],
+        SearchSchemaSortOrder::Original,
+    );
+    TestAssertions::assert_search_matches(&setup, req, vec![7, 8]);
+
+    let req = RequestBuilder::search_schema_text(
+        "score",
+        TextSearchType::Contains,
+        false,
+        SearchSchemaSortOrder::Original,
+    );
+    TestAssertions::assert_search_matches(&setup, req, vec![] as Vec<i64>);
+
+    // Data values are selected by unfiltered column index, and excluded
+    // columns have no values
+    TestAssertions::assert_data_values(&setup, 0, 3, vec![7, 5], |columns| {
+        assert_eq!(columns[0].len(), 3);
+        assert_eq!(columns[1].len(), 0);
+    });
+
+    // Combined with a data type filter
+    let filters = vec![
+        FilterBuilder::text_contains("user", false),
+        FilterBuilder::match_data_types(vec![ColumnDisplayType::Integer]),
+    ];
+    setup.rpc(RequestBuilder::set_column_filters(filters));
+    TestAssertions::assert_state(&setup, |state| {
+        assert_eq!(state.table_shape.num_columns, 1);
+    });
+
+    // Clearing the filters restores all columns
+    setup.rpc(RequestBuilder::set_column_filters(vec![]));
+    TestAssertions::assert_state(&setup, |state| {
+        assert_eq!(state.table_shape.num_columns, 11);
+        assert!(state.column_filters.is_empty());
+    });
+    TestAssertions::assert_schema_columns(&setup, vec![0, 5, 10], 3);
+}
+
 #[test]
 fn test_search_schema_type_sort_orders() {
     // Create a simpler dataframe with multiple columns of different types for type sorting tests
@@ -3037,3 +3392,331 @@ fn test_single_row_data_frame_column_profiles() {
         });
     }
 }
+
+#[test]
+fn test_open_dataset_rpc() {
+    let setup = TestSetup::from_expression("x <- data.frame(a = 1:5)", Some("x")).unwrap();
+
+    let path = r_task(|| {
+        let path = harp::parse_eval_global(
+            "local({
+                path <- file.path(tempfile(), 'sales 2024.csv')
+                dir.create(dirname(path))
+                df <- data.frame(x = 1:3, y = c('a', 'b', 'c'))
+                utils::write.csv(df, path, row.names = FALSE)
+                path
+            })",
+        )
+        .unwrap();
+        String::try_from(path).unwrap()
+    });
+    let file_name = String::from("sales 2024.csv");
+    let uri = url::Url::from_file_path(&path).unwrap();
+
+    // The dataset is loaded into the requesting data viewer, which sends a
+    // schema update before replying
+    let req = DataExplorerBackendRequest::OpenDataset(OpenDatasetParams {
+        uri: uri.to_string(),
+    });
+    let msg = CommMsg::Rpc {
+        id: uuid::Uuid::new_v4().to_string(),
+        parent_header: dummy_jupyter_header(),
+        data: serde_json::to_value(req).unwrap(),
+    };
+    setup.socket.incoming_tx.send(msg).unwrap();
+
+    assert_match!(setup.iopub_rx.recv_comm_msg(),
+        CommMsg::Data(value) => {
+            assert_match!(serde_json::from_value::<DataExplorerFrontendEvent>(value).unwrap(),
+                DataExplorerFrontendEvent::SchemaUpdate);
+        }
+    );
+    assert_match!(setup.iopub_rx.recv_comm_msg(),
+        CommMsg::Rpc { data: value, .. } => {
+            assert_match!(serde_json::from_value::<DataExplorerBackendReply>(value).unwrap(),
+                DataExplorerBackendReply::OpenDatasetReply(result) => {
+                    assert_eq!(result.error_message, None);
+                }
+            );
+        }
+    );
+
+    TestAssertions::assert_state(&setup, |state| {
+        assert_eq!(state.display_name, file_name);
+        assert_eq!(state.table_shape.num_rows, 3);
+        assert_eq!(state.table_shape.num_columns, 2);
+    });
+
+    TestAssertions::assert_data_values(&setup, 0, 3, vec![1], |columns| {
+        assert_eq!(columns[0], vec![
+            ColumnValue::FormattedValue(String::from("a")),
+            ColumnValue::FormattedValue(String::from("b")),
+            ColumnValue::FormattedValue(String::from("c")),
+        ]);
+    });
+
+    // Converted code refers to the data by a syntactic name
+    let req = RequestBuilder::convert_to_code("dplyr", vec![], vec![]);
+    assert_match!(setup.rpc(req),
+        DataExplorerBackendReply::ConvertToCodeReply(code) => {
+            assert_eq!(code.converted_code, vec!["library(dplyr)", "", "sales.2024"]);
+        }
+    );
+
+    // The data viewer no longer tracks the binding it was opened with
+    r_task(|| {
+        harp::parse_eval_global("x <- data.frame(b = 1)").unwrap();
+    });
+    EVENTS.environment_changed.emit(());
+    assert!(setup
+        .iopub_rx
+        .recv_comm_msg_timeout(std::time::Duration::from_millis(200))
+        .is_none());
+
+    // Failures are reported in the reply and leave the data unchanged
+    let req = DataExplorerBackendRequest::OpenDataset(OpenDatasetParams {
+        uri: String::from("/path/to/nonexistent.csv"),
+    });
+    assert_match!(setup.rpc(req),
+        DataExplorerBackendReply::OpenDatasetReply(result) => {
+            assert!(result.error_message.unwrap().contains("Can't find dataset"));
+        }
+    );
+
+    let unsupported = r_task(|| {
+        let path = harp::parse_eval_global(
+            "local({ path <- tempfile(fileext = '.txt'); writeLines('x', path); path })",
+        )
+        .unwrap();
+        String::try_from(path).unwrap()
+    });
+    let req = DataExplorerBackendRequest::OpenDataset(OpenDatasetParams { uri: unsupported });
+    assert_match!(setup.rpc(req),
+        DataExplorerBackendReply::OpenDatasetReply(result) => {
+            assert!(result.error_message.unwrap().contains("Unsupported file type"));
+        }
+    );
+
+    TestAssertions::assert_state(&setup, |state| {
+        assert_eq!(state.display_name, file_name);
+        assert_eq!(state.table_shape.num_rows, 3);
+    });
+}
+
+/// Evaluate the code converted for each filter case in a separate R process,
+/// so that the packages attached by the code don't leak into the session
+/// shared by the tests. Returns the names of the resulting rows for each case,
+/// or the error message of the evaluation.
+fn eval_converted_code(data: &str, syntax: &str, cases: &[Vec<String>]) -> Vec<String> {
+    let dir = tempfile::tempdir().unwrap();
+    let script = dir.path().join("code.R");
+    let results = dir.path().join("results.txt");
+
+    let mut lines = vec![
+        format!("code_people <- {data}"),
+        "results <- character()".to_string(),
+    ];
+    for (i, code) in cases.iter().enumerate() {
+        let mut code = code.join("\n");
+        if syntax == "SQL" {
+            code = format!(
+                r#"local({{
+                    con <- DBI::dbConnect(duckdb::duckdb())
+                    on.exit(DBI::dbDisconnect(con, shutdown = TRUE))
+                    DBI::dbWriteTable(con, "code_people", code_people)
+                    DBI::dbGetQuery(con, r"---({code})---")
+                }})"#
+            );
+        }
+        lines.push(format!(
+            "results[[{}]] <- tryCatch({{\n  result <- {{\n{code}\n  }}\n  paste(as.character(result$name), collapse = \",\")\n}}, error = function(err) paste(\"Error:\", conditionMessage(err)))",
+            i + 1
+        ));
+    }
+    lines.push(format!(
+        "writeLines(results, r\"---({})---\")",
+        results.display()
+    ));
+    std::fs::write(&script, lines.join("\n")).unwrap();
+
+    let rscript: String = r_task(|| {
+        harp::parse_eval_global("file.path(R.home('bin'), 'Rscript')")
+            .unwrap()
+            .try_into()
+            .unwrap()
+    });
+    let output = std::process::Command::new(rscript)
+        .arg(&script)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "Can't evaluate {syntax} code:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    std::fs::read_to_string(&results)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn test_convert_to_code_syntaxes() {
+    let data = "data.frame(
+        name = c('Alice', 'Bob', 'Charlie', 'David', 'Eve'),
+        age = c(25, 30, NA, 22, 41),
+        team = c('a', 'b', 'c', 'b', 'a'),
+        nickname = c('Al', '', 'Chuck', '', 'Evie'),
+        active = c(TRUE, FALSE, TRUE, NA, TRUE)
+    )";
+    let setup =
+        TestSetup::from_expression(&format!("code_people <- {data}"), Some("code_people")).unwrap();
+
+    TestAssertions::assert_state(&setup, |state| {
+        let syntaxes: Vec<String> = state
+            .supported_features
+            .convert_to_code
+            .code_syntaxes
+            .clone()
+            .unwrap()
+            .into_iter()
+            .map(|syntax| syntax.code_syntax_name)
+            .collect();
+        assert_eq!(syntaxes, vec!["dplyr", "base R", "data.table", "duckplyr", "SQL"]);
+    });
+
+    assert_match!(setup.rpc(DataExplorerBackendRequest::SuggestCodeSyntax),
+        DataExplorerBackendReply::SuggestCodeSyntaxReply(syntax) => {
+            assert_eq!(syntax.code_syntax_name, "dplyr");
+        }
+    );
+
+    let schema = TestAssertions::get_column_schema(&setup, vec![0, 1, 2, 3, 4]);
+    let [name, age, team, nickname, active] = [0, 1, 2, 3, 4].map(|i| schema.columns[i].clone());
+
+    let search = |search_type, term: &str| {
+        RowFilterBuilder::text_search(name.clone(), search_type, term, true)
+    };
+
+    // Every filter type, on its own, and combined filters
+    let cases = vec![
+        vec![
+            RowFilterBuilder::not_null(age.clone()),
+            RowFilterBuilder::comparison(age.clone(), FilterComparisonOp::GtEq, "25"),
+        ],
+        vec![RowFilterBuilder::comparison(
+            age.clone(),
+            FilterComparisonOp::Lt,
+            "30",
+        )],
+        vec![RowFilterBuilder::between(age.clone(), "22", "30", true)],
+        vec![RowFilterBuilder::between(age.clone(), "22", "30", false)],
+        vec![RowFilterBuilder::is_null(age.clone())],
+        vec![RowFilterBuilder::is_true(active.clone())],
+        vec![RowFilterBuilder::is_false(active.clone())],
+        vec![RowFilterBuilder::is_empty(nickname.clone())],
+        vec![RowFilterBuilder::not_empty(nickname.clone())],
+        vec![search(TextSearchType::Contains, "a")],
+        vec![search(TextSearchType::NotContains, "a")],
+        vec![search(TextSearchType::StartsWith, "C")],
+        vec![search(TextSearchType::EndsWith, "e")],
+        vec![search(TextSearchType::RegexMatch, "^[AB]")],
+        vec![RowFilterBuilder::set_membership(
+            team.clone(),
+            vec![String::from("b")],
+            true,
+        )],
+        vec![RowFilterBuilder::set_membership(
+            team.clone(),
+            vec![String::from("b")],
+            false,
+        )],
+        vec![
+            search(TextSearchType::NotContains, "v"),
+            RowFilterBuilder::not_null(age.clone()),
+        ],
+    ];
+    let sort_keys = vec![
+        SelectionBuilder::column_sort_key(2, true),
+        SelectionBuilder::column_sort_key(1, false),
+    ];
+    TestAssertions::assert_sort_columns_applied(&setup, sort_keys.clone());
+
+    // The names in the order of the explorer's view, for each case
+    let expected: Vec<String> = cases
+        .iter()
+        .map(|filters| {
+            let req = RequestBuilder::set_row_filters(filters.clone());
+            assert_match!(setup.rpc(req),
+                DataExplorerBackendReply::SetRowFiltersReply(FilterResult { had_errors, .. }) => {
+                    assert_eq!(had_errors, Some(false), "Invalid filters {filters:?}");
+                }
+            );
+
+            let req = get_data_values_request(0, 10, vec![0], default_format_options());
+            match setup.rpc(req) {
+                DataExplorerBackendReply::GetDataValuesReply(data) => data.columns[0]
+                    .iter()
+                    .map(|value| match value {
+                        ColumnValue::FormattedValue(value) => value.clone(),
+                        _ => panic!("Unexpected special value"),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(","),
+                _ => panic!("Expected data values reply"),
+            }
+        })
+        .collect();
+    assert_eq!(expected[0], "Eve,Alice,Bob");
+
+    let syntaxes = [
+        ("dplyr", vec!["dplyr"]),
+        ("base R", vec![]),
+        ("data.table", vec!["data.table"]),
+        ("duckplyr", vec!["duckplyr"]),
+        ("SQL", vec!["DBI", "duckdb"]),
+    ];
+
+    for (syntax, packages) in syntaxes {
+        for package in packages {
+            let installed = r_task(|| {
+                let code = format!("requireNamespace('{package}', quietly = TRUE)");
+                let installed = harp::parse_eval_global(&code).unwrap();
+                harp::r_lgl_get(installed.sexp, 0) == 1
+            });
+            assert!(
+                installed,
+                "`{package}` must be installed to test the {syntax} code"
+            );
+        }
+
+        let code: Vec<Vec<String>> = cases
+            .iter()
+            .map(|filters| {
+                let req =
+                    RequestBuilder::convert_to_code(syntax, filters.clone(), sort_keys.clone());
+                match setup.rpc(req) {
+                    DataExplorerBackendReply::ConvertToCodeReply(code) => code.converted_code,
+                    _ => panic!("Expected converted code reply"),
+                }
+            })
+            .collect();
+
+        let results = eval_converted_code(data, syntax, &code);
+        assert_eq!(results.len(), cases.len());
+        for ((result, expected), code) in results.iter().zip(&expected).zip(&code) {
+            assert_eq!(
+                result,
+                expected,
+                "Unexpected result for {syntax} code:\n{}",
+                code.join("\n")
+            );
+        }
+    }
+
+    r_task(|| {
+        harp::parse_eval_global("rm(code_people)").unwrap();
+    });
+}